@@ -0,0 +1,4 @@
+-- Opt-in "merge when green" per workspace, and the merge method used for PRs per project
+ALTER TABLE workspaces ADD COLUMN auto_merge INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE projects ADD COLUMN pr_merge_method TEXT NOT NULL DEFAULT 'squash'
+    CHECK (pr_merge_method IN ('squash', 'merge', 'rebase'));
@@ -0,0 +1,15 @@
+-- Compacted log storage. Once a process is old enough, its execution_process_logs rows are
+-- folded into one zstd-compressed JSONL blob here. Logs of archived workspaces are moved
+-- out to a file, in which case data is NULL and file_path points at the compressed file.
+CREATE TABLE execution_process_log_archives (
+    execution_id    BLOB PRIMARY KEY,
+    contents        TEXT NOT NULL DEFAULT 'raw'
+                       CHECK (contents IN ('raw', 'normalized')),
+    data            BLOB,
+    file_path       TEXT,
+    original_bytes  INTEGER NOT NULL,
+    stored_bytes    INTEGER NOT NULL,
+    compacted_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    CHECK (data IS NOT NULL OR file_path IS NOT NULL)
+);
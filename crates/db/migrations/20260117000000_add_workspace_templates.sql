@@ -0,0 +1,20 @@
+-- Named, per-project presets for new workspaces: which repos and target branches to use,
+-- which executor profile to start, and extra env vars and copy_files overrides.
+CREATE TABLE workspace_templates (
+    id                  BLOB PRIMARY KEY,
+    project_id          BLOB NOT NULL,
+    name                TEXT NOT NULL,
+    repos               TEXT NOT NULL DEFAULT '[]',  -- JSON array of WorkspaceTemplateRepo
+    executor_profile_id TEXT,                        -- JSON ExecutorProfileId
+    agent_working_dir   TEXT,
+    env                 TEXT NOT NULL DEFAULT '{}',  -- JSON object of extra env vars
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, name)
+);
+
+CREATE INDEX idx_workspace_templates_project_id ON workspace_templates(project_id);
+
+-- Template a workspace was created from, so its env vars and copy_files overrides apply
+ALTER TABLE workspaces ADD COLUMN template_id BLOB REFERENCES workspace_templates(id) ON DELETE SET NULL;
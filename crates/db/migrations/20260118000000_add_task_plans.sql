@@ -0,0 +1,21 @@
+-- Plans drafted by a planning agent run. Execution of the task waits until the user has
+-- reviewed (and possibly edited) the plan and approved it.
+CREATE TABLE task_plans (
+    id                   BLOB PRIMARY KEY,
+    task_id              BLOB NOT NULL,
+    workspace_id         BLOB NOT NULL,
+    session_id           BLOB NOT NULL,  -- session of the planning run
+    content              TEXT,
+    status               TEXT NOT NULL DEFAULT 'drafting'
+                            CHECK (status IN ('drafting','awaiting_approval','approved','rejected')),
+    execution_session_id BLOB,           -- session started when the plan was approved
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_session_id) REFERENCES sessions(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_task_plans_task_id ON task_plans(task_id);
+CREATE INDEX idx_task_plans_session_id ON task_plans(session_id);
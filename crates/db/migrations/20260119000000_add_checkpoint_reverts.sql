@@ -0,0 +1,15 @@
+-- Reverts of a workspace's worktrees to a turn checkpoint. The worktree state before the
+-- revert is kept as a snapshot commit per repo so the revert can be undone.
+CREATE TABLE checkpoint_reverts (
+    id                   BLOB PRIMARY KEY,
+    workspace_id         BLOB NOT NULL,
+    execution_process_id BLOB NOT NULL,              -- turn reverted to (state before it ran)
+    repos                TEXT NOT NULL DEFAULT '[]', -- JSON array of CheckpointRevertRepo
+    dropped_process_ids  TEXT NOT NULL DEFAULT '[]', -- JSON array of process ids dropped by the revert
+    undone_at            TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_checkpoint_reverts_workspace_id ON checkpoint_reverts(workspace_id);
@@ -0,0 +1,8 @@
+-- Follow-up messages queued to run after a session's current execution. Persisted so that
+-- they survive a server restart.
+CREATE TABLE queued_messages (
+    session_id BLOB PRIMARY KEY,
+    data       TEXT NOT NULL, -- JSON DraftFollowUpData
+    queued_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- What to do when a coding agent is detected going around in circles
+ALTER TABLE projects ADD COLUMN loop_policy TEXT NOT NULL DEFAULT 'off'
+    CHECK (loop_policy IN ('off', 'follow_up', 'pause', 'stop'));
@@ -0,0 +1,3 @@
+-- Optional memory and CPU caps for the processes a project's workspaces run
+ALTER TABLE projects ADD COLUMN max_memory_mb INTEGER CHECK (max_memory_mb > 0);
+ALTER TABLE projects ADD COLUMN max_cpu_percent INTEGER CHECK (max_cpu_percent > 0);
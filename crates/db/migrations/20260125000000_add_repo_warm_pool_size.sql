@@ -0,0 +1,2 @@
+-- Number of pre-created worktrees, with the setup script already run, kept per repository
+ALTER TABLE repos ADD COLUMN warm_pool_size INTEGER NOT NULL DEFAULT 0 CHECK (warm_pool_size >= 0);
@@ -0,0 +1,3 @@
+-- Named dev processes, one `<name>: <command>` per line like a Procfile
+ALTER TABLE repos ADD COLUMN dev_processes TEXT;
+ALTER TABLE projects ADD COLUMN dev_processes TEXT;
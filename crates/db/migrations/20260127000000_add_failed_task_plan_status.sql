@@ -0,0 +1,49 @@
+-- Allow 'failed' as a task plan status, for planning runs that ended without presenting a
+-- plan. A failed plan can be rejected, so the task is not left waiting on it.
+-- SQLite cannot alter a CHECK constraint, so the table is rebuilt.
+
+-- Rebuilding needs FK disabled to avoid cascade deletes during DROP TABLE
+-- sqlx workaround: end auto-transaction to allow PRAGMA to take effect
+-- https://github.com/launchbadge/sqlx/issues/2085#issuecomment-1499859906
+COMMIT;
+
+PRAGMA foreign_keys = OFF;
+
+BEGIN TRANSACTION;
+
+CREATE TABLE task_plans_new (
+    id                   BLOB PRIMARY KEY,
+    task_id              BLOB NOT NULL,
+    workspace_id         BLOB NOT NULL,
+    session_id           BLOB NOT NULL,  -- session of the planning run
+    content              TEXT,
+    status               TEXT NOT NULL DEFAULT 'drafting'
+                            CHECK (status IN ('drafting','awaiting_approval','approved','rejected','failed')),
+    execution_session_id BLOB,           -- session started when the plan was approved
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_session_id) REFERENCES sessions(id) ON DELETE SET NULL
+);
+
+INSERT INTO task_plans_new (id, task_id, workspace_id, session_id, content, status, execution_session_id, created_at, updated_at)
+SELECT id, task_id, workspace_id, session_id, content, status, execution_session_id, created_at, updated_at
+FROM task_plans;
+
+DROP TABLE task_plans;
+ALTER TABLE task_plans_new RENAME TO task_plans;
+
+CREATE INDEX idx_task_plans_task_id ON task_plans(task_id);
+CREATE INDEX idx_task_plans_session_id ON task_plans(session_id);
+
+-- Verify foreign key constraints before committing
+PRAGMA foreign_key_check;
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
+
+-- sqlx workaround: start empty transaction for sqlx to close gracefully
+BEGIN TRANSACTION;
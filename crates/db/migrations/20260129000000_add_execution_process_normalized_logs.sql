@@ -0,0 +1,8 @@
+-- Conversation patches of a finished coding agent execution, re-normalized from its raw logs
+-- on request. When present, they are served instead of normalizing the raw logs on every view.
+CREATE TABLE execution_process_normalized_logs (
+    execution_id    BLOB PRIMARY KEY,
+    logs            TEXT NOT NULL,
+    normalized_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
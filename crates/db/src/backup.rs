@@ -0,0 +1,549 @@
+//! Snapshots of the local SQLite database.
+//!
+//! Backups are taken with `VACUUM INTO`, which produces a consistent, compacted copy while
+//! the pool stays online. It runs inside a read transaction, so the snapshot includes anything
+//! still sitting in the WAL. The online backup API (`sqlite3_backup_*`) would do the same page by
+//! page, but sqlx does not expose it and reaching it means unsafe FFI on the raw handle. They live in `asset_dir()/backups` as
+//! `db-<kind>-<timestamp>.sqlite`. Restores are staged next to the live database and applied
+//! by [`DBService::new`](crate::DBService::new) before the pool is opened, because the file
+//! cannot be swapped underneath open connections.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    Connection, SqlitePool,
+    sqlite::{SqliteConnectOptions, SqliteConnection},
+};
+use strum_macros::{Display, EnumString};
+use thiserror::Error;
+use tokio::fs;
+use ts_rs::TS;
+use utils::assets::asset_dir;
+
+const DB_FILE_NAME: &str = "db.sqlite";
+const PENDING_RESTORE_FILE_NAME: &str = "db.sqlite.restore";
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Backup not found: {0}")]
+    NotFound(String),
+    #[error("Invalid backup name: {0}")]
+    InvalidName(String),
+    #[error("Backup failed integrity check: {0}")]
+    Corrupt(String),
+    #[error("Backup has no migration history; it is not a Vibe Kanban database")]
+    MissingSchema,
+    #[error("Backup contains migration {0}, which this version does not know; upgrade first")]
+    NewerSchema(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum BackupKind {
+    PreMigration,
+    Scheduled,
+    Manual,
+    PreRestore,
+}
+
+impl BackupKind {
+    /// Number of backups of this kind kept by rotation; `None` keeps all of them
+    pub fn retention(&self) -> Option<usize> {
+        match self {
+            BackupKind::PreMigration => Some(5),
+            BackupKind::Scheduled => Some(8),
+            BackupKind::PreRestore => Some(3),
+            BackupKind::Manual => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct BackupInfo {
+    pub name: String,
+    pub kind: BackupKind,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+    /// Latest migration applied in the backup
+    pub schema_version: Option<i64>,
+}
+
+pub fn database_path() -> PathBuf {
+    asset_dir().join(DB_FILE_NAME)
+}
+
+pub fn backups_dir() -> PathBuf {
+    asset_dir().join("backups")
+}
+
+fn pending_restore_path() -> PathBuf {
+    asset_dir().join(PENDING_RESTORE_FILE_NAME)
+}
+
+fn backup_name(kind: BackupKind, created_at: DateTime<Utc>) -> String {
+    format!("db-{kind}-{}.sqlite", created_at.format(TIMESTAMP_FORMAT))
+}
+
+fn parse_backup_name(name: &str) -> Option<(BackupKind, DateTime<Utc>)> {
+    let stem = name.strip_prefix("db-")?.strip_suffix(".sqlite")?;
+    let (kind, timestamp) = stem.rsplit_once('-')?;
+    let kind = BackupKind::from_str(kind).ok()?;
+    let created_at = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+        .ok()?
+        .and_utc();
+    Some((kind, created_at))
+}
+
+/// Resolve a backup name to its path, rejecting anything that is not a backup file name
+async fn backup_path(name: &str) -> Result<PathBuf, BackupError> {
+    if parse_backup_name(name).is_none() || name.contains(['/', '\\']) {
+        return Err(BackupError::InvalidName(name.to_string()));
+    }
+    let path = backups_dir().join(name);
+    if !fs::metadata(&path).await.is_ok_and(|m| m.is_file()) {
+        return Err(BackupError::NotFound(name.to_string()));
+    }
+    Ok(path)
+}
+
+async fn open_read_only(path: &Path) -> Result<SqliteConnection, sqlx::Error> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .read_only(true)
+        .create_if_missing(false);
+    SqliteConnection::connect_with(&options).await
+}
+
+/// `VACUUM INTO` from a database file that has no pool open on it. Closing the connection
+/// checkpoints the WAL back into the file.
+async fn snapshot_file(source: &Path, dest: &Path) -> Result<(), sqlx::Error> {
+    let options = SqliteConnectOptions::new()
+        .filename(source)
+        .create_if_missing(false);
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+    sqlx::query("VACUUM INTO ?")
+        .bind(dest.to_string_lossy().to_string())
+        .execute(&mut conn)
+        .await?;
+    conn.close().await
+}
+
+/// Versions of the migrations that were applied successfully
+async fn applied_migrations(conn: &mut SqliteConnection) -> Result<Vec<i64>, sqlx::Error> {
+    let has_table: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations')",
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    if !has_table {
+        return Ok(Vec::new());
+    }
+    sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success = 1 ORDER BY version")
+        .fetch_all(&mut *conn)
+        .await
+}
+
+async fn backup_info(path: &Path) -> Result<BackupInfo, BackupError> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let (kind, created_at) =
+        parse_backup_name(&name).ok_or_else(|| BackupError::InvalidName(name.clone()))?;
+    let size_bytes = fs::metadata(path).await?.len();
+    let mut conn = open_read_only(path).await?;
+    let schema_version = applied_migrations(&mut conn).await?.last().copied();
+    conn.close().await?;
+
+    Ok(BackupInfo {
+        name,
+        kind,
+        created_at,
+        size_bytes,
+        schema_version,
+    })
+}
+
+/// Snapshot the live database and rotate older backups of the same kind
+pub async fn create_backup(pool: &SqlitePool, kind: BackupKind) -> Result<BackupInfo, BackupError> {
+    let dir = backups_dir();
+    fs::create_dir_all(&dir).await?;
+    let path = dir.join(backup_name(kind, Utc::now()));
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().to_string())
+        .execute(pool)
+        .await?;
+
+    let info = backup_info(&path).await?;
+    tracing::info!("Created {} database backup {}", kind, info.name);
+    rotate_backups(&dir, kind).await?;
+    Ok(info)
+}
+
+/// All backups, newest first. Unreadable files are skipped.
+pub async fn list_backups() -> Result<Vec<BackupInfo>, BackupError> {
+    let dir = backups_dir();
+    if !fs::try_exists(&dir).await? {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    let mut entries = fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let is_backup = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| parse_backup_name(n).is_some());
+        if !is_backup {
+            continue;
+        }
+        match backup_info(&path).await {
+            Ok(info) => backups.push(info),
+            Err(e) => tracing::warn!("Skipping unreadable backup {}: {}", path.display(), e),
+        }
+    }
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(backups)
+}
+
+async fn rotate_backups(dir: &Path, kind: BackupKind) -> Result<(), BackupError> {
+    let Some(keep) = kind.retention() else {
+        return Ok(());
+    };
+
+    let mut backups: Vec<(DateTime<Utc>, PathBuf)> = Vec::new();
+    let mut entries = fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let parsed = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(parse_backup_name);
+        if let Some((backup_kind, created_at)) = parsed
+            && backup_kind == kind
+        {
+            backups.push((created_at, path));
+        }
+    }
+    backups.sort_by(|a, b| b.0.cmp(&a.0));
+
+    for (_, path) in backups.into_iter().skip(keep) {
+        if let Err(e) = fs::remove_file(&path).await {
+            tracing::warn!("Failed to remove old backup {}: {}", path.display(), e);
+        }
+    }
+    Ok(())
+}
+
+/// Check that a database file is intact and that every migration it contains is known to
+/// this build. Returns the latest applied migration version.
+pub async fn validate_backup(path: &Path) -> Result<i64, BackupError> {
+    let mut conn = open_read_only(path).await?;
+
+    let integrity: String = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_one(&mut conn)
+        .await?;
+    if integrity != "ok" {
+        return Err(BackupError::Corrupt(integrity));
+    }
+
+    let applied = applied_migrations(&mut conn).await?;
+    conn.close().await?;
+
+    let known: HashSet<i64> = sqlx::migrate!("./migrations")
+        .iter()
+        .map(|m| m.version)
+        .collect();
+    if let Some(unknown) = applied.iter().find(|v| !known.contains(v)) {
+        return Err(BackupError::NewerSchema(*unknown));
+    }
+    applied.last().copied().ok_or(BackupError::MissingSchema)
+}
+
+/// Validate a backup and stage it to replace the live database on the next start
+pub async fn stage_restore(name: &str) -> Result<BackupInfo, BackupError> {
+    let path = backup_path(name).await?;
+    validate_backup(&path).await?;
+
+    // Copy then rename so a crash never leaves a half-written staged restore behind
+    let staging = asset_dir().join(format!("{PENDING_RESTORE_FILE_NAME}.tmp"));
+    fs::copy(&path, &staging).await?;
+    fs::rename(&staging, pending_restore_path()).await?;
+
+    tracing::info!("Staged restore of database backup {}", name);
+    backup_info(&path).await
+}
+
+/// Replace the live database with a staged restore, if any. Must run before the pool is
+/// opened. The current database is kept as a `pre-restore` backup.
+pub(crate) async fn apply_pending_restore() -> Result<bool, BackupError> {
+    let pending = pending_restore_path();
+    if !fs::try_exists(&pending).await? {
+        return Ok(false);
+    }
+
+    if let Err(e) = validate_backup(&pending).await {
+        let rejected = asset_dir().join(format!("{PENDING_RESTORE_FILE_NAME}.rejected"));
+        fs::rename(&pending, &rejected).await?;
+        tracing::error!(
+            "Staged database restore is invalid ({}); kept the current database and moved it to {}",
+            e,
+            rejected.display()
+        );
+        return Ok(false);
+    }
+
+    let live = database_path();
+    if fs::try_exists(&live).await? {
+        // Committed transactions may still live only in the WAL, which is deleted below, so
+        // copying the main file alone could lose them
+        let dir = backups_dir();
+        fs::create_dir_all(&dir).await?;
+        let pre_restore = dir.join(backup_name(BackupKind::PreRestore, Utc::now()));
+        snapshot_file(&live, &pre_restore).await?;
+        rotate_backups(&dir, BackupKind::PreRestore).await?;
+    }
+
+    // A leftover journal would be replayed against the restored file
+    for suffix in ["-journal", "-wal", "-shm"] {
+        let sidecar = asset_dir().join(format!("{DB_FILE_NAME}{suffix}"));
+        if fs::try_exists(&sidecar).await? {
+            fs::remove_file(sidecar).await?;
+        }
+    }
+    fs::rename(&pending, &live).await?;
+    tracing::info!("Restored database from staged backup");
+    Ok(true)
+}
+
+/// Take a `pre-migration` backup when the embedded migrations include versions the database
+/// has not applied yet. Fresh databases are skipped.
+pub(crate) async fn backup_before_migrations(
+    pool: &SqlitePool,
+) -> Result<Option<BackupInfo>, BackupError> {
+    let applied: HashSet<i64> = {
+        let mut conn = pool.acquire().await?;
+        applied_migrations(&mut *conn).await?.into_iter().collect()
+    };
+    if applied.is_empty() {
+        return Ok(None);
+    }
+
+    let has_pending = sqlx::migrate!("./migrations")
+        .iter()
+        .any(|m| !m.migration_type.is_down_migration() && !applied.contains(&m.version));
+    if !has_pending {
+        return Ok(None);
+    }
+
+    create_backup(pool, BackupKind::PreMigration)
+        .await
+        .map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use sqlx::sqlite::SqliteJournalMode;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, 16, hour, 0, 0).unwrap() + chrono::Duration::milliseconds(42)
+    }
+
+    /// Create a database whose migration history lists `versions`; `None` leaves the
+    /// history table out entirely
+    async fn create_database(path: &Path, versions: Option<&[i64]>) {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Delete);
+        let mut conn = SqliteConnection::connect_with(&options).await.unwrap();
+        sqlx::query("CREATE TABLE tasks (id INTEGER PRIMARY KEY)")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        if let Some(versions) = versions {
+            sqlx::query(
+                "CREATE TABLE _sqlx_migrations (version BIGINT PRIMARY KEY, success BOOLEAN NOT NULL)",
+            )
+            .execute(&mut conn)
+            .await
+            .unwrap();
+            for version in versions {
+                sqlx::query("INSERT INTO _sqlx_migrations (version, success) VALUES (?, 1)")
+                    .bind(version)
+                    .execute(&mut conn)
+                    .await
+                    .unwrap();
+            }
+        }
+        conn.close().await.unwrap();
+    }
+
+    fn latest_known_migration() -> i64 {
+        sqlx::migrate!("./migrations")
+            .iter()
+            .map(|m| m.version)
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn backup_names_round_trip() {
+        for kind in [
+            BackupKind::PreMigration,
+            BackupKind::Scheduled,
+            BackupKind::Manual,
+            BackupKind::PreRestore,
+        ] {
+            let name = backup_name(kind, at(6));
+            assert_eq!(parse_backup_name(&name), Some((kind, at(6))), "{name}");
+        }
+        assert_eq!(
+            backup_name(BackupKind::PreMigration, at(6)),
+            "db-pre-migration-20260116T060000042Z.sqlite"
+        );
+    }
+
+    #[test]
+    fn rejects_names_that_are_not_backups() {
+        for name in [
+            "db.sqlite",
+            "db.sqlite.restore",
+            "db-scheduled.sqlite",
+            "db-nightly-20260116T060000000Z.sqlite",
+            "db-scheduled-2026-01-16.sqlite",
+            "db-scheduled-20260116T060000000Z.sqlite.tmp",
+            "backup-scheduled-20260116T060000000Z.sqlite",
+        ] {
+            assert_eq!(parse_backup_name(name), None, "{name}");
+        }
+    }
+
+    #[tokio::test]
+    async fn rotation_keeps_newest_backups_of_the_rotated_kind() {
+        let dir = TempDir::new().unwrap();
+        for hour in 0..10 {
+            fs::write(
+                dir.path()
+                    .join(backup_name(BackupKind::Scheduled, at(hour))),
+                b"",
+            )
+            .await
+            .unwrap();
+        }
+        for hour in 0..2 {
+            fs::write(
+                dir.path().join(backup_name(BackupKind::Manual, at(hour))),
+                b"",
+            )
+            .await
+            .unwrap();
+        }
+        fs::write(dir.path().join("notes.txt"), b"").await.unwrap();
+
+        rotate_backups(dir.path(), BackupKind::Scheduled)
+            .await
+            .unwrap();
+
+        let mut remaining = Vec::new();
+        let mut entries = fs::read_dir(dir.path()).await.unwrap();
+        while let Some(entry) = entries.next_entry().await.unwrap() {
+            remaining.push(entry.file_name().to_string_lossy().to_string());
+        }
+        let scheduled: Vec<_> = remaining
+            .iter()
+            .filter_map(|n| parse_backup_name(n))
+            .filter(|(kind, _)| *kind == BackupKind::Scheduled)
+            .map(|(_, created_at)| created_at)
+            .collect();
+        assert_eq!(scheduled.len(), 8);
+        assert!(scheduled.iter().all(|created_at| *created_at >= at(2)));
+        assert_eq!(remaining.len(), 8 + 2 + 1);
+    }
+
+    #[tokio::test]
+    async fn rotation_never_removes_manual_backups() {
+        let dir = TempDir::new().unwrap();
+        for hour in 0..12 {
+            fs::write(
+                dir.path().join(backup_name(BackupKind::Manual, at(hour))),
+                b"",
+            )
+            .await
+            .unwrap();
+        }
+
+        rotate_backups(dir.path(), BackupKind::Manual)
+            .await
+            .unwrap();
+
+        let mut count = 0;
+        let mut entries = fs::read_dir(dir.path()).await.unwrap();
+        while entries.next_entry().await.unwrap().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, 12);
+    }
+
+    #[tokio::test]
+    async fn validates_backup_with_known_migrations() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("db.sqlite");
+        let latest = latest_known_migration();
+        create_database(&path, Some(&[latest])).await;
+
+        assert_eq!(validate_backup(&path).await.unwrap(), latest);
+    }
+
+    #[tokio::test]
+    async fn rejects_backup_without_migration_history() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("db.sqlite");
+        create_database(&path, None).await;
+
+        assert!(matches!(
+            validate_backup(&path).await,
+            Err(BackupError::MissingSchema)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_backup_from_newer_version() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("db.sqlite");
+        let unknown = latest_known_migration() + 1;
+        create_database(&path, Some(&[latest_known_migration(), unknown])).await;
+
+        assert!(matches!(
+            validate_backup(&path).await,
+            Err(BackupError::NewerSchema(version)) if version == unknown
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_file_that_is_not_a_database() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("db.sqlite");
+        fs::write(&path, vec![0xab; 4096]).await.unwrap();
+
+        assert!(validate_backup(&path).await.is_err());
+    }
+}
@@ -1,13 +1,25 @@
-use std::{str::FromStr, sync::Arc};
+use std::{
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
 
 use sqlx::{
     Error, Pool, Sqlite, SqlitePool,
     migrate::MigrateError,
     sqlite::{SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePoolOptions},
 };
-use utils::assets::asset_dir;
 
+pub mod backup;
 pub mod models;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
+/// Set once a staged restore has been considered, so a second pool opened by the same
+/// process never swaps the file underneath the first one
+static RESTORE_CHECKED: AtomicBool = AtomicBool::new(false);
 
 async fn run_migrations(pool: &Pool<Sqlite>) -> Result<(), Error> {
     use std::collections::HashSet;
@@ -74,18 +86,54 @@ pub struct DBService {
 
 impl DBService {
     pub async fn new() -> Result<DBService, Error> {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.sqlite").to_string_lossy()
-        );
-        let options = SqliteConnectOptions::from_str(&database_url)?
-            .create_if_missing(true)
-            .journal_mode(SqliteJournalMode::Delete);
+        let options = Self::connect_options().await?;
         let pool = SqlitePool::connect_with(options).await?;
-        run_migrations(&pool).await?;
+        Self::migrate(&pool).await?;
         Ok(DBService { pool })
     }
 
+    /// Connection options for the live database. Any restore staged through
+    /// [`backup::stage_restore`] or `VK_RESTORE_BACKUP` is applied first, since the file can
+    /// only be swapped while no connections are open.
+    async fn connect_options() -> Result<SqliteConnectOptions, Error> {
+        if !RESTORE_CHECKED.swap(true, Ordering::SeqCst) {
+            if let Ok(name) = std::env::var("VK_RESTORE_BACKUP")
+                && !name.is_empty()
+                && let Err(e) = backup::stage_restore(&name).await
+            {
+                tracing::error!("Failed to stage restore of backup {}: {}", name, e);
+            }
+            if let Err(e) = backup::apply_pending_restore().await {
+                tracing::error!("Failed to apply staged database restore: {}", e);
+            }
+        }
+
+        let database_url = format!("sqlite://{}", backup::database_path().to_string_lossy());
+        Ok(SqliteConnectOptions::from_str(&database_url)?
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Delete))
+    }
+
+    async fn migrate(pool: &Pool<Sqlite>) -> Result<(), Error> {
+        let pre_migration = match backup::backup_before_migrations(pool).await {
+            Ok(info) => info,
+            Err(e) => {
+                tracing::warn!("Failed to back up database before migrating: {}", e);
+                None
+            }
+        };
+
+        run_migrations(pool).await.inspect_err(|e| {
+            if let Some(info) = &pre_migration {
+                tracing::error!(
+                    "Database migration failed ({}); the previous database was saved as backup {}",
+                    e,
+                    info.name
+                );
+            }
+        })
+    }
+
     pub async fn new_with_after_connect<F>(after_connect: F) -> Result<DBService, Error>
     where
         F: for<'a> Fn(
@@ -110,13 +158,7 @@ impl DBService {
             + Sync
             + 'static,
     {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.sqlite").to_string_lossy()
-        );
-        let options = SqliteConnectOptions::from_str(&database_url)?
-            .create_if_missing(true)
-            .journal_mode(SqliteJournalMode::Delete);
+        let options = Self::connect_options().await?;
 
         let pool = if let Some(hook) = after_connect {
             SqlitePoolOptions::new()
@@ -133,7 +175,7 @@ impl DBService {
             SqlitePool::connect_with(options).await?
         };
 
-        run_migrations(&pool).await?;
+        Self::migrate(&pool).await?;
         Ok(pool)
     }
 }
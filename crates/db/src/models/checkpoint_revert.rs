@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Worktree state of one repository just before a revert
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CheckpointRevertRepo {
+    pub repo_id: Uuid,
+    /// HEAD before the revert
+    pub head_commit: String,
+    /// Commit holding the worktree contents before the revert, uncommitted changes included
+    pub snapshot_commit: String,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct CheckpointRevert {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    /// The worktrees were reset to the state before this turn ran
+    pub execution_process_id: Uuid,
+    #[ts(type = "Array<CheckpointRevertRepo>")]
+    pub repos: Json<Vec<CheckpointRevertRepo>>,
+    #[ts(type = "Array<string>")]
+    pub dropped_process_ids: Json<Vec<Uuid>>,
+    pub undone_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl CheckpointRevert {
+    /// Ref keeping the snapshot commit of `repo_id` alive until the workspace is deleted
+    pub fn snapshot_ref(revert_id: Uuid, repo_id: Uuid) -> String {
+        format!("refs/vibe-kanban/reverts/{revert_id}/{repo_id}")
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        id: Uuid,
+        workspace_id: Uuid,
+        execution_process_id: Uuid,
+        repos: &[CheckpointRevertRepo],
+        dropped_process_ids: &[Uuid],
+    ) -> Result<Self, sqlx::Error> {
+        let repos = Json(repos);
+        let dropped_process_ids = Json(dropped_process_ids);
+        sqlx::query_as!(
+            CheckpointRevert,
+            r#"INSERT INTO checkpoint_reverts (id, workspace_id, execution_process_id, repos, dropped_process_ids)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         execution_process_id as "execution_process_id!: Uuid",
+                         repos as "repos!: Json<Vec<CheckpointRevertRepo>>",
+                         dropped_process_ids as "dropped_process_ids!: Json<Vec<Uuid>>",
+                         undone_at as "undone_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            execution_process_id,
+            repos,
+            dropped_process_ids
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            CheckpointRevert,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      repos as "repos!: Json<Vec<CheckpointRevertRepo>>",
+                      dropped_process_ids as "dropped_process_ids!: Json<Vec<Uuid>>",
+                      undone_at as "undone_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM checkpoint_reverts
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Reverts of a workspace, newest first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            CheckpointRevert,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      repos as "repos!: Json<Vec<CheckpointRevertRepo>>",
+                      dropped_process_ids as "dropped_process_ids!: Json<Vec<Uuid>>",
+                      undone_at as "undone_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM checkpoint_reverts
+               WHERE workspace_id = $1
+               ORDER BY created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_undone(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE checkpoint_reverts SET undone_at = datetime('now', 'subsec') WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM checkpoint_reverts WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -219,4 +219,21 @@ impl CodingAgentTurn {
 
         Ok(result.into_iter().collect())
     }
+
+    /// Agent session ids recorded for the coding agent turns of a workspace
+    pub async fn find_agent_session_ids_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT cat.agent_session_id as "agent_session_id!"
+               FROM coding_agent_turns cat
+               JOIN execution_processes ep ON cat.execution_process_id = ep.id
+               JOIN sessions s ON ep.session_id = s.id
+               WHERE s.workspace_id = $1 AND cat.agent_session_id IS NOT NULL"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
 }
@@ -47,6 +47,8 @@ pub enum ExecutionProcessStatus {
     Completed,
     Failed,
     Killed,
+    /// Stopped after exceeding its wall-clock or idle limit
+    TimedOut,
 }
 
 #[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
@@ -57,6 +59,8 @@ pub enum ExecutionProcessRunReason {
     CleanupScript,
     CodingAgent,
     DevServer,
+    /// Interactive shell opened by a user in the workspace
+    Terminal,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
@@ -304,7 +308,7 @@ impl ExecutionProcess {
         .await
     }
 
-    /// Check if there are running processes (excluding dev servers) for a workspace (across all sessions)
+    /// Check if there are running processes (excluding dev servers and terminals) for a workspace (across all sessions)
     pub async fn has_running_non_dev_server_processes_for_workspace(
         pool: &SqlitePool,
         workspace_id: Uuid,
@@ -315,7 +319,26 @@ impl ExecutionProcess {
                JOIN sessions s ON ep.session_id = s.id
                WHERE s.workspace_id = $1
                  AND ep.status = 'running'
-                 AND ep.run_reason != 'devserver'"#,
+                 AND ep.run_reason NOT IN ('devserver', 'terminal')"#,
+            workspace_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(count > 0)
+    }
+
+    /// Check if a workspace has any running execution processes, dev servers and terminals
+    /// included
+    pub async fn has_running_processes_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let count: i64 = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM execution_processes ep
+               JOIN sessions s ON ep.session_id = s.id
+               WHERE s.workspace_id = $1
+                 AND ep.status = 'running'"#,
             workspace_id
         )
         .fetch_one(pool)
@@ -356,6 +379,38 @@ impl ExecutionProcess {
         .await
     }
 
+    /// Find all dev server processes of a workspace, newest first
+    pub async fn find_dev_servers_by_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcess,
+            r#"
+        SELECT
+            ep.id as "id!: Uuid",
+            ep.session_id as "session_id!: Uuid",
+            ep.run_reason as "run_reason!: ExecutionProcessRunReason",
+            ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
+            ep.status as "status!: ExecutionProcessStatus",
+            ep.exit_code,
+            ep.dropped as "dropped!: bool",
+            ep.started_at as "started_at!: DateTime<Utc>",
+            ep.completed_at as "completed_at?: DateTime<Utc>",
+            ep.created_at as "created_at!: DateTime<Utc>",
+            ep.updated_at as "updated_at!: DateTime<Utc>"
+        FROM execution_processes ep
+        JOIN sessions s ON ep.session_id = s.id
+        WHERE s.workspace_id = ?
+          AND ep.run_reason = 'devserver'
+        ORDER BY ep.created_at DESC
+        "#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Find latest coding_agent_turn agent_session_id by session (simple scalar query)
     pub async fn find_latest_coding_agent_turn_session_id(
         pool: &SqlitePool,
@@ -557,6 +612,73 @@ impl ExecutionProcess {
         Ok(result.rows_affected() as i64)
     }
 
+    /// Ids of the processes a restore to `boundary_process_id` drops: every non-dropped
+    /// process of the workspace created at or after it, in any session. Dev servers and terminals
+    /// are kept.
+    pub async fn find_droppable_ids_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        boundary_process_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT ep.id as "id!: Uuid"
+               FROM execution_processes ep
+               JOIN sessions s ON s.id = ep.session_id
+              WHERE s.workspace_id = $1
+                AND ep.created_at >= (SELECT created_at FROM execution_processes WHERE id = $2)
+                AND ep.dropped = FALSE
+                AND ep.run_reason NOT IN ('devserver', 'terminal')"#,
+            workspace_id,
+            boundary_process_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn set_dropped(
+        pool: &SqlitePool,
+        id: Uuid,
+        dropped: bool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE execution_processes SET dropped = $2 WHERE id = $1",
+            id,
+            dropped
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Coding agent turns of a workspace across all of its sessions, including dropped ones
+    pub async fn find_coding_agent_turns_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcess,
+            r#"SELECT
+                      ep.id              as "id!: Uuid",
+                      ep.session_id      as "session_id!: Uuid",
+                      ep.run_reason      as "run_reason!: ExecutionProcessRunReason",
+                      ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
+                      ep.status          as "status!: ExecutionProcessStatus",
+                      ep.exit_code,
+                      ep.dropped as "dropped!: bool",
+                      ep.started_at      as "started_at!: DateTime<Utc>",
+                      ep.completed_at    as "completed_at?: DateTime<Utc>",
+                      ep.created_at      as "created_at!: DateTime<Utc>",
+                      ep.updated_at      as "updated_at!: DateTime<Utc>"
+               FROM execution_processes ep
+               JOIN sessions s ON s.id = ep.session_id
+               WHERE s.workspace_id = ? AND ep.run_reason = 'codingagent'
+               ORDER BY ep.created_at ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Find the previous process's after_head_commit before the given boundary process
     /// for a specific repository
     pub async fn find_prev_after_head_commit(
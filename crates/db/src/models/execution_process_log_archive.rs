@@ -0,0 +1,234 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use uuid::Uuid;
+
+/// zstd level used for log archives; JSONL compresses well even at low levels
+const COMPRESSION_LEVEL: i32 = 3;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq)]
+#[sqlx(type_name = "log_archive_contents", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum LogArchiveContents {
+    /// Stdout and stderr exactly as they were streamed
+    Raw,
+    /// Only the normalized conversation patches; raw output was dropped
+    Normalized,
+}
+
+/// Compacted logs of a finished execution process
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionProcessLogArchive {
+    pub execution_id: Uuid,
+    pub contents: LogArchiveContents,
+    /// zstd-compressed JSONL; `None` once the archive was moved to `file_path`
+    pub data: Option<Vec<u8>>,
+    pub file_path: Option<String>,
+    pub original_bytes: i64,
+    pub stored_bytes: i64,
+    pub compacted_at: DateTime<Utc>,
+}
+
+/// Finished process whose logs are eligible for compaction
+#[derive(Debug, Clone, FromRow)]
+pub struct LogCompactionCandidate {
+    pub execution_id: Uuid,
+    /// Bytes currently held in `execution_process_logs` and `execution_process_log_archives`
+    pub stored_bytes: i64,
+}
+
+impl ExecutionProcessLogArchive {
+    pub async fn find_by_execution_id(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessLogArchive,
+            r#"SELECT
+                execution_id as "execution_id!: Uuid",
+                contents as "contents!: LogArchiveContents",
+                data,
+                file_path,
+                original_bytes,
+                stored_bytes,
+                compacted_at as "compacted_at!: DateTime<Utc>"
+               FROM execution_process_log_archives
+               WHERE execution_id = $1"#,
+            execution_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Decompress the archived JSONL, reading it from disk if it was moved to a file
+    pub fn read_jsonl(&self) -> Result<String, sqlx::Error> {
+        let compressed = match (&self.data, &self.file_path) {
+            (Some(data), _) => data.clone(),
+            (None, Some(path)) => std::fs::read(path)?,
+            (None, None) => return Ok(String::new()),
+        };
+        let bytes = zstd::decode_all(compressed.as_slice())?;
+        String::from_utf8(bytes).map_err(|e| sqlx::Error::Decode(Box::new(e)))
+    }
+
+    /// Store `jsonl` as the compressed archive of `execution_id`, replacing any previous
+    /// archive and removing the uncompressed rows it was built from. Returns the number of
+    /// bytes the archive occupies.
+    pub async fn store(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        contents: LogArchiveContents,
+        jsonl: &str,
+    ) -> Result<i64, sqlx::Error> {
+        let data = zstd::encode_all(jsonl.as_bytes(), COMPRESSION_LEVEL)?;
+        let original_bytes = jsonl.len() as i64;
+        let stored_bytes = data.len() as i64;
+
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            r#"INSERT INTO execution_process_log_archives
+                   (execution_id, contents, data, file_path, original_bytes, stored_bytes, compacted_at)
+               VALUES ($1, $2, $3, NULL, $4, $5, datetime('now', 'subsec'))
+               ON CONFLICT(execution_id) DO UPDATE SET
+                   contents = excluded.contents,
+                   data = excluded.data,
+                   file_path = NULL,
+                   original_bytes = excluded.original_bytes,
+                   stored_bytes = excluded.stored_bytes,
+                   compacted_at = excluded.compacted_at"#,
+            execution_id,
+            contents,
+            data,
+            original_bytes,
+            stored_bytes
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM execution_process_logs WHERE execution_id = $1",
+            execution_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(stored_bytes)
+    }
+
+    /// Write the compressed archive to `path` and drop it from the database
+    pub async fn move_to_file(&self, pool: &SqlitePool, path: &Path) -> Result<(), sqlx::Error> {
+        let Some(data) = &self.data else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, data)?;
+
+        let file_path = path.to_string_lossy().to_string();
+        sqlx::query!(
+            r#"UPDATE execution_process_log_archives
+               SET data = NULL, file_path = $2
+               WHERE execution_id = $1"#,
+            self.execution_id,
+            file_path
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Finished processes that still have uncompressed log rows and completed before
+    /// `cutoff`, a SQLite datetime modifier such as `-3 days`
+    pub async fn find_uncompacted(
+        pool: &SqlitePool,
+        cutoff: &str,
+        limit: i64,
+    ) -> Result<Vec<LogCompactionCandidate>, sqlx::Error> {
+        sqlx::query_as!(
+            LogCompactionCandidate,
+            r#"SELECT
+                ep.id as "execution_id!: Uuid",
+                (SELECT COALESCE(SUM(l.byte_size), 0) FROM execution_process_logs l WHERE l.execution_id = ep.id)
+                    + COALESCE((SELECT a.stored_bytes FROM execution_process_log_archives a WHERE a.execution_id = ep.id), 0)
+                    as "stored_bytes!: i64"
+               FROM execution_processes ep
+               WHERE ep.status != 'running'
+                 AND ep.completed_at IS NOT NULL
+                 AND datetime(ep.completed_at) < datetime('now', $1)
+                 AND EXISTS (SELECT 1 FROM execution_process_logs l WHERE l.execution_id = ep.id)
+               ORDER BY ep.completed_at ASC
+               LIMIT $2"#,
+            cutoff,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Coding agent processes completed before `cutoff` whose archive still holds raw output
+    pub async fn find_raw_coding_agent_archives(
+        pool: &SqlitePool,
+        cutoff: &str,
+        limit: i64,
+    ) -> Result<Vec<LogCompactionCandidate>, sqlx::Error> {
+        sqlx::query_as!(
+            LogCompactionCandidate,
+            r#"SELECT
+                a.execution_id as "execution_id!: Uuid",
+                a.stored_bytes as "stored_bytes!: i64"
+               FROM execution_process_log_archives a
+               JOIN execution_processes ep ON ep.id = a.execution_id
+               WHERE a.contents = 'raw'
+                 AND a.data IS NOT NULL
+                 AND ep.run_reason = 'codingagent'
+                 AND datetime(ep.completed_at) < datetime('now', $1)
+               ORDER BY ep.completed_at ASC
+               LIMIT $2"#,
+            cutoff,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Archives still stored in the database although their workspace is archived
+    pub async fn find_in_archived_workspaces(
+        pool: &SqlitePool,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessLogArchive,
+            r#"SELECT
+                a.execution_id as "execution_id!: Uuid",
+                a.contents as "contents!: LogArchiveContents",
+                a.data,
+                a.file_path,
+                a.original_bytes,
+                a.stored_bytes,
+                a.compacted_at as "compacted_at!: DateTime<Utc>"
+               FROM execution_process_log_archives a
+               JOIN execution_processes ep ON ep.id = a.execution_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               WHERE a.data IS NOT NULL AND w.archived = 1
+               LIMIT $1"#,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Paths of all archives that were moved to files
+    pub async fn find_file_paths(pool: &SqlitePool) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT file_path as "file_path!: String"
+               FROM execution_process_log_archives
+               WHERE file_path IS NOT NULL"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -5,6 +5,8 @@ use ts_rs::TS;
 use utils::log_msg::LogMsg;
 use uuid::Uuid;
 
+use super::execution_process_log_archive::ExecutionProcessLogArchive;
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct ExecutionProcessLogs {
     pub execution_id: Uuid,
@@ -14,12 +16,24 @@ pub struct ExecutionProcessLogs {
 }
 
 impl ExecutionProcessLogs {
-    /// Find logs by execution process ID
+    /// Find logs by execution process ID, including logs that were compacted into an archive
     pub async fn find_by_execution_id(
         pool: &SqlitePool,
         execution_id: Uuid,
     ) -> Result<Vec<Self>, sqlx::Error> {
-        sqlx::query_as!(
+        let mut records = Vec::new();
+        if let Some(archive) =
+            ExecutionProcessLogArchive::find_by_execution_id(pool, execution_id).await?
+        {
+            records.push(ExecutionProcessLogs {
+                execution_id,
+                logs: archive.read_jsonl()?,
+                byte_size: archive.original_bytes,
+                inserted_at: archive.compacted_at,
+            });
+        }
+
+        let rows = sqlx::query_as!(
             ExecutionProcessLogs,
             r#"SELECT 
                 execution_id as "execution_id!: Uuid",
@@ -32,7 +46,9 @@ impl ExecutionProcessLogs {
             execution_id
         )
         .fetch_all(pool)
-        .await
+        .await?;
+        records.extend(rows);
+        Ok(records)
     }
 
     /// Parse JSONL logs back into Vec<LogMsg>
@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+/// Normalized conversation of a finished execution, stored when it was re-normalized
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionProcessNormalizedLogs {
+    pub execution_id: Uuid,
+    /// JSONL-encoded `LogMsg::JsonPatch` values
+    pub logs: String,
+    pub normalized_at: DateTime<Utc>,
+}
+
+impl ExecutionProcessNormalizedLogs {
+    pub async fn find_by_execution_id(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessNormalizedLogs,
+            r#"SELECT
+                execution_id as "execution_id!: Uuid",
+                logs,
+                normalized_at as "normalized_at!: DateTime<Utc>"
+               FROM execution_process_normalized_logs
+               WHERE execution_id = $1"#,
+            execution_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Store `logs` as the normalized conversation of `execution_id`, replacing any earlier one
+    pub async fn upsert(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        logs: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO execution_process_normalized_logs (execution_id, logs, normalized_at)
+               VALUES ($1, $2, datetime('now', 'subsec'))
+               ON CONFLICT(execution_id) DO UPDATE SET
+                   logs = excluded.logs,
+                   normalized_at = excluded.normalized_at"#,
+            execution_id,
+            logs
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub fn parse_logs(&self) -> Result<Vec<LogMsg>, serde_json::Error> {
+        self.logs
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect()
+    }
+}
@@ -1,17 +1,23 @@
+pub mod checkpoint_revert;
 pub mod coding_agent_turn;
 pub mod execution_process;
+pub mod execution_process_log_archive;
 pub mod execution_process_logs;
+pub mod execution_process_normalized_logs;
 pub mod execution_process_repo_state;
 pub mod image;
 pub mod merge;
 pub mod project;
 pub mod project_group;
 pub mod project_repo;
+pub mod queued_message;
 pub mod repo;
 pub mod scratch;
 pub mod session;
 pub mod tag;
 pub mod task;
 pub mod task_deduplication;
+pub mod task_plan;
 pub mod workspace;
 pub mod workspace_repo;
+pub mod workspace_template;
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -17,6 +17,34 @@ pub enum ProjectError {
     CreateFailed(String),
 }
 
+/// How pull requests for a project are merged by the git host
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PrMergeMethod {
+    #[default]
+    Squash,
+    Merge,
+    Rebase,
+}
+
+/// What happens when a coding agent of the project is caught in a loop, such as rerunning the
+/// same failing command or undoing its own edits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum LoopPolicy {
+    /// Don't watch for loops
+    #[default]
+    Off,
+    /// Stop the turn and continue the session with a prompt pointing out the loop
+    FollowUp,
+    /// Stop the turn and wait for the user to follow up
+    Pause,
+    /// Stop the turn and mark it as failed
+    Stop,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct Project {
     pub id: Uuid,
@@ -25,6 +53,15 @@ pub struct Project {
     pub remote_project_id: Option<Uuid>,
     pub group_id: Option<Uuid>,
     pub task_prefix: Option<String>,
+    pub pr_merge_method: PrMergeMethod,
+    pub loop_policy: LoopPolicy,
+    /// Memory cap for each process the project's workspaces run, in MiB
+    pub max_memory_mb: Option<u32>,
+    /// CPU cap for each process the project's workspaces run; 100 is one full core
+    pub max_cpu_percent: Option<u32>,
+    /// Named dev processes run in the workspace directory, one `<name>: <command>` per line
+    /// like a Procfile
+    pub dev_processes: Option<String>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -41,6 +78,11 @@ pub struct CreateProject {
 pub struct UpdateProject {
     pub name: Option<String>,
     pub group_id: Option<Uuid>,
+    pub pr_merge_method: Option<PrMergeMethod>,
+    pub loop_policy: Option<LoopPolicy>,
+    pub max_memory_mb: Option<u32>,
+    pub max_cpu_percent: Option<u32>,
+    pub dev_processes: Option<String>,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -76,6 +118,11 @@ impl Project {
                       remote_project_id as "remote_project_id: Uuid",
                       group_id as "group_id: Uuid",
                       task_prefix,
+                      pr_merge_method as "pr_merge_method!: PrMergeMethod",
+                      loop_policy as "loop_policy!: LoopPolicy",
+                      max_memory_mb as "max_memory_mb: u32",
+                      max_cpu_percent as "max_cpu_percent: u32",
+                      dev_processes,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -95,6 +142,11 @@ impl Project {
                    p.remote_project_id as "remote_project_id: Uuid",
                    p.group_id as "group_id: Uuid",
                    p.task_prefix,
+                   p.pr_merge_method as "pr_merge_method!: PrMergeMethod",
+                   p.loop_policy as "loop_policy!: LoopPolicy",
+                   p.max_memory_mb as "max_memory_mb: u32",
+                   p.max_cpu_percent as "max_cpu_percent: u32",
+                   p.dev_processes,
                    p.created_at as "created_at!: DateTime<Utc>", p.updated_at as "updated_at!: DateTime<Utc>"
             FROM projects p
             WHERE p.id IN (
@@ -120,6 +172,11 @@ impl Project {
                       remote_project_id as "remote_project_id: Uuid",
                       group_id as "group_id: Uuid",
                       task_prefix,
+                      pr_merge_method as "pr_merge_method!: PrMergeMethod",
+                      loop_policy as "loop_policy!: LoopPolicy",
+                      max_memory_mb as "max_memory_mb: u32",
+                      max_cpu_percent as "max_cpu_percent: u32",
+                      dev_processes,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -139,6 +196,11 @@ impl Project {
                       remote_project_id as "remote_project_id: Uuid",
                       group_id as "group_id: Uuid",
                       task_prefix,
+                      pr_merge_method as "pr_merge_method!: PrMergeMethod",
+                      loop_policy as "loop_policy!: LoopPolicy",
+                      max_memory_mb as "max_memory_mb: u32",
+                      max_cpu_percent as "max_cpu_percent: u32",
+                      dev_processes,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -161,6 +223,11 @@ impl Project {
                       remote_project_id as "remote_project_id: Uuid",
                       group_id as "group_id: Uuid",
                       task_prefix,
+                      pr_merge_method as "pr_merge_method!: PrMergeMethod",
+                      loop_policy as "loop_policy!: LoopPolicy",
+                      max_memory_mb as "max_memory_mb: u32",
+                      max_cpu_percent as "max_cpu_percent: u32",
+                      dev_processes,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -206,6 +273,11 @@ impl Project {
                           remote_project_id as "remote_project_id: Uuid",
                           group_id as "group_id: Uuid",
                           task_prefix,
+                          pr_merge_method as "pr_merge_method!: PrMergeMethod",
+                          loop_policy as "loop_policy!: LoopPolicy",
+                          max_memory_mb as "max_memory_mb: u32",
+                          max_cpu_percent as "max_cpu_percent: u32",
+                          dev_processes,
                           created_at as "created_at!: DateTime<Utc>",
                           updated_at as "updated_at!: DateTime<Utc>""#,
             project_id,
@@ -227,11 +299,16 @@ impl Project {
 
         let name = payload.name.clone().unwrap_or(existing.name);
         let group_id = payload.group_id;
+        let pr_merge_method = payload.pr_merge_method.unwrap_or(existing.pr_merge_method);
+        let loop_policy = payload.loop_policy.unwrap_or(existing.loop_policy);
+        let max_memory_mb = payload.max_memory_mb;
+        let max_cpu_percent = payload.max_cpu_percent;
+        let dev_processes = payload.dev_processes.clone();
 
         sqlx::query_as!(
             Project,
             r#"UPDATE projects
-               SET name = $2, group_id = $3, updated_at = datetime('now', 'subsec')
+               SET name = $2, group_id = $3, pr_merge_method = $4, loop_policy = $5, max_memory_mb = $6, max_cpu_percent = $7, dev_processes = $8, updated_at = datetime('now', 'subsec')
                WHERE id = $1
                RETURNING id as "id!: Uuid",
                          name,
@@ -239,11 +316,21 @@ impl Project {
                          remote_project_id as "remote_project_id: Uuid",
                          group_id as "group_id: Uuid",
                          task_prefix,
+                         pr_merge_method as "pr_merge_method!: PrMergeMethod",
+                         loop_policy as "loop_policy!: LoopPolicy",
+                         max_memory_mb as "max_memory_mb: u32",
+                         max_cpu_percent as "max_cpu_percent: u32",
+                         dev_processes,
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             name,
             group_id,
+            pr_merge_method,
+            loop_policy,
+            max_memory_mb,
+            max_cpu_percent,
+            dev_processes,
         )
         .fetch_one(pool)
         .await
@@ -265,6 +352,11 @@ impl Project {
                          remote_project_id as "remote_project_id: Uuid",
                          group_id as "group_id: Uuid",
                          task_prefix,
+                         pr_merge_method as "pr_merge_method!: PrMergeMethod",
+                         loop_policy as "loop_policy!: LoopPolicy",
+                         max_memory_mb as "max_memory_mb: u32",
+                         max_cpu_percent as "max_cpu_percent: u32",
+                         dev_processes,
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
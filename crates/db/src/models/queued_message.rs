@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::scratch::DraftFollowUpData;
+
+/// Represents a queued follow-up message for a session
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct QueuedMessage {
+    /// The session this message is queued for
+    pub session_id: Uuid,
+    /// The follow-up data (message + variant)
+    #[ts(type = "DraftFollowUpData")]
+    pub data: Json<DraftFollowUpData>,
+    /// Timestamp when the message was queued
+    pub queued_at: DateTime<Utc>,
+}
+
+impl QueuedMessage {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"SELECT session_id as "session_id!: Uuid",
+                      data as "data!: Json<DraftFollowUpData>",
+                      queued_at as "queued_at!: DateTime<Utc>"
+               FROM queued_messages"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Queue a message for a session, replacing any message already queued
+    pub async fn upsert(pool: &SqlitePool, message: &QueuedMessage) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO queued_messages (session_id, data, queued_at)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(session_id) DO UPDATE SET
+                   data = excluded.data,
+                   queued_at = excluded.queued_at"#,
+            message.session_id,
+            message.data,
+            message.queued_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, session_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM queued_messages WHERE session_id = $1",
+            session_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
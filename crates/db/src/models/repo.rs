@@ -26,6 +26,10 @@ pub struct Repo {
     pub copy_files: Option<String>,
     pub parallel_setup_script: bool,
     pub dev_server_script: Option<String>,
+    /// Worktrees kept ready with the setup script already run, for new workspaces to claim
+    pub warm_pool_size: u32,
+    /// Named dev processes, one `<name>: <command>` per line like a Procfile
+    pub dev_processes: Option<String>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -41,6 +45,8 @@ pub struct UpdateRepo {
     pub copy_files: Option<String>,
     pub parallel_setup_script: Option<bool>,
     pub dev_server_script: Option<String>,
+    pub warm_pool_size: Option<u32>,
+    pub dev_processes: Option<String>,
 }
 
 impl Repo {
@@ -58,6 +64,8 @@ impl Repo {
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
+                      warm_pool_size as "warm_pool_size!: u32",
+                      dev_processes,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -96,6 +104,8 @@ impl Repo {
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
+                      warm_pool_size as "warm_pool_size!: u32",
+                      dev_processes,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -151,6 +161,8 @@ impl Repo {
                          copy_files,
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
+                         warm_pool_size as "warm_pool_size!: u32",
+                         dev_processes,
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
@@ -185,6 +197,8 @@ impl Repo {
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
+                      warm_pool_size as "warm_pool_size!: u32",
+                      dev_processes,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -214,6 +228,8 @@ impl Repo {
             .parallel_setup_script
             .unwrap_or(existing.parallel_setup_script);
         let dev_server_script = payload.dev_server_script.clone();
+        let warm_pool_size = payload.warm_pool_size.unwrap_or(existing.warm_pool_size);
+        let dev_processes = payload.dev_processes.clone();
 
         sqlx::query_as!(
             Repo,
@@ -224,8 +240,10 @@ impl Repo {
                    copy_files = $4,
                    parallel_setup_script = $5,
                    dev_server_script = $6,
+                   warm_pool_size = $7,
+                   dev_processes = $8,
                    updated_at = datetime('now', 'subsec')
-               WHERE id = $7
+               WHERE id = $9
                RETURNING id as "id!: Uuid",
                          path,
                          name,
@@ -235,6 +253,8 @@ impl Repo {
                          copy_files,
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
+                         warm_pool_size as "warm_pool_size!: u32",
+                         dev_processes,
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             display_name,
@@ -243,6 +263,8 @@ impl Repo {
             copy_files,
             parallel_setup_script,
             dev_server_script,
+            warm_pool_size,
+            dev_processes,
             id
         )
         .fetch_one(pool)
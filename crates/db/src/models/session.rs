@@ -47,7 +47,8 @@ impl Session {
     }
 
     /// Find all sessions for a workspace, ordered by most recently used.
-    /// "Most recently used" is defined as the most recent non-dev server execution process.
+    /// "Most recently used" is defined as the most recent execution process other than dev
+    /// servers and terminals.
     /// Sessions with no executions fall back to created_at for ordering.
     pub async fn find_by_workspace_id(
         pool: &SqlitePool,
@@ -64,7 +65,7 @@ impl Session {
                LEFT JOIN (
                    SELECT ep.session_id, MAX(ep.created_at) as last_used
                    FROM execution_processes ep
-                   WHERE ep.run_reason != 'devserver' AND ep.dropped = FALSE
+                   WHERE ep.run_reason NOT IN ('devserver', 'terminal') AND ep.dropped = FALSE
                    GROUP BY ep.session_id
                ) latest_ep ON s.id = latest_ep.session_id
                WHERE s.workspace_id = $1
@@ -76,7 +77,8 @@ impl Session {
     }
 
     /// Find the most recently used session for a workspace.
-    /// "Most recently used" is defined as the most recent non-dev server execution process.
+    /// "Most recently used" is defined as the most recent execution process other than dev
+    /// servers and terminals.
     /// Sessions with no executions fall back to created_at for ordering.
     pub async fn find_latest_by_workspace_id(
         pool: &SqlitePool,
@@ -93,7 +95,7 @@ impl Session {
                LEFT JOIN (
                    SELECT ep.session_id, MAX(ep.created_at) as last_used
                    FROM execution_processes ep
-                   WHERE ep.run_reason != 'devserver' AND ep.dropped = FALSE
+                   WHERE ep.run_reason NOT IN ('devserver', 'terminal') AND ep.dropped = FALSE
                    GROUP BY ep.session_id
                ) latest_ep ON s.id = latest_ep.session_id
                WHERE s.workspace_id = $1
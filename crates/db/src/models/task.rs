@@ -231,7 +231,7 @@ impl Task {
      AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
      ORDER BY ep.created_at DESC
      LIMIT 1
-  ) IN ('failed','killed') THEN 1 ELSE 0 END
+  ) IN ('failed','killed','timedout') THEN 1 ELSE 0 END
                                  AS "last_attempt_failed!: i64",
 
   ( SELECT s.executor
@@ -0,0 +1,262 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use strum_macros::{Display, EnumString};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum TaskPlanError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Plan not found")]
+    NotFound,
+}
+
+#[derive(
+    Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS, EnumString, Display,
+)]
+#[sqlx(type_name = "task_plan_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum TaskPlanStatus {
+    /// The planning agent is still running
+    Drafting,
+    AwaitingApproval,
+    /// Execution was started with the plan
+    Approved,
+    Rejected,
+    /// The planning run ended without presenting a plan; the plan can only be rejected
+    Failed,
+}
+
+/// Plan drafted by a planning run of a workspace. The execution step of the workspace's
+/// action chain only starts once the plan has been approved.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskPlan {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub workspace_id: Uuid,
+    /// Session of the planning run
+    pub session_id: Uuid,
+    pub content: Option<String>,
+    pub status: TaskPlanStatus,
+    /// Session started when the plan was approved
+    pub execution_session_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TaskPlan {
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        workspace_id: Uuid,
+        session_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            TaskPlan,
+            r#"INSERT INTO task_plans (id, task_id, workspace_id, session_id)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         session_id as "session_id!: Uuid",
+                         content,
+                         status as "status!: TaskPlanStatus",
+                         execution_session_id as "execution_session_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            task_id,
+            workspace_id,
+            session_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskPlan,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      session_id as "session_id!: Uuid",
+                      content,
+                      status as "status!: TaskPlanStatus",
+                      execution_session_id as "execution_session_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_plans
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Plans of a task, newest first
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskPlan,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      session_id as "session_id!: Uuid",
+                      content,
+                      status as "status!: TaskPlanStatus",
+                      execution_session_id as "execution_session_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_plans
+               WHERE task_id = $1
+               ORDER BY created_at DESC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Plan of a planning session that has not been approved or rejected yet. Failed plans
+    /// count as open, so later turns of the session still wait for the plan to be dealt with.
+    pub async fn find_open_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskPlan,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      session_id as "session_id!: Uuid",
+                      content,
+                      status as "status!: TaskPlanStatus",
+                      execution_session_id as "execution_session_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_plans
+               WHERE session_id = $1 AND status IN ('drafting', 'awaiting_approval', 'failed')
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Store the plan text and wait for the user's approval. Only a plan still being drafted
+    /// is written; returns whether it was.
+    pub async fn set_draft(
+        pool: &SqlitePool,
+        id: Uuid,
+        content: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE task_plans
+               SET content = $2, status = 'awaiting_approval', updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = 'drafting'"#,
+            id,
+            content
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Mark the plan of a planning session as failed while it is still being drafted
+    pub async fn fail_drafting(pool: &SqlitePool, session_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE task_plans
+               SET status = 'failed', updated_at = datetime('now', 'subsec')
+               WHERE session_id = $1 AND status = 'drafting'"#,
+            session_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Move the plan from awaiting approval to approved. Returns `false` when it was no longer
+    /// awaiting approval, e.g. because a concurrent request approved or rejected it first.
+    pub async fn claim_approval(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE task_plans
+               SET status = 'approved', updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = 'awaiting_approval'"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Undo [`Self::claim_approval`] when the execution step could not be started
+    pub async fn release_approval(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_plans
+               SET status = 'awaiting_approval', updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = 'approved' AND execution_session_id IS NULL"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_content(
+        pool: &SqlitePool,
+        id: Uuid,
+        content: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_plans
+               SET content = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            content
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn approve(
+        pool: &SqlitePool,
+        id: Uuid,
+        content: &str,
+        execution_session_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_plans
+               SET content = $2, status = 'approved', execution_session_id = $3,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            content,
+            execution_session_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Reject a plan awaiting approval or one whose planning run failed. Returns whether it
+    /// was rejected.
+    pub async fn reject(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE task_plans
+               SET status = 'rejected', updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status IN ('awaiting_approval', 'failed')"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+}
@@ -47,6 +47,8 @@ pub struct Workspace {
     pub updated_at: DateTime<Utc>,
     pub archived: bool,
     pub pinned: bool,
+    /// Merge the workspace's PRs automatically once CI passes and reviews are approved
+    pub auto_merge: bool,
     pub name: Option<String>,
 }
 
@@ -126,6 +128,7 @@ impl Workspace {
                               updated_at AS "updated_at!: DateTime<Utc>",
                               archived AS "archived!: bool",
                               pinned AS "pinned!: bool",
+                              auto_merge AS "auto_merge!: bool",
                               name
                        FROM workspaces
                        WHERE task_id = $1
@@ -147,6 +150,7 @@ impl Workspace {
                               updated_at AS "updated_at!: DateTime<Utc>",
                               archived AS "archived!: bool",
                               pinned AS "pinned!: bool",
+                              auto_merge AS "auto_merge!: bool",
                               name
                        FROM workspaces
                        ORDER BY created_at DESC"#
@@ -178,6 +182,7 @@ impl Workspace {
                        w.updated_at        AS "updated_at!: DateTime<Utc>",
                        w.archived          AS "archived!: bool",
                        w.pinned            AS "pinned!: bool",
+                       w.auto_merge        AS "auto_merge!: bool",
                        w.name
                FROM    workspaces w
                JOIN    tasks t ON w.task_id = t.id
@@ -267,6 +272,7 @@ impl Workspace {
                        updated_at        AS "updated_at!: DateTime<Utc>",
                        archived          AS "archived!: bool",
                        pinned            AS "pinned!: bool",
+                       auto_merge        AS "auto_merge!: bool",
                        name
                FROM    workspaces
                WHERE   id = $1"#,
@@ -289,6 +295,7 @@ impl Workspace {
                        updated_at        AS "updated_at!: DateTime<Utc>",
                        archived          AS "archived!: bool",
                        pinned            AS "pinned!: bool",
+                       auto_merge        AS "auto_merge!: bool",
                        name
                FROM    workspaces
                WHERE   rowid = $1"#,
@@ -332,6 +339,7 @@ impl Workspace {
                 w.updated_at as "updated_at!: DateTime<Utc>",
                 w.archived as "archived!: bool",
                 w.pinned as "pinned!: bool",
+                w.auto_merge as "auto_merge!: bool",
                 w.name
             FROM workspaces w
             JOIN tasks t ON w.task_id = t.id
@@ -381,7 +389,7 @@ impl Workspace {
             Workspace,
             r#"INSERT INTO workspaces (id, task_id, container_ref, branch, agent_working_dir, setup_completed_at)
                VALUES ($1, $2, $3, $4, $5, $6)
-               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", container_ref, branch, agent_working_dir, setup_completed_at as "setup_completed_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", archived as "archived!: bool", pinned as "pinned!: bool", name"#,
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", container_ref, branch, agent_working_dir, setup_completed_at as "setup_completed_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", archived as "archived!: bool", pinned as "pinned!: bool", auto_merge as "auto_merge!: bool", name"#,
             id,
             task_id,
             Option::<String>::None,
@@ -448,6 +456,36 @@ impl Workspace {
         Ok(())
     }
 
+    /// Record the template a workspace was created from
+    pub async fn set_template(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        template_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE workspaces SET template_id = $1 WHERE id = $2",
+            template_id,
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record that the setup scripts of the workspace's repositories have already run
+    pub async fn set_setup_completed(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE workspaces SET setup_completed_at = datetime('now', 'subsec') WHERE id = $1",
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     /// Update workspace fields. Only non-None values will be updated.
     /// For `name`, pass `Some("")` to clear the name, `Some("foo")` to set it, or `None` to leave unchanged.
     pub async fn update(
@@ -455,6 +493,7 @@ impl Workspace {
         workspace_id: Uuid,
         archived: Option<bool>,
         pinned: Option<bool>,
+        auto_merge: Option<bool>,
         name: Option<&str>,
     ) -> Result<(), sqlx::Error> {
         // Convert empty string to None for name field (to store as NULL)
@@ -465,11 +504,13 @@ impl Workspace {
             r#"UPDATE workspaces SET
                 archived = COALESCE($1, archived),
                 pinned = COALESCE($2, pinned),
-                name = CASE WHEN $3 THEN $4 ELSE name END,
+                auto_merge = COALESCE($3, auto_merge),
+                name = CASE WHEN $4 THEN $5 ELSE name END,
                 updated_at = datetime('now', 'subsec')
-            WHERE id = $5"#,
+            WHERE id = $6"#,
             archived,
             pinned,
+            auto_merge,
             name_provided,
             name_value,
             workspace_id
@@ -532,6 +573,7 @@ impl Workspace {
                 w.updated_at AS "updated_at!: DateTime<Utc>",
                 w.archived AS "archived!: bool",
                 w.pinned AS "pinned!: bool",
+                w.auto_merge AS "auto_merge!: bool",
                 w.name,
 
                 CASE WHEN EXISTS (
@@ -552,7 +594,7 @@ impl Workspace {
                       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
                     ORDER BY ep.created_at DESC
                     LIMIT 1
-                ) IN ('failed','killed') THEN 1 ELSE 0 END AS "is_errored!: i64"
+                ) IN ('failed','killed','timedout') THEN 1 ELSE 0 END AS "is_errored!: i64"
 
             FROM workspaces w
             ORDER BY w.updated_at DESC"#
@@ -574,6 +616,7 @@ impl Workspace {
                     updated_at: rec.updated_at,
                     archived: rec.archived,
                     pinned: rec.pinned,
+                    auto_merge: rec.auto_merge,
                     name: rec.name,
                 },
                 is_running: rec.is_running != 0,
@@ -593,7 +636,7 @@ impl Workspace {
                 && let Some(prompt) = Self::get_first_user_message(pool, ws.workspace.id).await?
             {
                 let name = Self::truncate_to_name(&prompt, WORKSPACE_NAME_MAX_LEN);
-                Self::update(pool, ws.workspace.id, None, None, Some(&name)).await?;
+                Self::update(pool, ws.workspace.id, None, None, None, Some(&name)).await?;
                 ws.workspace.name = Some(name);
             }
         }
@@ -625,6 +668,7 @@ impl Workspace {
                 w.updated_at AS "updated_at!: DateTime<Utc>",
                 w.archived AS "archived!: bool",
                 w.pinned AS "pinned!: bool",
+                w.auto_merge AS "auto_merge!: bool",
                 w.name,
 
                 CASE WHEN EXISTS (
@@ -645,7 +689,7 @@ impl Workspace {
                       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
                     ORDER BY ep.created_at DESC
                     LIMIT 1
-                ) IN ('failed','killed') THEN 1 ELSE 0 END AS "is_errored!: i64"
+                ) IN ('failed','killed','timedout') THEN 1 ELSE 0 END AS "is_errored!: i64"
 
             FROM workspaces w
             WHERE w.id = $1"#,
@@ -670,6 +714,7 @@ impl Workspace {
                 updated_at: rec.updated_at,
                 archived: rec.archived,
                 pinned: rec.pinned,
+                auto_merge: rec.auto_merge,
                 name: rec.name,
             },
             is_running: rec.is_running != 0,
@@ -680,7 +725,7 @@ impl Workspace {
             && let Some(prompt) = Self::get_first_user_message(pool, ws.workspace.id).await?
         {
             let name = Self::truncate_to_name(&prompt, WORKSPACE_NAME_MAX_LEN);
-            Self::update(pool, ws.workspace.id, None, None, Some(&name)).await?;
+            Self::update(pool, ws.workspace.id, None, None, None, Some(&name)).await?;
             ws.workspace.name = Some(name);
         }
 
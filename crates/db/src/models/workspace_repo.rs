@@ -111,6 +111,8 @@ impl WorkspaceRepo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.warm_pool_size as "warm_pool_size!: u32",
+                      r.dev_processes,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -137,6 +139,8 @@ impl WorkspaceRepo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.warm_pool_size as "warm_pool_size!: u32",
+                      r.dev_processes,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>",
                       wr.target_branch
@@ -162,6 +166,8 @@ impl WorkspaceRepo {
                     copy_files: row.copy_files,
                     parallel_setup_script: row.parallel_setup_script,
                     dev_server_script: row.dev_server_script,
+                    warm_pool_size: row.warm_pool_size,
+                    dev_processes: row.dev_processes,
                     created_at: row.created_at,
                     updated_at: row.updated_at,
                 },
@@ -192,6 +198,27 @@ impl WorkspaceRepo {
         .await
     }
 
+    /// Target branch of the most recently created workspace of the repository in the project
+    pub async fn find_latest_target_branch(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT wr.target_branch
+               FROM workspace_repos wr
+               JOIN workspaces w ON w.id = wr.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE wr.repo_id = $1 AND t.project_id = $2
+               ORDER BY wr.created_at DESC
+               LIMIT 1"#,
+            repo_id,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
     pub async fn update_target_branch(
         pool: &SqlitePool,
         workspace_id: Uuid,
@@ -248,6 +275,8 @@ impl WorkspaceRepo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.warm_pool_size as "warm_pool_size!: u32",
+                      r.dev_processes,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
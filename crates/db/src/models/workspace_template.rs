@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum WorkspaceTemplateError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Workspace template not found")]
+    NotFound,
+    #[error("A workspace template with this name already exists in the project")]
+    DuplicateName,
+}
+
+/// Repository entry of a workspace template
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WorkspaceTemplateRepo {
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    /// Replaces the repository's `copy_files` for workspaces created from the template
+    #[serde(default)]
+    pub copy_files: Option<String>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkspaceTemplate {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    #[ts(type = "Array<WorkspaceTemplateRepo>")]
+    pub repos: Json<Vec<WorkspaceTemplateRepo>>,
+    #[ts(type = "ExecutorProfileId | null")]
+    pub executor_profile_id: Option<Json<ExecutorProfileId>>,
+    pub agent_working_dir: Option<String>,
+    /// Extra environment variables for every process run in the workspace
+    #[ts(type = "{ [key in string]?: string }")]
+    pub env: Json<HashMap<String, String>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Body for creating a template, and for replacing one on update
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct SaveWorkspaceTemplate {
+    pub name: String,
+    pub repos: Vec<WorkspaceTemplateRepo>,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    pub agent_working_dir: Option<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+fn map_unique_violation(err: sqlx::Error) -> WorkspaceTemplateError {
+    match &err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            WorkspaceTemplateError::DuplicateName
+        }
+        _ => WorkspaceTemplateError::Database(err),
+    }
+}
+
+impl WorkspaceTemplate {
+    /// Copy_files override for `repo_id`, if the template defines one
+    pub fn copy_files_for(&self, repo_id: Uuid) -> Option<&str> {
+        self.repos
+            .iter()
+            .find(|r| r.repo_id == repo_id)
+            .and_then(|r| r.copy_files.as_deref())
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceTemplate,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      repos as "repos!: Json<Vec<WorkspaceTemplateRepo>>",
+                      executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>",
+                      agent_working_dir,
+                      env as "env!: Json<HashMap<String, String>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM workspace_templates
+               WHERE project_id = $1
+               ORDER BY name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceTemplate,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      repos as "repos!: Json<Vec<WorkspaceTemplateRepo>>",
+                      executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>",
+                      agent_working_dir,
+                      env as "env!: Json<HashMap<String, String>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM workspace_templates
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Template the workspace was created from, if any
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceTemplate,
+            r#"SELECT t.id as "id!: Uuid",
+                      t.project_id as "project_id!: Uuid",
+                      t.name,
+                      t.repos as "repos!: Json<Vec<WorkspaceTemplateRepo>>",
+                      t.executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>",
+                      t.agent_working_dir,
+                      t.env as "env!: Json<HashMap<String, String>>",
+                      t.created_at as "created_at!: DateTime<Utc>",
+                      t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM workspace_templates t
+               JOIN workspaces w ON w.template_id = t.id
+               WHERE w.id = $1"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &SaveWorkspaceTemplate,
+    ) -> Result<Self, WorkspaceTemplateError> {
+        let id = Uuid::new_v4();
+        let repos = Json(&data.repos);
+        let executor_profile_id = data.executor_profile_id.as_ref().map(Json);
+        let env = Json(&data.env);
+        sqlx::query_as!(
+            WorkspaceTemplate,
+            r#"INSERT INTO workspace_templates (id, project_id, name, repos, executor_profile_id, agent_working_dir, env)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         name,
+                         repos as "repos!: Json<Vec<WorkspaceTemplateRepo>>",
+                         executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>",
+                         agent_working_dir,
+                         env as "env!: Json<HashMap<String, String>>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            data.name,
+            repos,
+            executor_profile_id,
+            data.agent_working_dir,
+            env
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(map_unique_violation)
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &SaveWorkspaceTemplate,
+    ) -> Result<Self, WorkspaceTemplateError> {
+        let repos = Json(&data.repos);
+        let executor_profile_id = data.executor_profile_id.as_ref().map(Json);
+        let env = Json(&data.env);
+        sqlx::query_as!(
+            WorkspaceTemplate,
+            r#"UPDATE workspace_templates
+               SET name = $2, repos = $3, executor_profile_id = $4, agent_working_dir = $5, env = $6,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         name,
+                         repos as "repos!: Json<Vec<WorkspaceTemplateRepo>>",
+                         executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>",
+                         agent_working_dir,
+                         env as "env!: Json<HashMap<String, String>>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.name,
+            repos,
+            executor_profile_id,
+            data.agent_working_dir,
+            env
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(map_unique_violation)?
+        .ok_or(WorkspaceTemplateError::NotFound)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM workspace_templates WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
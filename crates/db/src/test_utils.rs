@@ -0,0 +1,24 @@
+//! Helpers for tests of the crates built on the database, behind the `test-utils` feature
+
+use std::str::FromStr;
+
+use sqlx::{
+    SqlitePool,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+};
+
+/// A migrated in-memory database. Foreign keys are off, so tests only need to create the rows
+/// they are about.
+pub async fn test_pool() -> SqlitePool {
+    let options = SqliteConnectOptions::from_str("sqlite::memory:")
+        .unwrap()
+        .foreign_keys(false);
+    // A single connection, since every connection to `:memory:` opens its own database
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .unwrap();
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    pool
+}
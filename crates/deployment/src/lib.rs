@@ -21,6 +21,7 @@ use services::services::{
     auth::AuthContext,
     config::{Config, ConfigError},
     container::{ContainerError, ContainerService},
+    db_backup::DbBackupService,
     events::{EventError, EventService},
     file_search_cache::FileSearchCache,
     filesystem::{FilesystemError, FilesystemService},
@@ -30,6 +31,7 @@ use services::services::{
     project::ProjectService,
     queued_message::QueuedMessageService,
     repo::RepoService,
+    secrets::SecretsService,
     share::SharePublisher,
     worktree_manager::WorktreeError,
 };
@@ -108,6 +110,8 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn queued_message_service(&self) -> &QueuedMessageService;
 
+    fn secrets(&self) -> &SecretsService;
+
     fn auth_context(&self) -> &AuthContext;
 
     fn share_publisher(&self) -> Result<SharePublisher, RemoteClientNotConfigured>;
@@ -126,6 +130,21 @@ pub trait Deployment: Clone + Send + Sync + 'static {
     /// to provide the necessary services for conflict detection.
     async fn spawn_pr_monitor_service(&self) -> tokio::task::JoinHandle<()>;
 
+    /// Spawn the service applying the log retention policy
+    async fn spawn_log_compaction_service(&self) -> tokio::task::JoinHandle<()>;
+
+    /// Spawn the service applying the worktree garbage collection policy
+    async fn spawn_worktree_gc_service(&self) -> tokio::task::JoinHandle<()>;
+
+    /// Spawn the reverse proxy serving stable preview URLs of dev servers, unless it is
+    /// disabled or its port is taken
+    async fn spawn_preview_proxy(&self) -> Option<tokio::task::JoinHandle<()>>;
+
+    /// Spawn the service taking scheduled snapshots of the database
+    async fn spawn_db_backup_service(&self) -> tokio::task::JoinHandle<()> {
+        DbBackupService::spawn(self.db().clone(), self.config().clone()).await
+    }
+
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
         let analytics_enabled = self.config().read().await.analytics_enabled;
         // Track events unless user has explicitly opted out
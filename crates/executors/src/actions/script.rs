@@ -25,6 +25,8 @@ pub enum ScriptContext {
     CleanupScript,
     DevServer,
     ToolInstallScript,
+    /// Interactive shell; `script` is the shell program, run on a PTY rather than spawned here
+    Terminal,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -36,6 +38,10 @@ pub struct ScriptRequest {
     /// If None, uses the container_ref directory directly.
     #[serde(default)]
     pub working_dir: Option<String>,
+    /// Name of the dev process this dev server runs, for projects and repositories that
+    /// define several of them
+    #[serde(default)]
+    pub dev_process: Option<String>,
 }
 
 #[async_trait]
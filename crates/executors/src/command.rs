@@ -6,7 +6,7 @@ use thiserror::Error;
 use ts_rs::TS;
 use workspace_utils::shell::resolve_executable_path;
 
-use crate::executors::ExecutorError;
+use crate::{env::ExecutionEnv, executors::ExecutorError};
 
 #[derive(Debug, Error)]
 pub enum CommandBuildError {
@@ -36,6 +36,18 @@ impl CommandParts {
             .ok_or(ExecutorError::ExecutableNotFound { program })?;
         Ok((executable, args))
     }
+
+    /// Like [`Self::into_resolved`], wrapped in the sandbox of `env` if it has one
+    pub async fn into_resolved_in(
+        self,
+        env: &ExecutionEnv,
+    ) -> Result<(PathBuf, Vec<String>), ExecutorError> {
+        let (executable, args) = self.into_resolved().await?;
+        match &env.sandbox {
+            Some(sandbox) => sandbox.wrap(executable, args).await,
+            None => Ok((executable, args)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema, Default)]
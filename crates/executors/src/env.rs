@@ -1,19 +1,26 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fs::File, sync::Arc};
 
 use tokio::process::Command;
 
-use crate::command::CmdOverrides;
+use crate::{command::CmdOverrides, sandbox::SandboxPolicy};
 
 /// Environment variables to inject into executor processes
 #[derive(Debug, Clone, Default)]
 pub struct ExecutionEnv {
     pub vars: HashMap<String, String>,
+    /// Run the executor inside this sandbox
+    pub sandbox: Option<SandboxPolicy>,
+    /// `cgroup.procs` of the cgroup the process joins before it starts, so that everything
+    /// it runs is capped from the first instruction (Linux only)
+    pub cgroup_procs: Option<Arc<File>>,
 }
 
 impl ExecutionEnv {
     pub fn new() -> Self {
         Self {
             vars: HashMap::new(),
+            sandbox: None,
+            cgroup_procs: None,
         }
     }
 
@@ -43,11 +50,24 @@ impl ExecutionEnv {
         }
     }
 
-    /// Apply all environment variables to a Command
+    /// Apply all environment variables to a Command, and have it join the cgroup if one is set
     pub fn apply_to_command(&self, command: &mut Command) {
         for (key, value) in &self.vars {
             command.env(key, value);
         }
+        #[cfg(unix)]
+        if let Some(procs) = self.cgroup_procs.clone() {
+            // Writing 0 to cgroup.procs moves the writing process, here the forked child
+            // between fork and exec.
+            // SAFETY: the closure only makes one write(2) on a file descriptor opened before
+            // the fork; it allocates nothing and takes no locks.
+            unsafe {
+                command.pre_exec(move || {
+                    use std::io::Write;
+                    (&*procs).write_all(b"0")
+                });
+            }
+        }
     }
 
     pub fn contains_key(&self, key: &str) -> bool {
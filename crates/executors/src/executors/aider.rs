@@ -0,0 +1,315 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use command_group::AsyncCommandGroup;
+use futures::StreamExt;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::{fs, process::Command};
+use ts_rs::TS;
+use uuid::Uuid;
+use workspace_utils::{msg_store::MsgStore, shell::resolve_executable_path_blocking};
+
+use crate::{
+    command::{CmdOverrides, CommandBuilder, CommandParts, apply_overrides},
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+    },
+    logs::utils::EntryIndexProvider,
+    stdout_dup,
+};
+
+pub mod normalize_logs;
+
+use normalize_logs::normalize_logs;
+
+/// Aider executor configuration
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct Aider {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Model",
+        description = "Model to use (e.g., sonnet, gpt-4o, deepseek/deepseek-chat)"
+    )]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Edit Format",
+        description = "Edit format the model should use: diff, diff-fenced, udiff or whole"
+    )]
+    pub edit_format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Auto Commits",
+        description = "Let Aider commit each edit itself instead of leaving changes for Vibe Kanban to commit"
+    )]
+    pub auto_commits: Option<bool>,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+}
+
+impl Aider {
+    pub fn base_command() -> &'static str {
+        "aider"
+    }
+
+    fn build_command_builder(&self) -> CommandBuilder {
+        let mut builder = CommandBuilder::new(Self::base_command()).params([
+            "--yes-always",
+            "--no-pretty",
+            "--no-stream",
+            "--no-fancy-input",
+            "--no-check-update",
+            "--no-show-release-notes",
+            "--no-analytics",
+        ]);
+
+        if !self.auto_commits.unwrap_or(false) {
+            builder = builder.extend_params(["--no-auto-commits", "--no-dirty-commits"]);
+        }
+
+        if let Some(model) = &self.model {
+            builder = builder.extend_params(["--model", model]);
+        }
+
+        if let Some(edit_format) = &self.edit_format {
+            builder = builder.extend_params(["--edit-format", edit_format]);
+        }
+
+        apply_overrides(builder, &self.cmd)
+    }
+
+    /// Session state lives outside the worktree so Aider's history files never show up in diffs
+    fn sessions_dir() -> Result<PathBuf, ExecutorError> {
+        let mut vk_dir = dirs::home_dir()
+            .ok_or_else(|| {
+                ExecutorError::Io(std::io::Error::other("Could not determine home directory"))
+            })?
+            .join(".vibe-kanban");
+
+        if cfg!(debug_assertions) {
+            vk_dir = vk_dir.join("dev");
+        }
+
+        Ok(vk_dir.join("aider_sessions"))
+    }
+
+    /// Arguments pointing Aider at the history files of `session_id`, plus the message file
+    /// holding `prompt`, which is returned too so it can be removed once Aider exits
+    async fn session_args(
+        session_id: &str,
+        prompt: &str,
+        restore: bool,
+    ) -> Result<(Vec<String>, PathBuf), ExecutorError> {
+        let session_dir = Self::sessions_dir()?.join(session_id);
+        fs::create_dir_all(&session_dir)
+            .await
+            .map_err(ExecutorError::Io)?;
+
+        // Passing the prompt through a file avoids command line length limits on long task descriptions
+        let message_file = session_dir.join(format!("message-{}.md", Uuid::new_v4()));
+        fs::write(&message_file, prompt)
+            .await
+            .map_err(ExecutorError::Io)?;
+
+        let mut args = vec![
+            "--chat-history-file".to_string(),
+            session_dir
+                .join("chat.history.md")
+                .to_string_lossy()
+                .to_string(),
+            "--input-history-file".to_string(),
+            session_dir
+                .join("input.history")
+                .to_string_lossy()
+                .to_string(),
+            "--message-file".to_string(),
+            message_file.to_string_lossy().to_string(),
+        ];
+        if restore {
+            args.push("--restore-chat-history".to_string());
+        }
+        Ok((args, message_file))
+    }
+
+    /// Copy the history of `session_id` into a fresh session so follow-ups never mutate
+    /// the history an earlier turn may later be resumed from.
+    async fn fork_session(session_id: &str) -> Result<String, ExecutorError> {
+        let sessions_dir = Self::sessions_dir()?;
+        let source = sessions_dir.join(session_id);
+        if !fs::try_exists(source.join("chat.history.md"))
+            .await
+            .unwrap_or(false)
+        {
+            return Err(ExecutorError::FollowUpNotSupported(format!(
+                "No Aider chat history found for session {session_id}"
+            )));
+        }
+
+        let forked_id = Uuid::new_v4().to_string();
+        let target = sessions_dir.join(&forked_id);
+        fs::create_dir_all(&target)
+            .await
+            .map_err(ExecutorError::Io)?;
+        for file in ["chat.history.md", "input.history"] {
+            let from = source.join(file);
+            if fs::try_exists(&from).await.unwrap_or(false) {
+                fs::copy(&from, target.join(file))
+                    .await
+                    .map_err(ExecutorError::Io)?;
+            }
+        }
+        Ok(forked_id)
+    }
+
+    /// Delete the history of the given sessions, e.g. once their workspace is deleted. Every
+    /// follow-up forks a new session, so nothing else ever removes them. Ids of other
+    /// executors' sessions are skipped, as Aider's are always UUIDs.
+    pub async fn remove_sessions(session_ids: &[String]) {
+        let Ok(sessions_dir) = Self::sessions_dir() else {
+            return;
+        };
+        for session_id in session_ids {
+            if Uuid::parse_str(session_id).is_err() {
+                continue;
+            }
+            let session_dir = sessions_dir.join(session_id);
+            match fs::remove_dir_all(&session_dir).await {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => tracing::warn!(
+                    "Failed to remove Aider session {}: {}",
+                    session_dir.display(),
+                    err
+                ),
+            }
+        }
+    }
+
+    async fn spawn_aider(
+        &self,
+        command_parts: CommandParts,
+        session_id: &str,
+        message_file: PathBuf,
+        current_dir: &Path,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let (program_path, args) = command_parts.into_resolved_in(env).await?;
+
+        let mut command = Command::new(program_path);
+        command
+            .kill_on_drop(true)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .current_dir(current_dir)
+            .args(&args)
+            .env("PYTHONUNBUFFERED", "1")
+            .env("NO_COLOR", "1");
+
+        env.clone()
+            .with_profile(&self.cmd)
+            .apply_to_command(&mut command);
+
+        let mut child = match command.group_spawn() {
+            Ok(child) => child,
+            Err(err) => {
+                let _ = fs::remove_file(&message_file).await;
+                return Err(err.into());
+            }
+        };
+
+        // Aider has no notion of session ids, so report ours through stdout for the normalizer
+        let (mut stdout, appender) = stdout_dup::tee_stdout_with_appender(&mut child)?;
+        appender.append_line(format!("{}{}", normalize_logs::SESSION_PREFIX, session_id));
+
+        // Aider's stdout closes when it exits, after which the message file is no longer needed
+        tokio::spawn(async move {
+            while stdout.next().await.is_some() {}
+            if let Err(err) = fs::remove_file(&message_file).await {
+                tracing::debug!(
+                    "Failed to remove Aider message file {}: {}",
+                    message_file.display(),
+                    err
+                );
+            }
+        });
+
+        Ok(child.into())
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for Aider {
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let session_id = Uuid::new_v4().to_string();
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        let (session_args, message_file) =
+            Self::session_args(&session_id, &combined_prompt, false).await?;
+        let aider_command = self
+            .build_command_builder()
+            .extend_params(session_args)
+            .build_initial()?;
+
+        self.spawn_aider(aider_command, &session_id, message_file, current_dir, env)
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let forked_session_id = Self::fork_session(session_id).await?;
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        let (session_args, message_file) =
+            Self::session_args(&forked_session_id, &combined_prompt, true).await?;
+        let aider_command = self
+            .build_command_builder()
+            .build_follow_up(&session_args)?;
+
+        self.spawn_aider(
+            aider_command,
+            &forked_session_id,
+            message_file,
+            current_dir,
+            env,
+        )
+        .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        normalize_logs(
+            msg_store.clone(),
+            worktree_path,
+            EntryIndexProvider::start_from(&msg_store),
+        );
+    }
+
+    // Aider has no MCP support
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        if resolve_executable_path_blocking(Self::base_command()).is_some() {
+            AvailabilityInfo::InstallationFound
+        } else {
+            AvailabilityInfo::NotFound
+        }
+    }
+}
@@ -0,0 +1,736 @@
+//! Normalizer for Aider's plain-text output.
+//!
+//! Aider runs with `--no-pretty --no-stream`, so every reply is printed as markdown once the
+//! model has finished. SEARCH/REPLACE blocks (and `udiff` fences) become `FileEdit` tool uses,
+//! shell fences become `CommandRun` tool uses, and the "Applied edit to" / "Running" status
+//! lines that follow update those entries in place.
+
+use std::{path::Path, sync::Arc};
+
+use futures::{StreamExt, future::ready};
+use workspace_utils::{
+    diff::{create_unified_diff, normalize_unified_diff},
+    msg_store::MsgStore,
+    path::make_path_relative,
+};
+
+use crate::logs::{
+    ActionType, CommandRunResult, FileChange, NormalizedEntry, NormalizedEntryError,
+    NormalizedEntryType, ToolStatus,
+    stderr_processor::normalize_stderr_logs,
+    utils::{
+        EntryIndexProvider,
+        patch::{add_normalized_entry, replace_normalized_entry},
+    },
+};
+
+/// Prefix of the line the executor injects into stdout to report the session id
+pub const SESSION_PREFIX: &str = "[aider-session] ";
+
+const SEARCH_MARKER: &str = "<<<<<<< SEARCH";
+const DIVIDER_MARKER: &str = "=======";
+const REPLACE_MARKER: &str = ">>>>>>> REPLACE";
+
+/// Status lines Aider prints around a turn, grouped into a single system message
+const STATUS_PREFIXES: &[&str] = &[
+    "Aider v",
+    "Main model:",
+    "Weak model:",
+    "Editor model:",
+    "Model:",
+    "Git repo:",
+    "Repo-map:",
+    "Cur working dir:",
+    "Git working dir:",
+    "Use /help",
+    "Restored previous conversation history",
+];
+
+const SHELL_LANGUAGES: &[&str] = &["bash", "sh", "shell", "zsh", "console", "cmd", "powershell"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AiderEvent {
+    SessionId(String),
+    System(String),
+    Assistant(String),
+    Error(String),
+    /// A SEARCH/REPLACE block; an empty `search` creates the file
+    SearchReplace {
+        path: String,
+        search: String,
+        replace: String,
+    },
+    /// One file section of a `udiff` fence
+    UnifiedDiff {
+        path: String,
+        diff: String,
+    },
+    ShellCommand(String),
+    EditApplied(String),
+    EditFailed(String),
+    CommandStarted(String),
+    CommandOutput(String),
+    CommandFinished,
+}
+
+#[derive(Debug)]
+struct Fence {
+    language: String,
+    lines: Vec<String>,
+    in_search_replace: bool,
+}
+
+/// Line-oriented parser turning Aider's stdout into [`AiderEvent`]s
+#[derive(Debug, Default)]
+pub struct AiderOutputParser {
+    text: Vec<String>,
+    status: Vec<String>,
+    fence: Option<Fence>,
+    /// Bare SEARCH/REPLACE block emitted without a surrounding fence
+    bare_block: Option<Vec<String>>,
+    in_command_output: bool,
+    /// Shell commands suggested by the model that Aider has not run yet
+    pending_commands: usize,
+}
+
+impl AiderOutputParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn process_line(&mut self, line: &str) -> Vec<AiderEvent> {
+        let line = line.trim_end_matches('\r');
+        let mut events = Vec::new();
+
+        if let Some(session_id) = line.strip_prefix(SESSION_PREFIX) {
+            events.push(AiderEvent::SessionId(session_id.trim().to_string()));
+            return events;
+        }
+
+        if self.fence.is_some() {
+            self.process_fence_line(line, &mut events);
+            return events;
+        }
+
+        if let Some(block) = self.bare_block.as_mut() {
+            block.push(line.to_string());
+            if line.trim() == REPLACE_MARKER {
+                let block = self.bare_block.take().unwrap_or_default();
+                let default_path = self.take_filename_line();
+                self.flush(&mut events);
+                events.extend(parse_search_replace(&block, default_path));
+            }
+            return events;
+        }
+
+        let trimmed = line.trim();
+
+        // Confirmation prompts are answered automatically thanks to `--yes-always`
+        if trimmed.contains("(Y)es/(N)o") {
+            if self.in_command_output {
+                self.in_command_output = false;
+                events.push(AiderEvent::CommandFinished);
+            }
+            return events;
+        }
+
+        if self.pending_commands > 0
+            && let Some(command) = trimmed.strip_prefix("Running ")
+        {
+            self.pending_commands -= 1;
+            self.flush(&mut events);
+            if self.in_command_output {
+                events.push(AiderEvent::CommandFinished);
+            }
+            self.in_command_output = true;
+            events.push(AiderEvent::CommandStarted(command.to_string()));
+            return events;
+        }
+
+        if self.in_command_output {
+            events.push(AiderEvent::CommandOutput(line.to_string()));
+            return events;
+        }
+
+        if trimmed.starts_with("Tokens:") || trimmed.starts_with("Cost:") {
+            return events;
+        }
+        if trimmed.starts_with("Added ") && trimmed.contains("of output to the chat") {
+            return events;
+        }
+
+        if let Some(path) = trimmed.strip_prefix("Applied edit to ") {
+            self.flush(&mut events);
+            events.push(AiderEvent::EditApplied(path.trim().to_string()));
+            return events;
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("## SearchReplaceNoExactMatch:")
+            && let Some((_, path)) = rest.rsplit_once(" in ")
+        {
+            self.flush(&mut events);
+            events.push(AiderEvent::EditFailed(path.trim().to_string()));
+            return events;
+        }
+
+        if trimmed.starts_with("litellm.") || trimmed.starts_with("Error:") {
+            self.flush(&mut events);
+            events.push(AiderEvent::Error(trimmed.to_string()));
+            return events;
+        }
+
+        let is_status = STATUS_PREFIXES.iter().any(|p| trimmed.starts_with(p))
+            || (trimmed.starts_with("Added ") && trimmed.ends_with(" to the chat."))
+            || is_commit_line(trimmed);
+        if is_status {
+            self.flush_text(&mut events);
+            self.status.push(trimmed.to_string());
+            return events;
+        }
+        self.flush_status(&mut events);
+
+        if let Some(language) = trimmed.strip_prefix("```") {
+            self.fence = Some(Fence {
+                language: language.trim().to_lowercase(),
+                lines: Vec::new(),
+                in_search_replace: false,
+            });
+            return events;
+        }
+
+        if trimmed == SEARCH_MARKER {
+            self.bare_block = Some(vec![line.to_string()]);
+            return events;
+        }
+
+        if !trimmed.is_empty() || !self.text.is_empty() {
+            self.text.push(line.to_string());
+        }
+        events
+    }
+
+    /// Flush everything still buffered once the stream has ended
+    pub fn finish(&mut self) -> Vec<AiderEvent> {
+        let mut events = Vec::new();
+        if let Some(fence) = self.fence.take() {
+            self.text.push(format!("```{}", fence.language));
+            self.text.extend(fence.lines);
+        }
+        if let Some(block) = self.bare_block.take() {
+            self.text.extend(block);
+        }
+        self.flush(&mut events);
+        if self.in_command_output {
+            self.in_command_output = false;
+            events.push(AiderEvent::CommandFinished);
+        }
+        events
+    }
+
+    fn process_fence_line(&mut self, line: &str, events: &mut Vec<AiderEvent>) {
+        let Some(fence) = self.fence.as_mut() else {
+            return;
+        };
+        let trimmed = line.trim();
+
+        // Code inside a SEARCH/REPLACE block may contain fences of its own
+        if fence.in_search_replace {
+            if trimmed == REPLACE_MARKER {
+                fence.in_search_replace = false;
+            }
+            fence.lines.push(line.to_string());
+            return;
+        }
+        if trimmed == SEARCH_MARKER {
+            fence.in_search_replace = true;
+            fence.lines.push(line.to_string());
+            return;
+        }
+        if trimmed != "```" {
+            fence.lines.push(line.to_string());
+            return;
+        }
+
+        let fence = self.fence.take().unwrap();
+        if fence.lines.iter().any(|l| l.trim() == SEARCH_MARKER) {
+            let default_path = self.take_filename_line();
+            self.flush(events);
+            events.extend(parse_search_replace(&fence.lines, default_path));
+        } else if fence.language == "diff" && fence.lines.iter().any(|l| l.starts_with("+++ ")) {
+            self.flush(events);
+            events.extend(parse_unified_diff(&fence.lines));
+        } else if SHELL_LANGUAGES.contains(&fence.language.as_str()) {
+            self.flush(events);
+            for command in fence
+                .lines
+                .iter()
+                .map(|l| l.trim())
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            {
+                self.pending_commands += 1;
+                events.push(AiderEvent::ShellCommand(command.to_string()));
+            }
+        } else {
+            // Ordinary code sample, keep it as part of the assistant message
+            self.text.push(format!("```{}", fence.language));
+            self.text.extend(fence.lines);
+            self.text.push("```".to_string());
+        }
+    }
+
+    /// Aider prints the target path on its own line right before a SEARCH/REPLACE fence
+    fn take_filename_line(&mut self) -> Option<String> {
+        while self.text.last().is_some_and(|l| l.trim().is_empty()) {
+            self.text.pop();
+        }
+        let candidate = self
+            .text
+            .last()?
+            .trim()
+            .trim_matches(['`', '*', ':'])
+            .trim();
+        if candidate.is_empty() || candidate.contains(char::is_whitespace) {
+            return None;
+        }
+        let candidate = candidate.to_string();
+        self.text.pop();
+        Some(candidate)
+    }
+
+    fn flush(&mut self, events: &mut Vec<AiderEvent>) {
+        self.flush_status(events);
+        self.flush_text(events);
+    }
+
+    fn flush_text(&mut self, events: &mut Vec<AiderEvent>) {
+        let content = self.text.join("\n").trim().to_string();
+        self.text.clear();
+        if !content.is_empty() {
+            events.push(AiderEvent::Assistant(content));
+        }
+    }
+
+    fn flush_status(&mut self, events: &mut Vec<AiderEvent>) {
+        if !self.status.is_empty() {
+            events.push(AiderEvent::System(self.status.join("\n")));
+            self.status.clear();
+        }
+    }
+}
+
+/// `Commit 1a2b3c4 <message>`, printed when auto commits are enabled
+fn is_commit_line(line: &str) -> bool {
+    line.strip_prefix("Commit ")
+        .and_then(|rest| rest.split_whitespace().next())
+        .is_some_and(|hash| hash.len() >= 7 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+enum BlockSection {
+    Header,
+    Search,
+    Replace,
+}
+
+fn parse_search_replace(lines: &[String], default_path: Option<String>) -> Vec<AiderEvent> {
+    let mut events = Vec::new();
+    let mut path = default_path;
+    let mut section = BlockSection::Header;
+    let mut search = Vec::new();
+    let mut replace = Vec::new();
+
+    for line in lines {
+        match (&section, line.trim()) {
+            (BlockSection::Header, SEARCH_MARKER) => section = BlockSection::Search,
+            (BlockSection::Header, header) => {
+                // `diff-fenced` puts the path inside the fence
+                if !header.is_empty() {
+                    path = Some(header.trim_matches(['`', '*', ':']).to_string());
+                }
+            }
+            (BlockSection::Search, DIVIDER_MARKER) => section = BlockSection::Replace,
+            (BlockSection::Search, _) => search.push(line.as_str()),
+            (BlockSection::Replace, REPLACE_MARKER) => {
+                if let Some(path) = &path {
+                    events.push(AiderEvent::SearchReplace {
+                        path: path.clone(),
+                        search: join_block(&search),
+                        replace: join_block(&replace),
+                    });
+                }
+                search.clear();
+                replace.clear();
+                section = BlockSection::Header;
+            }
+            (BlockSection::Replace, _) => replace.push(line.as_str()),
+        }
+    }
+    events
+}
+
+fn join_block(lines: &[&str]) -> String {
+    if lines.is_empty() {
+        String::new()
+    } else {
+        let mut content = lines.join("\n");
+        content.push('\n');
+        content
+    }
+}
+
+fn parse_unified_diff(lines: &[String]) -> Vec<AiderEvent> {
+    let mut events = Vec::new();
+    let mut current: Option<(String, Vec<&str>)> = None;
+
+    for (i, line) in lines.iter().enumerate() {
+        let starts_file = line.starts_with("--- ")
+            && lines
+                .get(i + 1)
+                .is_some_and(|next| next.starts_with("+++ "));
+        if starts_file && let Some((path, diff)) = current.take() {
+            events.push(AiderEvent::UnifiedDiff {
+                path,
+                diff: diff.join("\n"),
+            });
+        }
+        if let Some(target) = line.strip_prefix("+++ ") {
+            let target = target.trim();
+            let path = target.strip_prefix("b/").unwrap_or(target).to_string();
+            current = Some((path, vec![]));
+            continue;
+        }
+        if starts_file {
+            continue;
+        }
+        if let Some((_, diff)) = current.as_mut() {
+            diff.push(line);
+        }
+    }
+    if let Some((path, diff)) = current {
+        events.push(AiderEvent::UnifiedDiff {
+            path,
+            diff: diff.join("\n"),
+        });
+    }
+    events
+}
+
+struct PendingEdit {
+    index: usize,
+    path: String,
+    entry: NormalizedEntry,
+}
+
+struct PendingCommand {
+    index: usize,
+    command: String,
+    output: String,
+    started: bool,
+    entry: NormalizedEntry,
+}
+
+/// Tracks tool entries whose status is reported by later status lines
+struct EntryState {
+    msg_store: Arc<MsgStore>,
+    index_provider: EntryIndexProvider,
+    worktree_path: String,
+    edits: Vec<PendingEdit>,
+    commands: Vec<PendingCommand>,
+    running: Option<usize>,
+}
+
+impl EntryState {
+    fn add(&self, entry_type: NormalizedEntryType, content: String) -> (usize, NormalizedEntry) {
+        let entry = NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content,
+            metadata: None,
+        };
+        let index = add_normalized_entry(&self.msg_store, &self.index_provider, entry.clone());
+        (index, entry)
+    }
+
+    fn add_edit(&mut self, path: &str, change: FileChange) {
+        let path = make_path_relative(path, &self.worktree_path);
+        let (index, entry) = self.add(
+            NormalizedEntryType::ToolUse {
+                tool_name: "edit".to_string(),
+                action_type: ActionType::FileEdit {
+                    path: path.clone(),
+                    changes: vec![change],
+                },
+                status: ToolStatus::Created,
+            },
+            path.clone(),
+        );
+        self.edits.push(PendingEdit { index, path, entry });
+    }
+
+    fn add_command(&mut self, command: String, started: bool) -> usize {
+        let (index, entry) = self.add(
+            NormalizedEntryType::ToolUse {
+                tool_name: "bash".to_string(),
+                action_type: ActionType::CommandRun {
+                    command: command.clone(),
+                    result: None,
+                },
+                status: ToolStatus::Created,
+            },
+            command.clone(),
+        );
+        self.commands.push(PendingCommand {
+            index,
+            command,
+            output: String::new(),
+            started,
+            entry,
+        });
+        self.commands.len() - 1
+    }
+
+    fn resolve_edits(&mut self, path: &str, status: ToolStatus) {
+        let path = make_path_relative(path, &self.worktree_path);
+        let (resolved, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.edits)
+            .into_iter()
+            .partition(|edit| edit.path == path);
+        self.edits = pending;
+        for edit in resolved {
+            if let Some(entry) = edit.entry.with_tool_status(status.clone()) {
+                replace_normalized_entry(&self.msg_store, edit.index, entry);
+            }
+        }
+    }
+
+    fn update_running_command(&mut self, status: ToolStatus) {
+        let Some(command) = self.running.and_then(|i| self.commands.get_mut(i)) else {
+            return;
+        };
+        command.entry.entry_type = NormalizedEntryType::ToolUse {
+            tool_name: "bash".to_string(),
+            action_type: ActionType::CommandRun {
+                command: command.command.clone(),
+                result: Some(CommandRunResult {
+                    exit_status: None,
+                    output: Some(command.output.clone()),
+                }),
+            },
+            status,
+        };
+        replace_normalized_entry(&self.msg_store, command.index, command.entry.clone());
+    }
+
+    fn apply(&mut self, event: AiderEvent) {
+        match event {
+            AiderEvent::SessionId(session_id) => self.msg_store.push_session_id(session_id),
+            AiderEvent::System(content) => {
+                self.add(NormalizedEntryType::SystemMessage, content);
+            }
+            AiderEvent::Assistant(content) => {
+                self.add(NormalizedEntryType::AssistantMessage, content);
+            }
+            AiderEvent::Error(content) => {
+                self.add(
+                    NormalizedEntryType::ErrorMessage {
+                        error_type: NormalizedEntryError::Other,
+                    },
+                    content,
+                );
+            }
+            AiderEvent::SearchReplace {
+                path,
+                search,
+                replace,
+            } => {
+                let change = if search.is_empty() {
+                    FileChange::Write { content: replace }
+                } else {
+                    let relative = make_path_relative(&path, &self.worktree_path);
+                    FileChange::Edit {
+                        unified_diff: create_unified_diff(&relative, &search, &replace),
+                        has_line_numbers: false,
+                    }
+                };
+                self.add_edit(&path, change);
+            }
+            AiderEvent::UnifiedDiff { path, diff } => {
+                let relative = make_path_relative(&path, &self.worktree_path);
+                let change = FileChange::Edit {
+                    unified_diff: normalize_unified_diff(&relative, &diff),
+                    has_line_numbers: false,
+                };
+                self.add_edit(&path, change);
+            }
+            AiderEvent::ShellCommand(command) => {
+                self.add_command(command, false);
+            }
+            AiderEvent::EditApplied(path) => self.resolve_edits(&path, ToolStatus::Success),
+            AiderEvent::EditFailed(path) => self.resolve_edits(&path, ToolStatus::Failed),
+            AiderEvent::CommandStarted(command) => {
+                let position = self
+                    .commands
+                    .iter()
+                    .position(|c| !c.started && c.command == command);
+                let position = match position {
+                    Some(position) => {
+                        self.commands[position].started = true;
+                        position
+                    }
+                    None => self.add_command(command, true),
+                };
+                self.running = Some(position);
+            }
+            AiderEvent::CommandOutput(line) => {
+                if let Some(command) = self.running.and_then(|i| self.commands.get_mut(i)) {
+                    command.output.push_str(&line);
+                    command.output.push('\n');
+                }
+                self.update_running_command(ToolStatus::Created);
+            }
+            AiderEvent::CommandFinished => {
+                self.update_running_command(ToolStatus::Success);
+                self.running = None;
+            }
+        }
+    }
+}
+
+pub fn normalize_logs(
+    msg_store: Arc<MsgStore>,
+    worktree_path: &Path,
+    entry_index_provider: EntryIndexProvider,
+) {
+    normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone());
+
+    let worktree_path = worktree_path.to_string_lossy().to_string();
+    tokio::spawn(async move {
+        let mut parser = AiderOutputParser::new();
+        let mut state = EntryState {
+            msg_store: msg_store.clone(),
+            index_provider: entry_index_provider,
+            worktree_path,
+            edits: Vec::new(),
+            commands: Vec::new(),
+            running: None,
+        };
+
+        let mut lines = msg_store
+            .stdout_lines_stream()
+            .filter_map(|res| ready(res.ok()));
+
+        while let Some(line) = lines.next().await {
+            let line = strip_ansi_escapes::strip_str(&line);
+            for event in parser.process_line(&line) {
+                state.apply(event);
+            }
+        }
+        for event in parser.finish() {
+            state.apply(event);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(output: &str) -> Vec<AiderEvent> {
+        let mut parser = AiderOutputParser::new();
+        let mut events: Vec<AiderEvent> = output
+            .lines()
+            .flat_map(|line| parser.process_line(line))
+            .collect();
+        events.extend(parser.finish());
+        events
+    }
+
+    #[test]
+    fn test_search_replace_block_becomes_edit() {
+        let events = parse(
+            "Aider v0.86.1\nMain model: sonnet with diff edit format\n\nI'll update the greeting.\n\nsrc/main.py\n```python\n<<<<<<< SEARCH\nprint(\"hi\")\n=======\nprint(\"hello\")\n>>>>>>> REPLACE\n```\n\nTokens: 2.1k sent, 120 received.\nApplied edit to src/main.py\n",
+        );
+        assert_eq!(
+            events,
+            vec![
+                AiderEvent::System(
+                    "Aider v0.86.1\nMain model: sonnet with diff edit format".to_string()
+                ),
+                AiderEvent::Assistant("I'll update the greeting.".to_string()),
+                AiderEvent::SearchReplace {
+                    path: "src/main.py".to_string(),
+                    search: "print(\"hi\")\n".to_string(),
+                    replace: "print(\"hello\")\n".to_string(),
+                },
+                AiderEvent::EditApplied("src/main.py".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_fenced_block_with_path_inside_fence() {
+        let events = parse(
+            "```\nnew_file.txt\n<<<<<<< SEARCH\n=======\ncontent\n```\nmore\n>>>>>>> REPLACE\n```\n",
+        );
+        assert_eq!(
+            events,
+            vec![AiderEvent::SearchReplace {
+                path: "new_file.txt".to_string(),
+                search: String::new(),
+                replace: "content\n```\nmore\n".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_shell_block_and_command_output() {
+        let events = parse(
+            "Run the tests:\n\n```bash\n# comment\ncargo test\n```\nRun shell command? (Y)es/(N)o/(D)on't ask again [Yes]: y\n\nRunning cargo test\nok\nAdd command output to the chat? (Y)es/(N)o/(D)on't ask again [Yes]: y\nAdded 1 line of output to the chat.\n",
+        );
+        assert_eq!(
+            events,
+            vec![
+                AiderEvent::Assistant("Run the tests:".to_string()),
+                AiderEvent::ShellCommand("cargo test".to_string()),
+                AiderEvent::CommandStarted("cargo test".to_string()),
+                AiderEvent::CommandOutput("ok".to_string()),
+                AiderEvent::CommandFinished,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_udiff_fence_splits_per_file() {
+        let events = parse(
+            "```diff\n--- a/a.txt\n+++ b/a.txt\n@@ ... @@\n-old\n+new\n--- b.txt\n+++ b.txt\n@@ ... @@\n+added\n```\n",
+        );
+        assert_eq!(
+            events,
+            vec![
+                AiderEvent::UnifiedDiff {
+                    path: "a.txt".to_string(),
+                    diff: "@@ ... @@\n-old\n+new".to_string(),
+                },
+                AiderEvent::UnifiedDiff {
+                    path: "b.txt".to_string(),
+                    diff: "@@ ... @@\n+added".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plain_code_block_stays_in_message() {
+        let events = parse("Example:\n```rust\nfn main() {}\n```\nThat's it.\n");
+        assert_eq!(
+            events,
+            vec![AiderEvent::Assistant(
+                "Example:\n```rust\nfn main() {}\n```\nThat's it.".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_session_line_is_reported() {
+        let events = parse(&format!("{SESSION_PREFIX}abc\n"));
+        assert_eq!(events, vec![AiderEvent::SessionId("abc".to_string())]);
+    }
+}
@@ -52,7 +52,7 @@ impl StandardCodingAgentExecutor for Amp {
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
         let command_parts = self.build_command_builder().build_initial()?;
-        let (executable_path, args) = command_parts.into_resolved().await?;
+        let (executable_path, args) = command_parts.into_resolved_in(env).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
@@ -94,7 +94,7 @@ impl StandardCodingAgentExecutor for Amp {
             "fork".to_string(),
             session_id.to_string(),
         ])?;
-        let (fork_program, fork_args) = fork_line.into_resolved().await?;
+        let (fork_program, fork_args) = fork_line.into_resolved_in(env).await?;
         let fork_output = Command::new(fork_program)
             .kill_on_drop(true)
             .stdout(Stdio::piped())
@@ -125,7 +125,7 @@ impl StandardCodingAgentExecutor for Amp {
             "continue".to_string(),
             new_thread_id.clone(),
         ])?;
-        let (continue_program, continue_args) = continue_line.into_resolved().await?;
+        let (continue_program, continue_args) = continue_line.into_resolved_in(env).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
@@ -0,0 +1,579 @@
+//! Executor for arbitrary CLIs, configured entirely from profiles.
+//!
+//! The command templates may contain `{prompt}` and `{session_id}` placeholders. When the
+//! initial command has no `{prompt}` placeholder the prompt is written to stdin instead.
+//! Output is either shown as plain text or, for JSON-lines CLIs, mapped to normalized
+//! entries through an ordered list of [`CustomOutputRule`]s.
+
+use std::{path::Path, process::Stdio, sync::Arc};
+
+use async_trait::async_trait;
+use command_group::AsyncCommandGroup;
+use futures::{StreamExt, future::ready};
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{io::AsyncWriteExt, process::Command};
+use ts_rs::TS;
+use workspace_utils::{
+    msg_store::MsgStore,
+    path::{expand_tilde, make_path_relative},
+    shell::resolve_executable_path_blocking,
+};
+
+use crate::{
+    command::{CmdOverrides, CommandBuilder, apply_overrides},
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+    },
+    logs::{
+        ActionType, NormalizedEntry, NormalizedEntryError, NormalizedEntryType, ToolStatus,
+        plain_text_processor::PlainTextLogProcessor,
+        stderr_processor::normalize_stderr_logs,
+        utils::{EntryIndexProvider, patch::add_normalized_entry},
+    },
+};
+
+pub const PROMPT_PLACEHOLDER: &str = "{prompt}";
+pub const SESSION_ID_PLACEHOLDER: &str = "{session_id}";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomOutputFormat {
+    #[default]
+    PlainText,
+    JsonLines,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomEntryType {
+    AssistantMessage,
+    UserMessage,
+    SystemMessage,
+    Thinking,
+    ErrorMessage,
+    ToolUse,
+    Ignore,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomToolAction {
+    CommandRun,
+    FileRead,
+    FileEdit,
+    Search,
+    WebFetch,
+    Tool,
+}
+
+/// Maps JSON output lines to a normalized entry. Paths are dotted, e.g. `message.content.0.text`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct CustomOutputRule {
+    #[schemars(
+        title = "Match Field",
+        description = "Path of the field this rule matches on, e.g. `type`"
+    )]
+    pub field: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Equals",
+        description = "Value the field must have. When empty, the rule matches whenever the field is present"
+    )]
+    pub equals: Option<String>,
+    #[schemars(
+        title = "Entry Type",
+        description = "Entry produced for matching lines; ignore drops them"
+    )]
+    pub entry_type: CustomEntryType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Content Path",
+        description = "Path of the text shown for the entry"
+    )]
+    pub content_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Tool Name Path",
+        description = "Path of the tool name (tool_use only)"
+    )]
+    pub tool_name_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Tool Action",
+        description = "How the tool call is rendered (tool_use only)"
+    )]
+    pub tool_action: Option<CustomToolAction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Tool Argument Path",
+        description = "Path of the command, file path, query, URL or arguments of the tool call"
+    )]
+    pub tool_argument_path: Option<String>,
+}
+
+/// Custom executor configuration
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct Custom {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[schemars(
+        title = "Command",
+        description = "Command for initial runs. `{prompt}` is replaced by the prompt, otherwise the prompt is written to stdin"
+    )]
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Follow-up Arguments",
+        description = "Arguments appended to the command for follow-ups, e.g. [\"--resume\", \"{session_id}\"]. Follow-ups are disabled when empty"
+    )]
+    pub follow_up_args: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Session ID Regex",
+        description = "Regex matched against stdout lines; the first capture group is the session id"
+    )]
+    pub session_id_regex: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Session ID JSON Path",
+        description = "Path of the session id in JSON output lines"
+    )]
+    pub session_id_json_path: Option<String>,
+    #[serde(default)]
+    #[schemars(
+        title = "Output Format",
+        description = "plain_text shows stdout as assistant messages; json_lines maps each JSON line through the output rules"
+    )]
+    pub output_format: CustomOutputFormat,
+    #[serde(default)]
+    #[schemars(
+        title = "Output Rules",
+        description = "Rules mapping JSON output lines to conversation entries; the first match wins"
+    )]
+    pub output_rules: Vec<CustomOutputRule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "MCP Config Path",
+        description = "Path of the CLI's MCP configuration file, if it supports MCP"
+    )]
+    pub mcp_config_path: Option<String>,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+}
+
+impl Custom {
+    async fn spawn_custom(
+        &self,
+        prompt: &str,
+        session_id: Option<&str>,
+        current_dir: &Path,
+        env: &ExecutionEnv,
+        additional_args: &[String],
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let command_parts = self
+            .build_command_builder()
+            .build_follow_up(additional_args)?;
+        let (program_path, args) = command_parts.into_resolved_in(env).await?;
+
+        let prompt_in_args = args.iter().any(|arg| arg.contains(PROMPT_PLACEHOLDER));
+        // Substitute the session id first so placeholders inside the prompt are left alone
+        let args: Vec<String> = args
+            .into_iter()
+            .map(|arg| {
+                arg.replace(SESSION_ID_PLACEHOLDER, session_id.unwrap_or_default())
+                    .replace(PROMPT_PLACEHOLDER, prompt)
+            })
+            .collect();
+
+        let mut command = Command::new(program_path);
+        command
+            .kill_on_drop(true)
+            .stdin(if prompt_in_args {
+                Stdio::null()
+            } else {
+                Stdio::piped()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .current_dir(current_dir)
+            .args(&args);
+
+        env.clone()
+            .with_profile(&self.cmd)
+            .apply_to_command(&mut command);
+
+        let mut child = command.group_spawn()?;
+
+        if !prompt_in_args && let Some(mut stdin) = child.inner().stdin.take() {
+            stdin.write_all(prompt.as_bytes()).await?;
+            stdin.shutdown().await?;
+        }
+
+        Ok(child.into())
+    }
+
+    fn build_command_builder(&self) -> CommandBuilder {
+        apply_overrides(CommandBuilder::new(self.command.clone()), &self.cmd)
+    }
+
+    fn extract_session_id(
+        &self,
+        session_regex: Option<&Regex>,
+        line: &str,
+        json: Option<&Value>,
+    ) -> Option<String> {
+        if let Some(path) = &self.session_id_json_path
+            && let Some(value) = json.and_then(|json| lookup(json, path))
+        {
+            return Some(value_to_string(value));
+        }
+        let captures = session_regex?.captures(line)?;
+        captures
+            .get(1)
+            .or_else(|| captures.get(0))
+            .map(|m| m.as_str().to_string())
+    }
+
+    /// Map a JSON output line through the first matching rule. `None` means the line is dropped.
+    pub fn map_json_line(&self, json: &Value, worktree_path: &str) -> Option<NormalizedEntry> {
+        let rule = self.output_rules.iter().find(|rule| {
+            lookup(json, &rule.field).is_some_and(|value| match &rule.equals {
+                Some(expected) => value_to_string(value) == *expected,
+                None => true,
+            })
+        })?;
+
+        let content = rule
+            .content_path
+            .as_deref()
+            .and_then(|path| lookup(json, path))
+            .map(value_to_string);
+
+        let entry_type = match rule.entry_type {
+            CustomEntryType::Ignore => return None,
+            CustomEntryType::AssistantMessage => NormalizedEntryType::AssistantMessage,
+            CustomEntryType::UserMessage => NormalizedEntryType::UserMessage,
+            CustomEntryType::SystemMessage => NormalizedEntryType::SystemMessage,
+            CustomEntryType::Thinking => NormalizedEntryType::Thinking,
+            CustomEntryType::ErrorMessage => NormalizedEntryType::ErrorMessage {
+                error_type: NormalizedEntryError::Other,
+            },
+            CustomEntryType::ToolUse => {
+                let argument = rule
+                    .tool_argument_path
+                    .as_deref()
+                    .and_then(|path| lookup(json, path));
+                let argument_str = argument.map(value_to_string).unwrap_or_default();
+                let tool_name = rule
+                    .tool_name_path
+                    .as_deref()
+                    .and_then(|path| lookup(json, path))
+                    .map(value_to_string)
+                    .unwrap_or_else(|| "tool".to_string());
+                let action_type = match rule.tool_action.unwrap_or(CustomToolAction::Tool) {
+                    CustomToolAction::CommandRun => ActionType::CommandRun {
+                        command: argument_str.clone(),
+                        result: None,
+                    },
+                    CustomToolAction::FileRead => ActionType::FileRead {
+                        path: make_path_relative(&argument_str, worktree_path),
+                    },
+                    CustomToolAction::FileEdit => ActionType::FileEdit {
+                        path: make_path_relative(&argument_str, worktree_path),
+                        changes: vec![],
+                    },
+                    CustomToolAction::Search => ActionType::Search {
+                        query: argument_str.clone(),
+                    },
+                    CustomToolAction::WebFetch => ActionType::WebFetch {
+                        url: argument_str.clone(),
+                    },
+                    CustomToolAction::Tool => ActionType::Tool {
+                        tool_name: tool_name.clone(),
+                        arguments: argument.cloned(),
+                        result: None,
+                    },
+                };
+                let content = content.unwrap_or_else(|| {
+                    if argument_str.is_empty() {
+                        tool_name.clone()
+                    } else {
+                        argument_str
+                    }
+                });
+                return Some(NormalizedEntry {
+                    timestamp: None,
+                    entry_type: NormalizedEntryType::ToolUse {
+                        tool_name,
+                        action_type,
+                        status: ToolStatus::Success,
+                    },
+                    content,
+                    metadata: Some(json.clone()),
+                });
+            }
+        };
+
+        Some(NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.unwrap_or_default(),
+            metadata: None,
+        })
+    }
+}
+
+/// Resolve a dotted path such as `message.content.0.text`
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .try_fold(value, |current, segment| match current {
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            Value::Object(map) => map.get(segment),
+            _ => None,
+        })
+        .filter(|value| !value.is_null())
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for Custom {
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        self.spawn_custom(&combined_prompt, None, current_dir, env, &[])
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let Some(follow_up_args) = self.follow_up_args.as_ref().filter(|args| !args.is_empty())
+        else {
+            return Err(ExecutorError::FollowUpNotSupported(
+                "No follow_up_args configured for this custom executor".to_string(),
+            ));
+        };
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        self.spawn_custom(
+            &combined_prompt,
+            Some(session_id),
+            current_dir,
+            env,
+            follow_up_args,
+        )
+        .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
+        normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone());
+
+        let config = self.clone();
+        let worktree_path = worktree_path.to_string_lossy().to_string();
+        tokio::spawn(async move {
+            let session_regex = config.session_id_regex.as_deref().and_then(|pattern| {
+                Regex::new(pattern)
+                    .inspect_err(|e| tracing::warn!("Invalid session_id_regex '{pattern}': {e}"))
+                    .ok()
+            });
+            let mut session_id_extracted = false;
+            let mut processor = (config.output_format == CustomOutputFormat::PlainText)
+                .then(|| Self::create_plain_text_processor(entry_index_provider.clone()));
+
+            let mut lines = msg_store
+                .stdout_lines_stream()
+                .filter_map(|res| ready(res.ok()));
+
+            while let Some(line) = lines.next().await {
+                let line = strip_ansi_escapes::strip_str(&line);
+                let json = match config.output_format {
+                    CustomOutputFormat::JsonLines => serde_json::from_str::<Value>(line.trim())
+                        .ok()
+                        .filter(Value::is_object),
+                    CustomOutputFormat::PlainText => None,
+                };
+
+                if !session_id_extracted
+                    && let Some(session_id) =
+                        config.extract_session_id(session_regex.as_ref(), &line, json.as_ref())
+                {
+                    msg_store.push_session_id(session_id);
+                    session_id_extracted = true;
+                }
+
+                if let Some(processor) = processor.as_mut() {
+                    for patch in processor.process(line + "\n") {
+                        msg_store.push_patch(patch);
+                    }
+                    continue;
+                }
+
+                let entry = match json {
+                    Some(json) => config.map_json_line(&json, &worktree_path),
+                    // Non-JSON output such as warnings is still worth showing
+                    None if !line.trim().is_empty() => Some(NormalizedEntry {
+                        timestamp: None,
+                        entry_type: NormalizedEntryType::SystemMessage,
+                        content: line.trim().to_string(),
+                        metadata: None,
+                    }),
+                    None => None,
+                };
+                if let Some(entry) = entry {
+                    add_normalized_entry(&msg_store, &entry_index_provider, entry);
+                }
+            }
+        });
+    }
+
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        self.mcp_config_path.as_deref().map(expand_tilde)
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        let command = self
+            .cmd
+            .base_command_override
+            .as_deref()
+            .unwrap_or(&self.command);
+        let program = command.split_whitespace().next().unwrap_or_default();
+        if !program.is_empty() && resolve_executable_path_blocking(program).is_some() {
+            AvailabilityInfo::InstallationFound
+        } else {
+            AvailabilityInfo::NotFound
+        }
+    }
+}
+
+impl Custom {
+    fn create_plain_text_processor(index_provider: EntryIndexProvider) -> PlainTextLogProcessor {
+        PlainTextLogProcessor::builder()
+            .normalized_entry_producer(Box::new(|content: String| NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::AssistantMessage,
+                content,
+                metadata: None,
+            }))
+            .index_provider(index_provider)
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn executor(rules: Value) -> Custom {
+        serde_json::from_value(json!({
+            "command": "my-agent --json",
+            "output_format": "json_lines",
+            "session_id_json_path": "session.id",
+            "output_rules": rules,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_lookup_dotted_paths() {
+        let value = json!({"message": {"content": [{"text": "hi"}]}, "n": null});
+        assert_eq!(lookup(&value, "message.content.0.text"), Some(&json!("hi")));
+        assert_eq!(lookup(&value, "message.content.1.text"), None);
+        assert_eq!(lookup(&value, "n"), None);
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let custom = executor(json!([
+            {"field": "type", "equals": "debug", "entry_type": "ignore"},
+            {"field": "type", "equals": "text", "entry_type": "assistant_message", "content_path": "text"},
+            {"field": "text", "entry_type": "system_message", "content_path": "text"}
+        ]));
+
+        let entry = custom
+            .map_json_line(&json!({"type": "text", "text": "Hello"}), "/repo")
+            .unwrap();
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::AssistantMessage
+        ));
+        assert_eq!(entry.content, "Hello");
+
+        assert!(
+            custom
+                .map_json_line(&json!({"type": "debug", "text": "x"}), "/repo")
+                .is_none()
+        );
+        assert!(
+            custom
+                .map_json_line(&json!({"type": "other"}), "/repo")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_tool_use_rule_maps_action() {
+        let custom = executor(json!([
+            {
+                "field": "type",
+                "equals": "tool",
+                "entry_type": "tool_use",
+                "tool_name_path": "name",
+                "tool_action": "file_read",
+                "tool_argument_path": "input.path"
+            }
+        ]));
+        let entry = custom
+            .map_json_line(
+                &json!({"type": "tool", "name": "read", "input": {"path": "/repo/src/lib.rs"}}),
+                "/repo",
+            )
+            .unwrap();
+        match entry.entry_type {
+            NormalizedEntryType::ToolUse {
+                tool_name,
+                action_type: ActionType::FileRead { path },
+                ..
+            } => {
+                assert_eq!(tool_name, "read");
+                assert_eq!(path, "src/lib.rs");
+            }
+            other => panic!("unexpected entry type: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_session_id_extraction() {
+        let custom = executor(json!([]));
+        assert_eq!(
+            custom.extract_session_id(None, "", Some(&json!({"session": {"id": "abc"}}))),
+            Some("abc".to_string())
+        );
+
+        let regex = Regex::new(r"session: (\S+)").unwrap();
+        assert_eq!(
+            custom.extract_session_id(Some(&regex), "started session: 42", None),
+            Some("42".to_string())
+        );
+    }
+}
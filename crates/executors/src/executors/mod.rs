@@ -20,18 +20,21 @@ use crate::{
     command::CommandBuildError,
     env::ExecutionEnv,
     executors::{
-        amp::Amp, claude::ClaudeCode, codex::Codex, copilot::Copilot, cursor::CursorAgent,
-        droid::Droid, gemini::Gemini, opencode::Opencode, qwen::QwenCode,
+        aider::Aider, amp::Amp, claude::ClaudeCode, codex::Codex, copilot::Copilot,
+        cursor::CursorAgent, custom::Custom, droid::Droid, gemini::Gemini, opencode::Opencode,
+        qwen::QwenCode,
     },
     mcp_config::McpConfig,
 };
 
 pub mod acp;
+pub mod aider;
 pub mod amp;
 pub mod claude;
 pub mod codex;
 pub mod copilot;
 pub mod cursor;
+pub mod custom;
 pub mod droid;
 pub mod gemini;
 pub mod opencode;
@@ -44,6 +47,9 @@ pub mod qwen;
 #[ts(use_ts_enum)]
 pub enum BaseAgentCapability {
     SessionFork,
+    /// Agent keeps its session history while a turn runs, so a session cut off mid-turn can be
+    /// continued in a follow-up
+    SessionResume,
     /// Agent requires a setup script before it can run (e.g., login, installation)
     SetupHelper,
 }
@@ -74,6 +80,8 @@ pub enum ExecutorError {
     SetupHelperNotSupported,
     #[error("Auth required: {0}")]
     AuthRequired(String),
+    #[error("Sandbox unavailable: {0}")]
+    SandboxUnavailable(String),
 }
 
 #[enum_dispatch]
@@ -104,6 +112,8 @@ pub enum CodingAgent {
     QwenCode,
     Copilot,
     Droid,
+    Aider,
+    Custom,
     #[cfg(feature = "qa-mode")]
     QaMock(QaMockExecutor),
 }
@@ -166,13 +176,18 @@ impl CodingAgent {
             | Self::Gemini(_)
             | Self::QwenCode(_)
             | Self::Droid(_)
-            | Self::Opencode(_) => vec![BaseAgentCapability::SessionFork],
+            | Self::Opencode(_)
+            | Self::Aider(_) => vec![
+                BaseAgentCapability::SessionFork,
+                BaseAgentCapability::SessionResume,
+            ],
             Self::Codex(_) => vec![
                 BaseAgentCapability::SessionFork,
+                BaseAgentCapability::SessionResume,
                 BaseAgentCapability::SetupHelper,
             ],
             Self::CursorAgent(_) => vec![BaseAgentCapability::SetupHelper],
-            Self::Copilot(_) => vec![],
+            Self::Copilot(_) | Self::Custom(_) => vec![],
             #[cfg(feature = "qa-mode")]
             Self::QaMock(_) => vec![], // QA mock doesn't need special capabilities
         }
@@ -65,7 +65,7 @@ impl Opencode {
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
         let command_parts = self.build_command_builder().build_initial()?;
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = command_parts.into_resolved_in(env).await?;
 
         let mut command = Command::new(program_path);
         command
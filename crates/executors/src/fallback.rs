@@ -0,0 +1,225 @@
+//! Failure signatures that make a coding agent turn worth retrying with another executor.
+//!
+//! The chain of executors to try is configured per profile in `profiles.json`, see
+//! [`crate::profile::ExecutorConfigs::fallback_chain`].
+
+use std::{fmt::Write as _, sync::LazyLock};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+use workspace_utils::text::truncate_to_char_boundary;
+
+use crate::{
+    logs::{NormalizedEntry, NormalizedEntryError, NormalizedEntryType},
+    profile::ExecutorProfileId,
+};
+
+/// Longest message of the failed agent quoted in a fallback prompt
+const MAX_PROGRESS_BYTES: usize = 2000;
+
+/// Why a coding agent turn failed, when another executor could plausibly succeed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AgentFailure {
+    /// Usage limit, quota or rate limit of the provider
+    RateLimited,
+    /// The executor is not logged in or its credentials were rejected
+    AuthRequired,
+    /// The executor's CLI is not installed
+    ExecutableNotFound,
+}
+
+impl AgentFailure {
+    pub fn description(&self) -> &'static str {
+        match self {
+            AgentFailure::RateLimited => "hit a usage or rate limit",
+            AgentFailure::AuthRequired => "is not authenticated",
+            AgentFailure::ExecutableNotFound => "is not installed",
+        }
+    }
+}
+
+static RATE_LIMIT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)(usage limit|rate[ _-]?limit|too many requests|\b429\b|quota (exceeded|exhausted)|resource[ _]exhausted|overloaded)",
+    )
+    .unwrap()
+});
+
+static AUTH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)(not logged in|please (log|sign) ?in|login required|authentication (failed|required|error)|unauthori[sz]ed|\b401\b|invalid (api[ _-]?key|credentials|token)|api key (is )?(missing|not set))",
+    )
+    .unwrap()
+});
+
+static NOT_INSTALLED_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(executable .* not (installed|found)|command not found|not installed)")
+        .unwrap()
+});
+
+/// Classify a single normalized entry. Only error messages are considered; agents often
+/// discuss rate limits or auth in their regular output.
+pub fn classify_entry(entry: &NormalizedEntry) -> Option<AgentFailure> {
+    let NormalizedEntryType::ErrorMessage { error_type } = &entry.entry_type else {
+        return None;
+    };
+    let content = entry.content.as_str();
+    match error_type {
+        NormalizedEntryError::SetupRequired if NOT_INSTALLED_RE.is_match(content) => {
+            Some(AgentFailure::ExecutableNotFound)
+        }
+        NormalizedEntryError::SetupRequired => Some(AgentFailure::AuthRequired),
+        NormalizedEntryError::Other if RATE_LIMIT_RE.is_match(content) => {
+            Some(AgentFailure::RateLimited)
+        }
+        NormalizedEntryError::Other if AUTH_RE.is_match(content) => {
+            Some(AgentFailure::AuthRequired)
+        }
+        NormalizedEntryError::Other => None,
+    }
+}
+
+/// The failure signature of a turn, taken from its last classifiable error message
+pub fn detect_failure(entries: &[NormalizedEntry]) -> Option<AgentFailure> {
+    entries.iter().rev().find_map(classify_entry)
+}
+
+/// The failed agent's last message, quoted to the next executor as its progress so far
+pub fn last_assistant_message(entries: &[NormalizedEntry]) -> Option<&str> {
+    entries
+        .iter()
+        .rev()
+        .find(|entry| matches!(entry.entry_type, NormalizedEntryType::AssistantMessage))
+        .map(|entry| entry.content.trim())
+        .filter(|content| !content.is_empty())
+}
+
+/// Prompt for the next executor of a fallback chain. The failed turn's changes are already in
+/// the worktree, so besides the request it only needs the reason and the last progress report.
+pub fn fallback_prompt(
+    failed_profile: &ExecutorProfileId,
+    failure: AgentFailure,
+    progress: Option<&str>,
+    prompt: &str,
+) -> String {
+    let mut out = format!(
+        "You are taking over this task from another coding agent. The previous agent ({failed_profile}) {}.\n\
+         Its changes are already in the working tree. Review them (for example with `git status` and `git diff`) and continue from there instead of starting over.\n\n",
+        failure.description()
+    );
+    if let Some(progress) = progress {
+        let _ = writeln!(
+            out,
+            "## Progress so far\n\n{}\n",
+            truncate_to_char_boundary(progress, MAX_PROGRESS_BYTES)
+        );
+    }
+    let _ = writeln!(out, "## Request\n\n{}", prompt.trim());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn error(error_type: NormalizedEntryError, content: &str) -> NormalizedEntry {
+        entry(NormalizedEntryType::ErrorMessage { error_type }, content)
+    }
+
+    #[test]
+    fn detects_known_signatures() {
+        let cases = [
+            (
+                error(
+                    NormalizedEntryError::Other,
+                    "Claude AI usage limit reached|1760000000",
+                ),
+                Some(AgentFailure::RateLimited),
+            ),
+            (
+                error(
+                    NormalizedEntryError::Other,
+                    "stream error: 429 Too Many Requests",
+                ),
+                Some(AgentFailure::RateLimited),
+            ),
+            (
+                error(
+                    NormalizedEntryError::Other,
+                    "Invalid API key · Please run /login",
+                ),
+                Some(AgentFailure::AuthRequired),
+            ),
+            (
+                error(
+                    NormalizedEntryError::SetupRequired,
+                    "Please log in to Cursor",
+                ),
+                Some(AgentFailure::AuthRequired),
+            ),
+            (
+                error(
+                    NormalizedEntryError::SetupRequired,
+                    "The required executable `claude` is not installed.",
+                ),
+                Some(AgentFailure::ExecutableNotFound),
+            ),
+            (
+                error(NormalizedEntryError::Other, "Tests failed: 3 assertions"),
+                None,
+            ),
+        ];
+        for (entry, expected) in cases {
+            assert_eq!(classify_entry(&entry), expected, "{}", entry.content);
+        }
+    }
+
+    #[test]
+    fn ignores_non_error_entries() {
+        let entries = vec![
+            entry(
+                NormalizedEntryType::AssistantMessage,
+                "I'll add retry handling for the 429 rate limit responses.",
+            ),
+            error(NormalizedEntryError::Other, "Process exited with code 1"),
+        ];
+        assert_eq!(detect_failure(&entries), None);
+    }
+
+    #[test]
+    fn prompt_quotes_last_progress() {
+        let entries = vec![
+            entry(NormalizedEntryType::AssistantMessage, "Exploring auth."),
+            entry(
+                NormalizedEntryType::AssistantMessage,
+                "Added a limiter; tests still fail.",
+            ),
+            error(NormalizedEntryError::Other, "Claude AI usage limit reached"),
+        ];
+        let profile = ExecutorProfileId::new(crate::executors::BaseCodingAgent::ClaudeCode);
+        let prompt = fallback_prompt(
+            &profile,
+            AgentFailure::RateLimited,
+            last_assistant_message(&entries),
+            "Add rate limiting to /login\n",
+        );
+
+        assert!(prompt.starts_with(
+            "You are taking over this task from another coding agent. The previous agent (CLAUDE_CODE) hit a usage or rate limit.\n"
+        ));
+        assert!(prompt.contains("## Progress so far\n\nAdded a limiter; tests still fail.\n\n"));
+        assert!(prompt.ends_with("## Request\n\nAdd rate limiting to /login\n"));
+    }
+}
@@ -0,0 +1,121 @@
+//! Wall-clock and idle limits for executions.
+//!
+//! Defaults per run reason come from the app config; a profile in `profiles.json` can override
+//! them for its coding agent turns, see [`crate::profile::ExecutorConfigs::limits_for`].
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Limits of one execution. A missing or zero limit means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct ExecutionLimits {
+    /// Stop the process once it has run this many seconds
+    #[serde(default)]
+    pub max_runtime_secs: Option<u32>,
+    /// Stop the process after this many seconds without output
+    #[serde(default)]
+    pub max_idle_secs: Option<u32>,
+}
+
+impl ExecutionLimits {
+    /// These limits, with the ones left unset taken from `defaults`
+    pub fn or(self, defaults: ExecutionLimits) -> Self {
+        Self {
+            max_runtime_secs: self.max_runtime_secs.or(defaults.max_runtime_secs),
+            max_idle_secs: self.max_idle_secs.or(defaults.max_idle_secs),
+        }
+    }
+
+    pub fn max_runtime(&self) -> Option<Duration> {
+        to_duration(self.max_runtime_secs)
+    }
+
+    pub fn max_idle(&self) -> Option<Duration> {
+        to_duration(self.max_idle_secs)
+    }
+}
+
+fn to_duration(secs: Option<u32>) -> Option<Duration> {
+    secs.filter(|secs| *secs > 0)
+        .map(|secs| Duration::from_secs(secs.into()))
+}
+
+/// The limit an execution exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionTimeout {
+    Runtime(Duration),
+    Idle(Duration),
+}
+
+impl ExecutionTimeout {
+    pub fn description(&self) -> String {
+        match self {
+            ExecutionTimeout::Runtime(limit) => format!("ran longer than {}", human(*limit)),
+            ExecutionTimeout::Idle(limit) => {
+                format!("produced no output for {}", human(*limit))
+            }
+        }
+    }
+}
+
+fn human(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 && secs % 3600 == 0 {
+        format!("{}h", secs / 3600)
+    } else if secs >= 60 && secs % 60 == 0 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{secs}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_limits_fall_back_to_defaults() {
+        let profile = ExecutionLimits {
+            max_runtime_secs: Some(600),
+            max_idle_secs: None,
+        };
+        let defaults = ExecutionLimits {
+            max_runtime_secs: Some(60),
+            max_idle_secs: Some(120),
+        };
+        assert_eq!(
+            profile.or(defaults),
+            ExecutionLimits {
+                max_runtime_secs: Some(600),
+                max_idle_secs: Some(120),
+            }
+        );
+
+        // Zero disables a limit, even over a default
+        let disabled = ExecutionLimits {
+            max_runtime_secs: Some(0),
+            max_idle_secs: None,
+        }
+        .or(defaults);
+        assert_eq!(disabled.max_runtime(), None);
+        assert_eq!(disabled.max_idle(), Some(Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn describes_timeouts() {
+        assert_eq!(
+            ExecutionTimeout::Runtime(Duration::from_secs(7200)).description(),
+            "ran longer than 2h"
+        );
+        assert_eq!(
+            ExecutionTimeout::Idle(Duration::from_secs(900)).description(),
+            "produced no output for 15m"
+        );
+        assert_eq!(
+            ExecutionTimeout::Idle(Duration::from_secs(90)).description(),
+            "produced no output for 90s"
+        );
+    }
+}
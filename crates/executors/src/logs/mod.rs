@@ -4,6 +4,7 @@ use ts_rs::TS;
 use workspace_utils::approvals::ApprovalStatus;
 
 pub mod plain_text_processor;
+pub mod replay;
 pub mod stderr_processor;
 pub mod utils;
 
@@ -0,0 +1,308 @@
+//! Replay recorded raw executor logs through a normalizer.
+//!
+//! Raw logs are stored in `execution_process_logs` as JSONL-encoded [`LogMsg`] values.
+//! Feeding them back through `normalize_logs` on a fresh [`MsgStore`] reproduces the
+//! exact patch stream the UI saw, which makes normalizer changes testable without the
+//! agent CLIs and lets old sessions be re-rendered with the current normalizers.
+
+use std::{path::Path, sync::Arc, time::Duration};
+
+use json_patch::Patch;
+use serde_json::Value;
+use tokio::sync::broadcast::error::RecvError;
+use workspace_utils::{log_msg::LogMsg, msg_store::MsgStore};
+
+use crate::{
+    executors::StandardCodingAgentExecutor,
+    logs::{
+        NormalizedEntry,
+        utils::patch::{apply_conversation_patches, normalized_entries_from_conversation},
+    },
+};
+
+/// Upper bound on how long a normalizer may take to drain a replayed log
+pub const REPLAY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Patch stream produced by replaying a raw log
+#[derive(Debug, Clone, Default)]
+pub struct ReplayOutput {
+    pub patches: Vec<Patch>,
+    /// Whether every patch the normalizer emitted was collected. False when the replay fell
+    /// behind the normalizer or hit [`REPLAY_TIMEOUT`], in which case `patches` is partial.
+    pub complete: bool,
+}
+
+impl ReplayOutput {
+    /// Conversation entries (normalized entries, stdout and stderr) after applying every patch
+    pub fn conversation(&self) -> Vec<Value> {
+        apply_conversation_patches(&self.patches)
+    }
+
+    pub fn normalized_entries(&self) -> Vec<NormalizedEntry> {
+        normalized_entries_from_conversation(self.conversation())
+    }
+
+    /// Pretty-printed patch stream, suitable for committing as a snapshot
+    pub fn snapshot(&self) -> String {
+        let mut snapshot = serde_json::to_string_pretty(&self.patches).unwrap_or_default();
+        snapshot.push('\n');
+        snapshot
+    }
+}
+
+/// Parse raw logs in the JSONL format stored in `execution_process_logs`
+pub fn parse_raw_logs(jsonl: &str) -> Result<Vec<LogMsg>, serde_json::Error> {
+    jsonl
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect()
+}
+
+/// Run `executor`'s normalizer over `raw_logs` and collect the patches it emits.
+///
+/// Only stdout, stderr and already-normalized patches are replayed, matching what is
+/// persisted for an execution. The store is finished up front so the normalizer tasks
+/// drain the history and exit; they signal that they are done by dropping the store,
+/// which closes its channel.
+pub async fn replay_logs<E>(
+    executor: &E,
+    raw_logs: impl IntoIterator<Item = LogMsg>,
+    worktree_path: &Path,
+) -> ReplayOutput
+where
+    E: StandardCodingAgentExecutor + ?Sized,
+{
+    let msg_store = Arc::new(MsgStore::new());
+    let mut patches = Vec::new();
+    for msg in raw_logs {
+        match msg {
+            LogMsg::JsonPatch(patch) => {
+                patches.push(patch.clone());
+                msg_store.push(LogMsg::JsonPatch(patch));
+            }
+            LogMsg::Stdout(_) | LogMsg::Stderr(_) => msg_store.push(msg),
+            _ => {}
+        }
+    }
+    msg_store.push_finished();
+
+    let mut receiver = msg_store.get_receiver();
+    executor.normalize_logs(msg_store, worktree_path);
+
+    let mut complete = true;
+    let collect = async {
+        loop {
+            match receiver.recv().await {
+                Ok(LogMsg::JsonPatch(patch)) => patches.push(patch),
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Log replay skipped {} normalizer messages", skipped);
+                    complete = false;
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    };
+    if tokio::time::timeout(REPLAY_TIMEOUT, collect).await.is_err() {
+        tracing::warn!(
+            "Log normalizer did not finish within {:?}; returning partial replay",
+            REPLAY_TIMEOUT
+        );
+        complete = false;
+    }
+
+    ReplayOutput { patches, complete }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use serde::de::DeserializeOwned;
+
+    use super::*;
+    use crate::{
+        executors::{
+            claude::ClaudeCode, codex::Codex, droid::Droid, gemini::Gemini, opencode::Opencode,
+        },
+        logs::{ActionType, NormalizedEntryType},
+    };
+
+    const CLAUDE_BASIC: &str = include_str!("fixtures/claude_basic.jsonl");
+    const CODEX_BASIC: &str = include_str!("fixtures/codex_basic.jsonl");
+    const OPENCODE_BASIC: &str = include_str!("fixtures/opencode_basic.jsonl");
+    const DROID_BASIC: &str = include_str!("fixtures/droid_basic.jsonl");
+    /// Gemini and Qwen both speak ACP and share its normalizer
+    const ACP_BASIC: &str = include_str!("fixtures/acp_basic.jsonl");
+
+    fn fixtures_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/logs/fixtures")
+    }
+
+    fn default_executor<E: DeserializeOwned>() -> E {
+        serde_json::from_value(serde_json::json!({})).unwrap()
+    }
+
+    /// Replay a fixture, which all record the same session: the agent reads the README of the
+    /// worktree and answers "Done."
+    async fn replay_fixture<E>(executor: &E, fixture: &str) -> Vec<NormalizedEntry>
+    where
+        E: StandardCodingAgentExecutor,
+    {
+        let raw_logs = parse_raw_logs(fixture).unwrap();
+        let output = replay_logs(executor, raw_logs, Path::new("/tmp/replay-worktree")).await;
+        assert!(output.complete);
+        output.normalized_entries()
+    }
+
+    fn assistant_messages(entries: &[NormalizedEntry]) -> Vec<&str> {
+        entries
+            .iter()
+            .filter(|e| matches!(e.entry_type, NormalizedEntryType::AssistantMessage))
+            .map(|e| e.content.as_str())
+            .collect()
+    }
+
+    fn has_thinking(entries: &[NormalizedEntry], content: &str) -> bool {
+        entries
+            .iter()
+            .any(|e| matches!(e.entry_type, NormalizedEntryType::Thinking) && e.content == content)
+    }
+
+    fn reads_readme(entries: &[NormalizedEntry]) -> bool {
+        entries.iter().any(|e| {
+            matches!(
+                &e.entry_type,
+                NormalizedEntryType::ToolUse {
+                    action_type: ActionType::FileRead { path },
+                    ..
+                } if path.ends_with("README.md")
+            )
+        })
+    }
+
+    /// Compare against a committed snapshot. `UPDATE_SNAPSHOTS=1` writes missing snapshots
+    /// and rewrites existing ones after an intended normalizer change.
+    fn assert_snapshot(name: &str, output: &ReplayOutput) {
+        let path = fixtures_dir().join(format!("{name}.snap.json"));
+        let actual = output.snapshot();
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(&path, &actual).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+            panic!(
+                "missing snapshot {}: {e}; rerun with UPDATE_SNAPSHOTS=1 to create it",
+                path.display()
+            )
+        });
+        assert_eq!(
+            expected, actual,
+            "replay of {name} changed; rerun with UPDATE_SNAPSHOTS=1 if this is intended"
+        );
+    }
+
+    #[test]
+    fn test_parse_raw_logs_skips_blank_lines() {
+        let logs = parse_raw_logs("{\"Stdout\":\"a\\n\"}\n\n{\"Stderr\":\"b\"}\n").unwrap();
+        assert_eq!(logs.len(), 2);
+        assert!(matches!(&logs[0], LogMsg::Stdout(s) if s == "a\n"));
+        assert!(matches!(&logs[1], LogMsg::Stderr(s) if s == "b"));
+    }
+
+    #[tokio::test]
+    async fn test_replay_claude_log() {
+        let raw_logs = parse_raw_logs(CLAUDE_BASIC).unwrap();
+        let executor: ClaudeCode = serde_json::from_value(serde_json::json!({})).unwrap();
+        let output = replay_logs(&executor, raw_logs, Path::new("/tmp/replay-worktree")).await;
+        assert!(output.complete);
+
+        let entries = output.normalized_entries();
+        assert!(
+            entries
+                .iter()
+                .any(|e| matches!(e.entry_type, NormalizedEntryType::SystemMessage))
+        );
+        let assistant: Vec<&str> = entries
+            .iter()
+            .filter(|e| matches!(e.entry_type, NormalizedEntryType::AssistantMessage))
+            .map(|e| e.content.as_str())
+            .collect();
+        assert_eq!(assistant, vec!["I'll read the README first.", "Done."]);
+        assert!(entries.iter().any(|e| matches!(
+            &e.entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::FileRead { path },
+                ..
+            } if path.ends_with("README.md")
+        )));
+
+        assert_snapshot("claude_basic", &output);
+    }
+
+    #[tokio::test]
+    async fn test_replay_codex_log() {
+        let entries = replay_fixture(&default_executor::<Codex>(), CODEX_BASIC).await;
+
+        assert!(has_thinking(&entries, "Reading the README."));
+        assert!(entries.iter().any(|e| matches!(
+            &e.entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::CommandRun { command, .. },
+                ..
+            } if command == "cat README.md"
+        )));
+        assert_eq!(assistant_messages(&entries), vec!["Done."]);
+    }
+
+    #[tokio::test]
+    async fn test_replay_opencode_log() {
+        let entries = replay_fixture(&default_executor::<Opencode>(), OPENCODE_BASIC).await;
+
+        assert!(
+            entries
+                .iter()
+                .any(|e| matches!(e.entry_type, NormalizedEntryType::SystemMessage))
+        );
+        assert!(has_thinking(&entries, "Reading the README."));
+        assert!(reads_readme(&entries));
+        assert_eq!(
+            assistant_messages(&entries),
+            vec!["I'll read the README first.", "Done."]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replay_droid_log() {
+        let entries = replay_fixture(&default_executor::<Droid>(), DROID_BASIC).await;
+
+        assert!(entries.iter().any(|e| e.content == "model: glm-4.6"));
+        assert!(reads_readme(&entries));
+        assert_eq!(
+            assistant_messages(&entries),
+            vec!["I'll read the README first.", "Done."]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replay_acp_log() {
+        let entries = replay_fixture(&default_executor::<Gemini>(), ACP_BASIC).await;
+
+        assert!(has_thinking(&entries, "Reading the README."));
+        assert!(reads_readme(&entries));
+        assert_eq!(
+            assistant_messages(&entries),
+            vec!["I'll read the README first.", "Done."]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replay_is_deterministic() {
+        let executor: ClaudeCode = serde_json::from_value(serde_json::json!({})).unwrap();
+        let worktree = Path::new("/tmp/replay-worktree");
+        let first = replay_logs(&executor, parse_raw_logs(CLAUDE_BASIC).unwrap(), worktree).await;
+        let second = replay_logs(&executor, parse_raw_logs(CLAUDE_BASIC).unwrap(), worktree).await;
+        assert_eq!(first.snapshot(), second.snapshot());
+    }
+}
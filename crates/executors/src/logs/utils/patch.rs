@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use json_patch::Patch;
 use serde::{Deserialize, Serialize};
-use serde_json::{from_value, json, to_value};
+use serde_json::{Value, from_value, json, to_value};
 use ts_rs::TS;
 use workspace_utils::{diff::Diff, msg_store::MsgStore};
 
@@ -142,6 +142,33 @@ pub fn extract_normalized_entry_from_patch(patch: &Patch) -> Option<(usize, Norm
     })
 }
 
+/// Apply conversation patches in order and return the resulting `/entries` array, the
+/// same document the frontend builds from the normalized log stream
+pub fn apply_conversation_patches<'a>(patches: impl IntoIterator<Item = &'a Patch>) -> Vec<Value> {
+    let mut document = json!({ "entries": [] });
+    for patch in patches {
+        if let Err(err) = json_patch::patch(&mut document, &patch.0) {
+            tracing::debug!("Skipping conversation patch that does not apply: {}", err);
+        }
+    }
+    match document.get_mut("entries").map(Value::take) {
+        Some(Value::Array(entries)) => entries,
+        _ => Vec::new(),
+    }
+}
+
+/// Keep only the `NormalizedEntry` items of an applied conversation
+pub fn normalized_entries_from_conversation(conversation: Vec<Value>) -> Vec<NormalizedEntry> {
+    conversation
+        .into_iter()
+        .filter(|value| value.get("type").and_then(Value::as_str) == Some("NORMALIZED_ENTRY"))
+        .filter_map(|mut value| {
+            let content = value.get_mut("content")?.take();
+            from_value(content).ok()
+        })
+        .collect()
+}
+
 pub fn upsert_normalized_entry(
     msg_store: &Arc<MsgStore>,
     index: usize,
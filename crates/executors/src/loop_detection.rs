@@ -0,0 +1,372 @@
+//! Detection of coding agents going around in circles within a turn.
+//!
+//! [`LoopDetector`] is fed the normalized entries of a turn as they stream in. Entries are
+//! keyed by their index in the conversation because executors replace an entry in place when
+//! a tool call finishes, so the same index is usually seen more than once.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::logs::{
+    ActionType, CommandExitStatus, FileChange, NormalizedEntry, NormalizedEntryType, ToolStatus,
+};
+
+/// Failures of the same command, with no file edits in between, before it counts as a loop
+const MAX_COMMAND_FAILURES: usize = 5;
+/// Edits undoing an earlier edit of the same file before it counts as a loop
+const MAX_EDIT_REVERSALS: usize = 3;
+/// Error entries in a row before it counts as a loop
+const MAX_CONSECUTIVE_ERRORS: usize = 5;
+
+/// A pattern of unproductive activity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AgentLoop {
+    RepeatedFailingCommand { command: String, failures: usize },
+    OscillatingEdits { path: String, reversals: usize },
+    ConsecutiveErrors { count: usize },
+}
+
+impl AgentLoop {
+    /// What the agent did, phrased to follow "The agent"
+    pub fn description(&self) -> String {
+        match self {
+            AgentLoop::RepeatedFailingCommand { command, failures } => {
+                format!("ran `{command}` {failures} times and it failed every time")
+            }
+            AgentLoop::OscillatingEdits { path, reversals } => {
+                format!("undid its own edits to {path} {reversals} times")
+            }
+            AgentLoop::ConsecutiveErrors { count } => {
+                format!("ran into {count} errors in a row")
+            }
+        }
+    }
+}
+
+/// Lines an edit removes and adds, used to recognise an edit that undoes another
+type EditSignature = (Vec<String>, Vec<String>);
+
+#[derive(Default)]
+struct FileHistory {
+    edits: Vec<EditSignature>,
+    /// Contents written with full-file writes, oldest first
+    writes: Vec<String>,
+    reversals: usize,
+}
+
+#[derive(Default)]
+struct CommandFailures {
+    count: usize,
+    /// [`LoopDetector::edits`] at the last failure
+    edits_at_last_failure: usize,
+}
+
+#[derive(Default)]
+pub struct LoopDetector {
+    seen: HashSet<usize>,
+    finished_commands: HashSet<usize>,
+    command_failures: HashMap<String, CommandFailures>,
+    files: HashMap<String, FileHistory>,
+    /// Successful file edits so far, to tell a rerun after a fix from a blind retry
+    edits: usize,
+    error_streak: usize,
+}
+
+impl LoopDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the entry at `index`, returning the loop it completes, if any
+    pub fn observe(&mut self, index: usize, entry: &NormalizedEntry) -> Option<AgentLoop> {
+        let first_sighting = self.seen.insert(index);
+        match &entry.entry_type {
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::CommandRun { command, result },
+                ..
+            } => {
+                if first_sighting {
+                    self.error_streak = 0;
+                }
+                let exit_status = result.as_ref()?.exit_status.as_ref()?;
+                if !self.finished_commands.insert(index) {
+                    return None;
+                }
+                self.observe_command(command, exit_status)
+            }
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::FileEdit { path, changes },
+                status,
+                ..
+            } if first_sighting => {
+                self.error_streak = 0;
+                if matches!(status, ToolStatus::Failed | ToolStatus::Denied { .. }) {
+                    return None;
+                }
+                self.edits += 1;
+                self.observe_edit(path, changes)
+            }
+            NormalizedEntryType::ErrorMessage { .. } if first_sighting => {
+                self.error_streak += 1;
+                (self.error_streak >= MAX_CONSECUTIVE_ERRORS).then_some(
+                    AgentLoop::ConsecutiveErrors {
+                        count: self.error_streak,
+                    },
+                )
+            }
+            NormalizedEntryType::Thinking
+            | NormalizedEntryType::Loading
+            | NormalizedEntryType::SystemMessage => None,
+            _ if first_sighting => {
+                self.error_streak = 0;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn observe_command(
+        &mut self,
+        command: &str,
+        exit_status: &CommandExitStatus,
+    ) -> Option<AgentLoop> {
+        let command = command.split_whitespace().collect::<Vec<_>>().join(" ");
+        let failed = match exit_status {
+            CommandExitStatus::ExitCode { code } => *code != 0,
+            CommandExitStatus::Success { success } => !success,
+        };
+        if !failed {
+            // The command works now; earlier failures were progress, not a loop
+            self.command_failures.remove(&command);
+            return None;
+        }
+
+        let failures = self.command_failures.entry(command.clone()).or_default();
+        if failures.count > 0 && failures.edits_at_last_failure != self.edits {
+            // Files changed since the last failure, so this is a rerun after an attempted fix
+            failures.count = 0;
+        }
+        failures.count += 1;
+        failures.edits_at_last_failure = self.edits;
+        (failures.count >= MAX_COMMAND_FAILURES).then(|| AgentLoop::RepeatedFailingCommand {
+            command,
+            failures: failures.count,
+        })
+    }
+
+    fn observe_edit(&mut self, path: &str, changes: &[FileChange]) -> Option<AgentLoop> {
+        let history = self.files.entry(path.to_string()).or_default();
+        for change in changes {
+            match change {
+                FileChange::Edit { unified_diff, .. } => {
+                    let (removed, added) = edit_signature(unified_diff);
+                    if removed.is_empty() && added.is_empty() {
+                        continue;
+                    }
+                    let reverts_earlier = history
+                        .edits
+                        .iter()
+                        .any(|(r, a)| *r == added && *a == removed);
+                    if reverts_earlier {
+                        history.reversals += 1;
+                    }
+                    history.edits.push((removed, added));
+                }
+                FileChange::Write { content } => {
+                    // Writing back content the file had before, other than its current one
+                    let restores_earlier =
+                        history.writes.split_last().is_some_and(|(last, earlier)| {
+                            last != content && earlier.contains(content)
+                        });
+                    if restores_earlier {
+                        history.reversals += 1;
+                    }
+                    history.writes.push(content.clone());
+                }
+                FileChange::Delete | FileChange::Rename { .. } => {}
+            }
+        }
+
+        (history.reversals >= MAX_EDIT_REVERSALS).then(|| AgentLoop::OscillatingEdits {
+            path: path.to_string(),
+            reversals: history.reversals,
+        })
+    }
+}
+
+fn edit_signature(unified_diff: &str) -> EditSignature {
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    for line in unified_diff.lines() {
+        if line.starts_with("---") || line.starts_with("+++") {
+            continue;
+        }
+        if let Some(line) = line.strip_prefix('-') {
+            removed.push(line.trim_end().to_string());
+        } else if let Some(line) = line.strip_prefix('+') {
+            added.push(line.trim_end().to_string());
+        }
+    }
+    (removed, added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::{CommandRunResult, NormalizedEntryError};
+
+    fn entry(entry_type: NormalizedEntryType) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: String::new(),
+            metadata: None,
+        }
+    }
+
+    fn command(command: &str, code: Option<i32>) -> NormalizedEntry {
+        entry(NormalizedEntryType::ToolUse {
+            tool_name: "bash".to_string(),
+            action_type: ActionType::CommandRun {
+                command: command.to_string(),
+                result: code.map(|code| CommandRunResult {
+                    exit_status: Some(CommandExitStatus::ExitCode { code }),
+                    output: None,
+                }),
+            },
+            status: ToolStatus::Success,
+        })
+    }
+
+    fn edit(path: &str, from: &str, to: &str) -> NormalizedEntry {
+        entry(NormalizedEntryType::ToolUse {
+            tool_name: "edit".to_string(),
+            action_type: ActionType::FileEdit {
+                path: path.to_string(),
+                changes: vec![FileChange::Edit {
+                    unified_diff: format!(
+                        "--- a/{path}\n+++ b/{path}\n@@ -1 +1 @@\n-{from}\n+{to}\n"
+                    ),
+                    has_line_numbers: true,
+                }],
+            },
+            status: ToolStatus::Success,
+        })
+    }
+
+    fn error() -> NormalizedEntry {
+        entry(NormalizedEntryType::ErrorMessage {
+            error_type: NormalizedEntryError::Other,
+        })
+    }
+
+    #[test]
+    fn flags_repeated_failing_command() {
+        let mut detector = LoopDetector::new();
+        // Created without a result, then replaced in place once it finishes
+        assert_eq!(detector.observe(0, &command("cargo test", None)), None);
+        assert_eq!(detector.observe(0, &command("cargo test", Some(101))), None);
+        assert_eq!(detector.observe(0, &command("cargo test", Some(101))), None);
+        assert_eq!(
+            detector.observe(1, &command("cargo  test", Some(101))),
+            None
+        );
+        for index in 2..4 {
+            assert_eq!(
+                detector.observe(index, &command("cargo test", Some(101))),
+                None
+            );
+        }
+        assert_eq!(
+            detector.observe(4, &command("cargo test", Some(101))),
+            Some(AgentLoop::RepeatedFailingCommand {
+                command: "cargo test".to_string(),
+                failures: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn success_resets_command_failures() {
+        let mut detector = LoopDetector::new();
+        for (index, code) in [101, 101, 0, 101, 101].into_iter().enumerate() {
+            assert_eq!(
+                detector.observe(index, &command("cargo test", Some(code))),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn fix_and_rerun_is_not_a_loop() {
+        let mut detector = LoopDetector::new();
+        let mut index = 0;
+        for attempt in 0..10 {
+            assert_eq!(
+                detector.observe(index, &command("cargo test", Some(101))),
+                None
+            );
+            let fix = edit(
+                "src/lib.rs",
+                &format!("v{attempt}"),
+                &format!("v{}", attempt + 1),
+            );
+            assert_eq!(detector.observe(index + 1, &fix), None);
+            index += 2;
+        }
+    }
+
+    #[test]
+    fn failures_after_the_last_edit_still_count() {
+        let mut detector = LoopDetector::new();
+        assert_eq!(detector.observe(0, &command("cargo test", Some(101))), None);
+        assert_eq!(detector.observe(1, &edit("src/lib.rs", "a", "b")), None);
+        for index in 2..6 {
+            assert_eq!(
+                detector.observe(index, &command("cargo test", Some(101))),
+                None
+            );
+        }
+        assert_eq!(
+            detector.observe(6, &command("cargo test", Some(101))),
+            Some(AgentLoop::RepeatedFailingCommand {
+                command: "cargo test".to_string(),
+                failures: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn flags_oscillating_edits() {
+        let mut detector = LoopDetector::new();
+        assert_eq!(detector.observe(0, &edit("src/lib.rs", "a", "b")), None);
+        assert_eq!(detector.observe(1, &edit("src/main.rs", "b", "a")), None);
+        assert_eq!(detector.observe(2, &edit("src/lib.rs", "b", "a")), None);
+        assert_eq!(detector.observe(3, &edit("src/lib.rs", "a", "b")), None);
+        assert_eq!(
+            detector.observe(4, &edit("src/lib.rs", "b", "a")),
+            Some(AgentLoop::OscillatingEdits {
+                path: "src/lib.rs".to_string(),
+                reversals: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn flags_consecutive_errors() {
+        let mut detector = LoopDetector::new();
+        for index in 0..4 {
+            assert_eq!(detector.observe(index, &error()), None);
+        }
+        assert_eq!(
+            detector.observe(4, &entry(NormalizedEntryType::AssistantMessage)),
+            None
+        );
+        for index in 5..9 {
+            assert_eq!(detector.observe(index, &error()), None);
+        }
+        assert_eq!(
+            detector.observe(9, &error()),
+            Some(AgentLoop::ConsecutiveErrors { count: 5 })
+        );
+    }
+}
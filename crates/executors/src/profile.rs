@@ -10,8 +10,9 @@ use serde::{Deserialize, Deserializer, Serialize, de::Error as DeError};
 use thiserror::Error;
 use ts_rs::TS;
 
-use crate::executors::{
-    AvailabilityInfo, BaseCodingAgent, CodingAgent, StandardCodingAgentExecutor,
+use crate::{
+    executors::{AvailabilityInfo, BaseCodingAgent, CodingAgent, StandardCodingAgentExecutor},
+    limits::ExecutionLimits,
 };
 
 /// Return the canonical form for variant keys.
@@ -170,14 +171,47 @@ impl ExecutorConfig {
     }
 }
 
+/// Key of a profile in [`ExecutorConfigs::fallbacks`] and [`ExecutorConfigs::limits`]:
+/// `EXECUTOR:VARIANT`, with a missing variant meaning `DEFAULT`
+fn fallback_key(executor: &str, variant: Option<&str>) -> String {
+    format!(
+        "{}:{}",
+        executor.trim().replace('-', "_").to_ascii_uppercase(),
+        canonical_variant_key(variant.map(str::trim).unwrap_or("DEFAULT"))
+    )
+}
+
+fn canonical_profile_keys<T>(map: HashMap<String, T>) -> HashMap<String, T> {
+    map.into_iter()
+        .map(|(key, value)| {
+            let (executor, variant) = match key.split_once(':') {
+                Some((executor, variant)) => (executor, Some(variant)),
+                None => (key.as_str(), None),
+            };
+            (fallback_key(executor, variant), value)
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct ExecutorConfigs {
     pub executors: HashMap<BaseCodingAgent, ExecutorConfig>,
+    /// Profiles to retry a coding agent turn with, in order, when it fails with a usage
+    /// limit, an auth error or a missing CLI. Keyed by `EXECUTOR:VARIANT`.
+    #[serde(default)]
+    pub fallbacks: HashMap<String, Vec<ExecutorProfileId>>,
+    /// Timeouts for a profile's coding agent turns, overriding the app's defaults. Keyed by
+    /// `EXECUTOR:VARIANT`.
+    #[serde(default)]
+    pub limits: HashMap<String, ExecutionLimits>,
 }
 
 impl ExecutorConfigs {
     /// Normalise all variant keys in-place
     fn canonicalise(&mut self) {
+        self.fallbacks = canonical_profile_keys(std::mem::take(&mut self.fallbacks));
+        self.limits = canonical_profile_keys(std::mem::take(&mut self.limits));
+
         for profile in self.executors.values_mut() {
             let mut replacements = Vec::new();
             for key in profile.configurations.keys().cloned().collect::<Vec<_>>() {
@@ -281,6 +315,9 @@ impl ExecutorConfigs {
                 }
             }
         }
+        // Fallback chains and limits are replaced as a whole
+        defaults.fallbacks.extend(overrides.fallbacks);
+        defaults.limits.extend(overrides.limits);
         defaults
     }
 
@@ -288,6 +325,18 @@ impl ExecutorConfigs {
     fn compute_overrides(defaults: &Self, current: &Self) -> Result<Self, ProfileError> {
         let mut overrides = Self {
             executors: HashMap::new(),
+            fallbacks: current
+                .fallbacks
+                .iter()
+                .filter(|(key, chain)| defaults.fallbacks.get(*key) != Some(*chain))
+                .map(|(key, chain)| (key.clone(), chain.clone()))
+                .collect(),
+            limits: current
+                .limits
+                .iter()
+                .filter(|(key, limits)| defaults.limits.get(*key) != Some(*limits))
+                .map(|(key, limits)| (key.clone(), *limits))
+                .collect(),
         };
 
         // Fast scan for any illegal deletions BEFORE allocating/cloning
@@ -376,6 +425,21 @@ impl ExecutorConfigs {
                 }
             }
         }
+
+        for (key, chain) in &merged.fallbacks {
+            for fallback in chain {
+                if merged.get_coding_agent(fallback).is_none() {
+                    return Err(ProfileError::Validation(format!(
+                        "Fallback '{fallback}' of '{key}' is not a configured profile"
+                    )));
+                }
+                if Self::profile_fallback_key(fallback) == *key {
+                    return Err(ProfileError::Validation(format!(
+                        "Profile '{key}' cannot fall back to itself"
+                    )));
+                }
+            }
+        }
         Ok(())
     }
 
@@ -401,6 +465,29 @@ impl ExecutorConfigs {
             .cloned()
     }
 
+    fn profile_fallback_key(executor_profile_id: &ExecutorProfileId) -> String {
+        fallback_key(
+            &executor_profile_id.executor.to_string(),
+            executor_profile_id.variant.as_deref(),
+        )
+    }
+
+    /// Profiles to retry with when a turn of `executor_profile_id` fails with a known error
+    pub fn fallback_chain(&self, executor_profile_id: &ExecutorProfileId) -> &[ExecutorProfileId] {
+        self.fallbacks
+            .get(&Self::profile_fallback_key(executor_profile_id))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Timeouts configured for turns of `executor_profile_id`; unset ones use the app's defaults
+    pub fn limits_for(&self, executor_profile_id: &ExecutorProfileId) -> ExecutionLimits {
+        self.limits
+            .get(&Self::profile_fallback_key(executor_profile_id))
+            .copied()
+            .unwrap_or_default()
+    }
+
     pub fn get_coding_agent_or_default(
         &self,
         executor_profile_id: &ExecutorProfileId,
@@ -0,0 +1,382 @@
+//! Optional bubblewrap sandbox around coding agent processes (Linux only).
+//!
+//! A sandboxed agent sees the whole filesystem read-only except for the paths of its
+//! [`SandboxPolicy`], so writes anywhere else fail with [`READ_ONLY_ERROR`]. With a
+//! [`NetworkBridge`] it also gets a network namespace of its own with nothing but loopback;
+//! `socat` forwards [`PROXY_PORT`] there to the container's host allow-list proxy, which listens
+//! on a Unix socket outside.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use workspace_utils::shell::resolve_executable_path;
+
+use crate::executors::ExecutorError;
+
+/// How a write outside the writable paths fails
+pub const READ_ONLY_ERROR: &str = "Read-only file system";
+
+/// Loopback port inside a network-isolated sandbox that reaches the allow-list proxy
+pub const PROXY_PORT: u16 = 3128;
+
+/// How long the bridge gets to start listening before the agent runs, in 50ms steps
+const BRIDGE_WAIT_STEPS: u32 = 100;
+
+/// Paths below the home directory that coding agents keep credentials, sessions and caches in
+const AGENT_STATE_PATHS: [&str; 12] = [
+    ".claude",
+    ".claude.json",
+    ".codex",
+    ".gemini",
+    ".qwen",
+    ".cursor",
+    ".factory",
+    ".copilot",
+    ".config/opencode",
+    ".local/share/opencode",
+    ".npm",
+    ".cache",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SandboxPolicy {
+    /// Paths the agent may write to; ones that do not exist are skipped
+    pub writable_paths: Vec<PathBuf>,
+    /// Cut the agent off the network except for the proxy behind this bridge. `None` leaves
+    /// the network alone.
+    pub network: Option<NetworkBridge>,
+}
+
+/// The allow-list proxy a network-isolated sandbox can reach
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkBridge {
+    /// Unix socket the proxy listens on, bridged to [`PROXY_PORT`] inside the sandbox
+    pub proxy_socket: PathBuf,
+}
+
+impl SandboxPolicy {
+    /// Resolve `bwrap` (and `socat` for network isolation) and wrap `program` with `args` in it
+    pub async fn wrap(
+        &self,
+        program: PathBuf,
+        args: Vec<String>,
+    ) -> Result<(PathBuf, Vec<String>), ExecutorError> {
+        if !cfg!(target_os = "linux") {
+            return Err(ExecutorError::SandboxUnavailable(
+                "sandboxing is only supported on Linux".to_string(),
+            ));
+        }
+        let bwrap = resolve_executable_path("bwrap").await.ok_or_else(|| {
+            ExecutorError::SandboxUnavailable("bubblewrap (`bwrap`) is not installed".to_string())
+        })?;
+        let socat = match &self.network {
+            Some(_) => Some(resolve_executable_path("socat").await.ok_or_else(|| {
+                ExecutorError::SandboxUnavailable(
+                    "`socat` is not installed; it is needed to restrict the network to the allowed hosts"
+                        .to_string(),
+                )
+            })?),
+            None => None,
+        };
+        Ok((bwrap, self.bwrap_args(&program, args, socat.as_deref())))
+    }
+
+    fn bwrap_args(&self, program: &Path, args: Vec<String>, socat: Option<&Path>) -> Vec<String> {
+        let mut bwrap: Vec<String> = ["--ro-bind", "/", "/", "--dev-bind", "/dev", "/dev"]
+            .map(String::from)
+            .to_vec();
+        if self.network.is_some() {
+            // A pid namespace ends the bridge together with the agent
+            bwrap.extend(["--unshare-net", "--unshare-pid", "--proc", "/proc"].map(String::from));
+        }
+        for path in &self.writable_paths {
+            let path = path.to_string_lossy().into_owned();
+            bwrap.extend(["--bind-try".to_string(), path.clone(), path]);
+        }
+        bwrap.extend(["--die-with-parent", "--"].map(String::from));
+        if let (Some(network), Some(socat)) = (&self.network, socat) {
+            bwrap.extend(bridge_command(network, socat));
+        }
+        bwrap.push(program.to_string_lossy().into_owned());
+        bwrap.extend(args);
+        bwrap
+    }
+}
+
+/// Shell prefix that starts `socat` forwarding [`PROXY_PORT`] to the proxy socket, waits for it
+/// to listen and then execs the program that follows. Paths are passed as arguments so they
+/// need no quoting.
+fn bridge_command(network: &NetworkBridge, socat: &Path) -> Vec<String> {
+    // /proc/net/tcp lists loopback listeners as 0100007F:<port in hex> with state 0A
+    let script = format!(
+        r#"socat="$1"; socket="$2"; shift 2
+"$socat" TCP-LISTEN:{PROXY_PORT},bind=127.0.0.1,fork,reuseaddr "UNIX-CONNECT:$socket" &
+i=0
+while [ "$i" -lt {BRIDGE_WAIT_STEPS} ] && ! grep -q '0100007F:{PROXY_PORT:04X} 00000000:0000 0A' /proc/net/tcp; do
+  sleep 0.05; i=$((i + 1))
+done
+exec "$@""#
+    );
+    vec![
+        "/bin/sh".to_string(),
+        "-c".to_string(),
+        script,
+        "sandbox-bridge".to_string(),
+        socat.to_string_lossy().into_owned(),
+        network.proxy_socket.to_string_lossy().into_owned(),
+    ]
+}
+
+/// Agent state directories in the home directory that a sandboxed agent needs to write to
+pub fn agent_state_paths() -> Vec<PathBuf> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    AGENT_STATE_PATHS
+        .iter()
+        .map(|path| home.join(path))
+        .collect()
+}
+
+/// The parts of the repository's git directory a worktree needs to write to for commits and
+/// branch updates: its own admin directory, objects, refs and reflogs. Hooks and the config
+/// stay read-only, so an agent cannot plant code that later runs outside the sandbox.
+pub fn worktree_git_paths(worktree: &Path) -> Vec<PathBuf> {
+    let Some(admin_dir) = fs::read_to_string(worktree.join(".git"))
+        .ok()
+        .and_then(|gitfile| {
+            let gitdir = gitfile.trim().strip_prefix("gitdir:")?.trim().to_string();
+            Some(worktree.join(gitdir))
+        })
+    else {
+        return Vec::new();
+    };
+    let common_dir = fs::read_to_string(admin_dir.join("commondir"))
+        .map(|common| admin_dir.join(common.trim()))
+        .unwrap_or_else(|_| admin_dir.clone());
+    let mut paths = vec![admin_dir];
+    paths.extend(["objects", "refs", "logs"].map(|dir| common_dir.join(dir)));
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    /// A repository with a linked worktree, as workspaces have
+    fn repo_with_worktree(dir: &TempDir) -> (PathBuf, PathBuf) {
+        let repo = dir.path().join("repo");
+        let worktree = dir.path().join("worktree");
+        fs::create_dir(&repo).unwrap();
+        git(&repo, &["init", "-q", "-b", "main"]);
+        git(&repo, &["commit", "-q", "--allow-empty", "-m", "init"]);
+        git(
+            &repo,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "-b",
+                "vk/test",
+                worktree.to_str().unwrap(),
+            ],
+        );
+        (
+            repo.canonicalize().unwrap(),
+            worktree.canonicalize().unwrap(),
+        )
+    }
+
+    #[test]
+    fn worktree_git_paths_leave_hooks_and_config_out() {
+        let dir = TempDir::new().unwrap();
+        let (repo, worktree) = repo_with_worktree(&dir);
+        let git_dir = repo.join(".git");
+
+        let paths: Vec<PathBuf> = worktree_git_paths(&worktree)
+            .into_iter()
+            .map(|path| path.canonicalize().unwrap())
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                git_dir.join("worktrees/worktree"),
+                git_dir.join("objects"),
+                git_dir.join("refs"),
+                git_dir.join("logs"),
+            ]
+        );
+        assert!(worktree_git_paths(&repo).is_empty());
+    }
+
+    #[test]
+    #[ignore = "needs bubblewrap (`bwrap`) installed"]
+    fn sandboxed_worktree_can_commit_but_not_write_hooks() {
+        let bwrap = which("bwrap").expect("bwrap is not installed");
+        let dir = TempDir::new().unwrap();
+        let (repo, worktree) = repo_with_worktree(&dir);
+        let mut writable_paths = vec![worktree.clone()];
+        writable_paths.extend(worktree_git_paths(&worktree));
+        let policy = SandboxPolicy {
+            writable_paths,
+            network: None,
+        };
+
+        let run = |script: &str| {
+            Command::new(&bwrap)
+                .args(policy.bwrap_args(
+                    Path::new("/bin/sh"),
+                    vec!["-c".to_string(), script.to_string()],
+                    None,
+                ))
+                .current_dir(&worktree)
+                .status()
+                .unwrap()
+                .success()
+        };
+
+        assert!(run(
+            "git -c user.name=Test -c user.email=test@example.com commit -q --allow-empty -m turn"
+        ));
+        let hook = repo.join(".git/hooks/post-checkout");
+        assert!(!run(&format!("echo 'exit 0' > {}", hook.display())));
+        assert!(!hook.exists());
+        assert!(!run(&format!(
+            "echo '[core]' >> {}",
+            repo.join(".git/config").display()
+        )));
+    }
+
+    fn which(program: &str) -> Option<PathBuf> {
+        std::env::var_os("PATH").and_then(|path| {
+            std::env::split_paths(&path)
+                .map(|dir| dir.join(program))
+                .find(|candidate| candidate.is_file())
+        })
+    }
+
+    #[test]
+    fn binds_writable_paths_over_read_only_root() {
+        let policy = SandboxPolicy {
+            writable_paths: vec![PathBuf::from("/work/tree"), PathBuf::from("/tmp")],
+            network: None,
+        };
+        let args = policy.bwrap_args(
+            Path::new("/usr/bin/npx"),
+            vec!["-y".to_string(), "agent".to_string()],
+            None,
+        );
+        assert_eq!(
+            args,
+            [
+                "--ro-bind",
+                "/",
+                "/",
+                "--dev-bind",
+                "/dev",
+                "/dev",
+                "--bind-try",
+                "/work/tree",
+                "/work/tree",
+                "--bind-try",
+                "/tmp",
+                "/tmp",
+                "--die-with-parent",
+                "--",
+                "/usr/bin/npx",
+                "-y",
+                "agent",
+            ]
+        );
+    }
+
+    #[test]
+    fn isolates_network_behind_proxy_bridge() {
+        let policy = SandboxPolicy {
+            writable_paths: Vec::new(),
+            network: Some(NetworkBridge {
+                proxy_socket: PathBuf::from("/tmp/proxy.sock"),
+            }),
+        };
+        let args = policy.bwrap_args(
+            Path::new("/usr/bin/agent"),
+            vec!["--run".to_string()],
+            Some(Path::new("/usr/bin/socat")),
+        );
+
+        assert_eq!(
+            args[6..10],
+            ["--unshare-net", "--unshare-pid", "--proc", "/proc"]
+        );
+        let command = &args[args.iter().position(|arg| arg == "--").unwrap() + 1..];
+        assert_eq!(command[..2], ["/bin/sh", "-c"]);
+        assert!(command[2].contains("TCP-LISTEN:3128,bind=127.0.0.1"));
+        assert!(command[2].contains("0100007F:0C38 00000000:0000 0A"));
+        assert_eq!(
+            command[3..],
+            [
+                "sandbox-bridge",
+                "/usr/bin/socat",
+                "/tmp/proxy.sock",
+                "/usr/bin/agent",
+                "--run",
+            ]
+        );
+    }
+
+    #[test]
+    #[ignore = "needs bubblewrap (`bwrap`) and `socat` installed"]
+    fn isolated_sandbox_only_reaches_proxy() {
+        use std::{io::Read, os::unix::net::UnixListener};
+
+        let bwrap = which("bwrap").expect("bwrap is not installed");
+        let socat = which("socat").expect("socat is not installed");
+        let dir = TempDir::new().unwrap();
+        let proxy_socket = dir.path().join("proxy.sock");
+        let listener = UnixListener::bind(&proxy_socket).unwrap();
+        let proxy = std::thread::spawn(move || {
+            let (mut client, _) = listener.accept().unwrap();
+            let mut received = String::new();
+            client.read_to_string(&mut received).unwrap();
+            received
+        });
+
+        let policy = SandboxPolicy {
+            writable_paths: Vec::new(),
+            network: Some(NetworkBridge { proxy_socket }),
+        };
+        let script = format!(
+            "echo through-proxy | {} - TCP:127.0.0.1:{PROXY_PORT} && ! {} - TCP:1.1.1.1:80 </dev/null",
+            socat.display(),
+            socat.display()
+        );
+        let status = Command::new(&bwrap)
+            .args(policy.bwrap_args(
+                Path::new("/bin/sh"),
+                vec!["-c".to_string(), script],
+                Some(&socat),
+            ))
+            .status()
+            .unwrap();
+
+        assert!(status.success());
+        assert_eq!(proxy.join().unwrap(), "through-proxy\n");
+    }
+}
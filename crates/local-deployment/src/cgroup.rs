@@ -0,0 +1,258 @@
+//! Memory and CPU caps for execution processes through cgroup v2 (Linux only).
+//!
+//! Capped processes join `executions/<execution id>` below the server's own cgroup between
+//! fork and exec, see [`ExecutionCgroup::open_procs`]. cgroup v2 only allows processes in leaf
+//! groups once controllers are enabled for children, so the server first moves itself and the
+//! processes it started into a `server` leaf. Other processes sharing the server's cgroup are
+//! left alone, and limits are unavailable while any are there. All of this needs the server's
+//! cgroup to be delegated, such as a systemd unit with `Delegate=yes` or a container with a
+//! writable cgroup filesystem.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::Duration,
+};
+
+use uuid::Uuid;
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+const CONTROLLERS: [&str; 2] = ["memory", "cpu"];
+const CPU_PERIOD_US: u64 = 100_000;
+
+static EXECUTIONS_ROOT: OnceLock<Result<PathBuf, String>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CgroupLimits {
+    pub max_memory_mb: Option<u32>,
+    /// 100 is one full core
+    pub max_cpu_percent: Option<u32>,
+}
+
+impl CgroupLimits {
+    pub fn is_empty(&self) -> bool {
+        self.max_memory_mb.is_none() && self.max_cpu_percent.is_none()
+    }
+}
+
+/// The cgroup of one execution, removed with [`Self::remove`] once the execution has exited
+pub struct ExecutionCgroup {
+    path: PathBuf,
+    limits: CgroupLimits,
+}
+
+impl ExecutionCgroup {
+    pub fn create(execution_id: Uuid, limits: CgroupLimits) -> io::Result<Self> {
+        let root = EXECUTIONS_ROOT
+            .get_or_init(|| init_executions_root().map_err(|e| e.to_string()))
+            .as_deref()
+            .map_err(|e| io::Error::other(e.to_string()))?;
+
+        let cgroup = Self {
+            path: root.join(execution_id.to_string()),
+            limits,
+        };
+        fs::create_dir_all(&cgroup.path)?;
+        if let Err(e) = cgroup.write_limits() {
+            let _ = fs::remove_dir(&cgroup.path);
+            return Err(e);
+        }
+        Ok(cgroup)
+    }
+
+    fn write_limits(&self) -> io::Result<()> {
+        if let Some(mb) = self.limits.max_memory_mb {
+            let bytes = u64::from(mb) * 1024 * 1024;
+            fs::write(self.path.join("memory.max"), bytes.to_string())?;
+        }
+        if let Some(percent) = self.limits.max_cpu_percent {
+            let quota = u64::from(percent) * CPU_PERIOD_US / 100;
+            fs::write(
+                self.path.join("cpu.max"),
+                format!("{quota} {CPU_PERIOD_US}"),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// `cgroup.procs` opened for writing, for a process to join the cgroup before it execs,
+    /// see [`executors::env::ExecutionEnv::cgroup_procs`]
+    pub fn open_procs(&self) -> io::Result<File> {
+        OpenOptions::new()
+            .write(true)
+            .open(self.path.join("cgroup.procs"))
+    }
+
+    pub fn limits(&self) -> CgroupLimits {
+        self.limits
+    }
+
+    /// Processes of the cgroup the kernel killed for exceeding the memory limit
+    pub fn oom_kills(&self) -> u64 {
+        fs::read_to_string(self.path.join("memory.events"))
+            .ok()
+            .and_then(|events| {
+                events.lines().find_map(|line| {
+                    line.strip_prefix("oom_kill ")
+                        .and_then(|count| count.trim().parse().ok())
+                })
+            })
+            .unwrap_or(0)
+    }
+
+    /// Kill whatever is left in the cgroup and delete it
+    pub async fn remove(self) {
+        for _ in 0..20 {
+            match fs::remove_dir(&self.path) {
+                Ok(()) => return,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return,
+                Err(_) => {
+                    // Processes that left the process group are still in here
+                    let _ = fs::write(self.path.join("cgroup.kill"), "1");
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            }
+        }
+        tracing::warn!("Failed to remove cgroup {}", self.path.display());
+    }
+}
+
+fn init_executions_root() -> io::Result<PathBuf> {
+    if !cfg!(target_os = "linux") {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "resource limits need cgroup v2, which is only available on Linux",
+        ));
+    }
+
+    let own = fs::read_to_string("/proc/self/cgroup")?;
+    let relative = own
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .ok_or_else(|| io::Error::other("cgroup v2 is not mounted"))?;
+    let base = Path::new(CGROUP_ROOT).join(relative.trim().trim_start_matches('/'));
+
+    let available = fs::read_to_string(base.join("cgroup.controllers"))?;
+    for controller in CONTROLLERS {
+        if !available.split_whitespace().any(|c| c == controller) {
+            return Err(io::Error::other(format!(
+                "the {controller} controller is not delegated to {}",
+                base.display()
+            )));
+        }
+    }
+
+    // Controllers can only be enabled for the children of a cgroup without processes of its
+    // own, so the server and its children move into a child cgroup. Other processes would
+    // keep them from being enabled anyway, and are not ours to move.
+    let own_pid = std::process::id();
+    let procs: Vec<u32> = fs::read_to_string(base.join("cgroup.procs"))?
+        .lines()
+        .filter_map(|pid| pid.trim().parse().ok())
+        .collect();
+    if procs
+        .iter()
+        .any(|&pid| pid != own_pid && !is_descendant(pid, own_pid))
+    {
+        return Err(io::Error::other(format!(
+            "other processes share the server's cgroup {}; run the server in a cgroup of its own",
+            base.display()
+        )));
+    }
+
+    let server = base.join("server");
+    fs::create_dir_all(&server)?;
+    for pid in &procs {
+        // Ignore processes that exit in the meantime
+        let _ = fs::write(server.join("cgroup.procs"), pid.to_string());
+    }
+    if let Err(e) = enable_controllers(&base) {
+        // Leave the server where it was
+        for pid in &procs {
+            let _ = fs::write(base.join("cgroup.procs"), pid.to_string());
+        }
+        let _ = fs::remove_dir(&server);
+        return Err(io::Error::other(format!(
+            "cannot enable controllers for {}: {e}",
+            base.display()
+        )));
+    }
+
+    let root = base.join("executions");
+    fs::create_dir_all(&root)?;
+    enable_controllers(&root)?;
+
+    // Cgroups of executions that outlived a previous run of the server
+    for entry in fs::read_dir(&root)?.flatten() {
+        if entry.path().is_dir() {
+            let _ = fs::remove_dir(entry.path());
+        }
+    }
+    Ok(root)
+}
+
+/// Whether `ancestor` started `pid`, directly or through other processes
+fn is_descendant(pid: u32, ancestor: u32) -> bool {
+    let mut current = pid;
+    while let Some(parent) = parent_pid(current) {
+        if parent == ancestor {
+            return true;
+        }
+        if parent <= 1 {
+            return false;
+        }
+        current = parent;
+    }
+    false
+}
+
+fn parent_pid(pid: u32) -> Option<u32> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    parse_parent_pid(&stat)
+}
+
+/// The parent pid from `/proc/<pid>/stat`. The command name before it is in parentheses and
+/// may itself contain spaces and parentheses.
+fn parse_parent_pid(stat: &str) -> Option<u32> {
+    let (_, rest) = stat.rsplit_once(')')?;
+    rest.split_whitespace().nth(1)?.parse().ok()
+}
+
+fn enable_controllers(cgroup: &Path) -> io::Result<()> {
+    let enable = CONTROLLERS.map(|c| format!("+{c}")).join(" ");
+    fs::write(cgroup.join("cgroup.subtree_control"), enable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_parent_pid_from_stat() {
+        assert_eq!(
+            parse_parent_pid("4242 (cargo) S 4100 4242 4100 0 -1 4194560"),
+            Some(4100)
+        );
+        assert_eq!(
+            parse_parent_pid("4243 (tokio ) (worker)) R 4242 4242 4100 0 -1"),
+            Some(4242)
+        );
+        assert_eq!(parse_parent_pid("garbage"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn finds_own_children() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .unwrap();
+        let own = std::process::id();
+        assert!(is_descendant(child.id(), own));
+        assert!(!is_descendant(own, child.id()));
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
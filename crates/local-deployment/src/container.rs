@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     io,
     path::{Path, PathBuf},
     str::FromStr,
@@ -15,14 +15,22 @@ use db::{
     models::{
         coding_agent_turn::CodingAgentTurn,
         execution_process::{
-            ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+            CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessRunReason,
+            ExecutionProcessStatus,
         },
-        execution_process_repo_state::ExecutionProcessRepoState,
+        execution_process_logs::ExecutionProcessLogs,
+        execution_process_repo_state::{
+            CreateExecutionProcessRepoState, ExecutionProcessRepoState,
+        },
+        project::{LoopPolicy, Project},
+        project_repo::ProjectRepo,
         repo::Repo,
         scratch::{DraftFollowUpData, Scratch, ScratchType},
+        session::Session,
         task::{Task, TaskStatus},
         workspace::Workspace,
         workspace_repo::WorkspaceRepo,
+        workspace_template::WorkspaceTemplate,
     },
 };
 use deployment::{DeploymentError, RemoteClientNotConfigured};
@@ -31,39 +39,81 @@ use executors::{
         Executable, ExecutorAction, ExecutorActionType,
         coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
+        script::{ScriptContext, ScriptRequest},
     },
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
     env::ExecutionEnv,
     executors::{BaseCodingAgent, ExecutorExitResult, ExecutorExitSignal, InterruptSender},
-    logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
-    profile::ExecutorProfileId,
+    limits::{ExecutionLimits, ExecutionTimeout},
+    logs::{ActionType, NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
+    loop_detection::{AgentLoop, LoopDetector},
+    profile::{ExecutorConfigs, ExecutorProfileId},
+    sandbox::{
+        NetworkBridge, PROXY_PORT, READ_ONLY_ERROR, SandboxPolicy, agent_state_paths,
+        worktree_git_paths,
+    },
 };
-use futures::{FutureExt, TryStreamExt, stream::select};
+use futures::{FutureExt, StreamExt, TryStreamExt, future, stream::select};
 use serde_json::json;
 use services::services::{
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
-    config::Config,
-    container::{ContainerError, ContainerRef, ContainerService},
+    config::{Config, SandboxConfig},
+    container::{ContainerError, ContainerRef, ContainerService, FallbackState},
+    dev_processes,
     diff_stream::{self, DiffStreamHandle},
     git::{GitCli, GitService},
     image::ImageService,
     notification::NotificationService,
     queued_message::QueuedMessageService,
+    resource_usage::{ResourceMonitor, ResourceUsage},
+    secrets::SecretsService,
     share::SharePublisher,
+    terminal::TerminalInput,
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
 };
-use tokio::{sync::RwLock, task::JoinHandle};
+use tokio::{
+    sync::{RwLock, broadcast::error::RecvError, mpsc},
+    task::JoinHandle,
+    time::Instant,
+};
 use tokio_util::io::ReaderStream;
 use utils::{
     log_msg::LogMsg,
     msg_store::MsgStore,
     port_allocator::AllocatedPorts,
+    port_file::read_port_file,
+    redact::SecretRedactor,
     text::{git_branch_id, short_uuid, truncate_to_char_boundary},
 };
 use uuid::Uuid;
 
-use crate::{command, copy};
+use crate::{
+    cgroup::{CgroupLimits, ExecutionCgroup},
+    command,
+    copy::{self, CopySummary},
+    process_stats,
+    sandbox_proxy::{ProxyPolicy, SandboxProxy},
+    terminal,
+    warm_pool::{WarmPool, WarmWorktree},
+};
+
+/// Corrective follow-ups a session gets for loops before the agent is paused instead
+const MAX_LOOP_FOLLOW_UPS: u32 = 2;
+
+const RESOURCE_SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+/// How often warm worktree pools are checked for moved branches and missing worktrees
+const WARM_POOL_REFILL_INTERVAL: Duration = Duration::from_secs(60);
+/// Setup scripts of warm worktrees that run longer than this are killed
+const WARM_SETUP_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// The setup script run of a claimed warm worktree
+struct WarmSetup {
+    repo: Repo,
+    commit: String,
+    /// Execution process log lines
+    log: Vec<String>,
+}
 
 #[derive(Clone)]
 pub struct LocalContainerService {
@@ -72,12 +122,28 @@ pub struct LocalContainerService {
     interrupt_senders: Arc<RwLock<HashMap<Uuid, InterruptSender>>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
     port_allocations: Arc<RwLock<HashMap<Uuid, AllocatedPorts>>>,
+    /// Ports of named dev processes by workspace, kept when the processes restart
+    dev_ports: Arc<RwLock<HashMap<Uuid, HashMap<String, u16>>>>,
+    fallback_states: Arc<RwLock<HashMap<Uuid, FallbackState>>>,
+    /// Corrective follow-ups started for loops, by session
+    loop_follow_ups: Arc<RwLock<HashMap<Uuid, u32>>>,
+    terminal_inputs: Arc<RwLock<HashMap<Uuid, mpsc::UnboundedSender<TerminalInput>>>>,
+    cgroups: Arc<RwLock<HashMap<Uuid, ExecutionCgroup>>>,
+    resource_monitor: ResourceMonitor,
+    sandbox_proxies: Arc<RwLock<HashMap<Uuid, SandboxProxy>>>,
+    warm_pool: WarmPool,
+    /// Copy summaries waiting for the setup script log, by workspace and repository name
+    copy_summaries: Arc<RwLock<HashMap<(Uuid, String), String>>>,
+    /// Setup scripts that ran in claimed warm worktrees, by workspace, waiting for the first
+    /// session to be recorded in
+    warm_setups: Arc<RwLock<HashMap<Uuid, WarmSetup>>>,
     config: Arc<RwLock<Config>>,
     git: GitService,
     image_service: ImageService,
     analytics: Option<AnalyticsContext>,
     approvals: Approvals,
     queued_message_service: QueuedMessageService,
+    secrets: SecretsService,
     publisher: Result<SharePublisher, RemoteClientNotConfigured>,
     notification_service: NotificationService,
 }
@@ -93,11 +159,22 @@ impl LocalContainerService {
         analytics: Option<AnalyticsContext>,
         approvals: Approvals,
         queued_message_service: QueuedMessageService,
+        secrets: SecretsService,
         publisher: Result<SharePublisher, RemoteClientNotConfigured>,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let interrupt_senders = Arc::new(RwLock::new(HashMap::new()));
         let port_allocations = Arc::new(RwLock::new(HashMap::new()));
+        let dev_ports = Arc::new(RwLock::new(HashMap::new()));
+        let fallback_states = Arc::new(RwLock::new(HashMap::new()));
+        let loop_follow_ups = Arc::new(RwLock::new(HashMap::new()));
+        let terminal_inputs = Arc::new(RwLock::new(HashMap::new()));
+        let cgroups = Arc::new(RwLock::new(HashMap::new()));
+        let resource_monitor = ResourceMonitor::new();
+        let sandbox_proxies = Arc::new(RwLock::new(HashMap::new()));
+        let warm_pool = WarmPool::new();
+        let copy_summaries = Arc::new(RwLock::new(HashMap::new()));
+        let warm_setups = Arc::new(RwLock::new(HashMap::new()));
         let notification_service = NotificationService::new(config.clone());
 
         let container = LocalContainerService {
@@ -106,17 +183,29 @@ impl LocalContainerService {
             interrupt_senders,
             msg_stores,
             port_allocations,
+            dev_ports,
+            fallback_states,
+            loop_follow_ups,
+            terminal_inputs,
+            cgroups,
+            resource_monitor,
+            sandbox_proxies,
+            warm_pool,
+            copy_summaries,
+            warm_setups,
             config,
             git,
             image_service,
             analytics,
             approvals,
             queued_message_service,
+            secrets,
             publisher,
             notification_service,
         };
 
         container.spawn_workspace_cleanup();
+        container.spawn_resource_sampler();
 
         container
     }
@@ -168,6 +257,39 @@ impl LocalContainerService {
         Ok(ports)
     }
 
+    /// Allocate a port to each named dev process of the workspace that has none yet
+    async fn allocate_dev_process_ports(
+        &self,
+        workspace: &Workspace,
+    ) -> Result<HashMap<String, u16>, ContainerError> {
+        let processes = dev_processes::workspace_dev_processes(&self.db.pool, workspace)
+            .await
+            .map_err(|e| ContainerError::Other(anyhow!("Invalid dev processes: {}", e)))?;
+
+        let mut map = self.dev_ports.write().await;
+        let ports = map.entry(workspace.id).or_default();
+        for process in processes {
+            if !ports.contains_key(&process.name) {
+                let port = utils::port_allocator::allocate_port().map_err(|e| {
+                    ContainerError::Other(anyhow!("Failed to allocate dev process port: {}", e))
+                })?;
+                tracing::info!(
+                    "Allocated port {} to dev process {} of workspace {}",
+                    port,
+                    process.name,
+                    workspace.id
+                );
+                ports.insert(process.name, port);
+            }
+        }
+        Ok(ports.clone())
+    }
+
+    /// Ports allocated to a dev server running a repository's dev server script
+    pub(crate) async fn allocated_ports(&self, exec_id: &Uuid) -> Option<AllocatedPorts> {
+        self.port_allocations.read().await.get(exec_id).copied()
+    }
+
     /// Release allocated ports for an execution process
     async fn release_ports(&self, exec_id: &Uuid) {
         let mut map = self.port_allocations.write().await;
@@ -236,8 +358,12 @@ impl LocalContainerService {
     pub fn spawn_workspace_cleanup(&self) {
         let db = self.db.clone();
         let cleanup_expired = Self::cleanup_expired_workspaces;
+        let container = self.clone();
         tokio::spawn(async move {
             WorkspaceManager::cleanup_orphan_workspaces(&db.pool).await;
+            // Unclaimed warm worktrees of the previous run count as orphans, so the pools
+            // only start filling once those are gone
+            container.spawn_warm_pool_refill();
 
             let mut cleanup_interval =
                 tokio::time::interval(tokio::time::Duration::from_secs(1800)); // 30 minutes
@@ -251,6 +377,581 @@ impl LocalContainerService {
         });
     }
 
+    /// Sample the memory and CPU use of every running process group every few seconds
+    pub fn spawn_resource_sampler(&self) {
+        let container = self.clone();
+        tokio::spawn(async move {
+            // Session, time and CPU time of each process's previous sample
+            let mut previous = HashMap::new();
+            let mut interval = tokio::time::interval(RESOURCE_SAMPLE_INTERVAL);
+            loop {
+                interval.tick().await;
+                container.sample_resources(&mut previous).await;
+            }
+        });
+    }
+
+    async fn sample_resources(&self, previous: &mut HashMap<Uuid, (Uuid, Instant, Duration)>) {
+        let mut groups = Vec::new();
+        let child_store = self.child_store.read().await;
+        for (exec_id, child) in child_store.iter() {
+            // Skip children that are busy, such as while being stopped
+            if let Ok(child) = child.try_read()
+                && let Some(pid) = child.inner().id()
+            {
+                groups.push((*exec_id, pid));
+            }
+        }
+        previous.retain(|exec_id, _| {
+            let running = child_store.contains_key(exec_id);
+            if !running {
+                self.resource_monitor.remove(exec_id);
+            }
+            running
+        });
+        drop(child_store);
+        if groups.is_empty() {
+            return;
+        }
+
+        // Executors spawn their processes as group leaders, so the pid is also the group id
+        let pgids: Vec<u32> = groups.iter().map(|(_, pid)| *pid).collect();
+        let stats =
+            match tokio::task::spawn_blocking(move || process_stats::sample_process_groups(&pgids))
+                .await
+            {
+                Ok(Ok(stats)) => stats,
+                Ok(Err(e)) => {
+                    tracing::debug!("Failed to sample process resources: {}", e);
+                    return;
+                }
+                Err(e) => {
+                    tracing::error!("Resource sampling task failed: {}", e);
+                    return;
+                }
+            };
+
+        let now = Instant::now();
+        for (exec_id, pid) in groups {
+            let Some(group) = stats.get(&pid) else {
+                continue;
+            };
+            let (session_id, cpu_percent) = match previous.get(&exec_id) {
+                Some((session_id, sampled_at, cpu_time)) => {
+                    let elapsed = now.duration_since(*sampled_at).as_secs_f32();
+                    let used = group.cpu_time.saturating_sub(*cpu_time).as_secs_f32();
+                    (*session_id, used / elapsed.max(f32::EPSILON) * 100.0)
+                }
+                None => match ExecutionProcess::find_by_id(&self.db.pool, exec_id).await {
+                    Ok(Some(process)) => (process.session_id, 0.0),
+                    _ => continue,
+                },
+            };
+            previous.insert(exec_id, (session_id, now, group.cpu_time));
+            self.resource_monitor.record(ResourceUsage {
+                execution_process_id: exec_id,
+                session_id,
+                rss_bytes: group.rss_bytes,
+                cpu_percent,
+            });
+        }
+    }
+
+    /// Create the cgroup capping the memory and CPU of an execution, and have the process
+    /// join it before it starts
+    async fn prepare_resource_limits(
+        exec_id: Uuid,
+        limits: CgroupLimits,
+        env: &mut ExecutionEnv,
+    ) -> io::Result<ExecutionCgroup> {
+        let cgroup = ExecutionCgroup::create(exec_id, limits)?;
+        match cgroup.open_procs() {
+            Ok(procs) => {
+                env.cgroup_procs = Some(Arc::new(procs));
+                Ok(cgroup)
+            }
+            Err(e) => {
+                cgroup.remove().await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Keep the cgroup of a spawned execution. Failing to create it is reported in the
+    /// process's logs but does not stop it.
+    async fn track_resource_limits(&self, exec_id: Uuid, cgroup: io::Result<ExecutionCgroup>) {
+        match cgroup {
+            Ok(cgroup) => {
+                self.cgroups.write().await.insert(exec_id, cgroup);
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Resource limits not applied to execution {}: {}",
+                    exec_id,
+                    e
+                );
+                if let Some(store) = self.msg_stores.read().await.get(&exec_id) {
+                    store.push_stderr(format!("Resource limits not applied: {e}\n"));
+                }
+            }
+        }
+    }
+
+    /// Keep the warm worktree pools topped up, checking for moved branches periodically and
+    /// right after a worktree is claimed
+    fn spawn_warm_pool_refill(&self) {
+        let container = self.clone();
+        tokio::spawn(async move {
+            // Commit whose setup failed, by repository, so that it is not retried every pass
+            let mut failed: HashMap<Uuid, String> = HashMap::new();
+            let mut interval = tokio::time::interval(WARM_POOL_REFILL_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = container.warm_pool.refill_requested() => {}
+                }
+                container.refill_warm_pools(&mut failed).await;
+            }
+        });
+    }
+
+    async fn refill_warm_pools(&self, failed: &mut HashMap<Uuid, String>) {
+        let repos = match Repo::list_all(&self.db.pool).await {
+            Ok(repos) => repos,
+            Err(e) => {
+                tracing::warn!("Failed to load repositories for warm pools: {}", e);
+                return;
+            }
+        };
+
+        // Repositories that were removed or had their pool turned off
+        for repo_id in self.warm_pool.repo_ids() {
+            let repo = repos.iter().find(|r| r.id == repo_id);
+            if repo.is_none_or(|r| r.warm_pool_size == 0) {
+                for worktree in self.warm_pool.take_all(repo_id) {
+                    Self::discard_warm_worktree(worktree, repo).await;
+                }
+            }
+        }
+
+        for repo in repos.iter().filter(|r| r.warm_pool_size > 0) {
+            let Some(project) = self.warm_pool_project(repo).await else {
+                for worktree in self.warm_pool.take_all(repo.id) {
+                    Self::discard_warm_worktree(worktree, Some(repo)).await;
+                }
+                continue;
+            };
+            let Some(branch) = self.warm_pool_branch(&project, repo).await else {
+                continue;
+            };
+            let Ok(commit) = self.git().get_branch_oid(&repo.path, &branch) else {
+                // Deleted or unborn branch
+                continue;
+            };
+            let size = repo.warm_pool_size as usize;
+            for worktree in self
+                .warm_pool
+                .take_stale(repo.id, project.id, &branch, &commit, size)
+            {
+                Self::discard_warm_worktree(worktree, Some(repo)).await;
+            }
+            if failed.get(&repo.id) == Some(&commit) {
+                continue;
+            }
+            failed.remove(&repo.id);
+
+            while self.warm_pool.count(repo.id) < size {
+                match self
+                    .prepare_warm_worktree(repo, &project, &branch, &commit)
+                    .await
+                {
+                    Ok(worktree) => self.warm_pool.add(repo.id, worktree),
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to prepare warm worktree for repo '{}' at {}: {}",
+                            repo.name,
+                            commit,
+                            e
+                        );
+                        failed.insert(repo.id, commit.clone());
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// The project a repository's warm worktrees are prepared for. Setup scripts run with the
+    /// project's secrets, so repositories shared by several projects are not kept warm.
+    async fn warm_pool_project(&self, repo: &Repo) -> Option<Project> {
+        let project_repos = ProjectRepo::find_by_repo_id(&self.db.pool, repo.id)
+            .await
+            .ok()?;
+        let [project_repo] = project_repos.as_slice() else {
+            return None;
+        };
+        Project::find_by_id(&self.db.pool, project_repo.project_id)
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// The branch new workspaces of the repository are likely to target: the one its latest
+    /// workspace targeted, or its checked out branch before it has any
+    async fn warm_pool_branch(&self, project: &Project, repo: &Repo) -> Option<String> {
+        match WorkspaceRepo::find_latest_target_branch(&self.db.pool, project.id, repo.id).await {
+            Ok(Some(branch)) => Some(branch),
+            Ok(None) => self.git().get_current_branch(&repo.path).ok(),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to find the target branch of repo '{}': {}",
+                    repo.name,
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Check out `commit` in a new worktree, copy the repository's files into it and run its
+    /// setup script
+    async fn prepare_warm_worktree(
+        &self,
+        repo: &Repo,
+        project: &Project,
+        branch: &str,
+        commit: &str,
+    ) -> Result<WarmWorktree, ContainerError> {
+        let mut worktree = WarmWorktree {
+            dir: WorkspaceManager::get_workspace_base_dir()
+                .join(format!("warm-{}", short_uuid(&Uuid::new_v4()))),
+            project_id: project.id,
+            branch: branch.to_string(),
+            commit: commit.to_string(),
+            setup_log: Vec::new(),
+        };
+        let worktree_path = worktree.dir.join(&repo.name);
+        tokio::fs::create_dir_all(&worktree.dir).await?;
+
+        let prepared: Result<Vec<String>, ContainerError> = async {
+            let repo_path = repo.path.clone();
+            let args = [
+                "worktree".into(),
+                "add".into(),
+                "--detach".into(),
+                worktree_path.clone().into_os_string(),
+                commit.into(),
+            ];
+            tokio::task::spawn_blocking(move || GitCli::new().git(&repo_path, args))
+                .await
+                .map_err(|e| ContainerError::Other(anyhow!(e)))?
+                .map_err(|e| ContainerError::GitServiceError(e.into()))?;
+
+            let mut setup_log = Vec::new();
+            if let Some(copy_files) = repo.copy_files.as_deref()
+                && !copy_files.trim().is_empty()
+                && let Some(summary) = Self::copy_repo_files(&repo.path, &worktree_path, copy_files)
+                    .await?
+                    .describe()
+            {
+                tracing::info!(
+                    "Warm worktree for repo '{}': {}",
+                    repo.name,
+                    summary.trim_end()
+                );
+                setup_log.extend(log_line(&LogMsg::Stdout(summary)));
+            }
+            setup_log.extend(
+                self.run_warm_setup_script(repo, project, &worktree.dir)
+                    .await?,
+            );
+            Ok(setup_log)
+        }
+        .await;
+
+        match prepared {
+            Ok(setup_log) => worktree.setup_log = setup_log,
+            Err(e) => {
+                Self::discard_warm_worktree(worktree, Some(repo)).await;
+                return Err(e);
+            }
+        }
+        tracing::info!(
+            "Prepared warm worktree for repo '{}' at {}",
+            repo.name,
+            worktree.dir.display()
+        );
+        Ok(worktree)
+    }
+
+    /// Run the repository's setup script in a warm worktree through the same script action
+    /// as in a workspace, with the project's secrets and the `VK_*` variables that exist before
+    /// there is a workspace. Returns its output as log lines, secrets redacted.
+    async fn run_warm_setup_script(
+        &self,
+        repo: &Repo,
+        project: &Project,
+        dir: &Path,
+    ) -> Result<Vec<String>, ContainerError> {
+        let Some(action) = Self::setup_action_for_repo(repo) else {
+            return Ok(Vec::new());
+        };
+
+        let mut env = ExecutionEnv::new();
+        match self.secrets.env_for(project.id, &[repo.id]).await {
+            Ok(secrets) => env.merge(&secrets),
+            Err(e) => tracing::warn!(
+                "Secrets not injected into the warm setup script of repo '{}': {}",
+                repo.name,
+                e
+            ),
+        }
+        env.insert("VK_PROJECT_NAME", &project.name);
+        env.insert("VK_PROJECT_ID", project.id.to_string());
+        env.insert("VK_WARM_POOL", "1");
+
+        let store = Arc::new(MsgStore::with_redactor(
+            self.secrets.redactor_for(project.id).await,
+        ));
+        let mut spawned = action
+            .spawn(dir, Arc::new(NoopExecutorApprovalService {}), &env)
+            .await?;
+        let forwarder = Self::forward_child_output(&store, &mut spawned.child);
+        let status = match tokio::time::timeout(WARM_SETUP_TIMEOUT, spawned.child.wait()).await {
+            Ok(status) => status?,
+            Err(_) => {
+                command::kill_process_group(&mut spawned.child).await?;
+                return Err(ContainerError::Other(anyhow!("setup script timed out")));
+            }
+        };
+        // Background processes the script left behind may hold on to its output
+        let _ = tokio::time::timeout(Duration::from_secs(5), forwarder).await;
+        store.push_finished();
+
+        let history = store.get_history();
+        if !status.success() {
+            let output: String = history
+                .iter()
+                .filter_map(|msg| match msg {
+                    LogMsg::Stdout(text) | LogMsg::Stderr(text) => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect();
+            let tail: Vec<&str> = output.lines().rev().take(20).collect();
+            return Err(ContainerError::Other(anyhow!(
+                "setup script exited with {status}:\n{}",
+                tail.into_iter().rev().collect::<Vec<_>>().join("\n")
+            )));
+        }
+        Ok(history.iter().filter_map(log_line).collect())
+    }
+
+    async fn discard_warm_worktree(worktree: WarmWorktree, repo: Option<&Repo>) {
+        let result = match repo {
+            Some(repo) => {
+                WorkspaceManager::cleanup_workspace(&worktree.dir, std::slice::from_ref(repo))
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            // Without the repository only the files can go; git prunes its metadata later
+            None => tokio::fs::remove_dir_all(&worktree.dir)
+                .await
+                .map_err(|e| e.to_string()),
+        };
+        if let Err(e) = result {
+            tracing::warn!(
+                "Failed to remove warm worktree {}: {}",
+                worktree.dir.display(),
+                e
+            );
+        }
+    }
+
+    /// Take over a warm worktree for a single-repository workspace and check out the
+    /// workspace's branch in it. Returns the directory that becomes the workspace directory.
+    async fn claim_warm_worktree(
+        &self,
+        workspace: &Workspace,
+        inputs: &[RepoWorkspaceInput],
+    ) -> Option<PathBuf> {
+        let [input] = inputs else {
+            return None;
+        };
+        if input.repo.warm_pool_size == 0 {
+            return None;
+        }
+        // Templates can change which files are copied into the workspace
+        if WorkspaceTemplate::find_by_workspace_id(&self.db.pool, workspace.id)
+            .await
+            .ok()?
+            .is_some()
+        {
+            return None;
+        }
+        let project_id = workspace.parent_task(&self.db.pool).await.ok()??.project_id;
+        let commit = self
+            .git()
+            .get_branch_oid(&input.repo.path, &input.target_branch)
+            .ok()?;
+        let worktree =
+            self.warm_pool
+                .take(input.repo.id, project_id, &input.target_branch, &commit)?;
+        self.warm_pool.request_refill();
+
+        let worktree_path = worktree.dir.join(&input.repo.name);
+        let branch = workspace.branch.clone();
+        let checkout = tokio::task::spawn_blocking(move || {
+            GitCli::new().git(&worktree_path, ["checkout", "-b", branch.as_str()])
+        })
+        .await;
+        if let Err(e) = checkout
+            .map_err(|e| e.to_string())
+            .and_then(|r| r.map_err(|e| e.to_string()))
+        {
+            tracing::warn!(
+                "Failed to check out branch '{}' in warm worktree: {}",
+                workspace.branch,
+                e
+            );
+            Self::discard_warm_worktree(worktree, Some(&input.repo)).await;
+            return None;
+        }
+
+        if let Err(e) = Workspace::set_setup_completed(&self.db.pool, workspace.id).await {
+            tracing::warn!(
+                "Failed to mark setup of workspace {} completed: {}",
+                workspace.id,
+                e
+            );
+        }
+        if input.repo.setup_script.is_some() {
+            self.warm_setups.write().await.insert(
+                workspace.id,
+                WarmSetup {
+                    repo: input.repo.clone(),
+                    commit: worktree.commit.clone(),
+                    log: worktree.setup_log.clone(),
+                },
+            );
+        }
+        tracing::info!(
+            "Workspace {} claimed warm worktree {}",
+            workspace.id,
+            worktree.dir.display()
+        );
+        Some(worktree.dir)
+    }
+
+    /// Confine a coding agent's writes to its workspace and, when hosts are allow-listed, cut
+    /// it off the network except for a proxy enforcing the list
+    async fn sandbox_execution(
+        &self,
+        env: &mut ExecutionEnv,
+        exec_id: Uuid,
+        workspace: &Workspace,
+        current_dir: &Path,
+        config: &SandboxConfig,
+    ) -> Result<Option<SandboxProxy>, ContainerError> {
+        let mut writable_paths = vec![current_dir.to_path_buf(), std::env::temp_dir()];
+        // Worktrees keep their index, refs and objects in the main repository's git directory
+        let workspace_dir = self.workspace_to_current_dir(workspace);
+        for repo in WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id).await? {
+            writable_paths.extend(worktree_git_paths(&workspace_dir.join(&repo.name)));
+        }
+        writable_paths.extend(agent_state_paths());
+        writable_paths.extend(config.extra_writable_paths.iter().map(PathBuf::from));
+
+        let Some(allowed_hosts) = &config.allowed_hosts else {
+            env.sandbox = Some(SandboxPolicy {
+                writable_paths,
+                network: None,
+            });
+            return Ok(None);
+        };
+        // The MCP server talks to this server over HTTP, now only through the proxy
+        let policy = ProxyPolicy {
+            allowed_hosts: allowed_hosts.clone(),
+            local_ports: read_port_file("vibe-kanban").await.into_iter().collect(),
+        };
+        let proxy = SandboxProxy::start(
+            exec_id,
+            &std::env::temp_dir().join("vibe-kanban-sandbox"),
+            policy,
+            self.msg_stores.clone(),
+        )
+        .await
+        .map_err(|e| ContainerError::Other(anyhow!("Failed to start sandbox proxy: {e}")))?;
+        env.sandbox = Some(SandboxPolicy {
+            writable_paths,
+            network: Some(NetworkBridge {
+                proxy_socket: proxy.socket().to_path_buf(),
+            }),
+        });
+        let url = format!("http://127.0.0.1:{PROXY_PORT}");
+        for key in [
+            "HTTP_PROXY",
+            "HTTPS_PROXY",
+            "ALL_PROXY",
+            "http_proxy",
+            "https_proxy",
+            "all_proxy",
+        ] {
+            env.insert(key, &url);
+        }
+        // Nothing is reachable directly, local services included
+        for key in ["NO_PROXY", "no_proxy"] {
+            env.insert(key, "");
+        }
+        Ok(Some(proxy))
+    }
+
+    /// Point out writes the sandbox refused, which agents otherwise only see as a failing tool
+    fn spawn_sandbox_denial_watcher(&self, exec_id: Uuid) {
+        let msg_stores = self.msg_stores.clone();
+        tokio::spawn(async move {
+            let Some(mut messages) = msg_stores
+                .read()
+                .await
+                .get(&exec_id)
+                .map(|store| store.history_plus_stream())
+            else {
+                return;
+            };
+            let mut reported = HashSet::new();
+            while let Some(Ok(msg)) = messages.next().await {
+                let patch = match msg {
+                    LogMsg::JsonPatch(patch) => patch,
+                    LogMsg::Finished => break,
+                    _ => continue,
+                };
+                let Some((_, entry)) = extract_normalized_entry_from_patch(&patch) else {
+                    continue;
+                };
+                let NormalizedEntryType::ToolUse { action_type, .. } = &entry.entry_type else {
+                    continue;
+                };
+                let output = match action_type {
+                    ActionType::CommandRun {
+                        result: Some(result),
+                        ..
+                    } => result.output.as_deref().unwrap_or_default(),
+                    _ => "",
+                };
+                for line in entry.content.lines().chain(output.lines()) {
+                    let line = line.trim();
+                    if !line.contains(READ_ONLY_ERROR) || !reported.insert(line.to_string()) {
+                        continue;
+                    }
+                    if let Some(store) = msg_stores.read().await.get(&exec_id) {
+                        store.push_stderr(format!(
+                            "Sandbox blocked a write outside the workspace: {line}\n"
+                        ));
+                    }
+                }
+            }
+        });
+    }
+
     /// Record the current HEAD commit for each repository as the "after" state.
     /// Errors are silently ignored since this runs after the main execution completes
     /// and failure should not block process finalization.
@@ -384,6 +1085,8 @@ impl LocalContainerService {
         &self,
         exec_id: &Uuid,
         exit_signal: Option<ExecutorExitSignal>,
+        limits: ExecutionLimits,
+        loop_policy: LoopPolicy,
     ) -> JoinHandle<()> {
         let exec_id = *exec_id;
         let child_store = self.child_store.clone();
@@ -402,8 +1105,10 @@ impl LocalContainerService {
                 .unwrap_or_else(|| std::future::pending().boxed()); // no signal, stall forever
 
             let status_result: std::io::Result<std::process::ExitStatus>;
+            let mut timed_out = false;
+            let mut detected_loop = None;
 
-            // Wait for process to exit, or exit signal from executor
+            // Wait for process to exit, exit signal from executor, a timeout or a loop
             tokio::select! {
                 // Exit signal with result.
                 // Some coding agent processes do not automatically exit after processing the user request; instead the executor
@@ -428,9 +1133,49 @@ impl LocalContainerService {
                 exit_status_result = &mut process_exit_rx => {
                     status_result = exit_status_result.unwrap_or_else(|e| Err(std::io::Error::other(e)));
                 }
+                // The process exceeded its wall-clock or idle limit
+                timeout = container.watch_limits(exec_id, limits) => {
+                    tracing::info!("Execution process {} {}, stopping it", exec_id, timeout.description());
+                    if let Some(store) = msg_stores.read().await.get(&exec_id) {
+                        store.push_stderr(format!("\nTimed out: the process {}\n", timeout.description()));
+                    }
+                    if let Err(e) = container.interrupt_and_kill(&exec_id).await {
+                        tracing::error!("Failed to stop timed out execution process {}: {}", exec_id, e);
+                    }
+                    timed_out = true;
+                    status_result = Ok(failure_exit_status());
+                }
+                // The coding agent keeps repeating itself
+                agent_loop = container.watch_for_loops(exec_id, loop_policy) => {
+                    tracing::info!("Execution process {} is looping: the agent {}", exec_id, agent_loop.description());
+                    if let Some(store) = msg_stores.read().await.get(&exec_id) {
+                        store.push_stderr(format!("\nLoop detected: the agent {}\n", agent_loop.description()));
+                    }
+                    if let Err(e) = container.interrupt_and_kill(&exec_id).await {
+                        tracing::error!("Failed to stop looping execution process {}: {}", exec_id, e);
+                    }
+                    detected_loop = Some(agent_loop);
+                    status_result = Ok(failure_exit_status());
+                }
+            }
+
+            if let Some(cgroup) = container.cgroups.read().await.get(&exec_id)
+                && cgroup.oom_kills() > 0
+                && let Some(store) = msg_stores.read().await.get(&exec_id)
+            {
+                let limit = cgroup.limits().max_memory_mb.unwrap_or_default();
+                store.push_stderr(format!(
+                    "\nOut of memory: a process exceeded the project's {limit} MiB limit and was killed\n"
+                ));
             }
 
             let (exit_code, status) = match status_result {
+                _ if timed_out => (None, ExecutionProcessStatus::TimedOut),
+                // Only the stop policy fails the turn; otherwise it was stopped on purpose
+                _ if detected_loop.is_some() => match loop_policy {
+                    LoopPolicy::Stop => (None, ExecutionProcessStatus::Failed),
+                    _ => (None, ExecutionProcessStatus::Killed),
+                },
                 Ok(exit_status) => {
                     let code = exit_status.code().unwrap_or(-1) as i64;
                     let status = if exit_status.success() {
@@ -461,6 +1206,66 @@ impl LocalContainerService {
                     ExecutionProcessStatus::Completed
                 ) && exit_code == Some(0);
 
+                // Retry failed coding agent turns with the next executor of the profile's
+                // fallback chain when the failure looks like a rate limit or setup problem
+                let fallback_started = if matches!(
+                    ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::CodingAgent
+                ) {
+                    let failure =
+                        if matches!(ctx.execution_process.status, ExecutionProcessStatus::Failed)
+                            && detected_loop.is_none()
+                        {
+                            container.detect_agent_failure(&exec_id).await
+                        } else {
+                            None
+                        };
+                    match failure {
+                        Some(failure) => match container.try_start_fallback(&ctx, failure).await {
+                            Ok(fallback) => fallback.is_some(),
+                            Err(e) => {
+                                tracing::error!("Failed to start fallback executor: {}", e);
+                                false
+                            }
+                        },
+                        None => {
+                            container
+                                .fallback_states
+                                .write()
+                                .await
+                                .remove(&ctx.session.id);
+                            false
+                        }
+                    }
+                } else {
+                    false
+                };
+
+                if success
+                    && matches!(
+                        ctx.execution_process.run_reason,
+                        ExecutionProcessRunReason::CodingAgent
+                    )
+                {
+                    container
+                        .loop_follow_ups
+                        .write()
+                        .await
+                        .remove(&ctx.session.id);
+                }
+
+                // Point a looping agent at what it keeps doing, or leave the session waiting
+                // for the user once the corrective follow-ups are used up
+                let loop_follow_up_started = match &detected_loop {
+                    Some(agent_loop) if loop_policy == LoopPolicy::FollowUp => {
+                        container.try_start_loop_follow_up(&ctx, agent_loop).await
+                    }
+                    _ => false,
+                };
+                let loop_paused = detected_loop.is_some()
+                    && loop_policy != LoopPolicy::Stop
+                    && !loop_follow_up_started;
+
                 let cleanup_done = matches!(
                     ctx.execution_process.run_reason,
                     ExecutionProcessRunReason::CleanupScript
@@ -469,7 +1274,43 @@ impl LocalContainerService {
                     ExecutionProcessStatus::Running
                 );
 
-                if success || cleanup_done {
+                // A finished planning run waits for the plan to be approved instead of
+                // starting the execution step held in its next action
+                let plan_drafted = if success
+                    && matches!(
+                        ctx.execution_process.run_reason,
+                        ExecutionProcessRunReason::CodingAgent
+                    ) {
+                    match container.capture_plan(&ctx).await {
+                        Ok(plan) => plan.is_some(),
+                        Err(e) => {
+                            tracing::error!("Failed to store drafted plan: {}", e);
+                            false
+                        }
+                    }
+                } else {
+                    false
+                };
+                if plan_drafted {
+                    container.finalize_task(publisher.as_ref().ok(), &ctx).await;
+                }
+
+                // A planning run that failed and is not being retried or continued leaves its
+                // plan rejectable instead of drafting forever
+                if !success
+                    && !fallback_started
+                    && !loop_follow_up_started
+                    && !loop_paused
+                    && matches!(
+                        ctx.execution_process.run_reason,
+                        ExecutionProcessRunReason::CodingAgent
+                    )
+                    && let Err(e) = container.fail_plan(&ctx).await
+                {
+                    tracing::error!("Failed to mark plan as failed: {}", e);
+                }
+
+                if !plan_drafted && (success || cleanup_done) {
                     // Commit changes (if any) and get feedback about whether changes were made
                     let changes_committed = match container.try_commit_changes(&ctx).await {
                         Ok(committed) => committed,
@@ -505,16 +1346,34 @@ impl LocalContainerService {
                     }
                 }
 
-                if container.should_finalize(&ctx) {
+                if !fallback_started
+                    && !loop_follow_up_started
+                    && !plan_drafted
+                    && container.should_finalize(&ctx)
+                {
                     // Only execute queued messages if the execution succeeded
                     // If it failed or was killed, just clear the queue and finalize
-                    let should_execute_queued = !matches!(
-                        ctx.execution_process.status,
-                        ExecutionProcessStatus::Failed | ExecutionProcessStatus::Killed
-                    );
+                    // A turn paused for a loop was waiting for exactly this input
+                    let should_execute_queued = loop_paused
+                        || !matches!(
+                            ctx.execution_process.status,
+                            ExecutionProcessStatus::Failed
+                                | ExecutionProcessStatus::TimedOut
+                                | ExecutionProcessStatus::Killed
+                        );
 
-                    if let Some(queued_msg) =
-                        container.queued_message_service.take_queued(ctx.session.id)
+                    if let Some(queued_msg) = container
+                        .queued_message_service
+                        .take_queued(ctx.session.id)
+                        .await
+                        .unwrap_or_else(|e| {
+                            tracing::error!(
+                                "Failed to take queued message for session {}: {}",
+                                ctx.session.id,
+                                e
+                            );
+                            None
+                        })
                     {
                         if should_execute_queued {
                             tracing::info!(
@@ -559,6 +1418,22 @@ impl LocalContainerService {
                     }
                 }
 
+                // Paused turns are killed, which finalize_task does not notify about
+                if loop_paused && let Some(agent_loop) = &detected_loop {
+                    let title = format!("Task Paused: {}", ctx.task.title);
+                    let message = format!(
+                        "⏸️ '{}' paused because the agent {}\nBranch: {:?}\nExecutor: {:?}",
+                        ctx.task.title,
+                        agent_loop.description(),
+                        ctx.workspace.branch,
+                        ctx.session.executor
+                    );
+                    container
+                        .notification_service
+                        .notify(&title, &message)
+                        .await;
+                }
+
                 // Fire analytics event when CodingAgent execution has finished
                 if config.read().await.analytics_enabled
                     && matches!(
@@ -582,26 +1457,203 @@ impl LocalContainerService {
             // capture the HEAD OID as the definitive "after" state (best-effort).
             container.update_after_head_commits(exec_id).await;
 
-            // Cleanup msg store
-            if let Some(msg_arc) = msg_stores.write().await.remove(&exec_id) {
-                msg_arc.push_finished();
-                tokio::time::sleep(Duration::from_millis(50)).await; // Wait for the finish message to propogate
-                match Arc::try_unwrap(msg_arc) {
-                    Ok(inner) => drop(inner),
-                    Err(arc) => tracing::error!(
-                        "There are still {} strong Arcs to MsgStore for {}",
-                        Arc::strong_count(&arc),
+            // Cleanup msg store
+            if let Some(msg_arc) = msg_stores.write().await.remove(&exec_id) {
+                msg_arc.push_finished();
+                tokio::time::sleep(Duration::from_millis(50)).await; // Wait for the finish message to propogate
+                match Arc::try_unwrap(msg_arc) {
+                    Ok(inner) => drop(inner),
+                    Err(arc) => tracing::error!(
+                        "There are still {} strong Arcs to MsgStore for {}",
+                        Arc::strong_count(&arc),
+                        exec_id
+                    ),
+                }
+            }
+
+            // Cleanup child handle
+            child_store.write().await.remove(&exec_id);
+            container.resource_monitor.remove(&exec_id);
+            let cgroup = container.cgroups.write().await.remove(&exec_id);
+            if let Some(cgroup) = cgroup {
+                cgroup.remove().await;
+            }
+            container.sandbox_proxies.write().await.remove(&exec_id);
+
+            // Release allocated ports
+            container.release_ports(&exec_id).await;
+        })
+    }
+
+    /// Resolves once the execution exceeds `limits`, or never if it has none. Output in its
+    /// MsgStore resets the idle timer, and so does waiting for a tool approval.
+    async fn watch_limits(&self, exec_id: Uuid, limits: ExecutionLimits) -> ExecutionTimeout {
+        let started_at = Instant::now();
+        let mut last_activity = started_at;
+        let mut output = self
+            .msg_stores
+            .read()
+            .await
+            .get(&exec_id)
+            .map(|store| store.get_receiver());
+
+        loop {
+            let runtime_deadline = limits.max_runtime().map(|limit| started_at + limit);
+            let idle_deadline = limits.max_idle().map(|limit| last_activity + limit);
+            let Some(deadline) = runtime_deadline.into_iter().chain(idle_deadline).min() else {
+                return future::pending().await;
+            };
+
+            tokio::select! {
+                _ = tokio::time::sleep_until(deadline) => {
+                    let now = Instant::now();
+                    if let Some(limit) = limits.max_runtime()
+                        && runtime_deadline.is_some_and(|d| d <= now)
+                    {
+                        return ExecutionTimeout::Runtime(limit);
+                    }
+                    if self
+                        .approvals
+                        .get_pending_execution_process_ids(&[exec_id])
+                        .contains(&exec_id)
+                    {
+                        last_activity = now;
+                        continue;
+                    }
+                    if let Some(limit) = limits.max_idle() {
+                        return ExecutionTimeout::Idle(limit);
+                    }
+                }
+                msg = async {
+                    match output.as_mut() {
+                        Some(rx) => rx.recv().await,
+                        None => future::pending().await,
+                    }
+                } => match msg {
+                    Err(RecvError::Closed) => output = None,
+                    _ => last_activity = Instant::now(),
+                },
+            }
+        }
+    }
+
+    /// Resolves once the coding agent of the execution is caught in a loop, or never when
+    /// `policy` is off
+    async fn watch_for_loops(&self, exec_id: Uuid, policy: LoopPolicy) -> AgentLoop {
+        let messages = self
+            .msg_stores
+            .read()
+            .await
+            .get(&exec_id)
+            .map(|store| store.history_plus_stream());
+        let (Some(mut messages), false) = (messages, policy == LoopPolicy::Off) else {
+            return future::pending().await;
+        };
+
+        let mut detector = LoopDetector::new();
+        while let Some(Ok(msg)) = messages.next().await {
+            match msg {
+                LogMsg::JsonPatch(patch) => {
+                    if let Some((index, entry)) = extract_normalized_entry_from_patch(&patch)
+                        && let Some(agent_loop) = detector.observe(index, &entry)
+                    {
+                        return agent_loop;
+                    }
+                }
+                LogMsg::Finished => break,
+                _ => {}
+            }
+        }
+        future::pending().await
+    }
+
+    /// Continue the session of a looping turn with a prompt describing the loop. Returns
+    /// false when the session has used up its corrective follow-ups or cannot be continued.
+    async fn try_start_loop_follow_up(
+        &self,
+        ctx: &ExecutionContext,
+        agent_loop: &AgentLoop,
+    ) -> bool {
+        {
+            let mut follow_ups = self.loop_follow_ups.write().await;
+            let count = follow_ups.entry(ctx.session.id).or_default();
+            if *count >= MAX_LOOP_FOLLOW_UPS {
+                follow_ups.remove(&ctx.session.id);
+                return false;
+            }
+            *count += 1;
+        }
+
+        let prompt = format!(
+            "You were stopped because you {}. Repeating the same approach will not work. Step back, work out why it keeps failing, and try a different approach. If you are blocked, explain what you need instead of retrying.",
+            agent_loop.description()
+        );
+        match self.continue_agent_session(ctx, prompt).await {
+            Ok(follow_up) => follow_up.is_some(),
+            Err(e) => {
+                tracing::error!("Failed to start loop follow-up: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Ask the process to exit through its interrupt sender, then kill its process group
+    async fn interrupt_and_kill(&self, exec_id: &Uuid) -> Result<(), ContainerError> {
+        let child = self.get_child_from_store(exec_id).await.ok_or_else(|| {
+            ContainerError::Other(anyhow!("Child process not found for execution"))
+        })?;
+
+        if let Some(interrupt_sender) = self.take_interrupt_sender(exec_id).await {
+            // Send interrupt signal (ignore error if receiver dropped)
+            let _ = interrupt_sender.send(());
+
+            // Wait for graceful exit with timeout
+            let graceful_exit = {
+                let mut child_guard = child.write().await;
+                tokio::time::timeout(Duration::from_secs(5), child_guard.wait()).await
+            };
+
+            match graceful_exit {
+                Ok(Ok(_)) => {
+                    tracing::debug!("Process {} exited gracefully after interrupt", exec_id);
+                }
+                Ok(Err(e)) => {
+                    tracing::info!("Error waiting for process {}: {}", exec_id, e);
+                }
+                Err(_) => {
+                    tracing::debug!(
+                        "Graceful shutdown timed out for process {}, force killing",
                         exec_id
-                    ),
+                    );
                 }
             }
+        }
 
-            // Cleanup child handle
-            child_store.write().await.remove(&exec_id);
+        let mut child_guard = child.write().await;
+        command::kill_process_group(&mut child_guard).await
+    }
 
-            // Release allocated ports
-            container.release_ports(&exec_id).await;
-        })
+    /// Limits for an execution: a coding agent profile's own, with the ones it leaves unset
+    /// taken from the configured defaults for the run reason
+    async fn execution_limits(
+        &self,
+        run_reason: &ExecutionProcessRunReason,
+        executor_action: &ExecutorAction,
+    ) -> ExecutionLimits {
+        let defaults = self
+            .config
+            .read()
+            .await
+            .execution_limits
+            .for_run_reason(run_reason);
+        match executor_action.executor_profile_id() {
+            Some(profile) if *run_reason == ExecutionProcessRunReason::CodingAgent => {
+                ExecutorConfigs::get_cached()
+                    .limits_for(profile)
+                    .or(defaults)
+            }
+            _ => defaults,
+        }
     }
 
     pub fn spawn_os_exit_watcher(
@@ -646,9 +1698,24 @@ impl LocalContainerService {
         format!("{}-{}", short_uuid(workspace_id), task_title_id)
     }
 
-    async fn track_child_msgs_in_store(&self, id: Uuid, child: &mut AsyncGroupChild) {
-        let store = Arc::new(MsgStore::new());
+    async fn track_child_msgs_in_store(
+        &self,
+        id: Uuid,
+        child: &mut AsyncGroupChild,
+        redactor: SecretRedactor,
+    ) {
+        let store = Arc::new(MsgStore::with_redactor(redactor));
+        Self::forward_child_output(&store, child);
+
+        let mut map = self.msg_stores().write().await;
+        map.insert(id, store);
+    }
 
+    /// Forward a child's stdout and stderr into `store` until both are closed
+    fn forward_child_output(
+        store: &Arc<MsgStore>,
+        child: &mut AsyncGroupChild,
+    ) -> tokio::task::JoinHandle<()> {
         let out = child.inner().stdout.take().expect("no stdout");
         let err = child.inner().stderr.take().expect("no stderr");
 
@@ -664,10 +1731,85 @@ impl LocalContainerService {
 
         // Merge and forward into the store
         let merged = select(out, err); // Stream<Item = Result<LogMsg, io::Error>>
-        store.clone().spawn_forwarder(merged);
+        store.clone().spawn_forwarder(merged)
+    }
 
-        let mut map = self.msg_stores().write().await;
-        map.insert(id, store);
+    /// Stream a terminal's output into a MsgStore and feed it the input sent through
+    /// [`ContainerService::send_terminal_input`] until the shell exits or the terminal is closed
+    async fn spawn_terminal_monitor(
+        &self,
+        id: Uuid,
+        terminal: terminal::Terminal,
+        redactor: SecretRedactor,
+    ) -> Result<(), ContainerError> {
+        let terminal::Terminal { mut child, master } = terminal;
+        let master = Arc::new(master);
+
+        // Reads fail with EIO once the shell has exited; end the stream there
+        let output = futures::stream::unfold(master.clone(), |master| async move {
+            let mut buf = vec![0; 8192];
+            match master.read(&mut buf).await {
+                Ok(0) | Err(_) => None,
+                Ok(read) => {
+                    let chunk = String::from_utf8_lossy(&buf[..read]).into_owned();
+                    Some((Ok::<_, std::io::Error>(LogMsg::Stdout(chunk)), master))
+                }
+            }
+        });
+        let store = Arc::new(MsgStore::with_redactor(redactor));
+        store.clone().spawn_forwarder(output);
+        self.msg_stores.write().await.insert(id, store);
+
+        let (input_tx, mut input_rx) = mpsc::unbounded_channel();
+        self.terminal_inputs.write().await.insert(id, input_tx);
+
+        let db = self.db.clone();
+        let msg_stores = self.msg_stores.clone();
+        let terminal_inputs = self.terminal_inputs.clone();
+        tokio::spawn(async move {
+            let exit = loop {
+                tokio::select! {
+                    exit = child.wait() => break exit,
+                    input = input_rx.recv() => match input {
+                        Some(TerminalInput::Input { data }) => {
+                            if let Err(e) = master.write_all(data.as_bytes()).await {
+                                tracing::warn!("Failed to write to terminal {}: {}", id, e);
+                            }
+                        }
+                        Some(TerminalInput::Resize { cols, rows }) => {
+                            if let Err(e) = master.resize(cols, rows) {
+                                tracing::debug!("Failed to resize terminal {}: {}", id, e);
+                            }
+                        }
+                        None => {
+                            terminal::hang_up(&mut child).await;
+                            break child.wait().await;
+                        }
+                    },
+                }
+            };
+
+            let (status, exit_code) = match exit {
+                Ok(exit) if exit.success() => (ExecutionProcessStatus::Completed, Some(0)),
+                Ok(exit) => match exit.code() {
+                    Some(code) => (ExecutionProcessStatus::Failed, Some(code as i64)),
+                    None => (ExecutionProcessStatus::Killed, None),
+                },
+                Err(_) => (ExecutionProcessStatus::Failed, None),
+            };
+            if !ExecutionProcess::was_stopped(&db.pool, id).await
+                && let Err(e) =
+                    ExecutionProcess::update_completion(&db.pool, id, status, exit_code).await
+            {
+                tracing::error!("Failed to update terminal {} completion: {}", id, e);
+            }
+
+            terminal_inputs.write().await.remove(&id);
+            if let Some(store) = msg_stores.write().await.remove(&id) {
+                store.push_finished();
+            }
+        });
+        Ok(())
     }
 
     /// Create a live diff log stream for ongoing attempts for WebSocket
@@ -739,24 +1881,74 @@ impl LocalContainerService {
         workspace: &Workspace,
     ) -> Result<(), ContainerError> {
         let repos = WorkspaceRepo::find_repos_with_copy_files(&self.db.pool, workspace.id).await?;
+        let template = WorkspaceTemplate::find_by_workspace_id(&self.db.pool, workspace.id).await?;
 
         for repo in &repos {
-            if let Some(copy_files) = &repo.copy_files
+            // A template's copy_files override takes precedence over the repository setting
+            let copy_files = template
+                .as_ref()
+                .and_then(|t| t.copy_files_for(repo.id))
+                .or(repo.copy_files.as_deref());
+            if let Some(copy_files) = copy_files
                 && !copy_files.trim().is_empty()
             {
                 let worktree_path = workspace_dir.join(&repo.name);
-                self.copy_project_files(&repo.path, &worktree_path, copy_files)
-                    .await
-                    .unwrap_or_else(|e| {
+                match Self::copy_repo_files(&repo.path, &worktree_path, copy_files).await {
+                    Ok(summary) => {
+                        if let Some(summary) = summary.describe() {
+                            tracing::info!(
+                                "Workspace {} repo '{}': {}",
+                                workspace.id,
+                                repo.name,
+                                summary.trim_end()
+                            );
+                            // Also shown at the top of the setup script log
+                            if repo.setup_script.is_some() {
+                                self.copy_summaries
+                                    .write()
+                                    .await
+                                    .insert((workspace.id, repo.name.clone()), summary);
+                            }
+                        }
+                    }
+                    Err(e) => {
                         tracing::warn!(
                             "Failed to copy project files for repo '{}': {}",
                             repo.name,
                             e
                         );
-                    });
+                    }
+                }
             }
         }
 
+        self.copy_task_images(workspace_dir, workspace).await;
+
+        Ok(())
+    }
+
+    /// Copy a repository's `copy_files` into its worktree, with the strategies of the patterns
+    async fn copy_repo_files(
+        source_dir: &Path,
+        target_dir: &Path,
+        copy_files: &str,
+    ) -> Result<CopySummary, ContainerError> {
+        let source_dir = source_dir.to_path_buf();
+        let target_dir = target_dir.to_path_buf();
+        let copy_files = copy_files.to_string();
+
+        tokio::time::timeout(
+            std::time::Duration::from_secs(30),
+            tokio::task::spawn_blocking(move || {
+                copy::copy_project_files_impl(&source_dir, &target_dir, &copy_files)
+            }),
+        )
+        .await
+        .map_err(|_| ContainerError::Other(anyhow!("Copy project files timed out after 30s")))?
+        .map_err(|e| ContainerError::Other(anyhow!("Copy files task failed: {e}")))?
+    }
+
+    async fn copy_task_images(&self, workspace_dir: &Path, workspace: &Workspace) {
         if let Err(e) = self
             .image_service
             .copy_images_by_task_to_worktree(
@@ -768,8 +1960,6 @@ impl LocalContainerService {
         {
             tracing::warn!("Failed to copy task images to workspace: {}", e);
         }
-
-        Ok(())
     }
 
     /// Create workspace-level CLAUDE.md and AGENTS.md files that import from each repo.
@@ -907,6 +2097,16 @@ impl LocalContainerService {
     }
 }
 
+/// A persisted execution process log line for a stdout or stderr message
+fn log_line(msg: &LogMsg) -> Option<String> {
+    match msg {
+        LogMsg::Stdout(_) | LogMsg::Stderr(_) => serde_json::to_string(msg)
+            .ok()
+            .map(|line| format!("{line}\n")),
+        _ => None,
+    }
+}
+
 fn failure_exit_status() -> std::process::ExitStatus {
     #[cfg(unix)]
     {
@@ -942,10 +2142,26 @@ impl ContainerService for LocalContainerService {
         &self.notification_service
     }
 
+    fn queued_message_service(&self) -> &QueuedMessageService {
+        &self.queued_message_service
+    }
+
+    fn resource_monitor(&self) -> &ResourceMonitor {
+        &self.resource_monitor
+    }
+
+    fn fallback_states(&self) -> &Arc<RwLock<HashMap<Uuid, FallbackState>>> {
+        &self.fallback_states
+    }
+
     async fn git_branch_prefix(&self) -> String {
         self.config.read().await.git_branch_prefix.clone()
     }
 
+    async fn resume_interrupted_sessions(&self) -> bool {
+        self.config.read().await.resume_interrupted_sessions
+    }
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
         PathBuf::from(workspace.container_ref.clone().unwrap_or_default())
     }
@@ -984,35 +2200,42 @@ impl ContainerService for LocalContainerService {
             })
             .collect();
 
-        let created_workspace = WorkspaceManager::create_workspace(
-            &workspace_dir,
-            &workspace_inputs,
-            &workspace.branch,
-        )
-        .await?;
+        let workspace_dir = match self.claim_warm_worktree(workspace, &workspace_inputs).await {
+            Some(warm_dir) => {
+                // Project files were copied when the worktree was prepared
+                self.copy_task_images(&warm_dir, workspace).await;
+                warm_dir
+            }
+            None => {
+                let created_workspace = WorkspaceManager::create_workspace(
+                    &workspace_dir,
+                    &workspace_inputs,
+                    &workspace.branch,
+                )
+                .await?;
 
-        // Copy project files and images to workspace
-        self.copy_files_and_images(&created_workspace.workspace_dir, workspace)
-            .await?;
+                // Copy project files and images to workspace
+                self.copy_files_and_images(&created_workspace.workspace_dir, workspace)
+                    .await?;
+                created_workspace.workspace_dir
+            }
+        };
 
-        Self::create_workspace_config_files(&created_workspace.workspace_dir, &repositories)
-            .await?;
+        Self::create_workspace_config_files(&workspace_dir, &repositories).await?;
 
         Workspace::update_container_ref(
             &self.db.pool,
             workspace.id,
-            &created_workspace.workspace_dir.to_string_lossy(),
+            &workspace_dir.to_string_lossy(),
         )
         .await?;
 
-        Ok(created_workspace
-            .workspace_dir
-            .to_string_lossy()
-            .to_string())
+        Ok(workspace_dir.to_string_lossy().to_string())
     }
 
     async fn delete(&self, workspace: &Workspace) -> Result<(), ContainerError> {
         self.try_stop(workspace, true).await;
+        self.dev_ports.write().await.remove(&workspace.id);
         Self::cleanup_workspace(&self.db, workspace).await;
         Ok(())
     }
@@ -1064,6 +2287,55 @@ impl ContainerService for LocalContainerService {
         Ok(workspace_dir.to_string_lossy().to_string())
     }
 
+    async fn dev_process_ports(&self, workspace_id: Uuid) -> HashMap<String, u16> {
+        self.dev_ports
+            .read()
+            .await
+            .get(&workspace_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    async fn record_warm_setup(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+    ) -> Result<(), ContainerError> {
+        let Some(setup) = self.warm_setups.write().await.remove(&workspace.id) else {
+            return Ok(());
+        };
+        let Some(executor_action) = Self::setup_action_for_repo(&setup.repo) else {
+            return Ok(());
+        };
+        let process = ExecutionProcess::create(
+            &self.db.pool,
+            &CreateExecutionProcess {
+                session_id: session.id,
+                executor_action,
+                run_reason: ExecutionProcessRunReason::SetupScript,
+            },
+            Uuid::new_v4(),
+            &[CreateExecutionProcessRepoState {
+                repo_id: setup.repo.id,
+                before_head_commit: Some(setup.commit.clone()),
+                after_head_commit: Some(setup.commit),
+                merge_commit: None,
+            }],
+        )
+        .await?;
+        for line in &setup.log {
+            ExecutionProcessLogs::append_log_line(&self.db.pool, process.id, line).await?;
+        }
+        ExecutionProcess::update_completion(
+            &self.db.pool,
+            process.id,
+            ExecutionProcessStatus::Completed,
+            Some(0),
+        )
+        .await?;
+        Ok(())
+    }
+
     async fn is_container_clean(&self, workspace: &Workspace) -> Result<bool, ContainerError> {
         let Some(container_ref) = &workspace.container_ref else {
             return Ok(true);
@@ -1134,37 +2406,160 @@ impl ContainerService for LocalContainerService {
             .await?
             .ok_or(ContainerError::Other(anyhow!("Project not found for task")))?;
 
+        // Template env vars come first so the VK_* variables below cannot be overridden
+        if let Some(template) =
+            WorkspaceTemplate::find_by_workspace_id(&self.db.pool, workspace.id).await?
+        {
+            env.merge(&template.env);
+        }
+
+        // Project and repository secrets, masked in this process's logs below
+        let repo_ids: Vec<Uuid> = WorkspaceRepo::find_by_workspace_id(&self.db.pool, workspace.id)
+            .await?
+            .into_iter()
+            .map(|wr| wr.repo_id)
+            .collect();
+        match self.secrets.env_for(project.id, &repo_ids).await {
+            Ok(secrets) => env.merge(&secrets),
+            Err(e) => tracing::warn!(
+                "Secrets not injected into execution {}: {}",
+                execution_process.id,
+                e
+            ),
+        }
+        let redactor = self.secrets.redactor_for(project.id).await;
+
         env.insert("VK_PROJECT_NAME", &project.name);
         env.insert("VK_PROJECT_ID", project.id.to_string());
         env.insert("VK_TASK_ID", task.id.to_string());
         env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
         env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
 
-        // Allocate and inject unique ports for dev server processes
+        // Allocate and inject unique ports for dev server processes. Named dev processes
+        // see the ports of all their siblings so they can reach each other.
         if execution_process.run_reason == ExecutionProcessRunReason::DevServer {
-            let ports = self
-                .allocate_ports_for_execution(execution_process.id)
+            if let ExecutorActionType::ScriptRequest(ScriptRequest {
+                dev_process: Some(name),
+                ..
+            }) = executor_action.typ()
+            {
+                let ports = self.allocate_dev_process_ports(workspace).await?;
+                for (process, port) in &ports {
+                    env.insert(dev_processes::port_env_var(process), port.to_string());
+                }
+                if let Some(port) = ports.get(name) {
+                    env.insert("PORT", port.to_string());
+                }
+            } else {
+                let ports = self
+                    .allocate_ports_for_execution(execution_process.id)
+                    .await?;
+                env.insert("FRONTEND_PORT", ports.frontend.to_string());
+                env.insert("BACKEND_PORT", ports.backend.to_string());
+                env.insert("PORT", ports.frontend.to_string());
+            }
+        }
+
+        let sandbox_config = self.config.read().await.sandbox.clone();
+        let sandboxed = execution_process.run_reason == ExecutionProcessRunReason::CodingAgent
+            && sandbox_config.enabled;
+        let sandbox_proxy = if sandboxed {
+            self.sandbox_execution(
+                &mut env,
+                execution_process.id,
+                workspace,
+                &current_dir,
+                &sandbox_config,
+            )
+            .await?
+        } else {
+            None
+        };
+
+        if execution_process.run_reason == ExecutionProcessRunReason::Terminal {
+            let ExecutorActionType::ScriptRequest(request) = executor_action.typ() else {
+                return Err(ContainerError::Other(anyhow!(
+                    "Terminal processes must run a shell"
+                )));
+            };
+            let terminal = terminal::spawn(
+                Path::new(&request.script),
+                &self.workspace_to_current_dir(workspace),
+                &env,
+            )?;
+            self.spawn_terminal_monitor(execution_process.id, terminal, redactor)
                 .await?;
-            env.insert("FRONTEND_PORT", ports.frontend.to_string());
-            env.insert("BACKEND_PORT", ports.backend.to_string());
-            env.insert("PORT", ports.frontend.to_string());
+            return Ok(());
         }
 
+        let resource_limits = CgroupLimits {
+            max_memory_mb: project.max_memory_mb,
+            max_cpu_percent: project.max_cpu_percent,
+        };
+        let cgroup = if resource_limits.is_empty() {
+            None
+        } else {
+            Some(
+                Self::prepare_resource_limits(execution_process.id, resource_limits, &mut env)
+                    .await,
+            )
+        };
+
         // Create the child and stream, add to execution tracker with timeout
-        let mut spawned = tokio::time::timeout(
+        let spawned = match tokio::time::timeout(
             Duration::from_secs(30),
             executor_action.spawn(&current_dir, approvals_service, &env),
         )
         .await
-        .map_err(|_| {
-            ContainerError::Other(anyhow!(
+        {
+            Ok(result) => result.map_err(ContainerError::from),
+            Err(_) => Err(ContainerError::Other(anyhow!(
                 "Timeout: process took more than 30 seconds to start"
-            ))
-        })??;
+            ))),
+        };
+        let mut spawned = match spawned {
+            Ok(spawned) => spawned,
+            Err(e) => {
+                if let Some(Ok(cgroup)) = cgroup {
+                    cgroup.remove().await;
+                }
+                return Err(e);
+            }
+        };
 
-        self.track_child_msgs_in_store(execution_process.id, &mut spawned.child)
+        self.track_child_msgs_in_store(execution_process.id, &mut spawned.child, redactor)
             .await;
 
+        if let ExecutorActionType::ScriptRequest(ScriptRequest {
+            context: ScriptContext::SetupScript,
+            working_dir: Some(repo_name),
+            ..
+        }) = executor_action.typ()
+            && let Some(summary) = self
+                .copy_summaries
+                .write()
+                .await
+                .remove(&(workspace.id, repo_name.clone()))
+            && let Some(store) = self.msg_stores.read().await.get(&execution_process.id)
+        {
+            store.push_stdout(summary);
+        }
+
+        if sandboxed {
+            self.spawn_sandbox_denial_watcher(execution_process.id);
+        }
+        if let Some(proxy) = sandbox_proxy {
+            self.sandbox_proxies
+                .write()
+                .await
+                .insert(execution_process.id, proxy);
+        }
+
+        if let Some(cgroup) = cgroup {
+            self.track_resource_limits(execution_process.id, cgroup)
+                .await;
+        }
+
         self.add_child_to_store(execution_process.id, spawned.child)
             .await;
 
@@ -1174,23 +2569,63 @@ impl ContainerService for LocalContainerService {
                 .await;
         }
 
-        // Spawn unified exit monitor: watches OS exit and optional executor signal
-        let _hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
+        // Spawn unified exit monitor: watches OS exit, optional executor signal and timeouts
+        let limits = self
+            .execution_limits(&execution_process.run_reason, executor_action)
+            .await;
+        let loop_policy = match execution_process.run_reason {
+            ExecutionProcessRunReason::CodingAgent => project.loop_policy,
+            _ => LoopPolicy::Off,
+        };
+        let _hn = self.spawn_exit_monitor(
+            &execution_process.id,
+            spawned.exit_signal,
+            limits,
+            loop_policy,
+        );
 
         Ok(())
     }
 
+    async fn send_terminal_input(
+        &self,
+        execution_process_id: &Uuid,
+        input: TerminalInput,
+    ) -> Result<(), ContainerError> {
+        self.terminal_inputs
+            .read()
+            .await
+            .get(execution_process_id)
+            .ok_or_else(|| ContainerError::Other(anyhow!("Terminal is not running")))?
+            .send(input)
+            .map_err(|_| ContainerError::Other(anyhow!("Terminal is not running")))
+    }
+
     async fn stop_execution(
         &self,
         execution_process: &ExecutionProcess,
         status: ExecutionProcessStatus,
     ) -> Result<(), ContainerError> {
-        let child = self
+        if execution_process.run_reason == ExecutionProcessRunReason::Terminal {
+            ExecutionProcess::update_completion(&self.db.pool, execution_process.id, status, None)
+                .await?;
+            // Dropping the input sender makes the terminal monitor hang up the shell
+            self.terminal_inputs
+                .write()
+                .await
+                .remove(&execution_process.id);
+            return Ok(());
+        }
+
+        if self
             .get_child_from_store(&execution_process.id)
             .await
-            .ok_or_else(|| {
-                ContainerError::Other(anyhow!("Child process not found for execution"))
-            })?;
+            .is_none()
+        {
+            return Err(ContainerError::Other(anyhow!(
+                "Child process not found for execution"
+            )));
+        }
         let exit_code = if status == ExecutionProcessStatus::Completed {
             Some(0)
         } else {
@@ -1200,47 +2635,14 @@ impl ContainerService for LocalContainerService {
         ExecutionProcess::update_completion(&self.db.pool, execution_process.id, status, exit_code)
             .await?;
 
-        // Try graceful interrupt first, then force kill
-        if let Some(interrupt_sender) = self.take_interrupt_sender(&execution_process.id).await {
-            // Send interrupt signal (ignore error if receiver dropped)
-            let _ = interrupt_sender.send(());
-
-            // Wait for graceful exit with timeout
-            let graceful_exit = {
-                let mut child_guard = child.write().await;
-                tokio::time::timeout(Duration::from_secs(5), child_guard.wait()).await
-            };
-
-            match graceful_exit {
-                Ok(Ok(_)) => {
-                    tracing::debug!(
-                        "Process {} exited gracefully after interrupt",
-                        execution_process.id
-                    );
-                }
-                Ok(Err(e)) => {
-                    tracing::info!("Error waiting for process {}: {}", execution_process.id, e);
-                }
-                Err(_) => {
-                    tracing::debug!(
-                        "Graceful shutdown timed out for process {}, force killing",
-                        execution_process.id
-                    );
-                }
-            }
-        }
-
-        // Kill the child process and remove from the store
-        {
-            let mut child_guard = child.write().await;
-            if let Err(e) = command::kill_process_group(&mut child_guard).await {
-                tracing::error!(
-                    "Failed to stop execution process {}: {}",
-                    execution_process.id,
-                    e
-                );
-                return Err(e);
-            }
+        // Try graceful interrupt first, then force kill, and remove the child from the store
+        if let Err(e) = self.interrupt_and_kill(&execution_process.id).await {
+            tracing::error!(
+                "Failed to stop execution process {}: {}",
+                execution_process.id,
+                e
+            );
+            return Err(e);
         }
         self.remove_child_from_store(&execution_process.id).await;
         self.release_ports(&execution_process.id).await;
@@ -1395,19 +2797,9 @@ impl ContainerService for LocalContainerService {
         target_dir: &Path,
         copy_files: &str,
     ) -> Result<(), ContainerError> {
-        let source_dir = source_dir.to_path_buf();
-        let target_dir = target_dir.to_path_buf();
-        let copy_files = copy_files.to_string();
-
-        tokio::time::timeout(
-            std::time::Duration::from_secs(30),
-            tokio::task::spawn_blocking(move || {
-                copy::copy_project_files_impl(&source_dir, &target_dir, &copy_files)
-            }),
-        )
-        .await
-        .map_err(|_| ContainerError::Other(anyhow!("Copy project files timed out after 30s")))?
-        .map_err(|e| ContainerError::Other(anyhow!("Copy files task failed: {e}")))?
+        Self::copy_repo_files(source_dir, target_dir, copy_files)
+            .await
+            .map(|_| ())
     }
 
     async fn kill_all_running_processes(&self) -> Result<(), ContainerError> {
@@ -1,40 +1,121 @@
+//! Copying `copy_files` patterns from a repository into its worktrees.
+//!
+//! A pattern may start with a strategy: `reflink:`, `hardlink:`, `symlink:` or `copy:`, which
+//! is the default. Reflinks and hardlinks share file data with the repository instead of
+//! duplicating it, and a `symlink:` directory becomes a single link. Files whose strategy the
+//! filesystem does not support are copied.
+
 use std::{
     collections::HashSet,
-    fs,
+    fs, io,
     path::{Path, PathBuf},
 };
 
 use anyhow::anyhow;
 use globwalk::GlobWalkerBuilder;
 use services::services::container::ContainerError;
+use utils::path::dir_size;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CopyStrategy {
+    Copy,
+    /// Copy-on-write clone (`FICLONE`), supported by btrfs and XFS on Linux
+    Reflink,
+    Hardlink,
+    Symlink,
+}
+
+impl CopyStrategy {
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        match prefix.trim().to_ascii_lowercase().as_str() {
+            "copy" => Some(Self::Copy),
+            "reflink" => Some(Self::Reflink),
+            "hardlink" => Some(Self::Hardlink),
+            "symlink" => Some(Self::Symlink),
+            _ => None,
+        }
+    }
+}
+
+/// What a copy did, for the setup log
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CopySummary {
+    pub copied_files: u64,
+    /// Files and directories reflinked, hardlinked or symlinked instead of copied
+    pub shared: u64,
+    pub bytes_saved: u64,
+    /// Files copied because the filesystem does not support their strategy
+    pub fallbacks: u64,
+}
+
+impl CopySummary {
+    /// A log line, unless every pattern was a plain copy
+    pub(crate) fn describe(&self) -> Option<String> {
+        if self.shared == 0 && self.fallbacks == 0 {
+            return None;
+        }
+        let mut line = format!(
+            "Copy files: copied {} files and shared {} entries with the repository, saving {:.1} MiB of disk",
+            self.copied_files,
+            self.shared,
+            self.bytes_saved as f64 / (1024.0 * 1024.0)
+        );
+        if self.fallbacks > 0 {
+            line.push_str(&format!(
+                "; {} files could not be shared on this filesystem and were copied",
+                self.fallbacks
+            ));
+        }
+        line.push('\n');
+        Some(line)
+    }
+}
 
 /// Normalize pattern for cross-platform glob matching (convert backslashes to forward slashes)
 fn normalize_pattern(pattern: &str) -> String {
     pattern.replace('\\', "/")
 }
 
+/// Split a leading `<strategy>:` off a pattern
+fn parse_pattern(entry: &str) -> (CopyStrategy, &str) {
+    entry
+        .split_once(':')
+        .and_then(|(prefix, pattern)| {
+            CopyStrategy::from_prefix(prefix).map(|strategy| (strategy, pattern.trim()))
+        })
+        .unwrap_or((CopyStrategy::Copy, entry))
+}
+
 /// Copy project files from source to target directory based on glob patterns.
 /// Skips files that already exist at target with same size.
 pub(crate) fn copy_project_files_impl(
     source_dir: &Path,
     target_dir: &Path,
     copy_files: &str,
-) -> Result<(), ContainerError> {
-    let patterns: Vec<&str> = copy_files
+) -> Result<CopySummary, ContainerError> {
+    let patterns: Vec<(CopyStrategy, &str)> = copy_files
         .split(',')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
+        .map(|s| parse_pattern(s.trim()))
+        .filter(|(_, s)| !s.is_empty())
         .collect();
 
     // Track files to avoid duplicates
     let mut seen = HashSet::new();
+    let mut summary = CopySummary::default();
 
-    for pattern in patterns {
+    for (mut strategy, pattern) in patterns {
         let pattern = normalize_pattern(pattern);
         let pattern_path = source_dir.join(&pattern);
 
         if pattern_path.is_file() {
-            if let Err(e) = copy_single_file(&pattern_path, source_dir, target_dir, &mut seen) {
+            if let Err(e) = copy_single_file(
+                &pattern_path,
+                source_dir,
+                target_dir,
+                strategy,
+                &mut seen,
+                &mut summary,
+            ) {
                 tracing::warn!(
                     "Failed to copy file {} (from {}): {}",
                     pattern,
@@ -45,6 +126,20 @@ pub(crate) fn copy_project_files_impl(
             continue;
         }
 
+        if strategy == CopyStrategy::Symlink && pattern_path.is_dir() {
+            match symlink_directory(&pattern_path, source_dir, target_dir, &mut summary) {
+                Ok(()) => continue,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to symlink directory {}, copying it instead: {}",
+                        pattern,
+                        e
+                    );
+                    strategy = CopyStrategy::Copy;
+                }
+            }
+        }
+
         let glob_pattern = if pattern_path.is_dir() {
             // For directories, append /** to match all contents recursively
             format!("{pattern}/**")
@@ -64,20 +159,29 @@ pub(crate) fn copy_project_files_impl(
         };
 
         for entry in walker.flatten() {
-            if let Err(e) = copy_single_file(entry.path(), source_dir, target_dir, &mut seen) {
+            if let Err(e) = copy_single_file(
+                entry.path(),
+                source_dir,
+                target_dir,
+                strategy,
+                &mut seen,
+                &mut summary,
+            ) {
                 tracing::warn!("Failed to copy file {:?}: {e}", entry.path());
             }
         }
     }
 
-    Ok(())
+    Ok(summary)
 }
 
 fn copy_single_file(
     source_file: &Path,
     source_root: &Path,
     target_root: &Path,
+    strategy: CopyStrategy,
     seen: &mut HashSet<PathBuf>,
+    summary: &mut CopySummary,
 ) -> Result<bool, ContainerError> {
     let canonical_source = source_root.canonicalize()?;
     let canonical_file = source_file.canonicalize()?;
@@ -109,11 +213,128 @@ fn copy_single_file(
     {
         fs::create_dir_all(parent)?;
     }
-    fs::copy(source_file, &target_file)?;
+    place_file(&canonical_file, &target_file, strategy, summary)?;
 
     Ok(true)
 }
 
+/// Put `source` at `target` with `strategy`, copying it if the filesystem does not support that
+fn place_file(
+    source: &Path,
+    target: &Path,
+    strategy: CopyStrategy,
+    summary: &mut CopySummary,
+) -> io::Result<()> {
+    let shared = match strategy {
+        CopyStrategy::Copy => None,
+        CopyStrategy::Reflink => Some(reflink(source, target)),
+        CopyStrategy::Hardlink => Some(fs::hard_link(source, target)),
+        CopyStrategy::Symlink => Some(symlink_file(source, target)),
+    };
+    match shared {
+        Some(Ok(())) => {
+            summary.shared += 1;
+            summary.bytes_saved += fs::metadata(source)?.len();
+            return Ok(());
+        }
+        Some(Err(e)) => {
+            tracing::debug!(
+                "Cannot {:?} {}, copying it instead: {}",
+                strategy,
+                source.display(),
+                e
+            );
+            summary.fallbacks += 1;
+        }
+        None => {}
+    }
+    fs::copy(source, target)?;
+    summary.copied_files += 1;
+    Ok(())
+}
+
+/// Link the target's copy of a source directory to the directory itself
+fn symlink_directory(
+    source_dir: &Path,
+    source_root: &Path,
+    target_root: &Path,
+    summary: &mut CopySummary,
+) -> Result<(), ContainerError> {
+    let canonical_dir = source_dir.canonicalize()?;
+    let canonical_root = source_root.canonicalize()?;
+    let relative_path = canonical_dir.strip_prefix(&canonical_root).map_err(|_| {
+        ContainerError::Other(anyhow!(
+            "Directory {source_dir:?} is outside project directory"
+        ))
+    })?;
+    if relative_path.as_os_str().is_empty() {
+        return Err(ContainerError::Other(anyhow!(
+            "Cannot symlink the project directory itself"
+        )));
+    }
+
+    let target = target_root.join(relative_path);
+    if target.symlink_metadata().is_ok() {
+        return Ok(());
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    symlink_dir(&canonical_dir, &target)?;
+    summary.shared += 1;
+    summary.bytes_saved += dir_size(&canonical_dir);
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn reflink(source: &Path, target: &Path) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    // FICLONE from linux/fs.h, `_IOW(0x94, 9, int)`
+    nix::ioctl_write_int!(ficlone, 0x94, 9);
+
+    let source_file = fs::File::open(source)?;
+    let target_file = fs::File::create_new(target)?;
+    // SAFETY: both descriptors are open for the duration of the call
+    let cloned = unsafe { ficlone(target_file.as_raw_fd(), source_file.as_raw_fd() as _) };
+    let result = cloned
+        .map_err(io::Error::from)
+        .and_then(|_| target_file.set_permissions(source_file.metadata()?.permissions()));
+    if result.is_err() {
+        drop(target_file);
+        let _ = fs::remove_file(target);
+    }
+    result
+}
+
+#[cfg(not(target_os = "linux"))]
+fn reflink(_source: &Path, _target: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "reflinks are only supported on Linux",
+    ))
+}
+
+#[cfg(unix)]
+fn symlink_file(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(source, target)
+}
+
+#[cfg(unix)]
+fn symlink_dir(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(source, target)
+}
+
+#[cfg(windows)]
+fn symlink_file(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(source, target)
+}
+
+#[cfg(windows)]
+fn symlink_dir(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_dir(source, target)
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -309,6 +530,105 @@ mod tests {
         assert_eq!(fs::read_to_string(target_file).unwrap(), "library code");
     }
 
+    #[test]
+    fn test_parse_pattern_strategy_prefix() {
+        assert_eq!(
+            parse_pattern("hardlink: node_modules"),
+            (CopyStrategy::Hardlink, "node_modules")
+        );
+        assert_eq!(
+            parse_pattern("Reflink:target"),
+            (CopyStrategy::Reflink, "target")
+        );
+        assert_eq!(parse_pattern(".env"), (CopyStrategy::Copy, ".env"));
+        assert_eq!(
+            parse_pattern("config:local.json"),
+            (CopyStrategy::Copy, "config:local.json")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hardlink_strategy_shares_files() {
+        use std::os::unix::fs::MetadataExt;
+        let source_dir = TempDir::new().unwrap();
+        let target_dir = TempDir::new().unwrap();
+
+        let deps_dir = source_dir.path().join("node_modules/pkg");
+        fs::create_dir_all(&deps_dir).unwrap();
+        fs::write(deps_dir.join("index.js"), "module").unwrap();
+
+        let summary = copy_project_files_impl(
+            source_dir.path(),
+            target_dir.path(),
+            "hardlink:node_modules",
+        )
+        .unwrap();
+
+        let source = fs::metadata(deps_dir.join("index.js")).unwrap();
+        let target = fs::metadata(target_dir.path().join("node_modules/pkg/index.js")).unwrap();
+        assert_eq!(source.ino(), target.ino());
+        assert_eq!(summary.shared, 1);
+        assert_eq!(summary.bytes_saved, 6);
+        assert!(summary.describe().is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_strategy_links_whole_directory() {
+        let source_dir = TempDir::new().unwrap();
+        let target_dir = TempDir::new().unwrap();
+
+        let venv_dir = source_dir.path().join(".venv/lib");
+        fs::create_dir_all(&venv_dir).unwrap();
+        fs::write(venv_dir.join("site.py"), "python").unwrap();
+
+        let summary =
+            copy_project_files_impl(source_dir.path(), target_dir.path(), "symlink:.venv").unwrap();
+
+        let link = target_dir.path().join(".venv");
+        assert!(link.symlink_metadata().unwrap().file_type().is_symlink());
+        assert_eq!(
+            fs::read_to_string(link.join("lib/site.py")).unwrap(),
+            "python"
+        );
+        assert_eq!(summary.shared, 1);
+        assert_eq!(summary.bytes_saved, 6);
+    }
+
+    #[test]
+    fn test_reflink_strategy_copies_when_unsupported() {
+        let source_dir = TempDir::new().unwrap();
+        let target_dir = TempDir::new().unwrap();
+
+        fs::write(source_dir.path().join("data.bin"), "data").unwrap();
+
+        let summary =
+            copy_project_files_impl(source_dir.path(), target_dir.path(), "reflink:data.bin")
+                .unwrap();
+
+        // Whether the temp directory supports reflinks depends on its filesystem
+        assert_eq!(summary.shared + summary.fallbacks, 1);
+        assert_eq!(
+            fs::read_to_string(target_dir.path().join("data.bin")).unwrap(),
+            "data"
+        );
+    }
+
+    #[test]
+    fn test_plain_copy_has_no_summary() {
+        let source_dir = TempDir::new().unwrap();
+        let target_dir = TempDir::new().unwrap();
+
+        fs::write(source_dir.path().join(".env"), "secret").unwrap();
+
+        let summary =
+            copy_project_files_impl(source_dir.path(), target_dir.path(), ".env").unwrap();
+
+        assert_eq!(summary.copied_files, 1);
+        assert_eq!(summary.describe(), None);
+    }
+
     #[cfg(unix)]
     #[test]
     fn test_symlink_loop_is_skipped() {
@@ -15,26 +15,35 @@ use services::services::{
     filesystem::FilesystemService,
     git::GitService,
     image::ImageService,
+    log_compaction::LogCompactionService,
     oauth_credentials::OAuthCredentials,
     pr_monitor::PrMonitorService,
     project::ProjectService,
     queued_message::QueuedMessageService,
     remote_client::{RemoteClient, RemoteClientError},
     repo::RepoService,
+    secrets::SecretsService,
     share::{ShareConfig, SharePublisher},
+    worktree_gc::WorktreeGcService,
 };
 use tokio::sync::RwLock;
 use utils::{
     api::oauth::LoginStatus,
-    assets::{config_path, credentials_path},
+    assets::{config_path, credentials_path, secrets_key_path, secrets_path},
     msg_store::MsgStore,
 };
 use uuid::Uuid;
 
 use crate::container::LocalContainerService;
+mod cgroup;
 mod command;
 pub mod container;
 mod copy;
+mod preview_proxy;
+mod process_stats;
+mod sandbox_proxy;
+mod terminal;
+mod warm_pool;
 
 #[derive(Clone)]
 pub struct LocalDeployment {
@@ -52,6 +61,7 @@ pub struct LocalDeployment {
     file_search_cache: Arc<FileSearchCache>,
     approvals: Approvals,
     queued_message_service: QueuedMessageService,
+    secrets: SecretsService,
     share_publisher: Result<SharePublisher, RemoteClientNotConfigured>,
     share_config: Option<ShareConfig>,
     remote_client: Result<RemoteClient, RemoteClientNotConfigured>,
@@ -127,7 +137,15 @@ impl Deployment for LocalDeployment {
         }
 
         let approvals = Approvals::new(msg_stores.clone());
-        let queued_message_service = QueuedMessageService::new();
+        let queued_message_service = QueuedMessageService::new(db.clone().pool);
+        if let Err(e) = queued_message_service.load().await {
+            tracing::error!("Failed to restore queued messages: {}", e);
+        }
+
+        let secrets = SecretsService::new(secrets_path(), secrets_key_path());
+        if let Err(e) = secrets.load().await {
+            tracing::error!("Failed to unlock the secrets vault: {}", e);
+        }
 
         let share_config = ShareConfig::from_env();
 
@@ -182,6 +200,7 @@ impl Deployment for LocalDeployment {
             analytics_ctx,
             approvals.clone(),
             queued_message_service.clone(),
+            secrets.clone(),
             share_publisher.clone(),
         )
         .await;
@@ -205,6 +224,7 @@ impl Deployment for LocalDeployment {
             file_search_cache,
             approvals,
             queued_message_service,
+            secrets,
             share_publisher,
             share_config: share_config.clone(),
             remote_client,
@@ -271,6 +291,10 @@ impl Deployment for LocalDeployment {
         &self.queued_message_service
     }
 
+    fn secrets(&self) -> &SecretsService {
+        &self.secrets
+    }
+
     fn share_publisher(&self) -> Result<SharePublisher, RemoteClientNotConfigured> {
         self.share_publisher.clone()
     }
@@ -294,6 +318,38 @@ impl Deployment for LocalDeployment {
         let config = self.config().clone();
         PrMonitorService::spawn(db, analytics, publisher, git, container, config).await
     }
+
+    async fn spawn_log_compaction_service(&self) -> tokio::task::JoinHandle<()> {
+        let db = self.db().clone();
+        let container = self.container.clone();
+        let config = self.config().clone();
+        LogCompactionService::spawn(db, container, config).await
+    }
+
+    async fn spawn_worktree_gc_service(&self) -> tokio::task::JoinHandle<()> {
+        let db = self.db().clone();
+        let container = self.container.clone();
+        let config = self.config().clone();
+        WorktreeGcService::spawn(db, container, config).await
+    }
+
+    async fn spawn_preview_proxy(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let config = self.config().read().await.preview_proxy.clone();
+        if !config.enabled {
+            return None;
+        }
+        match preview_proxy::spawn(self.container.clone(), config.port).await {
+            Ok(handle) => Some(handle),
+            Err(e) => {
+                tracing::warn!(
+                    "Preview proxy not started, port {} is unavailable: {}",
+                    config.port,
+                    e
+                );
+                None
+            }
+        }
+    }
 }
 
 impl LocalDeployment {
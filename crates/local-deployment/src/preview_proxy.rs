@@ -0,0 +1,658 @@
+//! Reverse proxy giving each workspace's dev server a stable preview URL.
+//!
+//! Dev servers listen on ports allocated when they start, so their URLs change with every
+//! start. The proxy listens on the configured port and routes `<short id>.localhost:<port>`
+//! and `localhost:<port>/<short id>/` to the workspace's running dev server, where the short id
+//! is the one in the workspace's branch name; `<process>.<short id>.localhost` picks one of
+//! its named dev processes, matched case-insensitively with `-` standing in for the `_` that
+//! hostnames cannot contain. Requests outside the prefix of a path route are routed by the
+//! short id in their referer, so pages can still load assets by absolute path. Client
+//! connections are kept alive and every request on them is routed afresh; WebSocket and other
+//! upgrades are tunnelled to the dev server.
+
+use std::{convert::Infallible, io};
+
+use db::models::{execution_process::ExecutionProcess, workspace::Workspace};
+use http_body_util::{BodyExt, Full, combinators::UnsyncBoxBody};
+use hyper::{
+    Request, Response, StatusCode, Uri, Version,
+    body::{Bytes, Incoming},
+    client,
+    header::{self, HeaderMap, HeaderName, HeaderValue},
+    server::conn::http1,
+    service::service_fn,
+};
+use hyper_util::rt::TokioIo;
+use services::services::{container::ContainerService, dev_processes};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+use uuid::Uuid;
+
+use crate::container::LocalContainerService;
+
+/// Header names that only apply to one connection, on either side of the proxy
+const HOP_BY_HOP_HEADERS: [&str; 8] = [
+    "connection",
+    "keep-alive",
+    "proxy-connection",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+type ProxyBody = UnsyncBoxBody<Bytes, hyper::Error>;
+
+/// Listen on a loopback port and route previews to the dev servers of `container`
+pub async fn spawn(container: LocalContainerService, port: u16) -> io::Result<JoinHandle<()>> {
+    let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+    tracing::info!("Preview proxy running on http://localhost:{}", port);
+    Ok(tokio::spawn(async move {
+        while let Ok((client, _)) = listener.accept().await {
+            let container = container.clone();
+            let service = service_fn(move |request| {
+                let container = container.clone();
+                async move { Ok::<_, Infallible>(handle(request, &container).await) }
+            });
+            tokio::spawn(async move {
+                if let Err(e) = http1::Builder::new()
+                    .serve_connection(TokioIo::new(client), service)
+                    .with_upgrades()
+                    .await
+                {
+                    tracing::debug!("Preview proxy connection failed: {}", e);
+                }
+            });
+        }
+    }))
+}
+
+async fn handle(
+    request: Request<Incoming>,
+    container: &LocalContainerService,
+) -> Response<ProxyBody> {
+    let route = match route(&request) {
+        Some(Route::Redirect(location)) => return redirect(&location),
+        Some(Route::Proxy(route)) => route,
+        None => {
+            return text(
+                StatusCode::NOT_FOUND,
+                "Open a preview at http://<short id>.localhost:<port>/ or http://localhost:<port>/<short id>/",
+            );
+        }
+    };
+
+    let resolved = match workspaces_with_dev_servers(container).await {
+        Ok(workspaces) => match match_workspace(&workspaces, &request, route) {
+            Ok((workspace_id, route)) => dev_servers(container, workspace_id)
+                .await
+                .and_then(|servers| servers.port(route.process.as_deref()))
+                .map(|port| (port, route)),
+            Err(message) => Err(message),
+        },
+        Err(message) => Err(message),
+    };
+    let (port, route) = match resolved {
+        Ok(resolved) => resolved,
+        Err(message) => return text(StatusCode::NOT_FOUND, &message),
+    };
+    let upstream = match TcpStream::connect(("127.0.0.1", port)).await {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            let message =
+                format!("The dev server is not accepting connections on port {port} yet: {e}");
+            return text(StatusCode::BAD_GATEWAY, &message);
+        }
+    };
+
+    match forward(request, upstream, &route).await {
+        Ok(response) => response,
+        Err(e) => text(
+            StatusCode::BAD_GATEWAY,
+            &format!("The dev server on port {port} failed to respond: {e}"),
+        ),
+    }
+}
+
+/// Send `request` to the dev server on `upstream`. A switch of protocols is answered on both
+/// sides and the two upgraded connections are joined.
+async fn forward(
+    mut request: Request<Incoming>,
+    upstream: TcpStream,
+    route: &ProxyRoute,
+) -> Result<Response<ProxyBody>, hyper::Error> {
+    let client_upgrade = is_upgrade(&request).then(|| hyper::upgrade::on(&mut request));
+
+    let (mut sender, connection) = client::conn::http1::handshake(TokioIo::new(upstream)).await?;
+    tokio::spawn(async move {
+        if let Err(e) = connection.with_upgrades().await {
+            tracing::debug!("Preview proxy upstream connection failed: {}", e);
+        }
+    });
+    let mut response = sender
+        .send_request(upstream_request(request, route))
+        .await?;
+
+    if response.status() == StatusCode::SWITCHING_PROTOCOLS
+        && let Some(client_upgrade) = client_upgrade
+    {
+        let upstream_upgrade = hyper::upgrade::on(&mut response);
+        tokio::spawn(async move {
+            match tokio::try_join!(client_upgrade, upstream_upgrade) {
+                Ok((client, upstream)) => {
+                    let _ = tokio::io::copy_bidirectional(
+                        &mut TokioIo::new(client),
+                        &mut TokioIo::new(upstream),
+                    )
+                    .await;
+                }
+                Err(e) => tracing::debug!("Preview proxy upgrade failed: {}", e),
+            }
+        });
+    } else {
+        remove_hop_by_hop_headers(response.headers_mut());
+    }
+    Ok(response.map(|body| body.boxed_unsync()))
+}
+
+fn text(status: StatusCode, message: &str) -> Response<ProxyBody> {
+    let mut response = Response::new(full(format!("{message}\n")));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; charset=utf-8"),
+    );
+    response
+}
+
+fn redirect(location: &str) -> Response<ProxyBody> {
+    let Ok(location) = HeaderValue::from_str(location) else {
+        return text(StatusCode::BAD_REQUEST, "Malformed request");
+    };
+    let mut response = Response::new(full(String::new()));
+    *response.status_mut() = StatusCode::PERMANENT_REDIRECT;
+    response.headers_mut().insert(header::LOCATION, location);
+    response
+}
+
+fn full(body: String) -> ProxyBody {
+    Full::new(Bytes::from(body))
+        .map_err(|never| match never {})
+        .boxed_unsync()
+}
+
+/// Workspaces with a running dev server, archived ones included
+async fn workspaces_with_dev_servers(
+    container: &LocalContainerService,
+) -> Result<Vec<Uuid>, String> {
+    let pool = &container.db().pool;
+    let mut workspaces = Vec::new();
+    for archived in [false, true] {
+        workspaces.extend(
+            ExecutionProcess::find_workspaces_with_running_dev_servers(pool, archived)
+                .await
+                .map_err(|e| e.to_string())?,
+        );
+    }
+    Ok(workspaces)
+}
+
+/// The workspace a route points at. An absolute path of the dev server can look like a short
+/// id too, so a path route that matches no workspace falls back to the referer's.
+fn match_workspace<B>(
+    workspaces: &[Uuid],
+    request: &Request<B>,
+    route: ProxyRoute,
+) -> Result<(Uuid, ProxyRoute), String> {
+    match find_workspace(workspaces, &route.short_id) {
+        Err(message) if route.prefix.is_some() => match referer_route(request) {
+            Some(referer) => find_workspace(workspaces, &referer.short_id).map(|id| (id, referer)),
+            None => Err(message),
+        },
+        found => found.map(|id| (id, route)),
+    }
+}
+
+fn find_workspace(workspaces: &[Uuid], short_id: &str) -> Result<Uuid, String> {
+    let candidates: Vec<&Uuid> = workspaces
+        .iter()
+        .filter(|id| id.simple().to_string().starts_with(short_id))
+        .collect();
+    match candidates.as_slice() {
+        [] => Err(format!("No workspace {short_id} has a running dev server")),
+        [id] => Ok(**id),
+        _ => Err(format!(
+            "Several workspaces with running dev servers match {short_id}; use a longer prefix of the workspace id"
+        )),
+    }
+}
+
+/// What a workspace's running dev servers listen on
+#[derive(Debug, Default)]
+struct DevServers {
+    /// Frontend port of the dev server script
+    script_port: Option<u16>,
+    /// Named dev processes and their ports, in the order they are declared
+    processes: Vec<(String, u16)>,
+}
+
+impl DevServers {
+    /// The port to preview, of the process named by a host label if there is one. A dev
+    /// server script serves the preview on its frontend port, otherwise the first running
+    /// dev process does.
+    fn port(&self, process: Option<&str>) -> Result<u16, String> {
+        match process {
+            Some(label) => self
+                .processes
+                .iter()
+                .find(|(name, _)| host_label_matches(label, name))
+                .map(|(_, port)| *port)
+                .ok_or_else(|| format!("Dev process {label} is not running")),
+            None => self
+                .script_port
+                .or_else(|| self.processes.first().map(|(_, port)| *port))
+                .ok_or_else(|| "The workspace's dev server has no port allocated".to_string()),
+        }
+    }
+}
+
+/// Whether a host label names a dev process. Hostnames are case-insensitive and cannot contain
+/// `_`, so `storybook-ui` names `Storybook_UI`.
+fn host_label_matches(label: &str, name: &str) -> bool {
+    label.len() == name.len()
+        && label
+            .bytes()
+            .zip(name.bytes())
+            .all(|(l, n)| l.eq_ignore_ascii_case(&n) || (l == b'-' && n == b'_'))
+}
+
+async fn dev_servers(
+    container: &LocalContainerService,
+    workspace_id: Uuid,
+) -> Result<DevServers, String> {
+    let pool = &container.db().pool;
+    let running = ExecutionProcess::find_running_dev_servers_by_workspace(pool, workspace_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut servers = DevServers::default();
+    for process in &running {
+        if dev_processes::dev_process_name(process).is_none()
+            && let Some(allocated) = container.allocated_ports(&process.id).await
+        {
+            servers.script_port = Some(allocated.frontend);
+            break;
+        }
+    }
+
+    let running_names: Vec<String> = running
+        .iter()
+        .filter_map(dev_processes::dev_process_name)
+        .collect();
+    if running_names.is_empty() {
+        return Ok(servers);
+    }
+    let ports = container.dev_process_ports(workspace_id).await;
+    let workspace = Workspace::find_by_id(pool, workspace_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Workspace not found".to_string())?;
+    servers.processes = dev_processes::workspace_dev_processes(pool, &workspace)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|process| running_names.contains(&process.name))
+        .filter_map(|process| {
+            let port = ports.get(&process.name).copied()?;
+            Some((process.name, port))
+        })
+        .collect();
+    Ok(servers)
+}
+
+fn header_str<'a, B>(request: &'a Request<B>, name: HeaderName) -> Option<&'a str> {
+    request
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+}
+
+fn is_upgrade<B>(request: &Request<B>) -> bool {
+    request.headers().contains_key(header::UPGRADE)
+        && header_str(request, header::CONNECTION)
+            .is_some_and(|c| c.to_ascii_lowercase().contains("upgrade"))
+}
+
+fn path_and_query<B>(request: &Request<B>) -> &str {
+    request
+        .uri()
+        .path_and_query()
+        .map_or("/", |path| path.as_str())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Route {
+    Proxy(ProxyRoute),
+    /// `/<short id>` without the trailing slash that relative URLs need
+    Redirect(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ProxyRoute {
+    short_id: String,
+    process: Option<String>,
+    /// Prefix to remove from the path, for routes taken from it
+    prefix: Option<String>,
+}
+
+fn route<B>(request: &Request<B>) -> Option<Route> {
+    if let Some(route) = header_str(request, header::HOST).and_then(route_from_host) {
+        return Some(Route::Proxy(route));
+    }
+
+    if let Some((short_id, rest)) = split_path_prefix(path_and_query(request)) {
+        if rest.is_empty() || rest.starts_with('?') {
+            return Some(Route::Redirect(format!("/{short_id}/{rest}")));
+        }
+        return Some(Route::Proxy(ProxyRoute {
+            prefix: Some(format!("/{short_id}")),
+            short_id: short_id.to_string(),
+            process: None,
+        }));
+    }
+
+    referer_route(request).map(Route::Proxy)
+}
+
+/// Route of a request made by absolute path from a page under a path prefix
+fn referer_route<B>(request: &Request<B>) -> Option<ProxyRoute> {
+    let referer = header_str(request, header::REFERER)?;
+    let path = referer
+        .split_once("://")
+        .map_or(referer, |(_, rest)| rest)
+        .split_once('/')
+        .map(|(_, path)| format!("/{path}"))?;
+    let (short_id, _) = split_path_prefix(&path)?;
+    Some(ProxyRoute {
+        short_id: short_id.to_string(),
+        process: None,
+        prefix: None,
+    })
+}
+
+/// `<short id>.localhost` or `<process>.<short id>.localhost`, with or without a port
+fn route_from_host(host: &str) -> Option<ProxyRoute> {
+    let host = host.rsplit_once(':').map_or(host, |(host, _)| host);
+    let labels = host.to_ascii_lowercase();
+    let labels = labels.strip_suffix(".localhost")?;
+    let (process, short_id) = match labels.rsplit_once('.') {
+        Some((process, short_id)) => (Some(process.to_string()), short_id),
+        None => (None, labels),
+    };
+    is_short_id(short_id).then(|| ProxyRoute {
+        short_id: short_id.to_string(),
+        process,
+        prefix: None,
+    })
+}
+
+/// The short id a path starts with and the rest of the path after it
+fn split_path_prefix(path: &str) -> Option<(&str, &str)> {
+    let path = path.strip_prefix('/')?;
+    let end = path.find(['/', '?']).unwrap_or(path.len());
+    let (short_id, rest) = path.split_at(end);
+    is_short_id(short_id).then_some((short_id, rest))
+}
+
+/// A lowercase hex prefix of a workspace id, at least as long as the one in branch names
+fn is_short_id(id: &str) -> bool {
+    (4..=32).contains(&id.len()) && id.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// The request to send to the dev server: the path without the route's prefix, this
+/// connection's headers dropped and the original host and prefix passed on
+fn upstream_request<B>(request: Request<B>, route: &ProxyRoute) -> Request<B> {
+    let upgrade = is_upgrade(&request)
+        .then(|| request.headers().get(header::UPGRADE).cloned())
+        .flatten();
+    let path = path_and_query(&request);
+    let path = match &route.prefix {
+        Some(prefix) => match path.strip_prefix(prefix.as_str()) {
+            Some(rest) if rest.starts_with('/') => rest,
+            _ => "/",
+        },
+        None => path,
+    };
+    let uri = Uri::try_from(path).unwrap_or_else(|_| Uri::from_static("/"));
+
+    let (mut parts, body) = request.into_parts();
+    parts.uri = uri;
+    parts.version = Version::HTTP_11;
+    let headers = &mut parts.headers;
+    remove_hop_by_hop_headers(headers);
+    let forwarded: Vec<HeaderName> = headers
+        .keys()
+        .filter(|name| name.as_str().starts_with("x-forwarded-"))
+        .cloned()
+        .collect();
+    for name in forwarded {
+        headers.remove(name);
+    }
+
+    if let Some(upgrade) = upgrade {
+        headers.insert(header::UPGRADE, upgrade);
+        headers.insert(header::CONNECTION, HeaderValue::from_static("upgrade"));
+    }
+    if let Some(host) = headers.get(header::HOST).cloned() {
+        headers.insert("x-forwarded-host", host);
+    }
+    headers.insert("x-forwarded-proto", HeaderValue::from_static("http"));
+    if let Some(prefix) = route
+        .prefix
+        .as_deref()
+        .and_then(|prefix| HeaderValue::from_str(prefix).ok())
+    {
+        headers.insert("x-forwarded-prefix", prefix);
+    }
+    Request::from_parts(parts, body)
+}
+
+/// Drop [`HOP_BY_HOP_HEADERS`] and the headers the `Connection` header names
+fn remove_hop_by_hop_headers(headers: &mut HeaderMap) {
+    let named: Vec<HeaderName> = headers
+        .get_all(header::CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|name| HeaderName::from_bytes(name.trim().as_bytes()).ok())
+        .collect();
+    for name in named {
+        headers.remove(name);
+    }
+    for name in HOP_BY_HOP_HEADERS {
+        headers.remove(name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(path: &str, headers: &[(&str, &str)]) -> Request<()> {
+        let mut request = Request::builder().uri(path);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        request.body(()).unwrap()
+    }
+
+    fn proxy(short_id: &str, process: Option<&str>, prefix: Option<&str>) -> Option<Route> {
+        Some(Route::Proxy(ProxyRoute {
+            short_id: short_id.to_string(),
+            process: process.map(String::from),
+            prefix: prefix.map(String::from),
+        }))
+    }
+
+    #[test]
+    fn routes_by_host() {
+        assert_eq!(
+            route(&request("/app", &[("Host", "ab12.localhost:47800")])),
+            proxy("ab12", None, None)
+        );
+        assert_eq!(
+            route(&request("/", &[("Host", "Storybook.AB12.localhost:47800")])),
+            proxy("ab12", Some("storybook"), None)
+        );
+        assert_eq!(route_from_host("localhost:47800"), None);
+        assert_eq!(route_from_host("xyz1.localhost"), None);
+    }
+
+    #[test]
+    fn routes_by_path_prefix_and_referer() {
+        let host = ("Host", "localhost:47800");
+        assert_eq!(
+            route(&request("/ab12/login?next=/", &[host])),
+            proxy("ab12", None, Some("/ab12"))
+        );
+        assert_eq!(
+            route(&request("/ab12?tab=1", &[host])),
+            Some(Route::Redirect("/ab12/?tab=1".to_string()))
+        );
+        assert_eq!(
+            route(&request(
+                "/assets/app.js",
+                &[host, ("Referer", "http://localhost:47800/ab12/login")]
+            )),
+            proxy("ab12", None, None)
+        );
+        assert_eq!(route(&request("/assets/app.js", &[host])), None);
+    }
+
+    #[test]
+    fn path_routes_fall_back_to_the_referer() {
+        let workspace = Uuid::from_u128(0xab12 << 112);
+        let other = Uuid::from_u128(0xcd34 << 112);
+        let workspaces = [workspace, other];
+        let path_route = |short_id: &str| ProxyRoute {
+            short_id: short_id.to_string(),
+            process: None,
+            prefix: Some(format!("/{short_id}")),
+        };
+
+        // `/beef/...` is the dev server's own path on a page of workspace ab12
+        let request = request(
+            "/beef/logo.svg",
+            &[("Referer", "http://localhost:47800/ab12/")],
+        );
+        let (id, route) = match_workspace(&workspaces, &request, path_route("beef")).unwrap();
+        assert_eq!(id, workspace);
+        assert_eq!(route.prefix, None);
+
+        // A matching path prefix wins over the referer
+        let (id, route) = match_workspace(&workspaces, &request, path_route("cd34")).unwrap();
+        assert_eq!(id, other);
+        assert_eq!(route.prefix.as_deref(), Some("/cd34"));
+
+        // Host routes never fall back
+        let host_route = ProxyRoute {
+            prefix: None,
+            ..path_route("beef")
+        };
+        assert!(match_workspace(&workspaces, &request, host_route).is_err());
+    }
+
+    #[test]
+    fn rejects_ambiguous_short_ids() {
+        let workspaces = [
+            Uuid::from_u128(0xab12_0 << 108),
+            Uuid::from_u128(0xab12_f << 108),
+        ];
+        assert!(find_workspace(&workspaces, "ab12").is_err());
+        assert_eq!(find_workspace(&workspaces, "ab12f"), Ok(workspaces[1]));
+        assert!(find_workspace(&workspaces, "cd34").is_err());
+    }
+
+    #[test]
+    fn picks_the_dev_server_port() {
+        let processes = vec![("Web_UI".to_string(), 4001), ("api".to_string(), 4002)];
+        let servers = DevServers {
+            script_port: None,
+            processes: processes.clone(),
+        };
+        assert_eq!(servers.port(None), Ok(4001));
+        assert_eq!(servers.port(Some("api")), Ok(4002));
+        // Host labels are lowercase and spell `_` as `-`
+        assert_eq!(servers.port(Some("web-ui")), Ok(4001));
+        assert_eq!(servers.port(Some("web_ui")), Ok(4001));
+        assert!(servers.port(Some("worker")).is_err());
+
+        let servers = DevServers {
+            script_port: Some(3000),
+            processes,
+        };
+        assert_eq!(servers.port(None), Ok(3000));
+        assert_eq!(servers.port(Some("api")), Ok(4002));
+
+        assert!(DevServers::default().port(None).is_err());
+    }
+
+    #[test]
+    fn rewrites_requests_for_dev_server() {
+        let plain = request(
+            "/ab12/api/items?page=2",
+            &[
+                ("Host", "localhost:47800"),
+                ("Connection", "keep-alive, X-Trace"),
+                ("X-Trace", "1"),
+                ("X-Forwarded-For", "10.0.0.1"),
+                ("Accept", "*/*"),
+            ],
+        );
+        let route = ProxyRoute {
+            short_id: "ab12".to_string(),
+            process: None,
+            prefix: Some("/ab12".to_string()),
+        };
+        let upstream = upstream_request(plain, &route);
+        assert_eq!(upstream.uri(), "/api/items?page=2");
+        let mut headers: Vec<(&str, &str)> = upstream
+            .headers()
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.to_str().unwrap()))
+            .collect();
+        headers.sort();
+        assert_eq!(
+            headers,
+            [
+                ("accept", "*/*"),
+                ("host", "localhost:47800"),
+                ("x-forwarded-host", "localhost:47800"),
+                ("x-forwarded-prefix", "/ab12"),
+                ("x-forwarded-proto", "http"),
+            ]
+        );
+
+        let websocket = request(
+            "/ws",
+            &[
+                ("Host", "ab12.localhost:47800"),
+                ("Connection", "keep-alive, Upgrade"),
+                ("Upgrade", "websocket"),
+            ],
+        );
+        let route = ProxyRoute {
+            short_id: "ab12".to_string(),
+            process: None,
+            prefix: None,
+        };
+        let upstream = upstream_request(websocket, &route);
+        assert_eq!(upstream.uri(), "/ws");
+        assert_eq!(upstream.headers()[header::UPGRADE], "websocket");
+        assert_eq!(upstream.headers()[header::CONNECTION], "upgrade");
+        assert!(!upstream.headers().contains_key("x-forwarded-prefix"));
+    }
+}
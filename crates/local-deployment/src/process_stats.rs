@@ -0,0 +1,162 @@
+//! Memory and CPU time of process groups, summed over every process in the group.
+//!
+//! Linux reads `/proc`; other Unix systems ask `ps`. Sampling is not supported on Windows.
+
+use std::{collections::HashMap, io, time::Duration};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GroupStats {
+    pub rss_bytes: u64,
+    /// User plus system CPU time consumed so far
+    pub cpu_time: Duration,
+}
+
+/// Stats of the given process groups; groups without any live process are left out
+pub fn sample_process_groups(pgids: &[u32]) -> io::Result<HashMap<u32, GroupStats>> {
+    let mut stats: HashMap<u32, GroupStats> = HashMap::new();
+    for (pgid, process) in list_processes()? {
+        if pgids.contains(&pgid) {
+            let group = stats.entry(pgid).or_default();
+            group.rss_bytes += process.rss_bytes;
+            group.cpu_time += process.cpu_time;
+        }
+    }
+    Ok(stats)
+}
+
+#[cfg(target_os = "linux")]
+fn list_processes() -> io::Result<Vec<(u32, GroupStats)>> {
+    use nix::unistd::{SysconfVar, sysconf};
+
+    let page_size = sysconf(SysconfVar::PAGE_SIZE)
+        .ok()
+        .flatten()
+        .unwrap_or(4096) as u64;
+    let ticks_per_sec = sysconf(SysconfVar::CLK_TCK).ok().flatten().unwrap_or(100) as u64;
+
+    let mut processes = Vec::new();
+    for entry in std::fs::read_dir("/proc")? {
+        let Ok(entry) = entry else { continue };
+        if !entry
+            .file_name()
+            .to_string_lossy()
+            .bytes()
+            .all(|b| b.is_ascii_digit())
+        {
+            continue;
+        }
+        // The process may exit between listing and reading
+        let Ok(stat) = std::fs::read_to_string(entry.path().join("stat")) else {
+            continue;
+        };
+        if let Some((pgid, ticks, rss_pages)) = parse_proc_stat(&stat) {
+            processes.push((
+                pgid,
+                GroupStats {
+                    rss_bytes: rss_pages * page_size,
+                    cpu_time: Duration::from_millis(ticks * 1000 / ticks_per_sec),
+                },
+            ));
+        }
+    }
+    Ok(processes)
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn list_processes() -> io::Result<Vec<(u32, GroupStats)>> {
+    let output = std::process::Command::new("ps")
+        .args(["-A", "-o", "pgid=,rss=,time="])
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other("ps exited with an error"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_ps_line)
+        .collect())
+}
+
+#[cfg(windows)]
+fn list_processes() -> io::Result<Vec<(u32, GroupStats)>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "process sampling is not supported on Windows",
+    ))
+}
+
+/// Process group, CPU ticks and resident pages from the contents of `/proc/<pid>/stat`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_stat(stat: &str) -> Option<(u32, u64, u64)> {
+    // The command name may contain spaces and parentheses, so fields are counted from the
+    // last closing parenthesis; the state is field 3 of proc(5)
+    let fields: Vec<&str> = stat
+        .get(stat.rfind(')')? + 1..)?
+        .split_whitespace()
+        .collect();
+    let field = |n: usize| fields.get(n - 3).copied();
+    let pgid = field(5)?.parse().ok()?;
+    let utime: u64 = field(14)?.parse().ok()?;
+    let stime: u64 = field(15)?.parse().ok()?;
+    let rss: i64 = field(24)?.parse().ok()?;
+    Some((pgid, utime + stime, rss.max(0) as u64))
+}
+
+/// A `pgid rss time` line of `ps`, with rss in KiB and time as `[[dd-]hh:]mm:ss[.ff]`
+#[cfg_attr(any(target_os = "linux", windows), allow(dead_code))]
+fn parse_ps_line(line: &str) -> Option<(u32, GroupStats)> {
+    let mut fields = line.split_whitespace();
+    let pgid = fields.next()?.parse().ok()?;
+    let rss_kib: u64 = fields.next()?.parse().ok()?;
+    let cpu_time = parse_cpu_time(fields.next()?)?;
+    Some((
+        pgid,
+        GroupStats {
+            rss_bytes: rss_kib * 1024,
+            cpu_time,
+        },
+    ))
+}
+
+#[cfg_attr(any(target_os = "linux", windows), allow(dead_code))]
+fn parse_cpu_time(time: &str) -> Option<Duration> {
+    let (days, clock) = match time.split_once('-') {
+        Some((days, clock)) => (days.parse::<u64>().ok()?, clock),
+        None => (0, time),
+    };
+    let mut secs = 0.0;
+    for part in clock.split(':') {
+        secs = secs * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(Duration::from_secs(days * 86_400) + Duration::from_secs_f64(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_proc_stat_with_spaces_in_command() {
+        let stat = "4242 (npm run (test)) S 4200 4200 4200 0 -1 4194560 5 0 0 0 \
+                    150 50 0 0 20 0 1 0 100 1000000 2560 18446744073709551615";
+        assert_eq!(parse_proc_stat(stat), Some((4200, 200, 2560)));
+    }
+
+    #[test]
+    fn parses_ps_lines() {
+        assert_eq!(
+            parse_ps_line(" 4200  20480   1:02.50"),
+            Some((
+                4200,
+                GroupStats {
+                    rss_bytes: 20480 * 1024,
+                    cpu_time: Duration::from_millis(62_500),
+                }
+            ))
+        );
+        assert_eq!(
+            parse_cpu_time("1-02:00:05"),
+            Some(Duration::from_secs(86_400 + 7205))
+        );
+        assert_eq!(parse_ps_line("PGID RSS TIME"), None);
+    }
+}
@@ -0,0 +1,324 @@
+//! Host allow-list proxy for sandboxed coding agents.
+//!
+//! Each sandboxed execution gets its own listener on a Unix socket. The sandbox has no network
+//! of its own, and the socket is bridged to a loopback port inside it that the environment
+//! names as the HTTP(S) proxy, so this proxy is the agent's only way out. `CONNECT` tunnels and
+//! plain HTTP requests to allowed hosts are passed through; anything else is refused and
+//! reported once per host in the execution's log.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::{TcpStream, UnixListener},
+    sync::RwLock,
+    task::JoinHandle,
+};
+use utils::msg_store::MsgStore;
+use uuid::Uuid;
+
+/// Request heads larger than this are rejected
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+
+const ESTABLISHED: &[u8] = b"HTTP/1.1 200 Connection Established\r\n\r\n";
+const BAD_REQUEST: &[u8] = b"HTTP/1.1 400 Bad Request\r\nConnection: close\r\n\r\n";
+const FORBIDDEN: &[u8] =
+    b"HTTP/1.1 403 Forbidden\r\nConnection: close\r\n\r\nHost not allowed by the sandbox\n";
+const BAD_GATEWAY: &[u8] = b"HTTP/1.1 502 Bad Gateway\r\nConnection: close\r\n\r\n";
+
+/// Which destinations the proxy lets through
+#[derive(Debug, Clone, Default)]
+pub struct ProxyPolicy {
+    /// Hosts and their subdomains
+    pub allowed_hosts: Vec<String>,
+    /// Ports on this machine's loopback, such as the server's own for the MCP server. The
+    /// sandbox's loopback is its own, so local services are only reachable through here.
+    pub local_ports: Vec<u16>,
+}
+
+/// Stops listening and removes its socket when dropped
+pub struct SandboxProxy {
+    socket: PathBuf,
+    task: JoinHandle<()>,
+}
+
+impl SandboxProxy {
+    /// Listen on a socket in `dir`, reporting refused hosts to the log of `execution_id`
+    pub async fn start(
+        execution_id: Uuid,
+        dir: &Path,
+        policy: ProxyPolicy,
+        msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
+    ) -> io::Result<Self> {
+        tokio::fs::create_dir_all(dir).await?;
+        let socket = dir.join(format!("{execution_id}.sock"));
+        // Left behind if the server was killed while the execution ran
+        let _ = tokio::fs::remove_file(&socket).await;
+        let listener = UnixListener::bind(&socket)?;
+        let policy = Arc::new(policy);
+        let reported = Arc::new(Mutex::new(HashSet::new()));
+
+        let task = tokio::spawn(async move {
+            while let Ok((client, _)) = listener.accept().await {
+                let policy = policy.clone();
+                let reported = reported.clone();
+                let msg_stores = msg_stores.clone();
+                tokio::spawn(async move {
+                    let refused = match serve(client, &policy).await {
+                        Ok(refused) => refused,
+                        Err(e) => {
+                            tracing::debug!("Sandbox proxy connection failed: {}", e);
+                            return;
+                        }
+                    };
+                    let Some(host) = refused else { return };
+                    if !reported.lock().unwrap().insert(host.clone()) {
+                        return;
+                    }
+                    if let Some(store) = msg_stores.read().await.get(&execution_id) {
+                        store.push_stderr(format!(
+                            "Sandbox blocked network access to {host}, which is not in the allowed hosts\n"
+                        ));
+                    }
+                });
+            }
+        });
+
+        Ok(Self { socket, task })
+    }
+
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+}
+
+impl Drop for SandboxProxy {
+    fn drop(&mut self) {
+        self.task.abort();
+        let _ = std::fs::remove_file(&self.socket);
+    }
+}
+
+/// Proxy one client connection, returning the host if it was refused
+async fn serve<S>(mut client: S, policy: &ProxyPolicy) -> io::Result<Option<String>>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (head, body_start) = read_head(&mut client).await?;
+    let Some(request) = parse_request(&head) else {
+        client.write_all(BAD_REQUEST).await?;
+        return Ok(None);
+    };
+    if !policy.allows(&request.host, request.port) {
+        client.write_all(FORBIDDEN).await?;
+        return Ok(Some(format!("{}:{}", request.host, request.port)));
+    }
+
+    let mut upstream = match TcpStream::connect((request.host.as_str(), request.port)).await {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            client.write_all(BAD_GATEWAY).await?;
+            return Err(e);
+        }
+    };
+    match &request.upstream_head {
+        Some(head) => upstream.write_all(head.as_bytes()).await?,
+        None => client.write_all(ESTABLISHED).await?,
+    }
+    upstream.write_all(&body_start).await?;
+    tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+    Ok(None)
+}
+
+/// The request head and whatever was read past it
+async fn read_head<S>(client: &mut S) -> io::Result<(String, Vec<u8>)>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            let rest = buf.split_off(end + 4);
+            return Ok((String::from_utf8_lossy(&buf).into_owned(), rest));
+        }
+        if buf.len() > MAX_HEAD_BYTES {
+            return Err(io::Error::other("request head too large"));
+        }
+        let read = client.read(&mut chunk).await?;
+        if read == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        buf.extend_from_slice(&chunk[..read]);
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ProxyRequest {
+    host: String,
+    port: u16,
+    /// Head to send to the host; `None` for `CONNECT` tunnels
+    upstream_head: Option<String>,
+}
+
+fn parse_request(head: &str) -> Option<ProxyRequest> {
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split(' ');
+    let (method, target, version) = (
+        request_line.next()?,
+        request_line.next()?,
+        request_line.next()?,
+    );
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) = split_host_port(target, 443)?;
+        return Some(ProxyRequest {
+            host,
+            port,
+            upstream_head: None,
+        });
+    }
+
+    // Plain HTTP requests carry an absolute URI, which the host expects in origin form
+    let target = target.strip_prefix("http://")?;
+    let (authority, path) = match target.find('/') {
+        Some(slash) => target.split_at(slash),
+        None => (target, "/"),
+    };
+    let (host, port) = split_host_port(authority, 80)?;
+    let mut upstream_head = format!("{method} {path} {version}\r\n");
+    for line in lines.filter(|line| !line.is_empty()) {
+        if !line.to_ascii_lowercase().starts_with("proxy-") {
+            upstream_head.push_str(line);
+            upstream_head.push_str("\r\n");
+        }
+    }
+    upstream_head.push_str("\r\n");
+    Some(ProxyRequest {
+        host,
+        port,
+        upstream_head: Some(upstream_head),
+    })
+}
+
+fn split_host_port(authority: &str, default_port: u16) -> Option<(String, u16)> {
+    let (host, port) = if let Some(bracketed) = authority.strip_prefix('[') {
+        let (host, rest) = bracketed.split_once(']')?;
+        (host, rest.strip_prefix(':'))
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    let port = match port {
+        Some(port) => port.parse().ok()?,
+        None => default_port,
+    };
+    (!host.is_empty()).then(|| (host.to_ascii_lowercase(), port))
+}
+
+impl ProxyPolicy {
+    /// Loopback destinations are only allowed on [`Self::local_ports`], whatever the host list
+    /// says
+    fn allows(&self, host: &str, port: u16) -> bool {
+        if is_loopback(host) {
+            return self.local_ports.contains(&port);
+        }
+        is_allowed(host, &self.allowed_hosts)
+    }
+}
+
+fn is_loopback(host: &str) -> bool {
+    let host = host.trim_end_matches('.');
+    host == "localhost"
+        || host.ends_with(".localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback() || ip.is_unspecified())
+}
+
+/// Whether `host` is one of `allowed_hosts` or a subdomain of one
+fn is_allowed(host: &str, allowed_hosts: &[String]) -> bool {
+    let host = host.trim_end_matches('.');
+    allowed_hosts.iter().any(|allowed| {
+        let allowed = allowed
+            .trim()
+            .trim_start_matches("*.")
+            .trim_end_matches('.')
+            .to_ascii_lowercase();
+        !allowed.is_empty()
+            && (host == allowed
+                || host
+                    .strip_suffix(allowed.as_str())
+                    .is_some_and(|sub| sub.ends_with('.')))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_connect_requests() {
+        assert_eq!(
+            parse_request("CONNECT api.anthropic.com:443 HTTP/1.1\r\nHost: api.anthropic.com\r\n"),
+            Some(ProxyRequest {
+                host: "api.anthropic.com".to_string(),
+                port: 443,
+                upstream_head: None,
+            })
+        );
+        assert_eq!(
+            parse_request("CONNECT [::1]:8443 HTTP/1.1\r\n")
+                .map(|request| (request.host, request.port)),
+            Some(("::1".to_string(), 8443))
+        );
+    }
+
+    #[test]
+    fn rewrites_plain_http_requests() {
+        let request = parse_request(
+            "GET http://Example.com/a?b=c HTTP/1.1\r\nHost: example.com\r\nProxy-Connection: keep-alive\r\n",
+        )
+        .unwrap();
+        assert_eq!(request.host, "example.com");
+        assert_eq!(request.port, 80);
+        assert_eq!(
+            request.upstream_head.as_deref(),
+            Some("GET /a?b=c HTTP/1.1\r\nHost: example.com\r\n\r\n")
+        );
+        assert_eq!(parse_request("GET /relative HTTP/1.1\r\n"), None);
+    }
+
+    #[test]
+    fn allows_listed_hosts_and_their_subdomains() {
+        let allowed = vec!["github.com".to_string(), "*.npmjs.org".to_string()];
+        assert!(is_allowed("github.com", &allowed));
+        assert!(is_allowed("api.github.com", &allowed));
+        assert!(is_allowed("registry.npmjs.org", &allowed));
+        assert!(is_allowed("npmjs.org", &allowed));
+        assert!(!is_allowed("evilgithub.com", &allowed));
+        assert!(!is_allowed("github.com.evil.io", &allowed));
+        assert!(!is_allowed("example.com", &[]));
+    }
+
+    #[test]
+    fn only_allows_loopback_on_local_ports() {
+        let policy = ProxyPolicy {
+            allowed_hosts: vec!["localhost".to_string(), "github.com".to_string()],
+            local_ports: vec![3001],
+        };
+        assert!(policy.allows("127.0.0.1", 3001));
+        assert!(policy.allows("localhost", 3001));
+        assert!(!policy.allows("localhost", 22));
+        assert!(!policy.allows("::1", 5432));
+        assert!(!policy.allows("0.0.0.0", 8080));
+        assert!(policy.allows("github.com", 443));
+    }
+}
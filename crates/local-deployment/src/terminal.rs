@@ -0,0 +1,196 @@
+//! PTY-backed shells for workspace terminals.
+
+use std::{io, path::Path};
+
+use executors::env::ExecutionEnv;
+use tokio::process::Child;
+
+pub struct Terminal {
+    pub child: Child,
+    pub master: PtyMaster,
+}
+
+/// Controlling side of a PTY: reads return the shell's output, writes are its input. The fd is
+/// non-blocking and registered with the tokio reactor, so neither direction ties up a
+/// blocking-pool thread while the shell is idle.
+#[cfg(unix)]
+pub struct PtyMaster(tokio::io::unix::AsyncFd<std::fs::File>);
+
+#[cfg(windows)]
+pub enum PtyMaster {}
+
+#[cfg(unix)]
+impl PtyMaster {
+    fn new(file: std::fs::File) -> io::Result<Self> {
+        use std::os::fd::AsRawFd;
+
+        use nix::fcntl::{FcntlArg, OFlag, fcntl};
+
+        let flags = OFlag::from_bits_truncate(fcntl(file.as_raw_fd(), FcntlArg::F_GETFL)?);
+        fcntl(
+            file.as_raw_fd(),
+            FcntlArg::F_SETFL(flags | OFlag::O_NONBLOCK),
+        )?;
+        Ok(Self(tokio::io::unix::AsyncFd::new(file)?))
+    }
+
+    /// Read the next chunk of output. Fails with `EIO` once the shell has exited.
+    pub async fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        use std::io::Read;
+
+        loop {
+            let mut guard = self.0.readable().await?;
+            if let Ok(result) = guard.try_io(|fd| fd.get_ref().read(buf)) {
+                return result;
+            }
+        }
+    }
+
+    pub async fn write_all(&self, mut buf: &[u8]) -> io::Result<()> {
+        use std::io::Write;
+
+        while !buf.is_empty() {
+            let mut guard = self.0.writable().await?;
+            match guard.try_io(|fd| fd.get_ref().write(buf)) {
+                Ok(Ok(0)) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(Ok(written)) => buf = &buf[written..],
+                Ok(Err(e)) => return Err(e),
+                Err(_would_block) => {}
+            }
+        }
+        Ok(())
+    }
+
+    pub fn resize(&self, cols: u16, rows: u16) -> io::Result<()> {
+        use std::os::fd::AsRawFd;
+
+        use nix::{libc, pty::Winsize};
+
+        let size = Winsize {
+            ws_row: rows,
+            ws_col: cols,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+        // SAFETY: TIOCSWINSZ reads a winsize from the pointer, which outlives the call
+        if unsafe { libc::ioctl(self.0.as_raw_fd(), libc::TIOCSWINSZ, &size) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+impl PtyMaster {
+    pub async fn read(&self, _buf: &mut [u8]) -> io::Result<usize> {
+        match *self {}
+    }
+
+    pub async fn write_all(&self, _buf: &[u8]) -> io::Result<()> {
+        match *self {}
+    }
+
+    pub fn resize(&self, _cols: u16, _rows: u16) -> io::Result<()> {
+        match *self {}
+    }
+}
+
+/// Spawn `shell` as the session leader of a new PTY
+#[cfg(unix)]
+pub fn spawn(shell: &Path, current_dir: &Path, env: &ExecutionEnv) -> io::Result<Terminal> {
+    use std::process::Stdio;
+
+    use nix::{libc, pty::openpty, unistd::setsid};
+    use tokio::process::Command;
+
+    let pty = openpty(None, None)?;
+
+    let mut command = Command::new(shell);
+    command
+        .kill_on_drop(true)
+        .stdin(Stdio::from(pty.slave.try_clone()?))
+        .stdout(Stdio::from(pty.slave.try_clone()?))
+        .stderr(Stdio::from(pty.slave))
+        .current_dir(current_dir)
+        .env("TERM", "xterm-256color");
+    env.apply_to_command(&mut command);
+
+    // SAFETY: only async-signal-safe calls between fork and exec
+    unsafe {
+        command.pre_exec(|| {
+            // New session so the PTY becomes the shell's controlling terminal and job
+            // control (Ctrl-C, Ctrl-Z) reaches its foreground jobs
+            setsid()?;
+            if libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+
+    let child = command.spawn()?;
+    Ok(Terminal {
+        child,
+        master: PtyMaster::new(std::fs::File::from(pty.master))?,
+    })
+}
+
+#[cfg(windows)]
+pub fn spawn(_shell: &Path, _current_dir: &Path, _env: &ExecutionEnv) -> io::Result<Terminal> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Terminals are not supported on Windows",
+    ))
+}
+
+/// Hang up the shell's session so its jobs exit too, then kill the shell itself
+pub async fn hang_up(child: &mut Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        use nix::{
+            sys::signal::{Signal, killpg},
+            unistd::Pid,
+        };
+
+        // The shell leads its own session and process group
+        let _ = killpg(Pid::from_raw(pid as i32), Signal::SIGHUP);
+    }
+    let _ = child.kill().await;
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        time::Duration,
+    };
+
+    use super::*;
+
+    async fn read_until(master: &PtyMaster, needle: &str) {
+        let mut output = String::new();
+        let mut buf = [0; 256];
+        while !output.contains(needle) {
+            let read = tokio::time::timeout(Duration::from_secs(5), master.read(&mut buf))
+                .await
+                .expect("timed out reading the pty")
+                .unwrap();
+            output.push_str(&String::from_utf8_lossy(&buf[..read]));
+        }
+    }
+
+    #[tokio::test]
+    async fn pty_master_reads_and_writes_asynchronously() {
+        let pty = nix::pty::openpty(None, None).unwrap();
+        let master = PtyMaster::new(std::fs::File::from(pty.master)).unwrap();
+        let mut slave = std::fs::File::from(pty.slave);
+
+        slave.write_all(b"from the shell\n").unwrap();
+        read_until(&master, "from the shell").await;
+
+        master.write_all(b"typed\n").await.unwrap();
+        let mut buf = [0; 64];
+        let read = slave.read(&mut buf).unwrap();
+        assert_eq!(&buf[..read], b"typed\n");
+    }
+}
@@ -0,0 +1,183 @@
+//! Pre-created worktrees with the setup script already run, for new workspaces to claim.
+//!
+//! Each repository with a `warm_pool_size` keeps that many worktrees checked out on a detached
+//! commit of the branch its workspaces target. A new single-repository workspace of the same
+//! project that targets the branch at that commit takes one over instead of creating a
+//! worktree and waiting for its setup script. The pool only lives in memory: the directories
+//! of unclaimed worktrees sit next to the workspaces and are removed as orphans when the
+//! server restarts.
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarmWorktree {
+    /// Becomes the workspace directory; the worktree itself is `<dir>/<repo name>`
+    pub dir: PathBuf,
+    /// Project whose secrets and variables the setup script ran with
+    pub project_id: Uuid,
+    pub branch: String,
+    pub commit: String,
+    /// Output of the setup script as execution process log lines, secrets redacted
+    pub setup_log: Vec<String>,
+}
+
+impl WarmWorktree {
+    fn is_at(&self, project_id: Uuid, branch: &str, commit: &str) -> bool {
+        self.project_id == project_id && self.branch == branch && self.commit == commit
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct WarmPool {
+    worktrees: Arc<Mutex<HashMap<Uuid, Vec<WarmWorktree>>>>,
+    refill: Arc<Notify>,
+}
+
+impl WarmPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Claim a worktree of the repository prepared for `project_id` that is on `branch` at
+    /// `commit`
+    pub fn take(
+        &self,
+        repo_id: Uuid,
+        project_id: Uuid,
+        branch: &str,
+        commit: &str,
+    ) -> Option<WarmWorktree> {
+        let mut worktrees = self.worktrees.lock().unwrap();
+        let pool = worktrees.get_mut(&repo_id)?;
+        let index = pool
+            .iter()
+            .position(|w| w.is_at(project_id, branch, commit))?;
+        Some(pool.swap_remove(index))
+    }
+
+    pub fn add(&self, repo_id: Uuid, worktree: WarmWorktree) {
+        self.worktrees
+            .lock()
+            .unwrap()
+            .entry(repo_id)
+            .or_default()
+            .push(worktree);
+    }
+
+    /// Remove the repository's worktrees that are not prepared for `project_id` on `branch`
+    /// at `commit`, and any beyond `size`
+    pub fn take_stale(
+        &self,
+        repo_id: Uuid,
+        project_id: Uuid,
+        branch: &str,
+        commit: &str,
+        size: usize,
+    ) -> Vec<WarmWorktree> {
+        let mut worktrees = self.worktrees.lock().unwrap();
+        let Some(pool) = worktrees.get_mut(&repo_id) else {
+            return Vec::new();
+        };
+        let (current, mut stale): (Vec<_>, Vec<_>) = pool
+            .drain(..)
+            .partition(|w| w.is_at(project_id, branch, commit));
+        *pool = current;
+        if pool.len() > size {
+            stale.extend(pool.drain(size..));
+        }
+        stale
+    }
+
+    /// Remove all of the repository's worktrees
+    pub fn take_all(&self, repo_id: Uuid) -> Vec<WarmWorktree> {
+        self.worktrees
+            .lock()
+            .unwrap()
+            .remove(&repo_id)
+            .unwrap_or_default()
+    }
+
+    pub fn count(&self, repo_id: Uuid) -> usize {
+        self.worktrees
+            .lock()
+            .unwrap()
+            .get(&repo_id)
+            .map_or(0, Vec::len)
+    }
+
+    pub fn repo_ids(&self) -> Vec<Uuid> {
+        self.worktrees.lock().unwrap().keys().copied().collect()
+    }
+
+    /// Wake the refill task ahead of its next scheduled pass
+    pub fn request_refill(&self) {
+        self.refill.notify_one();
+    }
+
+    pub async fn refill_requested(&self) {
+        self.refill.notified().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROJECT: Uuid = Uuid::from_u128(1);
+
+    fn worktree(name: &str, commit: &str) -> WarmWorktree {
+        WarmWorktree {
+            dir: PathBuf::from(name),
+            project_id: PROJECT,
+            branch: "main".to_string(),
+            commit: commit.to_string(),
+            setup_log: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn takes_only_worktrees_at_the_requested_commit() {
+        let pool = WarmPool::new();
+        let repo_id = Uuid::new_v4();
+        pool.add(repo_id, worktree("a", "c1"));
+
+        assert_eq!(pool.take(repo_id, PROJECT, "main", "c2"), None);
+        assert_eq!(pool.take(repo_id, PROJECT, "dev", "c1"), None);
+        assert_eq!(pool.take(repo_id, Uuid::new_v4(), "main", "c1"), None);
+        assert_eq!(pool.take(Uuid::new_v4(), PROJECT, "main", "c1"), None);
+        assert_eq!(
+            pool.take(repo_id, PROJECT, "main", "c1"),
+            Some(worktree("a", "c1"))
+        );
+        assert_eq!(pool.count(repo_id), 0);
+    }
+
+    #[test]
+    fn drops_moved_and_surplus_worktrees() {
+        let pool = WarmPool::new();
+        let repo_id = Uuid::new_v4();
+        for (name, commit) in [("a", "c1"), ("b", "c2"), ("c", "c2"), ("d", "c2")] {
+            pool.add(repo_id, worktree(name, commit));
+        }
+        let mut other_project = worktree("e", "c2");
+        other_project.project_id = Uuid::new_v4();
+        pool.add(repo_id, other_project.clone());
+
+        let stale = pool.take_stale(repo_id, PROJECT, "main", "c2", 2);
+        assert_eq!(
+            stale,
+            vec![worktree("a", "c1"), other_project, worktree("d", "c2")],
+            "the old commit, the other project and the one over the pool size"
+        );
+        assert_eq!(pool.count(repo_id), 2);
+        assert_eq!(pool.take_all(repo_id).len(), 2);
+        assert!(pool.repo_ids().is_empty());
+    }
+}
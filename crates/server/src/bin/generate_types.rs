@@ -18,6 +18,8 @@ fn generate_types_content() -> String {
         db::models::project::Project::decl(),
         db::models::project::CreateProject::decl(),
         db::models::project::UpdateProject::decl(),
+        db::models::project::PrMergeMethod::decl(),
+        db::models::project::LoopPolicy::decl(),
         db::models::project::SearchResult::decl(),
         db::models::project::SearchMatchType::decl(),
         db::models::project_group::ProjectGroup::decl(),
@@ -27,6 +29,8 @@ fn generate_types_content() -> String {
         db::models::repo::UpdateRepo::decl(),
         db::models::project_repo::ProjectRepo::decl(),
         db::models::project_repo::CreateProjectRepo::decl(),
+        db::backup::BackupKind::decl(),
+        db::backup::BackupInfo::decl(),
         db::models::workspace_repo::WorkspaceRepo::decl(),
         db::models::workspace_repo::CreateWorkspaceRepo::decl(),
         db::models::workspace_repo::RepoWithTargetBranch::decl(),
@@ -56,6 +60,13 @@ fn generate_types_content() -> String {
         db::models::scratch::Scratch::decl(),
         db::models::scratch::CreateScratch::decl(),
         db::models::scratch::UpdateScratch::decl(),
+        db::models::workspace_template::WorkspaceTemplateRepo::decl(),
+        db::models::workspace_template::WorkspaceTemplate::decl(),
+        db::models::workspace_template::SaveWorkspaceTemplate::decl(),
+        db::models::task_plan::TaskPlanStatus::decl(),
+        db::models::task_plan::TaskPlan::decl(),
+        db::models::checkpoint_revert::CheckpointRevertRepo::decl(),
+        db::models::checkpoint_revert::CheckpointRevert::decl(),
         db::models::image::Image::decl(),
         db::models::image::CreateImage::decl(),
         db::models::workspace::Workspace::decl(),
@@ -110,6 +121,7 @@ fn generate_types_content() -> String {
         server::routes::repo::RegisterRepoRequest::decl(),
         server::routes::repo::InitRepoRequest::decl(),
         server::routes::tags::TagSearchParams::decl(),
+        server::routes::backups::RestoreBackupResponse::decl(),
         server::routes::oauth::TokenResponse::decl(),
         server::routes::config::UserSystemInfo::decl(),
         server::routes::config::Environment::decl(),
@@ -121,6 +133,9 @@ fn generate_types_content() -> String {
         server::routes::config::CheckAgentAvailabilityQuery::decl(),
         server::routes::oauth::CurrentUserResponse::decl(),
         server::routes::sessions::CreateFollowUpAttempt::decl(),
+        server::routes::sessions::SessionHandoffRequest::decl(),
+        server::routes::sessions::SessionHandoffResponse::decl(),
+        server::routes::execution_processes::RenormalizeResponse::decl(),
         server::routes::task_attempts::ChangeTargetBranchRequest::decl(),
         server::routes::task_attempts::ChangeTargetBranchResponse::decl(),
         server::routes::task_attempts::MergeTaskAttemptRequest::decl(),
@@ -129,6 +144,9 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::RenameBranchResponse::decl(),
         server::routes::task_attempts::OpenEditorRequest::decl(),
         server::routes::task_attempts::OpenEditorResponse::decl(),
+        server::routes::task_attempts::PreviewUrls::decl(),
+        server::routes::task_plans::UpdateTaskPlan::decl(),
+        server::routes::task_plans::ApproveTaskPlan::decl(),
         server::routes::shared_tasks::AssignSharedTaskRequest::decl(),
         server::routes::tasks::ShareTaskResponse::decl(),
         server::routes::tasks::CreateAndStartTaskRequest::decl(),
@@ -167,6 +185,9 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::workspace_summary::WorkspaceSummaryRequest::decl(),
         server::routes::task_attempts::workspace_summary::WorkspaceSummary::decl(),
         server::routes::task_attempts::workspace_summary::WorkspaceSummaryResponse::decl(),
+        server::routes::task_attempts::checkpoints::CheckpointRepo::decl(),
+        server::routes::task_attempts::checkpoints::Checkpoint::decl(),
+        server::routes::task_attempts::checkpoints::CheckpointTimeline::decl(),
         services::services::filesystem::DirectoryEntry::decl(),
         services::services::filesystem::DirectoryListResponse::decl(),
         services::services::config::Config::decl(),
@@ -179,11 +200,39 @@ fn generate_types_content() -> String {
         services::services::config::SoundFile::decl(),
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
+        services::services::config::LogRetentionConfig::decl(),
+        services::services::config::ExecutionLimitsConfig::decl(),
+        services::services::config::SandboxConfig::decl(),
+        services::services::config::WorktreeGcConfig::decl(),
+        services::services::config::PreviewProxyConfig::decl(),
+        services::services::config::DbBackupConfig::decl(),
         services::services::git::GitBranch::decl(),
         services::services::share::SharedTaskDetails::decl(),
-        services::services::queued_message::QueuedMessage::decl(),
+        db::models::queued_message::QueuedMessage::decl(),
         services::services::queued_message::QueueStatus::decl(),
+        services::services::resource_usage::ResourceUsage::decl(),
+        services::services::worktree_gc::WorktreeDiskUsage::decl(),
+        services::services::worktree_gc::WorkspaceDiskUsage::decl(),
+        services::services::worktree_gc::RepoDiskUsage::decl(),
+        services::services::worktree_gc::WorktreeGcReason::decl(),
+        services::services::worktree_gc::WorktreeGcSkipReason::decl(),
+        services::services::worktree_gc::WorktreeGcRemoval::decl(),
+        services::services::worktree_gc::WorktreeGcSkip::decl(),
+        services::services::worktree_gc::WorktreeGcReport::decl(),
+        services::services::worktree_gc::RunWorktreeGcRequest::decl(),
+        services::services::dev_processes::DevProcessStatus::decl(),
         services::services::git::ConflictOp::decl(),
+        services::services::git::DiffStat::decl(),
+        services::services::terminal::TerminalInput::decl(),
+        services::services::transcript::TranscriptFormat::decl(),
+        services::services::transcript::Transcript::decl(),
+        services::services::transcript::TranscriptTurn::decl(),
+        services::services::transcript::TranscriptEntry::decl(),
+        services::services::transcript::TranscriptEntryKind::decl(),
+        services::services::transcript::TranscriptToolStatus::decl(),
+        services::services::secrets::Secret::decl(),
+        services::services::secrets::CreateSecret::decl(),
+        services::services::secrets::UpdateSecret::decl(),
         executors::actions::ExecutorAction::decl(),
         executors::mcp_config::McpConfig::decl(),
         executors::actions::ExecutorActionType::decl(),
@@ -193,6 +242,7 @@ fn generate_types_content() -> String {
         executors::executors::BaseCodingAgent::decl(),
         executors::executors::CodingAgent::decl(),
         executors::executors::AvailabilityInfo::decl(),
+        executors::limits::ExecutionLimits::decl(),
         executors::command::CommandBuilder::decl(),
         executors::profile::ExecutorProfileId::decl(),
         executors::profile::ExecutorConfig::decl(),
@@ -214,6 +264,12 @@ fn generate_types_content() -> String {
         executors::executors::droid::Droid::decl(),
         executors::executors::droid::Autonomy::decl(),
         executors::executors::droid::ReasoningEffortLevel::decl(),
+        executors::executors::aider::Aider::decl(),
+        executors::executors::custom::Custom::decl(),
+        executors::executors::custom::CustomOutputFormat::decl(),
+        executors::executors::custom::CustomEntryType::decl(),
+        executors::executors::custom::CustomToolAction::decl(),
+        executors::executors::custom::CustomOutputRule::decl(),
         executors::executors::AppendPrompt::decl(),
         executors::actions::coding_agent_initial::CodingAgentInitialRequest::decl(),
         executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
@@ -315,6 +371,14 @@ fn generate_schemas() -> Result<HashMap<&'static str, String>, serde_json::Error
             "droid",
             generate_json_schema::<executors::executors::droid::Droid>()?,
         ),
+        (
+            "aider",
+            generate_json_schema::<executors::executors::aider::Aider>()?,
+        ),
+        (
+            "custom",
+            generate_json_schema::<executors::executors::custom::Custom>()?,
+        ),
     ]);
     println!(
         "✅ JSON schemas generated. {} schemas created.",
@@ -4,10 +4,14 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
 };
-use db::models::{
-    execution_process::ExecutionProcessError, project::ProjectError,
-    project_repo::ProjectRepoError, repo::RepoError, scratch::ScratchError, session::SessionError,
-    workspace::WorkspaceError,
+use db::{
+    backup::BackupError,
+    models::{
+        execution_process::ExecutionProcessError, project::ProjectError,
+        project_repo::ProjectRepoError, repo::RepoError, scratch::ScratchError,
+        session::SessionError, task_plan::TaskPlanError, workspace::WorkspaceError,
+        workspace_template::WorkspaceTemplateError,
+    },
 };
 use deployment::{DeploymentError, RemoteClientNotConfigured};
 use executors::executors::ExecutorError;
@@ -15,13 +19,16 @@ use git2::Error as Git2Error;
 use services::services::{
     config::{ConfigError, EditorOpenError},
     container::ContainerError,
+    dev_processes::DevProcessError,
     git::GitServiceError,
     git_host::GitHostError,
     image::ImageError,
     project::ProjectServiceError,
     remote_client::RemoteClientError,
     repo::RepoError as RepoServiceError,
+    secrets::SecretsError,
     share::ShareError,
+    transcript::TranscriptError,
     worktree_manager::WorktreeError,
 };
 use thiserror::Error;
@@ -57,9 +64,19 @@ pub enum ApiError {
     #[error(transparent)]
     Worktree(#[from] WorktreeError),
     #[error(transparent)]
+    Backup(#[from] BackupError),
+    #[error(transparent)]
+    WorkspaceTemplate(#[from] WorkspaceTemplateError),
+    #[error(transparent)]
+    TaskPlan(#[from] TaskPlanError),
+    #[error(transparent)]
+    Secrets(#[from] SecretsError),
+    #[error(transparent)]
     Config(#[from] ConfigError),
     #[error(transparent)]
     Image(#[from] ImageError),
+    #[error(transparent)]
+    Transcript(#[from] TranscriptError),
     #[error("Multipart error: {0}")]
     Multipart(#[from] MultipartError),
     #[error("IO error: {0}")]
@@ -126,6 +143,36 @@ impl IntoResponse for ApiError {
             ApiError::Executor(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutorError"),
             ApiError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DatabaseError"),
             ApiError::Worktree(_) => (StatusCode::INTERNAL_SERVER_ERROR, "WorktreeError"),
+            ApiError::Backup(err) => match err {
+                BackupError::NotFound(_) => (StatusCode::NOT_FOUND, "BackupError"),
+                BackupError::InvalidName(_) => (StatusCode::BAD_REQUEST, "BackupError"),
+                BackupError::Corrupt(_)
+                | BackupError::MissingSchema
+                | BackupError::NewerSchema(_) => (StatusCode::CONFLICT, "BackupError"),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "BackupError"),
+            },
+            ApiError::WorkspaceTemplate(err) => match err {
+                WorkspaceTemplateError::NotFound => {
+                    (StatusCode::NOT_FOUND, "WorkspaceTemplateError")
+                }
+                WorkspaceTemplateError::DuplicateName => {
+                    (StatusCode::CONFLICT, "WorkspaceTemplateError")
+                }
+                WorkspaceTemplateError::Database(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, "WorkspaceTemplateError")
+                }
+            },
+            ApiError::TaskPlan(err) => match err {
+                TaskPlanError::NotFound => (StatusCode::NOT_FOUND, "TaskPlanError"),
+                TaskPlanError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, "TaskPlanError"),
+            },
+            ApiError::Secrets(err) => match err {
+                SecretsError::NotFound => (StatusCode::NOT_FOUND, "SecretsError"),
+                SecretsError::DuplicateName(_) => (StatusCode::CONFLICT, "SecretsError"),
+                SecretsError::InvalidName(_) => (StatusCode::BAD_REQUEST, "SecretsError"),
+                SecretsError::Locked => (StatusCode::SERVICE_UNAVAILABLE, "SecretsError"),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "SecretsError"),
+            },
             ApiError::Config(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ConfigError"),
             ApiError::Image(img_err) => match img_err {
                 ImageError::InvalidFormat => (StatusCode::BAD_REQUEST, "InvalidImageFormat"),
@@ -133,6 +180,10 @@ impl IntoResponse for ApiError {
                 ImageError::NotFound => (StatusCode::NOT_FOUND, "ImageNotFound"),
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "ImageError"),
             },
+            ApiError::Transcript(err) => match err {
+                TranscriptError::WorkspaceNotFound(_) => (StatusCode::NOT_FOUND, "TranscriptError"),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "TranscriptError"),
+            },
             ApiError::Io(_) => (StatusCode::INTERNAL_SERVER_ERROR, "IoError"),
             ApiError::EditorOpen(err) => match err {
                 EditorOpenError::LaunchFailed { .. } => {
@@ -249,6 +300,20 @@ impl IntoResponse for ApiError {
                 RemoteClientError::Serde(_) => "Unexpected response from remote service.".to_string(),
                 RemoteClientError::Url(_) => "Remote service URL is invalid.".to_string(),
             },
+            ApiError::Backup(err) => match err {
+                BackupError::Database(_) | BackupError::Io(_) => format!("{}: {}", error_type, self),
+                _ => err.to_string(),
+            },
+            ApiError::WorkspaceTemplate(
+                err @ (WorkspaceTemplateError::NotFound | WorkspaceTemplateError::DuplicateName),
+            ) => err.to_string(),
+            ApiError::TaskPlan(err @ TaskPlanError::NotFound) => err.to_string(),
+            ApiError::Secrets(
+                err @ (SecretsError::NotFound
+                | SecretsError::DuplicateName(_)
+                | SecretsError::InvalidName(_)
+                | SecretsError::Locked),
+            ) => err.to_string(),
             ApiError::Unauthorized => "Unauthorized. Please sign in again.".to_string(),
             ApiError::BadRequest(msg) => msg.clone(),
             ApiError::Conflict(msg) => msg.clone(),
@@ -376,6 +441,15 @@ impl From<RepoServiceError> for ApiError {
     }
 }
 
+impl From<DevProcessError> for ApiError {
+    fn from(err: DevProcessError) -> Self {
+        match err {
+            DevProcessError::Database(db_err) => ApiError::Database(db_err),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
+
 impl From<ProjectRepoError> for ApiError {
     fn from(err: ProjectRepoError) -> Self {
         match err {
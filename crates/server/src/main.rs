@@ -68,6 +68,10 @@ async fn main() -> Result<(), VibeKanbanError> {
         .await
         .map_err(DeploymentError::from)?;
     deployment.spawn_pr_monitor_service().await;
+    deployment.spawn_db_backup_service().await;
+    deployment.spawn_log_compaction_service().await;
+    deployment.spawn_worktree_gc_service().await;
+    deployment.spawn_preview_proxy().await;
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
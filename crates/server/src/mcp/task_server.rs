@@ -6,6 +6,7 @@ use db::models::{
     tag::Tag,
     task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
     workspace::{Workspace, WorkspaceContext},
+    workspace_template::WorkspaceTemplate,
 };
 use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
 use regex::Regex;
@@ -85,6 +86,31 @@ pub struct ListReposResponse {
     pub project_id: String,
 }
 
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct McpWorkspaceTemplateSummary {
+    #[schemars(description = "The unique identifier of the workspace template")]
+    pub id: String,
+    #[schemars(description = "The name of the workspace template")]
+    pub name: String,
+    #[schemars(description = "Number of repositories the template sets up")]
+    pub repository_count: usize,
+    #[schemars(description = "The executor the template starts, if it pins one")]
+    pub executor: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListWorkspaceTemplatesRequest {
+    #[schemars(description = "The ID of the project to list workspace templates from")]
+    pub project_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListWorkspaceTemplatesResponse {
+    pub templates: Vec<McpWorkspaceTemplateSummary>,
+    pub count: usize,
+    pub project_id: String,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct ListProjectsResponse {
     pub projects: Vec<ProjectSummary>,
@@ -220,13 +246,20 @@ pub struct StartWorkspaceSessionRequest {
     #[schemars(description = "The ID of the task to start")]
     pub task_id: Uuid,
     #[schemars(
-        description = "The coding agent executor to run ('CLAUDE_CODE', 'CODEX', 'GEMINI', 'CURSOR_AGENT', 'OPENCODE')"
+        description = "The coding agent executor to run ('CLAUDE_CODE', 'CODEX', 'GEMINI', 'CURSOR_AGENT', 'OPENCODE'). Optional when `template_id` is given"
     )]
-    pub executor: String,
+    pub executor: Option<String>,
     #[schemars(description = "Optional executor variant, if needed")]
     pub variant: Option<String>,
-    #[schemars(description = "Base branch for each repository in the project")]
+    #[schemars(
+        description = "Base branch for each repository in the project. Optional when `template_id` is given"
+    )]
+    #[serde(default)]
     pub repos: Vec<McpWorkspaceRepoInput>,
+    #[schemars(
+        description = "Optional workspace template to start from. It supplies the repositories, branches and executor that are not given explicitly"
+    )]
+    pub template_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -580,6 +613,42 @@ impl TaskServer {
         TaskServer::success(&response)
     }
 
+    #[tool(
+        description = "List the workspace templates of a project. Pass a template's `id` as `template_id` to `start_workspace_session`. `project_id` is required!"
+    )]
+    async fn list_workspace_templates(
+        &self,
+        Parameters(ListWorkspaceTemplatesRequest { project_id }): Parameters<
+            ListWorkspaceTemplatesRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/projects/{}/workspace-templates", project_id));
+        let templates: Vec<WorkspaceTemplate> = match self.send_json(self.client.get(&url)).await {
+            Ok(ts) => ts,
+            Err(e) => return Ok(e),
+        };
+
+        let template_summaries: Vec<McpWorkspaceTemplateSummary> = templates
+            .into_iter()
+            .map(|t| McpWorkspaceTemplateSummary {
+                id: t.id.to_string(),
+                name: t.name,
+                repository_count: t.repos.len(),
+                executor: t
+                    .executor_profile_id
+                    .map(|profile| profile.0.executor.to_string()),
+            })
+            .collect();
+
+        let response = ListWorkspaceTemplatesResponse {
+            count: template_summaries.len(),
+            templates: template_summaries,
+            project_id: project_id.to_string(),
+        };
+
+        TaskServer::success(&response)
+    }
+
     #[tool(
         description = "List all the task/tickets in a project with optional filtering and execution status. `project_id` is required!"
     )]
@@ -641,7 +710,7 @@ impl TaskServer {
     }
 
     #[tool(
-        description = "Start working on a task by creating and launching a new workspace session."
+        description = "Start working on a task by creating and launching a new workspace session. Pass `template_id` to reuse a workspace template's repositories and executor."
     )]
     async fn start_workspace_session(
         &self,
@@ -650,45 +719,57 @@ impl TaskServer {
             executor,
             variant,
             repos,
+            template_id,
         }): Parameters<StartWorkspaceSessionRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        if repos.is_empty() {
+        if repos.is_empty() && template_id.is_none() {
             return Self::err(
                 "At least one repository must be specified.".to_string(),
                 None::<String>,
             );
         }
 
-        let executor_trimmed = executor.trim();
-        if executor_trimmed.is_empty() {
-            return Self::err("Executor must not be empty.".to_string(), None::<String>);
-        }
+        let executor_profile_id = match executor {
+            Some(executor) => {
+                let executor_trimmed = executor.trim();
+                if executor_trimmed.is_empty() {
+                    return Self::err("Executor must not be empty.".to_string(), None::<String>);
+                }
 
-        let normalized_executor = executor_trimmed.replace('-', "_").to_ascii_uppercase();
-        let base_executor = match BaseCodingAgent::from_str(&normalized_executor) {
-            Ok(exec) => exec,
-            Err(_) => {
+                let normalized_executor = executor_trimmed.replace('-', "_").to_ascii_uppercase();
+                let base_executor = match BaseCodingAgent::from_str(&normalized_executor) {
+                    Ok(exec) => exec,
+                    Err(_) => {
+                        return Self::err(
+                            format!("Unknown executor '{executor_trimmed}'."),
+                            None::<String>,
+                        );
+                    }
+                };
+
+                let variant = variant.and_then(|v| {
+                    let trimmed = v.trim();
+                    if trimmed.is_empty() {
+                        None
+                    } else {
+                        Some(trimmed.to_string())
+                    }
+                });
+
+                Some(ExecutorProfileId {
+                    executor: base_executor,
+                    variant,
+                })
+            }
+            None if template_id.is_some() => None,
+            None => {
                 return Self::err(
-                    format!("Unknown executor '{executor_trimmed}'."),
+                    "Executor must be specified when no template is given.".to_string(),
                     None::<String>,
                 );
             }
         };
 
-        let variant = variant.and_then(|v| {
-            let trimmed = v.trim();
-            if trimmed.is_empty() {
-                None
-            } else {
-                Some(trimmed.to_string())
-            }
-        });
-
-        let executor_profile_id = ExecutorProfileId {
-            executor: base_executor,
-            variant,
-        };
-
         let workspace_repos: Vec<WorkspaceRepoInput> = repos
             .into_iter()
             .map(|r| WorkspaceRepoInput {
@@ -701,6 +782,7 @@ impl TaskServer {
             task_id,
             executor_profile_id,
             repos: workspace_repos,
+            template_id,
         };
 
         let url = self.url("/api/task-attempts");
@@ -816,7 +898,7 @@ impl TaskServer {
 #[tool_handler]
 impl ServerHandler for TaskServer {
     fn get_info(&self) -> ServerInfo {
-        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`.. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_workspace_session', 'get_task', 'update_task', 'delete_task', 'list_repos'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string();
+        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`.. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_workspace_session', 'get_task', 'update_task', 'delete_task', 'list_repos', 'list_workspace_templates'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string();
         if self.context.is_some() {
             let context_instruction = "Use 'get_context' to fetch project/task/workspace metadata for the active Vibe Kanban workspace session when available.";
             instruction = format!("{} {}", context_instruction, instruction);
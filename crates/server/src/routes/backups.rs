@@ -0,0 +1,65 @@
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::backup::{self, BackupInfo, BackupKind};
+use deployment::Deployment;
+use serde::Serialize;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Serialize, TS)]
+pub struct RestoreBackupResponse {
+    pub backup: BackupInfo,
+    /// The restore is applied when Vibe Kanban next starts
+    pub restart_required: bool,
+}
+
+pub async fn list_backups() -> Result<ResponseJson<ApiResponse<Vec<BackupInfo>>>, ApiError> {
+    let backups = backup::list_backups().await?;
+    Ok(ResponseJson(ApiResponse::success(backups)))
+}
+
+pub async fn create_backup(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<BackupInfo>>, ApiError> {
+    let info = backup::create_backup(&deployment.db().pool, BackupKind::Manual).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "database_backup_created",
+            serde_json::json!({ "size_bytes": info.size_bytes }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(info)))
+}
+
+pub async fn restore_backup(
+    State(deployment): State<DeploymentImpl>,
+    Path(name): Path<String>,
+) -> Result<ResponseJson<ApiResponse<RestoreBackupResponse>>, ApiError> {
+    let info = backup::stage_restore(&name).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "database_backup_restore_staged",
+            serde_json::json!({ "kind": info.kind.to_string() }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(RestoreBackupResponse {
+        backup: info,
+        restart_required: true,
+    })))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/backups", get(list_backups).post(create_backup))
+        .route("/backups/{name}/restore", post(restore_backup))
+}
@@ -14,9 +14,11 @@ use db::models::{
     execution_process_repo_state::ExecutionProcessRepoState,
 };
 use deployment::Deployment;
-use futures_util::{SinkExt, StreamExt, TryStreamExt};
-use serde::Deserialize;
-use services::services::container::ContainerService;
+use executors::logs::NormalizedEntry;
+use futures_util::{SinkExt, StreamExt, TryStreamExt, stream};
+use serde::{Deserialize, Serialize};
+use services::services::{container::ContainerService, resource_usage::ResourceUsage};
+use ts_rs::TS;
 use utils::{log_msg::LogMsg, response::ApiResponse};
 use uuid::Uuid;
 
@@ -37,6 +39,18 @@ pub async fn get_execution_process_by_id(
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
+/// Latest CPU and memory sample of the process, or null when it is not running
+pub async fn get_execution_process_resources(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ResourceUsage>>>, ApiError> {
+    let usage = deployment
+        .container()
+        .resource_monitor()
+        .get(&execution_process.id);
+    Ok(ResponseJson(ApiResponse::success(usage)))
+}
+
 pub async fn stream_raw_logs_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
@@ -178,6 +192,32 @@ pub async fn stop_execution_process(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+#[derive(Debug, Serialize, TS)]
+pub struct RenormalizeResponse {
+    pub entries: Vec<NormalizedEntry>,
+}
+
+/// Re-render a finished execution with the current normalizer from its stored raw logs
+pub async fn renormalize_execution_process(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<RenormalizeResponse>>, ApiError> {
+    if execution_process.status == ExecutionProcessStatus::Running {
+        return Err(ApiError::Conflict(
+            "Execution process is still running".to_string(),
+        ));
+    }
+
+    let output = deployment
+        .container()
+        .renormalize_execution_process(&execution_process)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(RenormalizeResponse {
+        entries: output.normalized_entries(),
+    })))
+}
+
 pub async fn stream_execution_processes_by_session_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
@@ -203,12 +243,18 @@ async fn handle_execution_processes_by_session_ws(
     session_id: uuid::Uuid,
     show_soft_deleted: bool,
 ) -> anyhow::Result<()> {
-    // Get the raw stream and convert LogMsg to WebSocket messages
-    let mut stream = deployment
+    // Get the raw stream, with the resource usage of running processes alongside, and
+    // convert LogMsg to WebSocket messages
+    let processes = deployment
         .events()
         .stream_execution_processes_for_session_raw(session_id, show_soft_deleted)
-        .await?
-        .map_ok(|msg| msg.to_ws_message_unchecked());
+        .await?;
+    let resource_usage = deployment
+        .container()
+        .resource_monitor()
+        .stream_for_session(session_id);
+    let mut stream =
+        stream::select(processes, resource_usage).map_ok(|msg| msg.to_ws_message_unchecked());
 
     // Split socket into sender and receiver
     let (mut sender, mut receiver) = socket.split();
@@ -247,7 +293,9 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let workspace_id_router = Router::new()
         .route("/", get(get_execution_process_by_id))
         .route("/stop", post(stop_execution_process))
+        .route("/renormalize", post(renormalize_execution_process))
         .route("/repo-states", get(get_execution_process_repo_states))
+        .route("/resources", get(get_execution_process_resources))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
         .layer(from_fn_with_state(
@@ -6,6 +6,7 @@ use axum::{
 use crate::DeploymentImpl;
 
 pub mod approvals;
+pub mod backups;
 pub mod config;
 pub mod containers;
 pub mod events;
@@ -21,25 +22,32 @@ pub mod project_groups;
 pub mod projects;
 pub mod repo;
 pub mod scratch;
+pub mod secrets;
 pub mod sessions;
 pub mod shared_tasks;
 pub mod tags;
 pub mod task_attempts;
+pub mod task_plans;
 pub mod tasks;
+pub mod workspace_templates;
+pub mod worktrees;
 
 pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
     // Create routers with different middleware layers
     let base_routes = Router::new()
         .route("/health", get(health::health_check))
         .merge(config::router())
+        .merge(backups::router())
         .merge(containers::router(&deployment))
         .merge(projects::router(&deployment))
         .merge(project_groups::router(&deployment))
         .merge(tasks::router(&deployment))
         .merge(shared_tasks::router())
         .merge(task_attempts::router(&deployment))
+        .merge(task_plans::router())
         .merge(execution_processes::router(&deployment))
         .merge(tags::router(&deployment))
+        .merge(worktrees::router())
         .merge(oauth::router())
         .merge(organizations::router())
         .merge(filesystem::router())
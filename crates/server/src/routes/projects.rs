@@ -10,7 +10,7 @@ use axum::{
     http::StatusCode,
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
-    routing::{get, post},
+    routing::{get, post, put},
 };
 use db::models::{
     project::{CreateProject, Project, ProjectError, SearchResult, UpdateProject},
@@ -21,7 +21,7 @@ use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
 use services::services::{
-    file_search_cache::SearchQuery, project::ProjectServiceError,
+    dev_processes::parse_procfile, file_search_cache::SearchQuery, project::ProjectServiceError,
     remote_client::CreateRemoteProjectPayload,
 };
 use ts_rs::TS;
@@ -31,7 +31,21 @@ use utils::{
 };
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_project_middleware,
+    routes::{
+        secrets::{
+            create_project_secret, delete_project_secret, get_project_secrets,
+            update_project_secret,
+        },
+        workspace_templates::{
+            create_workspace_template, delete_workspace_template, get_project_workspace_templates,
+            update_workspace_template,
+        },
+    },
+};
 
 #[derive(Deserialize, TS)]
 pub struct LinkToExistingRequest {
@@ -266,6 +280,12 @@ pub async fn update_project(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<UpdateProject>,
 ) -> Result<ResponseJson<ApiResponse<Project>>, StatusCode> {
+    if let Some(Err(e)) = payload.dev_processes.as_deref().map(parse_procfile) {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Invalid dev processes: {e}"
+        ))));
+    }
+
     match deployment
         .project()
         .update_project(&deployment.db().pool, &existing_project, payload)
@@ -292,6 +312,9 @@ pub async fn delete_project(
             if rows_affected == 0 {
                 Err(StatusCode::NOT_FOUND)
             } else {
+                if let Err(e) = deployment.secrets().delete_for_project(project.id).await {
+                    tracing::warn!("Failed to delete secrets of project {}: {}", project.id, e);
+                }
                 deployment
                     .track_if_analytics_allowed(
                         "project_deleted",
@@ -586,6 +609,14 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/repositories",
             get(get_project_repositories).post(add_project_repository),
         )
+        .route(
+            "/workspace-templates",
+            get(get_project_workspace_templates).post(create_workspace_template),
+        )
+        .route(
+            "/secrets",
+            get(get_project_secrets).post(create_project_secret),
+        )
         .layer(from_fn_with_state(
             deployment.clone(),
             load_project_middleware,
@@ -597,6 +628,14 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/{project_id}/repositories/{repo_id}",
             get(get_project_repository).delete(delete_project_repository),
         )
+        .route(
+            "/{project_id}/workspace-templates/{template_id}",
+            put(update_workspace_template).delete(delete_workspace_template),
+        )
+        .route(
+            "/{project_id}/secrets/{secret_id}",
+            put(update_project_secret).delete(delete_project_secret),
+        )
         .route("/stream/ws", get(stream_projects_ws))
         .nest("/{id}", project_id_router);
 
@@ -7,7 +7,7 @@ use axum::{
 use db::models::repo::{Repo, UpdateRepo};
 use deployment::Deployment;
 use serde::Deserialize;
-use services::services::git::GitBranch;
+use services::services::{dev_processes::parse_procfile, git::GitBranch};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -115,6 +115,9 @@ pub async fn update_repo(
     Path(repo_id): Path<Uuid>,
     ResponseJson(payload): ResponseJson<UpdateRepo>,
 ) -> Result<ResponseJson<ApiResponse<Repo>>, ApiError> {
+    if let Some(dev_processes) = &payload.dev_processes {
+        parse_procfile(dev_processes)?;
+    }
     let repo = Repo::update(&deployment.db().pool, repo_id, &payload).await?;
     Ok(ResponseJson(ApiResponse::success(repo)))
 }
@@ -0,0 +1,70 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+};
+use db::models::{project::Project, project_repo::ProjectRepo};
+use deployment::Deployment;
+use services::services::secrets::{CreateSecret, Secret, UpdateSecret};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub async fn get_project_secrets(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Secret>>>, ApiError> {
+    let secrets = deployment.secrets().list(project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(secrets)))
+}
+
+pub async fn create_project_secret(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateSecret>,
+) -> Result<ResponseJson<ApiResponse<Secret>>, ApiError> {
+    if let Some(repo_id) = payload.repo_id
+        && ProjectRepo::find_by_project_and_repo(&deployment.db().pool, project.id, repo_id)
+            .await?
+            .is_none()
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Repository {repo_id} is not part of this project"
+        )));
+    }
+
+    let secret = deployment.secrets().create(project.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_secret_created",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "repo_scoped": secret.repo_id.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(secret)))
+}
+
+pub async fn update_project_secret(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, secret_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateSecret>,
+) -> Result<ResponseJson<ApiResponse<Secret>>, ApiError> {
+    let secret = deployment
+        .secrets()
+        .update(project_id, secret_id, &payload)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(secret)))
+}
+
+pub async fn delete_project_secret(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, secret_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    deployment.secrets().delete(project_id, secret_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
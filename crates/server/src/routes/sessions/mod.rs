@@ -4,9 +4,11 @@ use std::str::FromStr;
 
 use axum::{
     Extension, Json, Router,
+    body::Body,
     extract::{Query, State},
+    http::{StatusCode, header},
     middleware::from_fn_with_state,
-    response::Json as ResponseJson,
+    response::{Json as ResponseJson, Response},
     routing::{get, post},
 };
 use db::models::{
@@ -20,12 +22,17 @@ use deployment::Deployment;
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType, coding_agent_follow_up::CodingAgentFollowUpRequest,
+        coding_agent_initial::CodingAgentInitialRequest,
     },
     executors::BaseCodingAgent,
-    profile::ExecutorProfileId,
+    profile::{ExecutorConfigs, ExecutorProfileId},
+};
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    handoff::handoff_prompt,
+    transcript::{Transcript, TranscriptError, TranscriptFormat},
 };
-use serde::Deserialize;
-use services::services::container::ContainerService;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -40,6 +47,12 @@ pub struct SessionQuery {
     pub workspace_id: Uuid,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct TranscriptQuery {
+    #[serde(default)]
+    pub format: TranscriptFormat,
+}
+
 #[derive(Debug, Deserialize, TS)]
 pub struct CreateSessionRequest {
     pub workspace_id: Uuid,
@@ -118,30 +131,44 @@ pub async fn follow_up(
         .await?;
 
     // Get executor from the latest CodingAgent process, or fall back to session's executor
-    let base_executor =
-        match ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await? {
-            Some(profile) => profile.executor,
-            None => {
-                // No prior execution - use session's executor field
-                let executor_str = session.executor.as_ref().ok_or_else(|| {
-                    ApiError::Workspace(WorkspaceError::ValidationError(
-                        "No prior execution and no executor configured on session".to_string(),
-                    ))
-                })?;
-                BaseCodingAgent::from_str(&executor_str.replace('-', "_").to_ascii_uppercase())
-                    .map_err(|_| {
-                        ApiError::Workspace(WorkspaceError::ValidationError(format!(
-                            "Invalid executor: {}",
-                            executor_str
-                        )))
-                    })?
-            }
-        };
-
-    let executor_profile_id = ExecutorProfileId {
+    let latest_profile =
+        ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await?;
+    let base_executor = match &latest_profile {
+        Some(profile) => profile.executor,
+        None => {
+            // No prior execution - use session's executor field
+            let executor_str = session.executor.as_ref().ok_or_else(|| {
+                ApiError::Workspace(WorkspaceError::ValidationError(
+                    "No prior execution and no executor configured on session".to_string(),
+                ))
+            })?;
+            BaseCodingAgent::from_str(&executor_str.replace('-', "_").to_ascii_uppercase())
+                .map_err(|_| {
+                    ApiError::Workspace(WorkspaceError::ValidationError(format!(
+                        "Invalid executor: {}",
+                        executor_str
+                    )))
+                })?
+        }
+    };
+
+    // A fallback executor may have taken over the session, and the requested variant may
+    // only exist for the executor it started with; keep the latest variant in that case
+    let requested_profile = ExecutorProfileId {
         executor: base_executor,
         variant: payload.variant,
     };
+    let executor_profile_id = match latest_profile {
+        Some(latest)
+            if requested_profile.variant.is_none()
+                || ExecutorConfigs::get_cached()
+                    .get_coding_agent(&requested_profile)
+                    .is_none() =>
+        {
+            latest
+        }
+        _ => requested_profile,
+    };
 
     // If retry settings provided, perform replace-logic before proceeding
     if let Some(proc_id) = payload.retry_process_id {
@@ -235,10 +262,155 @@ pub async fn follow_up(
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct SessionHandoffRequest {
+    pub executor_profile_id: ExecutorProfileId,
+    /// What the new executor should do next; defaults to continuing the task
+    #[serde(default)]
+    pub prompt: String,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct SessionHandoffResponse {
+    pub session: Session,
+    pub execution_process: ExecutionProcess,
+}
+
+/// Continue the workspace with a different executor. Executors cannot resume each other's
+/// sessions, so this starts a new session whose first prompt summarizes this one.
+pub async fn handoff(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SessionHandoffRequest>,
+) -> Result<ResponseJson<ApiResponse<SessionHandoffResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace = Workspace::find_by_id(pool, session.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Stop the running agent before handing off the session".to_string(),
+        ));
+    }
+
+    let executor_profile_id = payload.executor_profile_id;
+    if ExecutorConfigs::get_cached()
+        .get_coding_agent(&executor_profile_id)
+        .is_none()
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Unknown executor profile {executor_profile_id}"
+        )));
+    }
+
+    deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+
+    let transcript = Transcript::for_session(deployment.container(), &session).await?;
+    let prompt = match payload.prompt.trim() {
+        "" => "Continue the task where the previous agent left off.",
+        prompt => prompt,
+    };
+    let prompt = handoff_prompt(
+        &transcript,
+        "The user switched executors to continue the work with you.",
+        prompt,
+    );
+
+    let new_session = Session::create(
+        pool,
+        &CreateSession {
+            executor: Some(executor_profile_id.executor.to_string()),
+        },
+        Uuid::new_v4(),
+        workspace.id,
+    )
+    .await?;
+
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let cleanup_action = deployment.container().cleanup_actions_for_repos(&repos);
+
+    let working_dir = workspace
+        .agent_working_dir
+        .as_ref()
+        .filter(|dir| !dir.is_empty())
+        .cloned();
+
+    let action = ExecutorAction::new(
+        ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+            prompt,
+            executor_profile_id: executor_profile_id.clone(),
+            working_dir,
+        }),
+        cleanup_action.map(Box::new),
+    );
+
+    let execution_process = deployment
+        .container()
+        .start_execution(
+            &workspace,
+            &new_session,
+            &action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "session_handed_off",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "from_executor": session.executor,
+                "to_executor": executor_profile_id.to_string(),
+                "turns": transcript.turns.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(SessionHandoffResponse {
+        session: new_session,
+        execution_process,
+    })))
+}
+
+/// Export the session history as Markdown, JSON or a self-contained HTML page
+pub async fn get_transcript(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TranscriptQuery>,
+) -> Result<Response, ApiError> {
+    let transcript = Transcript::for_session(deployment.container(), &session).await?;
+    let body = transcript.render(query.format)?;
+
+    let response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, query.format.content_type())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"{}\"",
+                transcript.file_name(query.format)
+            ),
+        )
+        .body(Body::from(body))
+        .map_err(|e| TranscriptError::ResponseBuildError(e.to_string()))?;
+    Ok(response)
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let session_id_router = Router::new()
         .route("/", get(get_session))
         .route("/follow-up", post(follow_up))
+        .route("/transcript", get(get_transcript))
+        .route("/handoff", post(handoff))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
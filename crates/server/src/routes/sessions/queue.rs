@@ -31,7 +31,8 @@ pub async fn queue_message(
 
     let queued = deployment
         .queued_message_service()
-        .queue_message(session.id, data);
+        .queue_message(session.id, data)
+        .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -55,7 +56,8 @@ pub async fn cancel_queued_message(
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
     deployment
         .queued_message_service()
-        .cancel_queued(session.id);
+        .cancel_queued(session.id)
+        .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -1,8 +1,11 @@
+pub mod checkpoints;
 pub mod codex_setup;
 pub mod cursor_setup;
+pub mod dev_processes;
 pub mod gh_cli_setup;
 pub mod images;
 pub mod pr;
+pub mod terminal;
 pub mod util;
 pub mod workspace_summary;
 
@@ -38,7 +41,7 @@ use executors::{
         ExecutorAction, ExecutorActionType,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
-    executors::{CodingAgent, ExecutorError},
+    executors::{CodingAgent, ExecutorError, aider::Aider},
     profile::{ExecutorConfigs, ExecutorProfileId},
 };
 use git2::BranchType;
@@ -50,12 +53,16 @@ use services::services::{
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{response::ApiResponse, text::short_uuid};
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_workspace_middleware,
-    routes::task_attempts::gh_cli_setup::GhCliSetupError,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_workspace_middleware,
+    routes::{
+        task_attempts::gh_cli_setup::GhCliSetupError, workspace_templates::resolve_workspace_setup,
+    },
 };
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -99,6 +106,7 @@ pub struct WorkspaceStreamQuery {
 pub struct UpdateWorkspace {
     pub archived: Option<bool>,
     pub pinned: Option<bool>,
+    pub auto_merge: Option<bool>,
     pub name: Option<String>,
 }
 
@@ -128,6 +136,7 @@ pub async fn update_workspace(
         workspace.id,
         request.archived,
         request.pinned,
+        request.auto_merge,
         request.name.as_deref(),
     )
     .await?;
@@ -140,8 +149,19 @@ pub async fn update_workspace(
 #[derive(Debug, Serialize, Deserialize, ts_rs::TS)]
 pub struct CreateTaskAttemptBody {
     pub task_id: Uuid,
-    pub executor_profile_id: ExecutorProfileId,
+    /// Falls back to the template's executor profile when omitted
+    #[serde(default)]
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    /// Falls back to the template's repositories when empty
+    #[serde(default)]
     pub repos: Vec<WorkspaceRepoInput>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub template_id: Option<Uuid>,
+    /// Draft a plan with this profile first; `executor_profile_id` starts once it is approved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub plan_executor_profile_id: Option<ExecutorProfileId>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ts_rs::TS)]
@@ -163,30 +183,20 @@ pub async fn create_task_attempt(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateTaskAttemptBody>,
 ) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
-    let executor_profile_id = payload.executor_profile_id.clone();
-
-    if payload.repos.is_empty() {
-        return Err(ApiError::BadRequest(
-            "At least one repository is required".to_string(),
-        ));
-    }
-
     let pool = &deployment.db().pool;
     let task = Task::find_by_id(&deployment.db().pool, payload.task_id)
         .await?
         .ok_or(SqlxError::RowNotFound)?;
 
-    // Compute agent_working_dir based on repo count:
-    // - Single repo: use repo name as working dir (agent runs in repo directory)
-    // - Multiple repos: use None (agent runs in workspace root)
-    let agent_working_dir = if payload.repos.len() == 1 {
-        let repo = Repo::find_by_id(pool, payload.repos[0].repo_id)
-            .await?
-            .ok_or(RepoError::NotFound)?;
-        Some(repo.name)
-    } else {
-        None
-    };
+    let setup = resolve_workspace_setup(
+        pool,
+        task.project_id,
+        payload.template_id,
+        payload.executor_profile_id,
+        payload.repos,
+    )
+    .await?;
+    let executor_profile_id = setup.executor_profile_id.clone();
 
     let attempt_id = Uuid::new_v4();
     let git_branch_name = deployment
@@ -198,14 +208,17 @@ pub async fn create_task_attempt(
         pool,
         &CreateWorkspace {
             branch: git_branch_name.clone(),
-            agent_working_dir,
+            agent_working_dir: setup.agent_working_dir,
         },
         attempt_id,
         payload.task_id,
     )
     .await?;
+    if let Some(template_id) = setup.template_id {
+        Workspace::set_template(pool, workspace.id, template_id).await?;
+    }
 
-    let workspace_repos: Vec<CreateWorkspaceRepo> = payload
+    let workspace_repos: Vec<CreateWorkspaceRepo> = setup
         .repos
         .iter()
         .map(|r| CreateWorkspaceRepo {
@@ -217,7 +230,11 @@ pub async fn create_task_attempt(
     WorkspaceRepo::create_many(pool, workspace.id, &workspace_repos).await?;
     if let Err(err) = deployment
         .container()
-        .start_workspace(&workspace, executor_profile_id.clone())
+        .start_workspace(
+            &workspace,
+            executor_profile_id.clone(),
+            payload.plan_executor_profile_id.clone(),
+        )
         .await
     {
         tracing::error!("Failed to start task attempt: {}", err);
@@ -231,7 +248,9 @@ pub async fn create_task_attempt(
                 "variant": &executor_profile_id.variant,
                 "executor": &executor_profile_id.executor,
                 "workspace_id": workspace.id.to_string(),
-                "repository_count": payload.repos.len(),
+                "repository_count": setup.repos.len(),
+                "from_template": setup.template_id.is_some(),
+                "plan_first": payload.plan_executor_profile_id.is_some(),
             }),
         )
         .await;
@@ -1236,26 +1255,16 @@ pub async fn start_dev_server(
         .filter(|r| r.dev_server_script.as_ref().is_some_and(|s| !s.is_empty()))
         .collect();
 
-    if repos_with_dev_script.is_empty() {
+    let named_processes =
+        services::services::dev_processes::workspace_dev_processes(pool, &workspace).await?;
+
+    if repos_with_dev_script.is_empty() && named_processes.is_empty() {
         return Ok(ResponseJson(ApiResponse::error(
             "No dev server script configured for any repository in this workspace",
         )));
     }
 
-    let session = match Session::find_latest_by_workspace_id(pool, workspace.id).await? {
-        Some(s) => s,
-        None => {
-            Session::create(
-                pool,
-                &CreateSession {
-                    executor: Some("dev-server".to_string()),
-                },
-                Uuid::new_v4(),
-                workspace.id,
-            )
-            .await?
-        }
-    };
+    let session = dev_processes::dev_server_session(pool, &workspace).await?;
 
     for repo in repos_with_dev_script {
         let executor_action = ExecutorAction::new(
@@ -1264,6 +1273,7 @@ pub async fn start_dev_server(
                 language: ScriptRequestLanguage::Bash,
                 context: ScriptContext::DevServer,
                 working_dir: Some(repo.name.clone()),
+                dev_process: None,
             }),
             None,
         );
@@ -1279,6 +1289,10 @@ pub async fn start_dev_server(
             .await?;
     }
 
+    for process in &named_processes {
+        dev_processes::start_dev_process(&deployment, &workspace, &session, process).await?;
+    }
+
     deployment
         .track_if_analytics_allowed(
             "dev_server_started",
@@ -1293,6 +1307,34 @@ pub async fn start_dev_server(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Stable URLs of the workspace's dev server through the preview proxy
+#[derive(Debug, Serialize, TS)]
+pub struct PreviewUrls {
+    /// `http://<short id>.localhost:<port>/`; `<process>.` in front of the host picks a named
+    /// dev process
+    pub host_url: String,
+    /// `http://localhost:<port>/<short id>/`, for clients that do not resolve `*.localhost`
+    pub path_url: String,
+}
+
+pub async fn get_preview_urls(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<PreviewUrls>>, ApiError> {
+    let proxy = deployment.config().read().await.preview_proxy.clone();
+    if !proxy.enabled {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The preview proxy is disabled in the settings",
+        )));
+    }
+
+    let short_id = short_uuid(&workspace.id);
+    Ok(ResponseJson(ApiResponse::success(PreviewUrls {
+        host_url: format!("http://{short_id}.localhost:{}/", proxy.port),
+        path_url: format!("http://localhost:{}/{short_id}/", proxy.port),
+    })))
+}
+
 pub async fn get_task_attempt_children(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
@@ -1615,6 +1657,9 @@ pub async fn delete_workspace(
     // Gather data needed for background cleanup
     let workspace_dir = workspace.container_ref.clone().map(PathBuf::from);
     let repositories = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let revert_refs = checkpoints::find_revert_refs(pool, workspace.id, &repositories).await?;
+    let agent_session_ids =
+        CodingAgentTurn::find_agent_session_ids_by_workspace_id(pool, workspace.id).await?;
 
     // Nullify parent_workspace_id for any child tasks before deletion
     let children_affected = Task::nullify_children_by_workspace_id(pool, workspace.id).await?;
@@ -1644,6 +1689,11 @@ pub async fn delete_workspace(
         .await;
 
     // Spawn background cleanup task for filesystem resources
+    let git = deployment.git().clone();
+    tokio::spawn(async move {
+        checkpoints::prune_revert_refs(&git, &revert_refs);
+        Aider::remove_sessions(&agent_session_ids).await;
+    });
     if let Some(workspace_dir) = workspace_dir {
         let workspace_id = workspace.id;
         tokio::spawn(async move {
@@ -1698,6 +1748,14 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/run-agent-setup", post(run_agent_setup))
         .route("/gh-cli-setup", post(gh_cli_setup_handler))
         .route("/start-dev-server", post(start_dev_server))
+        .route("/preview-urls", get(get_preview_urls))
+        .route("/dev-processes", get(dev_processes::get_dev_processes))
+        .route("/dev-processes/{name}/start", post(dev_processes::start))
+        .route("/dev-processes/{name}/stop", post(dev_processes::stop))
+        .route(
+            "/dev-processes/{name}/restart",
+            post(dev_processes::restart),
+        )
         .route("/run-setup-script", post(run_setup_script))
         .route("/run-cleanup-script", post(run_cleanup_script))
         .route("/branch-status", get(get_task_attempt_branch_status))
@@ -1718,6 +1776,16 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/repos", get(get_task_attempt_repos))
         .route("/first-message", get(get_first_user_message))
         .route("/mark-seen", put(mark_seen))
+        .route("/checkpoints", get(checkpoints::get_checkpoints))
+        .route("/terminal/ws", get(terminal::terminal_ws))
+        .route(
+            "/checkpoints/{process_id}/revert",
+            post(checkpoints::revert_to_checkpoint),
+        )
+        .route(
+            "/checkpoint-reverts/{revert_id}/undo",
+            post(checkpoints::undo_checkpoint_revert),
+        )
         .layer(from_fn_with_state(
             deployment.clone(),
             load_workspace_middleware,
@@ -0,0 +1,409 @@
+use std::path::PathBuf;
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    checkpoint_revert::{CheckpointRevert, CheckpointRevertRepo},
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    execution_process_repo_state::ExecutionProcessRepoState,
+    repo::Repo,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use executors::actions::ExecutorActionType;
+use serde::Serialize;
+use services::services::{
+    container::ContainerService,
+    git::{DiffStat, GitService},
+};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Serialize, TS)]
+pub struct CheckpointRepo {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub before_head_commit: Option<String>,
+    pub after_head_commit: Option<String>,
+    /// Changes committed by the turn; missing while it runs or when a commit is gone
+    pub diff_stat: Option<DiffStat>,
+}
+
+/// One coding agent turn of a workspace; reverting to it restores the worktrees to the state
+/// before the turn ran
+#[derive(Debug, Serialize, TS)]
+pub struct Checkpoint {
+    pub execution_process_id: Uuid,
+    pub session_id: Uuid,
+    pub prompt: Option<String>,
+    pub executor: Option<String>,
+    pub status: ExecutionProcessStatus,
+    pub dropped: bool,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub repos: Vec<CheckpointRepo>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct CheckpointTimeline {
+    /// Oldest first
+    pub checkpoints: Vec<Checkpoint>,
+    /// Newest first
+    pub reverts: Vec<CheckpointRevert>,
+}
+
+async fn ensure_no_running_processes(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+) -> Result<(), ApiError> {
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+        &deployment.db().pool,
+        workspace.id,
+    )
+    .await?
+    {
+        return Err(ApiError::Conflict(
+            "Stop the running processes before changing checkpoints".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn get_checkpoints(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<CheckpointTimeline>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let turns = ExecutionProcess::find_coding_agent_turns_for_workspace(pool, workspace.id).await?;
+
+    let mut checkpoints = Vec::with_capacity(turns.len());
+    for turn in turns {
+        let (prompt, executor) = match turn.executor_action().map(|a| a.typ()) {
+            Ok(ExecutorActionType::CodingAgentInitialRequest(request)) => (
+                Some(request.prompt.clone()),
+                Some(request.executor_profile_id.to_string()),
+            ),
+            Ok(ExecutorActionType::CodingAgentFollowUpRequest(request)) => (
+                Some(request.prompt.clone()),
+                Some(request.executor_profile_id.to_string()),
+            ),
+            _ => (None, None),
+        };
+
+        let repo_states =
+            ExecutionProcessRepoState::find_by_execution_process_id(pool, turn.id).await?;
+        let checkpoint_repos = repo_states
+            .into_iter()
+            .filter_map(|state| {
+                let repo = repos.iter().find(|r| r.id == state.repo_id)?;
+                let diff_stat = match (&state.before_head_commit, &state.after_head_commit) {
+                    (Some(before), Some(after)) => {
+                        deployment.git().diff_stat(&repo.path, before, after).ok()
+                    }
+                    _ => None,
+                };
+                Some(CheckpointRepo {
+                    repo_id: repo.id,
+                    repo_name: repo.name.clone(),
+                    before_head_commit: state.before_head_commit,
+                    after_head_commit: state.after_head_commit,
+                    diff_stat,
+                })
+            })
+            .collect();
+
+        checkpoints.push(Checkpoint {
+            execution_process_id: turn.id,
+            session_id: turn.session_id,
+            prompt,
+            executor,
+            status: turn.status,
+            dropped: turn.dropped,
+            started_at: turn.started_at,
+            completed_at: turn.completed_at,
+            repos: checkpoint_repos,
+        });
+    }
+
+    let reverts = CheckpointRevert::find_by_workspace_id(pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(CheckpointTimeline {
+        checkpoints,
+        reverts,
+    })))
+}
+
+fn delete_snapshot_refs(
+    deployment: &DeploymentImpl,
+    repos: &[Repo],
+    revert_id: Uuid,
+    reverted_repos: &[CheckpointRevertRepo],
+) {
+    for reverted in reverted_repos {
+        let Some(repo) = repos.iter().find(|r| r.id == reverted.repo_id) else {
+            continue;
+        };
+        let refname = CheckpointRevert::snapshot_ref(revert_id, repo.id);
+        if let Err(e) = deployment.git().delete_ref(&repo.path, &refname) {
+            tracing::warn!(
+                "Failed to delete {} in {}: {}",
+                refname,
+                repo.path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Snapshot refs of all reverts of a workspace, as (repository path, ref) pairs. Gather them
+/// before deleting the workspace, since its reverts go with it.
+pub async fn find_revert_refs(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    repos: &[Repo],
+) -> Result<Vec<(PathBuf, String)>, sqlx::Error> {
+    let reverts = CheckpointRevert::find_by_workspace_id(pool, workspace_id).await?;
+    Ok(reverts
+        .iter()
+        .flat_map(|revert| {
+            revert.repos.iter().filter_map(|reverted| {
+                let repo = repos.iter().find(|r| r.id == reverted.repo_id)?;
+                Some((
+                    repo.path.clone(),
+                    CheckpointRevert::snapshot_ref(revert.id, repo.id),
+                ))
+            })
+        })
+        .collect())
+}
+
+/// Delete snapshot refs gathered by [`find_revert_refs`]
+pub fn prune_revert_refs(git: &GitService, refs: &[(PathBuf, String)]) {
+    for (repo_path, refname) in refs {
+        if let Err(e) = git.delete_ref(repo_path, refname) {
+            tracing::warn!(
+                "Failed to delete {} in {}: {}",
+                refname,
+                repo_path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Revert the worktrees to the state before the given turn. Uncommitted changes are kept in a
+/// snapshot commit, and the turn and everything after it is marked as dropped.
+pub async fn revert_to_checkpoint(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_id, process_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<CheckpointRevert>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let is_turn = ExecutionProcess::find_coding_agent_turns_for_workspace(pool, workspace.id)
+        .await?
+        .iter()
+        .any(|turn| turn.id == process_id);
+    if !is_turn {
+        return Err(ApiError::BadRequest("Checkpoint not found".to_string()));
+    }
+    ensure_no_running_processes(&deployment, &workspace).await?;
+
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let repo_states =
+        ExecutionProcessRepoState::find_by_execution_process_id(pool, process_id).await?;
+    let workspace_dir = PathBuf::from(
+        deployment
+            .container()
+            .ensure_container_exists(&workspace)
+            .await?,
+    );
+
+    // Snapshot every worktree and record the revert before anything is reset, so the revert
+    // can always be undone, even when the server stops halfway through
+    let revert_id = Uuid::new_v4();
+    let mut targets = Vec::new();
+    let mut reverted_repos = Vec::new();
+    for repo in &repos {
+        let target = match repo_states
+            .iter()
+            .find(|s| s.repo_id == repo.id)
+            .and_then(|s| s.before_head_commit.clone())
+        {
+            Some(oid) => Some(oid),
+            None => {
+                ExecutionProcess::find_prev_after_head_commit(
+                    pool,
+                    workspace.id,
+                    process_id,
+                    repo.id,
+                )
+                .await?
+            }
+        };
+        let Some(target) = target else {
+            continue;
+        };
+
+        let worktree_path = workspace_dir.join(&repo.name);
+        let head_commit = deployment.git().get_head_info(&worktree_path)?.oid;
+        let snapshot_commit = match deployment.git().snapshot_worktree(
+            &worktree_path,
+            &CheckpointRevert::snapshot_ref(revert_id, repo.id),
+            &format!("Worktree before reverting to checkpoint {process_id}"),
+        ) {
+            Ok(sha) => sha,
+            Err(e) => {
+                delete_snapshot_refs(&deployment, &repos, revert_id, &reverted_repos);
+                return Err(e.into());
+            }
+        };
+        targets.push((worktree_path, target));
+        reverted_repos.push(CheckpointRevertRepo {
+            repo_id: repo.id,
+            head_commit,
+            snapshot_commit,
+        });
+    }
+
+    let dropped_ids =
+        ExecutionProcess::find_droppable_ids_for_workspace(pool, workspace.id, process_id).await?;
+    let revert = match CheckpointRevert::create(
+        pool,
+        revert_id,
+        workspace.id,
+        process_id,
+        &reverted_repos,
+        &dropped_ids,
+    )
+    .await
+    {
+        Ok(revert) => revert,
+        Err(e) => {
+            delete_snapshot_refs(&deployment, &repos, revert_id, &reverted_repos);
+            return Err(e.into());
+        }
+    };
+
+    for (index, (worktree_path, target)) in targets.iter().enumerate() {
+        if let Err(e) = deployment
+            .git()
+            .discard_and_reset_worktree(worktree_path, target)
+        {
+            // Put back every worktree touched so far, the failed one included, and forget the
+            // revert so the workspace is left as it was
+            for ((path, _), reverted) in targets.iter().zip(&reverted_repos).take(index + 1) {
+                if let Err(restore_err) = deployment.git().restore_worktree_snapshot(
+                    path,
+                    &reverted.snapshot_commit,
+                    &reverted.head_commit,
+                ) {
+                    tracing::error!(
+                        "Failed to restore {} after a failed checkpoint revert: {}",
+                        path.display(),
+                        restore_err
+                    );
+                }
+            }
+            CheckpointRevert::delete(pool, revert_id).await?;
+            delete_snapshot_refs(&deployment, &repos, revert_id, &reverted_repos);
+            return Err(e.into());
+        }
+    }
+
+    for id in &dropped_ids {
+        ExecutionProcess::set_dropped(pool, *id, true).await?;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "checkpoint_reverted",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "dropped_processes": dropped_ids.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(revert)))
+}
+
+/// Undo the latest revert: restore the worktrees exactly as they were before it, uncommitted
+/// changes included, and bring the dropped turns back
+pub async fn undo_checkpoint_revert(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_id, revert_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let revert = CheckpointRevert::find_by_id(pool, revert_id)
+        .await?
+        .filter(|revert| revert.workspace_id == workspace.id)
+        .ok_or_else(|| ApiError::BadRequest("Revert not found".to_string()))?;
+    if revert.undone_at.is_some() {
+        return Err(ApiError::Conflict(
+            "The revert was already undone".to_string(),
+        ));
+    }
+    let latest = CheckpointRevert::find_by_workspace_id(pool, workspace.id)
+        .await?
+        .into_iter()
+        .find(|r| r.undone_at.is_none());
+    if latest.map(|r| r.id) != Some(revert.id) {
+        return Err(ApiError::Conflict(
+            "Only the latest revert can be undone".to_string(),
+        ));
+    }
+    ensure_no_running_processes(&deployment, &workspace).await?;
+    let has_new_turns = ExecutionProcess::find_coding_agent_turns_for_workspace(pool, workspace.id)
+        .await?
+        .iter()
+        .any(|turn| !turn.dropped && turn.created_at > revert.created_at);
+    if has_new_turns {
+        return Err(ApiError::Conflict(
+            "The agent ran again after this revert; revert to a checkpoint instead".to_string(),
+        ));
+    }
+
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let workspace_dir = PathBuf::from(
+        deployment
+            .container()
+            .ensure_container_exists(&workspace)
+            .await?,
+    );
+    for reverted in revert.repos.iter() {
+        let Some(repo) = repos.iter().find(|r| r.id == reverted.repo_id) else {
+            continue;
+        };
+        deployment.git().restore_worktree_snapshot(
+            &workspace_dir.join(&repo.name),
+            &reverted.snapshot_commit,
+            &reverted.head_commit,
+        )?;
+    }
+
+    for id in revert.dropped_process_ids.iter() {
+        ExecutionProcess::set_dropped(pool, *id, false).await?;
+    }
+    CheckpointRevert::mark_undone(pool, revert.id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "checkpoint_revert_undone",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
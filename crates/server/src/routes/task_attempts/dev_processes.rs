@@ -0,0 +1,128 @@
+use axum::{
+    Extension,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    session::{CreateSession, Session},
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use executors::actions::{ExecutorAction, ExecutorActionType};
+use services::services::{
+    container::ContainerService,
+    dev_processes::{self, DevProcess, DevProcessStatus},
+};
+use sqlx::SqlitePool;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// The latest session of the workspace, or a new one for dev servers if it has none
+pub(super) async fn dev_server_session(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+) -> Result<Session, ApiError> {
+    match Session::find_latest_by_workspace_id(pool, workspace.id).await? {
+        Some(session) => Ok(session),
+        None => Ok(Session::create(
+            pool,
+            &CreateSession {
+                executor: Some("dev-server".to_string()),
+            },
+            Uuid::new_v4(),
+            workspace.id,
+        )
+        .await?),
+    }
+}
+
+pub(super) async fn start_dev_process(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    session: &Session,
+    process: &DevProcess,
+) -> Result<ExecutionProcess, ApiError> {
+    let executor_action = ExecutorAction::new(
+        ExecutorActionType::ScriptRequest(process.script_request()),
+        None,
+    );
+    Ok(deployment
+        .container()
+        .start_execution(
+            workspace,
+            session,
+            &executor_action,
+            &ExecutionProcessRunReason::DevServer,
+        )
+        .await?)
+}
+
+async fn stop_dev_process(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    name: &str,
+) -> Result<(), ApiError> {
+    for process in dev_processes::find_running(&deployment.db().pool, workspace.id, name).await? {
+        deployment
+            .container()
+            .stop_execution(&process, ExecutionProcessStatus::Killed)
+            .await?;
+    }
+    Ok(())
+}
+
+pub async fn get_dev_processes(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DevProcessStatus>>>, ApiError> {
+    let statuses = dev_processes::statuses(deployment.container(), &workspace).await?;
+    Ok(ResponseJson(ApiResponse::success(statuses)))
+}
+
+pub async fn start(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_id, name)): Path<(Uuid, String)>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let process = dev_processes::find_dev_process(pool, &workspace, &name).await?;
+    if !dev_processes::find_running(pool, workspace.id, &name)
+        .await?
+        .is_empty()
+    {
+        return Err(ApiError::Conflict(format!(
+            "Dev process `{name}` is already running"
+        )));
+    }
+
+    let session = dev_server_session(pool, &workspace).await?;
+    let execution_process = start_dev_process(&deployment, &workspace, &session, &process).await?;
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
+pub async fn stop(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_id, name)): Path<(Uuid, String)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    dev_processes::find_dev_process(&deployment.db().pool, &workspace, &name).await?;
+    stop_dev_process(&deployment, &workspace, &name).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn restart(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_id, name)): Path<(Uuid, String)>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let process = dev_processes::find_dev_process(pool, &workspace, &name).await?;
+    stop_dev_process(&deployment, &workspace, &name).await?;
+
+    let session = dev_server_session(pool, &workspace).await?;
+    let execution_process = start_dev_process(&deployment, &workspace, &session, &process).await?;
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
@@ -0,0 +1,132 @@
+use axum::{
+    Extension,
+    extract::{
+        Query, State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    response::IntoResponse,
+};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    session::Session,
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use services::services::{container::ContainerService, terminal::TerminalInput};
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct TerminalQuery {
+    /// Initial window size; the client can resize at any time afterwards
+    pub cols: Option<u16>,
+    pub rows: Option<u16>,
+}
+
+/// Open a shell in the workspace. The socket carries `LogMsg` output frames like `raw-logs/ws`
+/// and accepts `TerminalInput` messages; closing it hangs up the shell. The shell is recorded
+/// in the workspace's latest session, so the workspace needs one first.
+pub async fn terminal_ws(
+    ws: WebSocketUpgrade,
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TerminalQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let pool = &deployment.db().pool;
+    // Reload so the container ref set above is visible
+    let workspace = Workspace::find_by_id(pool, workspace.id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Workspace not found".to_string()))?;
+    let session = Session::find_latest_by_workspace_id(pool, workspace.id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::BadRequest(
+                "Start a session in this workspace before opening a terminal".to_string(),
+            )
+        })?;
+
+    Ok(ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_terminal_ws(socket, deployment, workspace, session, query).await {
+            tracing::warn!("terminal WS closed: {}", e);
+        }
+    }))
+}
+
+async fn handle_terminal_ws(
+    socket: WebSocket,
+    deployment: DeploymentImpl,
+    workspace: Workspace,
+    session: Session,
+    query: TerminalQuery,
+) -> anyhow::Result<()> {
+    let container = deployment.container();
+    let execution_process = container.open_terminal(&workspace, &session).await?;
+    let exec_id = execution_process.id;
+
+    deployment
+        .track_if_analytics_allowed(
+            "terminal_opened",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+            }),
+        )
+        .await;
+
+    if let (Some(cols), Some(rows)) = (query.cols, query.rows)
+        && cols > 0
+        && rows > 0
+    {
+        container
+            .send_terminal_input(&exec_id, TerminalInput::Resize { cols, rows })
+            .await?;
+    }
+
+    let mut output = container
+        .stream_raw_logs(&exec_id)
+        .await
+        .ok_or_else(|| anyhow::anyhow!("Terminal output not found"))?;
+    let (mut sender, mut receiver) = socket.split();
+
+    loop {
+        tokio::select! {
+            msg = output.next() => match msg {
+                Some(Ok(msg)) => {
+                    if sender.send(msg.to_ws_message_unchecked()).await.is_err() {
+                        break; // client disconnected
+                    }
+                }
+                Some(Err(e)) => {
+                    tracing::error!("terminal stream error: {}", e);
+                    break;
+                }
+                None => break, // shell exited
+            },
+            incoming = receiver.next() => match incoming {
+                Some(Ok(Message::Text(text))) => {
+                    if let Some(input) = TerminalInput::parse(&text)
+                        && let Err(e) = container.send_terminal_input(&exec_id, input).await
+                    {
+                        tracing::debug!("Dropped terminal input for {}: {}", exec_id, e);
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    if let Some(process) = ExecutionProcess::find_by_id(&deployment.db().pool, exec_id).await?
+        && process.status == ExecutionProcessStatus::Running
+    {
+        container
+            .stop_execution(&process, ExecutionProcessStatus::Killed)
+            .await?;
+    }
+    Ok(())
+}
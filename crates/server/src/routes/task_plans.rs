@@ -0,0 +1,167 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{post, put},
+};
+use db::models::{
+    execution_process::ExecutionProcess,
+    task::Task,
+    task_plan::{TaskPlan, TaskPlanError, TaskPlanStatus},
+};
+use deployment::Deployment;
+use executors::profile::{ExecutorConfigs, ExecutorProfileId};
+use serde::Deserialize;
+use services::services::container::ContainerService;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateTaskPlan {
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ApproveTaskPlan {
+    /// Edited plan; the stored plan is used when omitted
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Execute with a different profile than the one chosen when the workspace was started
+    #[serde(default)]
+    pub executor_profile_id: Option<ExecutorProfileId>,
+}
+
+async fn load_plan(deployment: &DeploymentImpl, plan_id: Uuid) -> Result<TaskPlan, ApiError> {
+    Ok(TaskPlan::find_by_id(&deployment.db().pool, plan_id)
+        .await?
+        .ok_or(TaskPlanError::NotFound)?)
+}
+
+fn not_awaiting_approval(status: TaskPlanStatus) -> ApiError {
+    match status {
+        TaskPlanStatus::Drafting => {
+            ApiError::Conflict("The plan is still being drafted".to_string())
+        }
+        TaskPlanStatus::Failed => {
+            ApiError::Conflict("The planning run failed; reject the plan instead".to_string())
+        }
+        _ => ApiError::Conflict(format!(
+            "The plan was already {}",
+            status.to_string().replace('_', " ")
+        )),
+    }
+}
+
+async fn load_awaiting_plan(
+    deployment: &DeploymentImpl,
+    plan_id: Uuid,
+) -> Result<TaskPlan, ApiError> {
+    let plan = load_plan(deployment, plan_id).await?;
+    match plan.status {
+        TaskPlanStatus::AwaitingApproval => Ok(plan),
+        status => Err(not_awaiting_approval(status)),
+    }
+}
+
+pub async fn get_task_plans(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskPlan>>>, ApiError> {
+    let plans = TaskPlan::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(plans)))
+}
+
+pub async fn update_task_plan(
+    State(deployment): State<DeploymentImpl>,
+    Path(plan_id): Path<Uuid>,
+    Json(payload): Json<UpdateTaskPlan>,
+) -> Result<ResponseJson<ApiResponse<TaskPlan>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let plan = load_awaiting_plan(&deployment, plan_id).await?;
+    TaskPlan::update_content(pool, plan.id, &payload.content).await?;
+    let plan = load_plan(&deployment, plan.id).await?;
+    Ok(ResponseJson(ApiResponse::success(plan)))
+}
+
+/// Approve the plan and start the execution step with it
+pub async fn approve_task_plan(
+    State(deployment): State<DeploymentImpl>,
+    Path(plan_id): Path<Uuid>,
+    Json(payload): Json<ApproveTaskPlan>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let plan = load_awaiting_plan(&deployment, plan_id).await?;
+    let content = payload
+        .content
+        .or_else(|| plan.content.clone())
+        .filter(|content| !content.trim().is_empty())
+        .ok_or_else(|| ApiError::BadRequest("The plan is empty".to_string()))?;
+    if let Some(executor_profile_id) = &payload.executor_profile_id
+        && ExecutorConfigs::get_cached()
+            .get_coding_agent(executor_profile_id)
+            .is_none()
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Unknown executor profile {executor_profile_id}"
+        )));
+    }
+
+    // Claim the plan first, so concurrent approvals cannot start the execution step twice
+    let pool = &deployment.db().pool;
+    if !TaskPlan::claim_approval(pool, plan.id).await? {
+        let status = load_plan(&deployment, plan.id).await?.status;
+        return Err(not_awaiting_approval(status));
+    }
+    let execution_process = match deployment
+        .container()
+        .start_plan_execution(&plan, &content, payload.executor_profile_id.clone())
+        .await
+    {
+        Ok(execution_process) => execution_process,
+        Err(e) => {
+            if let Err(release_err) = TaskPlan::release_approval(pool, plan.id).await {
+                tracing::error!(
+                    "Failed to reopen plan {} after its execution failed to start: {}",
+                    plan.id,
+                    release_err
+                );
+            }
+            return Err(e.into());
+        }
+    };
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_plan_approved",
+            serde_json::json!({
+                "task_id": plan.task_id.to_string(),
+                "workspace_id": plan.workspace_id.to_string(),
+                "edited": plan.content.as_deref() != Some(content.as_str()),
+                "executor_overridden": payload.executor_profile_id.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
+pub async fn reject_task_plan(
+    State(deployment): State<DeploymentImpl>,
+    Path(plan_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let plan = load_plan(&deployment, plan_id).await?;
+    if !TaskPlan::reject(&deployment.db().pool, plan.id).await? {
+        let status = load_plan(&deployment, plan.id).await?.status;
+        return Err(not_awaiting_approval(status));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/task-plans/{plan_id}", put(update_task_plan))
+        .route("/task-plans/{plan_id}/approve", post(approve_task_plan))
+        .route("/task-plans/{plan_id}/reject", post(reject_task_plan))
+}
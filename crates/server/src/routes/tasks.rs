@@ -13,8 +13,9 @@ use axum::{
     routing::{delete, get, post, put},
 };
 use db::models::{
+    coding_agent_turn::CodingAgentTurn,
     image::TaskImage,
-    repo::{Repo, RepoError},
+    repo::Repo,
     task::{CreateTask, Task, TaskLabel, TaskWithAttemptStatus, UpdateTask},
     task_deduplication::{
         BulkMergeRequest, BulkMergeResponse, FindDuplicatesResponse, MergeTasksRequest,
@@ -24,7 +25,7 @@ use db::models::{
     workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
 };
 use deployment::Deployment;
-use executors::profile::ExecutorProfileId;
+use executors::{executors::aider::Aider, profile::ExecutorProfileId};
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use services::services::{
@@ -37,8 +38,14 @@ use utils::{api::oauth::LoginStatus, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_task_middleware,
-    routes::task_attempts::WorkspaceRepoInput,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_task_middleware,
+    routes::{
+        task_attempts::{WorkspaceRepoInput, checkpoints},
+        task_plans::get_task_plans,
+        workspace_templates::resolve_workspace_setup,
+    },
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -147,22 +154,34 @@ pub async fn create_task(
 #[derive(Debug, Deserialize, TS)]
 pub struct CreateAndStartTaskRequest {
     pub task: CreateTask,
-    pub executor_profile_id: ExecutorProfileId,
+    /// Falls back to the template's executor profile when omitted
+    #[serde(default)]
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    /// Falls back to the template's repositories when empty
+    #[serde(default)]
     pub repos: Vec<WorkspaceRepoInput>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub template_id: Option<Uuid>,
 }
 
 pub async fn create_task_and_start(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateAndStartTaskRequest>,
 ) -> Result<ResponseJson<ApiResponse<TaskWithAttemptStatus>>, ApiError> {
-    if payload.repos.is_empty() {
-        return Err(ApiError::BadRequest(
-            "At least one repository is required".to_string(),
-        ));
-    }
-
     let pool = &deployment.db().pool;
 
+    // Resolve before creating the task so an invalid template leaves nothing behind
+    let setup = resolve_workspace_setup(
+        pool,
+        payload.task.project_id,
+        payload.template_id,
+        payload.executor_profile_id,
+        payload.repos,
+    )
+    .await?;
+    let executor_profile_id = setup.executor_profile_id.clone();
+
     let task_id = Uuid::new_v4();
     let task = Task::create(pool, &payload.task, task_id).await?;
 
@@ -188,30 +207,21 @@ pub async fn create_task_and_start(
         .git_branch_from_workspace(&attempt_id, &task.title)
         .await;
 
-    // Compute agent_working_dir based on repo count:
-    // - Single repo: use repo name as working dir (agent runs in repo directory)
-    // - Multiple repos: use None (agent runs in workspace root)
-    let agent_working_dir = if payload.repos.len() == 1 {
-        let repo = Repo::find_by_id(pool, payload.repos[0].repo_id)
-            .await?
-            .ok_or(RepoError::NotFound)?;
-        Some(repo.name)
-    } else {
-        None
-    };
-
     let workspace = Workspace::create(
         pool,
         &CreateWorkspace {
             branch: git_branch_name,
-            agent_working_dir,
+            agent_working_dir: setup.agent_working_dir,
         },
         attempt_id,
         task.id,
     )
     .await?;
+    if let Some(template_id) = setup.template_id {
+        Workspace::set_template(pool, workspace.id, template_id).await?;
+    }
 
-    let workspace_repos: Vec<CreateWorkspaceRepo> = payload
+    let workspace_repos: Vec<CreateWorkspaceRepo> = setup
         .repos
         .iter()
         .map(|r| CreateWorkspaceRepo {
@@ -223,7 +233,7 @@ pub async fn create_task_and_start(
 
     let is_attempt_running = deployment
         .container()
-        .start_workspace(&workspace, payload.executor_profile_id.clone())
+        .start_workspace(&workspace, executor_profile_id.clone(), None)
         .await
         .inspect_err(|err| tracing::error!("Failed to start task attempt: {}", err))
         .is_ok();
@@ -232,9 +242,10 @@ pub async fn create_task_and_start(
             "task_attempt_started",
             serde_json::json!({
                 "task_id": task.id.to_string(),
-                "executor": &payload.executor_profile_id.executor,
-                "variant": &payload.executor_profile_id.variant,
+                "executor": &executor_profile_id.executor,
+                "variant": &executor_profile_id.variant,
                 "workspace_id": workspace.id.to_string(),
+                "from_template": setup.template_id.is_some(),
             }),
         )
         .await;
@@ -248,7 +259,7 @@ pub async fn create_task_and_start(
         task,
         has_in_progress_attempt: is_attempt_running,
         last_attempt_failed: false,
-        executor: payload.executor_profile_id.executor.to_string(),
+        executor: executor_profile_id.executor.to_string(),
         pr_status: None, // New task has no PR yet
         ci_status: None, // New task has no CI status yet
     })))
@@ -352,6 +363,14 @@ pub async fn delete_task(
     }
 
     let repositories = WorkspaceRepo::find_unique_repos_for_task(pool, task.id).await?;
+    let mut revert_refs = Vec::new();
+    let mut agent_session_ids = Vec::new();
+    for attempt in &attempts {
+        revert_refs.extend(checkpoints::find_revert_refs(pool, attempt.id, &repositories).await?);
+        agent_session_ids.extend(
+            CodingAgentTurn::find_agent_session_ids_by_workspace_id(pool, attempt.id).await?,
+        );
+    }
 
     // Collect workspace directories that need cleanup
     let workspace_dirs: Vec<PathBuf> = attempts
@@ -409,7 +428,10 @@ pub async fn delete_task(
 
     let task_id = task.id;
     let pool = pool.clone();
+    let git = deployment.git().clone();
     tokio::spawn(async move {
+        checkpoints::prune_revert_refs(&git, &revert_refs);
+        Aider::remove_sessions(&agent_session_ids).await;
         tracing::info!(
             "Starting background cleanup for task {} ({} workspaces, {} repos)",
             task_id,
@@ -634,6 +656,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let task_id_router = Router::new()
         .route("/", get(get_task))
+        .route("/plans", get(get_task_plans))
         .merge(task_actions_router)
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
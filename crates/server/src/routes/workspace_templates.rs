@@ -0,0 +1,362 @@
+use std::collections::HashSet;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+};
+use db::models::{
+    project::Project,
+    project_repo::ProjectRepo,
+    repo::{Repo, RepoError},
+    workspace_template::{SaveWorkspaceTemplate, WorkspaceTemplate, WorkspaceTemplateError},
+};
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use sqlx::SqlitePool;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, routes::task_attempts::WorkspaceRepoInput};
+
+/// What a new workspace is created with, after filling in the gaps from a template
+#[derive(Debug)]
+pub struct WorkspaceSetup {
+    pub executor_profile_id: ExecutorProfileId,
+    pub repos: Vec<WorkspaceRepoInput>,
+    pub agent_working_dir: Option<String>,
+    pub template_id: Option<Uuid>,
+}
+
+/// Combine an explicit request with an optional template. Explicit values win; the template
+/// supplies the executor profile and repositories when they are omitted.
+pub async fn resolve_workspace_setup(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    template_id: Option<Uuid>,
+    executor_profile_id: Option<ExecutorProfileId>,
+    repos: Vec<WorkspaceRepoInput>,
+) -> Result<WorkspaceSetup, ApiError> {
+    let template = match template_id {
+        Some(id) => Some(find_project_template(pool, project_id, id).await?),
+        None => None,
+    };
+
+    let repos = if !repos.is_empty() {
+        repos
+    } else if let Some(template) = &template {
+        template
+            .repos
+            .iter()
+            .map(|r| WorkspaceRepoInput {
+                repo_id: r.repo_id,
+                target_branch: r.target_branch.clone(),
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    if repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+
+    let executor_profile_id = executor_profile_id
+        .or_else(|| {
+            template
+                .as_ref()
+                .and_then(|t| t.executor_profile_id.as_ref())
+                .map(|profile| profile.0.clone())
+        })
+        .ok_or_else(|| ApiError::BadRequest("An executor profile is required".to_string()))?;
+
+    // Compute agent_working_dir based on repo count unless the template pins one:
+    // - Single repo: use repo name as working dir (agent runs in repo directory)
+    // - Multiple repos: use None (agent runs in workspace root)
+    let agent_working_dir = match template.as_ref().and_then(|t| t.agent_working_dir.clone()) {
+        Some(dir) => Some(dir),
+        None if repos.len() == 1 => {
+            let repo = Repo::find_by_id(pool, repos[0].repo_id)
+                .await?
+                .ok_or(RepoError::NotFound)?;
+            Some(repo.name)
+        }
+        None => None,
+    };
+
+    Ok(WorkspaceSetup {
+        executor_profile_id,
+        repos,
+        agent_working_dir,
+        template_id: template.map(|t| t.id),
+    })
+}
+
+/// Template `template_id` of the project. A template of another project is reported as not
+/// found, so its id cannot be used to reach across projects.
+async fn find_project_template(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    template_id: Uuid,
+) -> Result<WorkspaceTemplate, WorkspaceTemplateError> {
+    WorkspaceTemplate::find_by_id(pool, template_id)
+        .await?
+        .filter(|template| template.project_id == project_id)
+        .ok_or(WorkspaceTemplateError::NotFound)
+}
+
+async fn validate_template(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    payload: &SaveWorkspaceTemplate,
+) -> Result<(), ApiError> {
+    if payload.name.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Template name must not be empty".to_string(),
+        ));
+    }
+
+    let project_repos: HashSet<Uuid> = ProjectRepo::find_by_project_id(pool, project_id)
+        .await?
+        .into_iter()
+        .map(|pr| pr.repo_id)
+        .collect();
+    if let Some(repo) = payload
+        .repos
+        .iter()
+        .find(|r| !project_repos.contains(&r.repo_id))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Repository {} is not part of this project",
+            repo.repo_id
+        )));
+    }
+
+    if let Some(key) = payload
+        .env
+        .keys()
+        .find(|key| key.is_empty() || key.contains('=') || key.contains('\0'))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid environment variable name '{key}'"
+        )));
+    }
+    Ok(())
+}
+
+pub async fn get_project_workspace_templates(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WorkspaceTemplate>>>, ApiError> {
+    let templates =
+        WorkspaceTemplate::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(templates)))
+}
+
+pub async fn create_workspace_template(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SaveWorkspaceTemplate>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceTemplate>>, ApiError> {
+    let pool = &deployment.db().pool;
+    validate_template(pool, project.id, &payload).await?;
+    let template = WorkspaceTemplate::create(pool, project.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "workspace_template_created",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "repository_count": template.repos.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+pub async fn update_workspace_template(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, template_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<SaveWorkspaceTemplate>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceTemplate>>, ApiError> {
+    let pool = &deployment.db().pool;
+    find_project_template(pool, project_id, template_id).await?;
+    validate_template(pool, project_id, &payload).await?;
+    let template = WorkspaceTemplate::update(pool, template_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+pub async fn delete_workspace_template(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, template_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    find_project_template(pool, project_id, template_id).await?;
+    let rows_affected = WorkspaceTemplate::delete(pool, template_id).await?;
+    if rows_affected == 0 {
+        return Err(WorkspaceTemplateError::NotFound.into());
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::PathBuf};
+
+    use db::{
+        models::{project::CreateProject, workspace_template::WorkspaceTemplateRepo},
+        test_utils::test_pool,
+    };
+    use executors::executors::BaseCodingAgent;
+
+    use super::*;
+
+    async fn insert_project(pool: &SqlitePool) -> Uuid {
+        let data = CreateProject {
+            name: "Project".to_string(),
+            repositories: vec![],
+        };
+        Project::create(pool, &data, Uuid::new_v4())
+            .await
+            .unwrap()
+            .id
+    }
+
+    async fn insert_repo(pool: &SqlitePool, project_id: Uuid, name: &str) -> Uuid {
+        let path = PathBuf::from(format!("/repos/{}/{name}", Uuid::new_v4()));
+        let repo = Repo::find_or_create(pool, &path, name).await.unwrap();
+        ProjectRepo::create(pool, project_id, repo.id)
+            .await
+            .unwrap();
+        repo.id
+    }
+
+    fn save_template(name: &str, repo_id: Uuid) -> SaveWorkspaceTemplate {
+        SaveWorkspaceTemplate {
+            name: name.to_string(),
+            repos: vec![WorkspaceTemplateRepo {
+                repo_id,
+                target_branch: "develop".to_string(),
+                copy_files: None,
+            }],
+            executor_profile_id: Some(ExecutorProfileId::new(BaseCodingAgent::ClaudeCode)),
+            agent_working_dir: None,
+            env: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn creates_template_with_repos_of_the_project() {
+        let pool = test_pool().await;
+        let project_id = insert_project(&pool).await;
+        let repo_id = insert_repo(&pool, project_id, "app").await;
+        let payload = save_template("Backend", repo_id);
+
+        validate_template(&pool, project_id, &payload)
+            .await
+            .unwrap();
+        let created = WorkspaceTemplate::create(&pool, project_id, &payload)
+            .await
+            .unwrap();
+
+        let templates = WorkspaceTemplate::find_by_project_id(&pool, project_id)
+            .await
+            .unwrap();
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].id, created.id);
+        assert_eq!(templates[0].repos[0].target_branch, "develop");
+
+        let mut unnamed = save_template("  ", repo_id);
+        assert!(
+            validate_template(&pool, project_id, &unnamed)
+                .await
+                .is_err()
+        );
+        unnamed.name = "Frontend".to_string();
+        unnamed.env.insert("A=B".to_string(), "c".to_string());
+        assert!(
+            validate_template(&pool, project_id, &unnamed)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn template_fills_in_what_the_request_omits() {
+        let pool = test_pool().await;
+        let project_id = insert_project(&pool).await;
+        let repo_id = insert_repo(&pool, project_id, "app").await;
+        let template =
+            WorkspaceTemplate::create(&pool, project_id, &save_template("Backend", repo_id))
+                .await
+                .unwrap();
+
+        let setup = resolve_workspace_setup(&pool, project_id, Some(template.id), None, vec![])
+            .await
+            .unwrap();
+        assert_eq!(setup.template_id, Some(template.id));
+        assert_eq!(
+            setup.executor_profile_id,
+            ExecutorProfileId::new(BaseCodingAgent::ClaudeCode)
+        );
+        assert_eq!(setup.repos.len(), 1);
+        assert_eq!(setup.repos[0].repo_id, repo_id);
+        assert_eq!(setup.repos[0].target_branch, "develop");
+        assert_eq!(setup.agent_working_dir.as_deref(), Some("app"));
+
+        // Explicit values win over the template
+        let explicit = resolve_workspace_setup(
+            &pool,
+            project_id,
+            Some(template.id),
+            Some(ExecutorProfileId::new(BaseCodingAgent::Codex)),
+            vec![WorkspaceRepoInput {
+                repo_id,
+                target_branch: "main".to_string(),
+            }],
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            explicit.executor_profile_id,
+            ExecutorProfileId::new(BaseCodingAgent::Codex)
+        );
+        assert_eq!(explicit.repos[0].target_branch, "main");
+    }
+
+    #[tokio::test]
+    async fn templates_are_scoped_to_their_project() {
+        let pool = test_pool().await;
+        let project_id = insert_project(&pool).await;
+        let other_project_id = insert_project(&pool).await;
+        let repo_id = insert_repo(&pool, project_id, "app").await;
+        let template =
+            WorkspaceTemplate::create(&pool, project_id, &save_template("Backend", repo_id))
+                .await
+                .unwrap();
+
+        assert!(
+            find_project_template(&pool, project_id, template.id)
+                .await
+                .is_ok()
+        );
+        assert!(matches!(
+            find_project_template(&pool, other_project_id, template.id).await,
+            Err(WorkspaceTemplateError::NotFound)
+        ));
+        assert!(matches!(
+            resolve_workspace_setup(&pool, other_project_id, Some(template.id), None, vec![]).await,
+            Err(ApiError::WorkspaceTemplate(
+                WorkspaceTemplateError::NotFound
+            ))
+        ));
+        // Repositories of another project cannot be put in a template
+        assert!(
+            validate_template(&pool, other_project_id, &save_template("Backend", repo_id))
+                .await
+                .is_err()
+        );
+    }
+}
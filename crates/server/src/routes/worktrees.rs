@@ -0,0 +1,55 @@
+use axum::{
+    Json, Router,
+    extract::State,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use deployment::Deployment;
+use services::services::worktree_gc::{
+    self, RunWorktreeGcRequest, WorktreeDiskUsage, WorktreeGcReport,
+};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub async fn get_disk_usage(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WorktreeDiskUsage>>, ApiError> {
+    let usage = worktree_gc::disk_usage(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(usage)))
+}
+
+/// Apply the configured garbage collection policy now, whether or not it is enabled
+pub async fn run_gc(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RunWorktreeGcRequest>,
+) -> Result<ResponseJson<ApiResponse<WorktreeGcReport>>, ApiError> {
+    let policy = deployment.config().read().await.worktree_gc.clone();
+    let report = worktree_gc::collect(
+        &deployment.db().pool,
+        deployment.container(),
+        &policy,
+        payload.dry_run,
+    )
+    .await?;
+
+    if !report.dry_run {
+        deployment
+            .track_if_analytics_allowed(
+                "worktree_gc_run",
+                serde_json::json!({
+                    "removed": report.removed.len(),
+                    "freed_bytes": report.freed_bytes,
+                }),
+            )
+            .await;
+    }
+
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/worktrees/usage", get(get_disk_usage))
+        .route("/worktrees/gc", post(run_gc))
+}
@@ -26,6 +26,12 @@ pub type EditorType = versions::v8::EditorType;
 pub type GitHubConfig = versions::v8::GitHubConfig;
 pub type UiLanguage = versions::v8::UiLanguage;
 pub type ShowcaseState = versions::v8::ShowcaseState;
+pub type LogRetentionConfig = versions::v8::LogRetentionConfig;
+pub type ExecutionLimitsConfig = versions::v8::ExecutionLimitsConfig;
+pub type SandboxConfig = versions::v8::SandboxConfig;
+pub type WorktreeGcConfig = versions::v8::WorktreeGcConfig;
+pub type PreviewProxyConfig = versions::v8::PreviewProxyConfig;
+pub type DbBackupConfig = versions::v8::DbBackupConfig;
 
 /// Will always return config, trying old schemas or eventually returning default
 pub async fn load_config_from_file(config_path: &PathBuf) -> Config {
@@ -1,5 +1,6 @@
 use anyhow::Error;
-use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use db::models::execution_process::ExecutionProcessRunReason;
+use executors::{executors::BaseCodingAgent, limits::ExecutionLimits, profile::ExecutorProfileId};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 pub use v7::{
@@ -47,12 +48,172 @@ pub struct Config {
     pub ci_failure_auto_fix_enabled: bool,
     #[serde(default)]
     pub ci_failure_resolution_prompt: Option<String>,
+    #[serde(default)]
+    pub log_retention: LogRetentionConfig,
+    /// Continue coding agent turns interrupted by a server restart, for executors that can
+    /// resume their sessions. Off by default, since a resumed turn runs the agent again
+    /// without anyone asking for it.
+    #[serde(default)]
+    pub resume_interrupted_sessions: bool,
+    #[serde(default)]
+    pub execution_limits: ExecutionLimitsConfig,
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
+    #[serde(default)]
+    pub worktree_gc: WorktreeGcConfig,
+    #[serde(default)]
+    pub preview_proxy: PreviewProxyConfig,
+    #[serde(default)]
+    pub db_backup: DbBackupConfig,
 }
 
 fn default_ci_failure_auto_fix_enabled() -> bool {
     true
 }
 
+/// How long execution logs are kept in full before being compacted
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[serde(default)]
+pub struct LogRetentionConfig {
+    pub enabled: bool,
+    /// Compress the logs of processes that finished more than this many days ago
+    pub compress_after_days: u32,
+    /// Replace raw agent output with its normalized entries after this many days.
+    /// `None` keeps raw output forever.
+    pub drop_raw_after_days: Option<u32>,
+    /// Move the logs of archived workspaces out of the database into files
+    pub archive_archived_workspaces: bool,
+}
+
+impl Default for LogRetentionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            compress_after_days: 2,
+            drop_raw_after_days: None,
+            archive_archived_workspaces: true,
+        }
+    }
+}
+
+/// Default timeouts per run reason. Dev servers and terminals are never stopped.
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[serde(default)]
+pub struct ExecutionLimitsConfig {
+    pub setup_script: ExecutionLimits,
+    pub cleanup_script: ExecutionLimits,
+    /// Profiles in `profiles.json` can override these for their own turns
+    pub coding_agent: ExecutionLimits,
+}
+
+impl ExecutionLimitsConfig {
+    pub fn for_run_reason(&self, run_reason: &ExecutionProcessRunReason) -> ExecutionLimits {
+        match run_reason {
+            ExecutionProcessRunReason::SetupScript => self.setup_script,
+            ExecutionProcessRunReason::CleanupScript => self.cleanup_script,
+            ExecutionProcessRunReason::CodingAgent => self.coding_agent,
+            ExecutionProcessRunReason::DevServer | ExecutionProcessRunReason::Terminal => {
+                ExecutionLimits::default()
+            }
+        }
+    }
+}
+
+impl Default for ExecutionLimitsConfig {
+    fn default() -> Self {
+        // Scripts waiting on an interactive prompt go quiet; agents can legitimately stay
+        // silent through long tool calls, so they get more slack
+        let script = ExecutionLimits {
+            max_runtime_secs: None,
+            max_idle_secs: Some(20 * 60),
+        };
+        Self {
+            setup_script: script,
+            cleanup_script: script,
+            coding_agent: ExecutionLimits {
+                max_runtime_secs: None,
+                max_idle_secs: Some(60 * 60),
+            },
+        }
+    }
+}
+
+/// Run coding agents in a bubblewrap sandbox (Linux only)
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS)]
+#[serde(default)]
+pub struct SandboxConfig {
+    pub enabled: bool,
+    /// Hosts agents may reach, subdomains included. The agent gets no network of its own and
+    /// connects through a proxy enforcing the list, which needs `socat`. `None` leaves the
+    /// network alone.
+    pub allowed_hosts: Option<Vec<String>>,
+    /// Writable paths on top of the workspace, the temp dir and agent state directories
+    pub extra_writable_paths: Vec<String>,
+}
+
+/// When workspace worktrees are removed to free disk. A removed worktree is recreated from its
+/// branch when the workspace is next opened, so worktrees with uncommitted changes are kept.
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[serde(default)]
+pub struct WorktreeGcConfig {
+    pub enabled: bool,
+    /// Remove worktrees of archived workspaces that were not used for this many days
+    pub archived_after_days: Option<u32>,
+    /// Remove worktrees of workspaces whose pull requests are merged
+    pub merged_prs: bool,
+    /// Remove the least recently used worktrees while all of them take more than this many GiB
+    pub max_total_gb: Option<u32>,
+}
+
+impl Default for WorktreeGcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            archived_after_days: Some(7),
+            merged_prs: true,
+            max_total_gb: None,
+        }
+    }
+}
+
+/// Reverse proxy giving each workspace's dev server a stable preview URL, at
+/// `http://<short id>.localhost:<port>` or `http://localhost:<port>/<short id>/`
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[serde(default)]
+pub struct PreviewProxyConfig {
+    pub enabled: bool,
+    /// Takes effect when Vibe Kanban restarts
+    pub port: u16,
+}
+
+impl Default for PreviewProxyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            port: 47800,
+        }
+    }
+}
+
+/// Scheduled snapshots of the database. Backups before migrations, before restores and
+/// manual ones are taken regardless.
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[serde(default)]
+pub struct DbBackupConfig {
+    pub enabled: bool,
+    /// Hours between scheduled backups
+    pub interval_hours: u32,
+}
+
+impl Default for DbBackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: 6,
+        }
+    }
+}
+
 impl Config {
     fn from_v7_config(old_config: v7::Config) -> Self {
         // Convert Option<bool> to bool: None or Some(true) become true, Some(false) stays false
@@ -79,6 +240,13 @@ impl Config {
             pr_conflict_resolution_prompt: None,
             ci_failure_auto_fix_enabled: true,
             ci_failure_resolution_prompt: None,
+            log_retention: LogRetentionConfig::default(),
+            resume_interrupted_sessions: false,
+            execution_limits: ExecutionLimitsConfig::default(),
+            sandbox: SandboxConfig::default(),
+            worktree_gc: WorktreeGcConfig::default(),
+            preview_proxy: PreviewProxyConfig::default(),
+            db_backup: DbBackupConfig::default(),
         }
     }
 
@@ -132,6 +300,13 @@ impl Default for Config {
             pr_conflict_resolution_prompt: None,
             ci_failure_auto_fix_enabled: true,
             ci_failure_resolution_prompt: None,
+            log_retention: LogRetentionConfig::default(),
+            resume_interrupted_sessions: false,
+            execution_limits: ExecutionLimitsConfig::default(),
+            sandbox: SandboxConfig::default(),
+            worktree_gc: WorktreeGcConfig::default(),
+            preview_proxy: PreviewProxyConfig::default(),
+            db_backup: DbBackupConfig::default(),
         }
     }
 }
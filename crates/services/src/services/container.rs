@@ -15,29 +15,35 @@ use db::{
             ExecutionProcessStatus,
         },
         execution_process_logs::ExecutionProcessLogs,
+        execution_process_normalized_logs::ExecutionProcessNormalizedLogs,
         execution_process_repo_state::{
             CreateExecutionProcessRepoState, ExecutionProcessRepoState,
         },
         repo::Repo,
         session::{CreateSession, Session, SessionError},
         task::{Task, TaskStatus},
+        task_plan::{TaskPlan, TaskPlanStatus},
         workspace::{Workspace, WorkspaceError},
         workspace_repo::WorkspaceRepo,
     },
 };
 #[cfg(feature = "qa-mode")]
 use executors::executors::qa_mock::QaMockExecutor;
-#[cfg(not(feature = "qa-mode"))]
-use executors::profile::ExecutorConfigs;
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
+        coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
-    executors::{ExecutorError, StandardCodingAgentExecutor},
-    logs::{NormalizedEntry, NormalizedEntryError, NormalizedEntryType, utils::ConversationPatch},
-    profile::ExecutorProfileId,
+    executors::{BaseAgentCapability, ExecutorError, StandardCodingAgentExecutor},
+    fallback::{AgentFailure, detect_failure, fallback_prompt, last_assistant_message},
+    logs::{
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
+        replay::{ReplayOutput, replay_logs},
+        utils::ConversationPatch,
+    },
+    profile::{ExecutorConfigs, ExecutorProfileId},
 };
 use futures::{StreamExt, future};
 use sqlx::Error as SqlxError;
@@ -53,12 +59,20 @@ use uuid::Uuid;
 use crate::services::{
     git::{GitService, GitServiceError},
     notification::NotificationService,
+    plan::{execution_prompt, plan_from_entries, planning_prompt},
+    queued_message::QueuedMessageService,
+    resource_usage::ResourceMonitor,
     share::SharePublisher,
+    terminal::{self, TerminalInput},
+    transcript::collect_normalized_entries,
     workspace_manager::WorkspaceError as WorkspaceManagerError,
     worktree_manager::WorktreeError,
 };
 pub type ContainerRef = String;
 
+/// Prompt for continuing a coding agent turn that a server restart cut off
+const RESUME_PROMPT: &str = "Your previous turn was interrupted by a restart before you finished. Changes you made so far are already in the working tree; review them (for example with `git status` and `git diff`) and continue from where you left off.";
+
 #[derive(Debug, Error)]
 pub enum ContainerError {
     #[error(transparent)]
@@ -83,6 +97,18 @@ pub enum ContainerError {
     Other(#[from] AnyhowError), // Catches any unclassified errors
 }
 
+/// A session whose coding agent turn is being retried along its profile's fallback chain
+#[derive(Debug, Clone)]
+pub struct FallbackState {
+    /// Executors still to try if the current one fails as well
+    pub remaining: Vec<ExecutorProfileId>,
+    /// The request being retried, without the fallback preamble
+    pub prompt: String,
+    /// Last message of an agent in the chain that got something done, carried over when a
+    /// later executor fails before saying anything
+    pub progress: Option<String>,
+}
+
 #[async_trait]
 pub trait ContainerService {
     fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>;
@@ -95,6 +121,13 @@ pub trait ContainerService {
 
     fn notification_service(&self) -> &NotificationService;
 
+    fn queued_message_service(&self) -> &QueuedMessageService;
+
+    fn resource_monitor(&self) -> &ResourceMonitor;
+
+    /// Fallback progress per session, see [`Self::try_start_fallback`]
+    fn fallback_states(&self) -> &Arc<RwLock<HashMap<Uuid, FallbackState>>>;
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf;
 
     async fn create(&self, workspace: &Workspace) -> Result<ContainerRef, ContainerError>;
@@ -126,7 +159,7 @@ pub trait ContainerService {
     }
 
     /// A context is finalized when
-    /// - Always when the execution process has failed or been killed
+    /// - Always when the execution process has failed, timed out or been killed
     /// - Never when the run reason is DevServer
     /// - Never when a setup script has no next_action (parallel mode)
     /// - The next action is None (no follow-up actions)
@@ -154,7 +187,9 @@ pub trait ContainerService {
         // Always finalize failed or killed executions, regardless of next action
         if matches!(
             ctx.execution_process.status,
-            ExecutionProcessStatus::Failed | ExecutionProcessStatus::Killed
+            ExecutionProcessStatus::Failed
+                | ExecutionProcessStatus::TimedOut
+                | ExecutionProcessStatus::Killed
         ) {
             return true;
         }
@@ -201,6 +236,10 @@ pub trait ContainerService {
                 "❌ '{}' execution failed\nBranch: {:?}\nExecutor: {:?}",
                 ctx.task.title, ctx.workspace.branch, ctx.session.executor
             ),
+            ExecutionProcessStatus::TimedOut => format!(
+                "⏱️ '{}' execution timed out\nBranch: {:?}\nExecutor: {:?}",
+                ctx.task.title, ctx.workspace.branch, ctx.session.executor
+            ),
             _ => {
                 tracing::warn!(
                     "Tried to notify workspace completion for {} but process is still running!",
@@ -212,9 +251,12 @@ pub trait ContainerService {
         self.notification_service().notify(&title, &message).await;
     }
 
-    /// Cleanup executions marked as running in the db, call at startup
+    /// Cleanup executions marked as running in the db, call at startup. Interrupted coding
+    /// agent turns are continued where possible, see [`Self::try_resume_interrupted_turn`];
+    /// the queued follow-up of a session that is not resumed goes back to being a draft.
     async fn cleanup_orphan_executions(&self) -> Result<(), ContainerError> {
         let running_processes = ExecutionProcess::find_running(&self.db().pool).await?;
+        let resume_enabled = self.resume_interrupted_sessions().await;
         for process in running_processes {
             tracing::info!(
                 "Found orphaned execution process {} for session {}",
@@ -237,8 +279,11 @@ pub trait ContainerService {
                 );
                 continue;
             }
+            let ctx = ExecutionProcess::load_context(&self.db().pool, process.id)
+                .await
+                .ok();
             // Capture after-head commit OID per repository
-            if let Ok(ctx) = ExecutionProcess::load_context(&self.db().pool, process.id).await
+            if let Some(ctx) = &ctx
                 && let Some(ref container_ref) = ctx.workspace.container_ref
             {
                 let workspace_root = PathBuf::from(container_ref);
@@ -264,6 +309,46 @@ pub trait ContainerService {
             }
             // Process marked as failed
             tracing::info!("Marked orphaned execution process {} as failed", process.id);
+
+            if resume_enabled
+                && process.run_reason == ExecutionProcessRunReason::CodingAgent
+                && !process.dropped
+                && let Some(ctx) = &ctx
+            {
+                match self.try_resume_interrupted_turn(ctx).await {
+                    Ok(Some(resumed)) => {
+                        tracing::info!(
+                            "Resumed interrupted execution process {} as {}",
+                            process.id,
+                            resumed.id
+                        );
+                        // Any queued follow-up runs once the resumed turn finishes
+                        continue;
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to resume interrupted execution process {}: {}",
+                            process.id,
+                            e
+                        );
+                    }
+                }
+            }
+            if !matches!(
+                process.run_reason,
+                ExecutionProcessRunReason::DevServer | ExecutionProcessRunReason::Terminal
+            ) && let Err(e) = self
+                .queued_message_service()
+                .return_to_draft(process.session_id)
+                .await
+            {
+                tracing::warn!(
+                    "Failed to return queued message of session {} to its draft: {}",
+                    process.session_id,
+                    e
+                );
+            }
             // Update task status to InReview for coding agent and setup script failures
             if matches!(
                 process.run_reason,
@@ -300,6 +385,85 @@ pub trait ContainerService {
         Ok(())
     }
 
+    /// Continue a coding agent turn that was cut off by a server restart, in the same agent
+    /// session. The resumed turn's prompt says that it was interrupted, so the recovery shows
+    /// up in the conversation. Returns `None` when the session cannot be continued, see
+    /// [`Self::continue_agent_session`].
+    async fn try_resume_interrupted_turn(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<ExecutionProcess>, ContainerError> {
+        let action = ctx.execution_process.executor_action()?;
+        let prompt = match action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => &request.prompt,
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => &request.prompt,
+            ExecutorActionType::ScriptRequest(_) => return Ok(None),
+        };
+        let prompt = format!(
+            "{RESUME_PROMPT}\n\n## Interrupted request\n\n{}",
+            prompt.trim()
+        );
+        self.continue_agent_session(ctx, prompt).await
+    }
+
+    /// Follow up on a coding agent turn with `prompt` in the same agent session, keeping the
+    /// turn's next action. Returns `None` when the executor cannot resume sessions or the
+    /// agent never reported a session id.
+    async fn continue_agent_session(
+        &self,
+        ctx: &ExecutionContext,
+        prompt: String,
+    ) -> Result<Option<ExecutionProcess>, ContainerError> {
+        let action = ctx.execution_process.executor_action()?;
+        let (executor_profile_id, working_dir) = match action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                (&request.executor_profile_id, request.working_dir.clone())
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                (&request.executor_profile_id, request.working_dir.clone())
+            }
+            ExecutorActionType::ScriptRequest(_) => return Ok(None),
+        };
+
+        let can_resume = ExecutorConfigs::get_cached()
+            .get_coding_agent(executor_profile_id)
+            .is_some_and(|agent| {
+                agent
+                    .capabilities()
+                    .contains(&BaseAgentCapability::SessionResume)
+            });
+        if !can_resume {
+            return Ok(None);
+        }
+        let Some(agent_session_id) = CodingAgentTurn::find_by_execution_process_id(
+            &self.db().pool,
+            ctx.execution_process.id,
+        )
+        .await?
+        .and_then(|turn| turn.agent_session_id) else {
+            return Ok(None);
+        };
+
+        self.ensure_container_exists(&ctx.workspace).await?;
+        let follow_up_action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                prompt,
+                session_id: agent_session_id,
+                executor_profile_id: executor_profile_id.clone(),
+                working_dir,
+            }),
+            action.next_action.clone(),
+        );
+        self.start_execution(
+            &ctx.workspace,
+            &ctx.session,
+            &follow_up_action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await
+        .map(Some)
+    }
+
     /// Backfill before_head_commit for legacy execution processes.
     /// Rules:
     /// - If a process has after_head_commit and missing before_head_commit,
@@ -396,6 +560,7 @@ pub trait ContainerService {
                 language: ScriptRequestLanguage::Bash,
                 context: ScriptContext::CleanupScript,
                 working_dir: Some(first.name.clone()),
+                dev_process: None,
             }),
             None,
         );
@@ -407,6 +572,7 @@ pub trait ContainerService {
                     language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::CleanupScript,
                     working_dir: Some(repo.name.clone()),
+                    dev_process: None,
                 }),
                 None,
             ));
@@ -430,6 +596,7 @@ pub trait ContainerService {
                 language: ScriptRequestLanguage::Bash,
                 context: ScriptContext::SetupScript,
                 working_dir: Some(first.name.clone()),
+                dev_process: None,
             }),
             None,
         );
@@ -441,6 +608,7 @@ pub trait ContainerService {
                     language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::SetupScript,
                     working_dir: Some(repo.name.clone()),
+                    dev_process: None,
                 }),
                 None,
             ));
@@ -457,6 +625,7 @@ pub trait ContainerService {
                     language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::SetupScript,
                     working_dir: Some(repo.name.clone()),
+                    dev_process: None,
                 }),
                 None,
             )
@@ -476,6 +645,7 @@ pub trait ContainerService {
                         language: ScriptRequestLanguage::Bash,
                         context: ScriptContext::SetupScript,
                         working_dir: Some(repo.name.clone()),
+                        dev_process: None,
                     }),
                     Some(Box::new(chained)),
                 );
@@ -526,6 +696,17 @@ pub trait ContainerService {
 
     async fn is_container_clean(&self, workspace: &Workspace) -> Result<bool, ContainerError>;
 
+    /// Record the setup script that ran ahead of time for a workspace created from a warm
+    /// worktree in its first session, so its output shows like that of any other setup run
+    async fn record_warm_setup(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+    ) -> Result<(), ContainerError>;
+
+    /// Ports allocated to the workspace's named dev processes, by process name
+    async fn dev_process_ports(&self, workspace_id: Uuid) -> HashMap<String, u16>;
+
     async fn start_execution_inner(
         &self,
         workspace: &Workspace,
@@ -539,6 +720,40 @@ pub trait ContainerService {
         status: ExecutionProcessStatus,
     ) -> Result<(), ContainerError>;
 
+    /// Forward input to a terminal started by [`Self::open_terminal`]
+    async fn send_terminal_input(
+        &self,
+        execution_process_id: &Uuid,
+        input: TerminalInput,
+    ) -> Result<(), ContainerError>;
+
+    /// Start an interactive shell in the workspace, recorded as a terminal execution process of
+    /// one of its coding sessions. Its output is the process's raw log stream; closing it is
+    /// done with [`Self::stop_execution`].
+    async fn open_terminal(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let executor_action = ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: terminal::shell_program(),
+                language: ScriptRequestLanguage::Bash,
+                context: ScriptContext::Terminal,
+                working_dir: None,
+                dev_process: None,
+            }),
+            None,
+        );
+        self.start_execution(
+            workspace,
+            session,
+            &executor_action,
+            &ExecutionProcessRunReason::Terminal,
+        )
+        .await
+    }
+
     async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError>;
 
     async fn copy_project_files(
@@ -563,6 +778,9 @@ pub trait ContainerService {
 
     async fn git_branch_prefix(&self) -> String;
 
+    /// Whether [`Self::cleanup_orphan_executions`] may resume interrupted coding agent turns
+    async fn resume_interrupted_sessions(&self) -> bool;
+
     async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task_title: &str) -> String {
         let task_title_id = git_branch_id(task_title);
         let prefix = self.git_branch_prefix().await;
@@ -641,6 +859,32 @@ pub trait ContainerService {
                     .boxed(),
             )
         } else {
+            // Conversations re-normalized on request are served as stored
+            match ExecutionProcessNormalizedLogs::find_by_execution_id(&self.db().pool, *id).await {
+                Ok(Some(normalized)) => match normalized.parse_logs() {
+                    Ok(messages) => {
+                        return Some(
+                            futures::stream::iter(messages.into_iter().map(Ok))
+                                .chain(futures::stream::once(async {
+                                    Ok::<_, std::io::Error>(LogMsg::Finished)
+                                }))
+                                .boxed(),
+                        );
+                    }
+                    Err(e) => tracing::error!(
+                        "Failed to parse normalized logs for execution {}: {}",
+                        id,
+                        e
+                    ),
+                },
+                Ok(None) => {}
+                Err(e) => tracing::error!(
+                    "Failed to fetch normalized logs for execution {}: {}",
+                    id,
+                    e
+                ),
+            }
+
             // Fallback: load from DB and normalize
             let log_records =
                 match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
@@ -786,6 +1030,104 @@ pub trait ContainerService {
         }
     }
 
+    /// Re-run the current log normalizer over the raw logs persisted for a finished
+    /// coding agent execution, e.g. to re-render an old session after a normalizer fix. The
+    /// result is stored and served in place of normalizing the raw logs on every view.
+    async fn renormalize_execution_process(
+        &self,
+        process: &ExecutionProcess,
+    ) -> Result<ReplayOutput, ContainerError> {
+        if process.status == ExecutionProcessStatus::Running {
+            return Err(ContainerError::Other(anyhow!(
+                "Cannot re-normalize a running execution process"
+            )));
+        }
+
+        let (workspace, _session) = process
+            .parent_workspace_and_session(&self.db().pool)
+            .await?
+            .ok_or_else(|| {
+                ContainerError::Other(anyhow!(
+                    "No workspace found for execution process {}",
+                    process.id
+                ))
+            })?;
+
+        let log_records =
+            ExecutionProcessLogs::find_by_execution_id(&self.db().pool, process.id).await?;
+        let raw_logs = ExecutionProcessLogs::parse_logs(&log_records)
+            .map_err(|e| ContainerError::Other(e.into()))?;
+        if !raw_logs
+            .iter()
+            .any(|msg| matches!(msg, LogMsg::Stdout(_) | LogMsg::Stderr(_)))
+        {
+            return Err(ContainerError::Other(anyhow!(
+                "The raw output of execution process {} is no longer stored",
+                process.id
+            )));
+        }
+
+        let output = self
+            .replay_execution_logs(process, &workspace, raw_logs)
+            .await?;
+        // A partial replay would replace a complete rendering with a truncated one
+        if !output.complete {
+            return Err(ContainerError::Other(anyhow!(
+                "Re-normalizing execution process {} did not complete",
+                process.id
+            )));
+        }
+
+        let mut jsonl = String::new();
+        for patch in &output.patches {
+            let line = serde_json::to_string(&LogMsg::JsonPatch(patch.clone()))
+                .map_err(|e| ContainerError::Other(e.into()))?;
+            jsonl.push_str(&line);
+            jsonl.push('\n');
+        }
+        ExecutionProcessNormalizedLogs::upsert(&self.db().pool, process.id, &jsonl).await?;
+
+        Ok(output)
+    }
+
+    /// Run the normalizer of a coding agent execution's executor over `raw_logs`. Paths are
+    /// resolved against the workspace directory, which does not need to exist on disk.
+    async fn replay_execution_logs(
+        &self,
+        process: &ExecutionProcess,
+        workspace: &Workspace,
+        raw_logs: Vec<LogMsg>,
+    ) -> Result<ReplayOutput, ContainerError> {
+        let current_dir = self.workspace_to_current_dir(workspace);
+
+        let (executor_profile_id, working_dir) = match process.executor_action()?.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => (
+                request.executor_profile_id.clone(),
+                request.effective_dir(&current_dir),
+            ),
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => (
+                request.executor_profile_id.clone(),
+                request.effective_dir(&current_dir),
+            ),
+            ExecutorActionType::ScriptRequest(_) => {
+                return Err(ContainerError::Other(anyhow!(
+                    "Only coding agent executions have normalized logs"
+                )));
+            }
+        };
+
+        #[cfg(feature = "qa-mode")]
+        let executor = {
+            let _ = executor_profile_id;
+            QaMockExecutor
+        };
+        #[cfg(not(feature = "qa-mode"))]
+        let executor =
+            ExecutorConfigs::get_cached().get_coding_agent_or_default(&executor_profile_id);
+
+        Ok(replay_logs(&executor, raw_logs, &working_dir).await)
+    }
+
     fn spawn_stream_raw_logs_to_db(&self, execution_id: &Uuid) -> JoinHandle<()> {
         let execution_id = *execution_id;
         let msg_stores = self.msg_stores().clone();
@@ -860,10 +1202,14 @@ pub trait ContainerService {
         })
     }
 
+    /// Start the first session of a workspace. With a `planning_profile_id`, that profile
+    /// first drafts a plan and `executor_profile_id` only runs once the plan is approved, see
+    /// [`Self::start_plan_execution`].
     async fn start_workspace(
         &self,
         workspace: &Workspace,
         executor_profile_id: ExecutorProfileId,
+        planning_profile_id: Option<ExecutorProfileId>,
     ) -> Result<ExecutionProcess, ContainerError> {
         // Create container
         self.create(workspace).await?;
@@ -893,7 +1239,21 @@ pub trait ContainerService {
 
         let prompt = task.to_prompt();
 
-        let repos_with_setup: Vec<_> = repos.iter().filter(|r| r.setup_script.is_some()).collect();
+        // Workspaces created from a warm worktree have already run their setup scripts
+        if workspace.setup_completed_at.is_some()
+            && let Err(e) = self.record_warm_setup(&workspace, &session).await
+        {
+            tracing::warn!(
+                "Failed to record the warm setup of workspace {}: {}",
+                workspace.id,
+                e
+            );
+        }
+        let repos_with_setup: Vec<_> = if workspace.setup_completed_at.is_some() {
+            Vec::new()
+        } else {
+            repos.iter().filter(|r| r.setup_script.is_some()).collect()
+        };
 
         let all_parallel = repos_with_setup.iter().all(|r| r.parallel_setup_script);
 
@@ -907,13 +1267,29 @@ pub trait ContainerService {
 
         let coding_action = ExecutorAction::new(
             ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
-                prompt,
+                prompt: prompt.clone(),
                 executor_profile_id: executor_profile_id.clone(),
-                working_dir,
+                working_dir: working_dir.clone(),
             }),
             cleanup_action.map(Box::new),
         );
 
+        // The execution step stays the planning run's next action until the plan is approved
+        let coding_action = match planning_profile_id {
+            Some(planning_profile_id) => {
+                TaskPlan::create(&self.db().pool, task.id, workspace.id, session.id).await?;
+                ExecutorAction::new(
+                    ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                        prompt: planning_prompt(&prompt),
+                        executor_profile_id: planning_profile_id,
+                        working_dir,
+                    }),
+                    Some(Box::new(coding_action)),
+                )
+            }
+            None => coding_action,
+        };
+
         let execution_process = if all_parallel {
             // All parallel: start each setup independently, then start coding agent
             for repo in &repos_with_setup {
@@ -965,7 +1341,10 @@ pub trait ContainerService {
             .await?
             .ok_or(SqlxError::RowNotFound)?;
         if task.status != TaskStatus::InProgress
-            && run_reason != &ExecutionProcessRunReason::DevServer
+            && !matches!(
+                run_reason,
+                ExecutionProcessRunReason::DevServer | ExecutionProcessRunReason::Terminal
+            )
         {
             Task::update_status(&self.db().pool, task.id, TaskStatus::InProgress).await?;
 
@@ -1100,6 +1479,28 @@ pub trait ContainerService {
                     )
                     .await;
                 }
+
+                if run_reason == &ExecutionProcessRunReason::CodingAgent {
+                    let fallback =
+                        match ExecutionProcess::load_context(&self.db().pool, execution_process.id)
+                            .await
+                        {
+                            Ok(ctx) => {
+                                self.try_start_fallback(&ctx, AgentFailure::ExecutableNotFound)
+                                    .await
+                            }
+                            Err(e) => Err(e.into()),
+                        };
+                    match fallback {
+                        Ok(Some(fallback_process)) => return Ok(fallback_process),
+                        Ok(None) => {}
+                        Err(e) => tracing::error!(
+                            "Failed to start fallback executor for {}: {}",
+                            execution_process.id,
+                            e
+                        ),
+                    }
+                }
             };
             return Err(start_error);
         }
@@ -1144,6 +1545,202 @@ pub trait ContainerService {
         Ok(execution_process)
     }
 
+    /// Store the plan drafted by a coding agent turn of a planning session. Returns `None`
+    /// when the session has no open plan, i.e. the turn was not a planning run. A plan that
+    /// is no longer being drafted is returned unchanged.
+    async fn capture_plan(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<TaskPlan>, ContainerError> {
+        let pool = &self.db().pool;
+        let Some(plan) = TaskPlan::find_open_by_session_id(pool, ctx.session.id).await? else {
+            return Ok(None);
+        };
+
+        if plan.status == TaskPlanStatus::Drafting {
+            let entries = collect_normalized_entries(self, &ctx.execution_process.id).await;
+            let content = plan_from_entries(&entries)
+                .unwrap_or_else(|| "The planning agent did not present a plan.".to_string());
+            TaskPlan::set_draft(pool, plan.id, &content).await?;
+        }
+        Ok(TaskPlan::find_by_id(pool, plan.id).await?)
+    }
+
+    /// Mark the plan of a planning session as failed after its run failed for good, so it
+    /// can be rejected instead of staying in drafting
+    async fn fail_plan(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
+        if TaskPlan::fail_drafting(&self.db().pool, ctx.session.id).await? {
+            tracing::info!(
+                "Planning run {} failed; marked the plan of session {} as failed",
+                ctx.execution_process.id,
+                ctx.session.id
+            );
+        }
+        Ok(())
+    }
+
+    /// Start the execution step held back by a plan, in a new session, with the approved
+    /// `content` injected into its prompt. `executor_profile_id` overrides the profile chosen
+    /// when the workspace was started.
+    async fn start_plan_execution(
+        &self,
+        plan: &TaskPlan,
+        content: &str,
+        executor_profile_id: Option<ExecutorProfileId>,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+        let workspace = Workspace::find_by_id(pool, plan.workspace_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+
+        // Follow-ups and fallbacks may have run in the planning session since; the first
+        // coding agent turn followed by another coding agent request holds the execution step
+        let (request, then) = ExecutionProcess::find_by_session_id(pool, plan.session_id, false)
+            .await?
+            .into_iter()
+            .filter(|p| p.run_reason == ExecutionProcessRunReason::CodingAgent)
+            .find_map(|p| {
+                let next = p.executor_action().ok()?.next_action()?;
+                match next.typ() {
+                    ExecutorActionType::CodingAgentInitialRequest(request) => {
+                        Some((request.clone(), next.next_action.clone()))
+                    }
+                    _ => None,
+                }
+            })
+            .ok_or_else(|| anyhow!("No execution step found for plan {}", plan.id))?;
+
+        let executor_profile_id =
+            executor_profile_id.unwrap_or_else(|| request.executor_profile_id.clone());
+        let action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt: execution_prompt(&request.prompt, content),
+                executor_profile_id: executor_profile_id.clone(),
+                working_dir: request.working_dir.clone(),
+            }),
+            then,
+        );
+
+        self.ensure_container_exists(&workspace).await?;
+        let session = Session::create(
+            pool,
+            &CreateSession {
+                executor: Some(executor_profile_id.executor.to_string()),
+            },
+            Uuid::new_v4(),
+            workspace.id,
+        )
+        .await?;
+        let execution_process = self
+            .start_execution(
+                &workspace,
+                &session,
+                &action,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await?;
+        TaskPlan::approve(pool, plan.id, content, session.id).await?;
+        Ok(execution_process)
+    }
+
+    /// Failure signature of a coding agent execution, if another executor could plausibly
+    /// complete the turn
+    async fn detect_agent_failure(&self, execution_id: &Uuid) -> Option<AgentFailure> {
+        let entries = collect_normalized_entries(self, execution_id).await;
+        detect_failure(&entries)
+    }
+
+    /// Retry a failed coding agent turn with the next executor of the profile's fallback chain,
+    /// in the same workspace and session. The new executor is told why the previous one stopped
+    /// and gets its last message as the progress so far. Returns `None` once the chain is
+    /// exhausted.
+    async fn try_start_fallback(
+        &self,
+        ctx: &ExecutionContext,
+        failure: AgentFailure,
+    ) -> Result<Option<ExecutionProcess>, ContainerError> {
+        let action = ctx.execution_process.executor_action()?;
+        let (failed_profile, prompt, working_dir) = match action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => (
+                &request.executor_profile_id,
+                &request.prompt,
+                request.working_dir.clone(),
+            ),
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => (
+                &request.executor_profile_id,
+                &request.prompt,
+                request.working_dir.clone(),
+            ),
+            ExecutorActionType::ScriptRequest(_) => return Ok(None),
+        };
+
+        // Only the first failure of a turn reads the configured chain; retries continue
+        // where the previous attempt left off so that cyclic chains terminate
+        let configs = ExecutorConfigs::get_cached();
+        let state = self.fallback_states().write().await.remove(&ctx.session.id);
+        let (chain, prompt, previous_progress) = match state {
+            Some(state) => (state.remaining, state.prompt, state.progress),
+            None => (
+                configs.fallback_chain(failed_profile).to_vec(),
+                prompt.clone(),
+                None,
+            ),
+        };
+
+        let mut chain = chain.into_iter();
+        let Some(next_profile) = chain
+            .by_ref()
+            .find(|profile| configs.get_coding_agent(profile).is_some())
+        else {
+            return Ok(None);
+        };
+
+        let entries = collect_normalized_entries(self, &ctx.execution_process.id).await;
+        let progress = last_assistant_message(&entries)
+            .map(str::to_string)
+            .or(previous_progress);
+        let fallback_action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt: fallback_prompt(failed_profile, failure, progress.as_deref(), &prompt),
+                executor_profile_id: next_profile.clone(),
+                working_dir,
+            }),
+            action.next_action.clone(),
+        );
+
+        tracing::info!(
+            "Coding agent {} {} ({}), retrying session {} with {}",
+            failed_profile,
+            failure.description(),
+            failure,
+            ctx.session.id,
+            next_profile
+        );
+        self.fallback_states().write().await.insert(
+            ctx.session.id,
+            FallbackState {
+                remaining: chain.collect(),
+                prompt,
+                progress,
+            },
+        );
+        match self
+            .start_execution(
+                &ctx.workspace,
+                &ctx.session,
+                &fallback_action,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await
+        {
+            Ok(process) => Ok(Some(process)),
+            Err(e) => {
+                self.fallback_states().write().await.remove(&ctx.session.id);
+                Err(e)
+            }
+        }
+    }
+
     async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
         let action = ctx.execution_process.executor_action()?;
         let next_action = if let Some(next_action) = action.next_action() {
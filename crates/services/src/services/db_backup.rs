@@ -0,0 +1,51 @@
+use std::{sync::Arc, time::Duration};
+
+use db::{
+    DBService,
+    backup::{self, BackupKind},
+};
+use tokio::sync::RwLock;
+use tracing::{error, info};
+
+use crate::services::config::{Config, DbBackupConfig};
+
+/// Takes rotating `scheduled` snapshots of the local database
+pub struct DbBackupService {
+    db: DBService,
+    config: Arc<RwLock<Config>>,
+}
+
+impl DbBackupService {
+    pub async fn spawn(db: DBService, config: Arc<RwLock<Config>>) -> tokio::task::JoinHandle<()> {
+        let service = Self { db, config };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    fn snapshot_interval(policy: &DbBackupConfig) -> Duration {
+        Duration::from_secs(u64::from(policy.interval_hours.max(1)) * 60 * 60)
+    }
+
+    async fn start(&self) {
+        let policy = self.config.read().await.db_backup.clone();
+        info!(
+            "Starting database backup service with interval {:?}",
+            Self::snapshot_interval(&policy)
+        );
+
+        // Startup already backs up before migrating, so the first snapshot waits one interval.
+        // The interval is re-read on every pass so config changes apply from the next one.
+        loop {
+            let policy = self.config.read().await.db_backup.clone();
+            tokio::time::sleep(Self::snapshot_interval(&policy)).await;
+
+            if !self.config.read().await.db_backup.enabled {
+                continue;
+            }
+            if let Err(e) = backup::create_backup(&self.db.pool, BackupKind::Scheduled).await {
+                error!("Failed to take scheduled database backup: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,390 @@
+//! Named dev processes, declared Procfile-style on projects and repositories.
+//!
+//! Each line of a `dev_processes` field is `<name>: <command>`; blank lines and lines starting
+//! with `#` are skipped. A command may end with `# ready: <regex>` to be ready once a line of
+//! its log matches, or `# ready: started` to be ready as soon as it runs; otherwise it is ready
+//! once its port accepts connections. Project processes run in the workspace directory and
+//! repository processes in their repository. Every process of a workspace gets its own port,
+//! which all of them see as `VK_PORT_<NAME>` and the process itself also as `PORT`.
+
+use std::time::Duration;
+
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use executors::actions::{
+    ExecutorActionType,
+    script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+};
+use regex::Regex;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::net::TcpStream;
+use ts_rs::TS;
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+use crate::services::container::ContainerService;
+
+/// How long a readiness check waits for the port to accept a connection
+const PORT_CHECK_TIMEOUT: Duration = Duration::from_millis(300);
+
+const READY_MARKER: &str = "# ready:";
+
+#[derive(Debug, Error)]
+pub enum DevProcessError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Line {line}: {message}")]
+    InvalidLine { line: usize, message: String },
+    #[error("Dev process `{0}` is defined more than once")]
+    Duplicate(String),
+    #[error("Dev process `{0}` not found")]
+    NotFound(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DevReadiness {
+    /// The process's port accepts connections
+    Port,
+    /// A line of the process's log matches the pattern
+    LogPattern(String),
+    /// The process has started
+    Started,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevProcess {
+    pub name: String,
+    pub command: String,
+    pub readiness: DevReadiness,
+    /// Repository that declares the process; `None` for project processes
+    pub repo_id: Option<Uuid>,
+    /// Directory to run in, relative to the workspace directory
+    pub working_dir: Option<String>,
+}
+
+impl DevProcess {
+    pub fn env_var(&self) -> String {
+        port_env_var(&self.name)
+    }
+
+    pub fn script_request(&self) -> ScriptRequest {
+        ScriptRequest {
+            script: self.command.clone(),
+            language: ScriptRequestLanguage::Bash,
+            context: ScriptContext::DevServer,
+            working_dir: self.working_dir.clone(),
+            dev_process: Some(self.name.clone()),
+        }
+    }
+
+    /// Whether the process started as `store` is ready, given the port it was allocated
+    pub async fn is_ready(&self, port: Option<u16>, store: Option<&MsgStore>) -> bool {
+        match &self.readiness {
+            DevReadiness::Started => true,
+            DevReadiness::Port => {
+                let Some(port) = port else { return false };
+                matches!(
+                    tokio::time::timeout(
+                        PORT_CHECK_TIMEOUT,
+                        TcpStream::connect(("127.0.0.1", port))
+                    )
+                    .await,
+                    Ok(Ok(_))
+                )
+            }
+            DevReadiness::LogPattern(pattern) => {
+                let (Some(store), Ok(regex)) = (store, Regex::new(pattern)) else {
+                    return false;
+                };
+                store.get_history().iter().any(|msg| match msg {
+                    LogMsg::Stdout(s) | LogMsg::Stderr(s) => s.lines().any(|l| regex.is_match(l)),
+                    _ => false,
+                })
+            }
+        }
+    }
+}
+
+/// A dev process of a workspace and its latest run
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DevProcessStatus {
+    pub name: String,
+    pub command: String,
+    pub repo_id: Option<Uuid>,
+    /// Variable that holds the process's port, e.g. `VK_PORT_API`
+    pub env_var: String,
+    pub port: Option<u16>,
+    /// Latest run, whose logs are streamed by the execution process endpoints
+    pub execution_process_id: Option<Uuid>,
+    pub running: bool,
+    pub ready: bool,
+}
+
+/// `VK_PORT_` followed by the name in upper case, with anything but letters and digits as `_`
+pub fn port_env_var(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("VK_PORT_{name}")
+}
+
+/// Parse the dev processes of a project or repository
+pub fn parse_procfile(text: &str) -> Result<Vec<DevProcess>, DevProcessError> {
+    let mut processes: Vec<DevProcess> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = |message: &str| DevProcessError::InvalidLine {
+            line: index + 1,
+            message: message.to_string(),
+        };
+
+        let (name, command) = line
+            .split_once(':')
+            .ok_or_else(|| invalid("expected `<name>: <command>`"))?;
+        let name = name.trim();
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(invalid(
+                "names may only contain letters, digits, `-` and `_`",
+            ));
+        }
+
+        let (command, readiness) = match command.rsplit_once(READY_MARKER) {
+            Some((command, ready)) => {
+                let readiness = match ready.trim() {
+                    "started" => DevReadiness::Started,
+                    "port" => DevReadiness::Port,
+                    pattern => {
+                        Regex::new(pattern).map_err(|e| invalid(&e.to_string()))?;
+                        DevReadiness::LogPattern(pattern.to_string())
+                    }
+                };
+                (command.trim(), readiness)
+            }
+            None => (command.trim(), DevReadiness::Port),
+        };
+        if command.is_empty() {
+            return Err(invalid("missing command"));
+        }
+
+        let process = DevProcess {
+            name: name.to_string(),
+            command: command.to_string(),
+            readiness,
+            repo_id: None,
+            working_dir: None,
+        };
+        if processes.iter().any(|p| p.env_var() == process.env_var()) {
+            return Err(DevProcessError::Duplicate(process.name));
+        }
+        processes.push(process);
+    }
+    Ok(processes)
+}
+
+/// The project's dev processes followed by those of the workspace's repositories
+pub async fn workspace_dev_processes(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+) -> Result<Vec<DevProcess>, DevProcessError> {
+    let project = match workspace.parent_task(pool).await? {
+        Some(task) => task.parent_project(pool).await?,
+        None => None,
+    };
+    let mut processes = match project.and_then(|p| p.dev_processes) {
+        Some(text) => parse_procfile(&text)?,
+        None => Vec::new(),
+    };
+
+    for repo in WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await? {
+        let Some(text) = &repo.dev_processes else {
+            continue;
+        };
+        for mut process in parse_procfile(text)? {
+            if processes.iter().any(|p| p.env_var() == process.env_var()) {
+                return Err(DevProcessError::Duplicate(process.name));
+            }
+            process.repo_id = Some(repo.id);
+            process.working_dir = Some(repo.name.clone());
+            processes.push(process);
+        }
+    }
+    Ok(processes)
+}
+
+/// Find a dev process of the workspace by name
+pub async fn find_dev_process(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+    name: &str,
+) -> Result<DevProcess, DevProcessError> {
+    workspace_dev_processes(pool, workspace)
+        .await?
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| DevProcessError::NotFound(name.to_string()))
+}
+
+/// The name of the dev process an execution process runs, if it runs one
+pub fn dev_process_name(process: &ExecutionProcess) -> Option<String> {
+    match process.executor_action().ok()?.typ() {
+        ExecutorActionType::ScriptRequest(request) => request.dev_process.clone(),
+        _ => None,
+    }
+}
+
+/// Running dev servers of the workspace that run the named dev process
+pub async fn find_running(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    name: &str,
+) -> Result<Vec<ExecutionProcess>, sqlx::Error> {
+    Ok(
+        ExecutionProcess::find_running_dev_servers_by_workspace(pool, workspace_id)
+            .await?
+            .into_iter()
+            .filter(|p| dev_process_name(p).as_deref() == Some(name))
+            .collect(),
+    )
+}
+
+/// Status of every dev process of the workspace
+pub async fn statuses<C: ContainerService + Sync>(
+    container: &C,
+    workspace: &Workspace,
+) -> Result<Vec<DevProcessStatus>, DevProcessError> {
+    let pool = &container.db().pool;
+    let processes = workspace_dev_processes(pool, workspace).await?;
+    let ports = container.dev_process_ports(workspace.id).await;
+    let runs = ExecutionProcess::find_dev_servers_by_workspace(pool, workspace.id).await?;
+
+    let mut statuses = Vec::with_capacity(processes.len());
+    for process in processes {
+        let port = ports.get(&process.name).copied();
+        let latest = runs
+            .iter()
+            .find(|run| dev_process_name(run).as_deref() == Some(process.name.as_str()));
+        let running = latest.is_some_and(|run| run.status == ExecutionProcessStatus::Running);
+        let ready = match latest {
+            Some(run) if running => {
+                let store = container.get_msg_store_by_id(&run.id).await;
+                process.is_ready(port, store.as_deref()).await
+            }
+            _ => false,
+        };
+        statuses.push(DevProcessStatus {
+            env_var: process.env_var(),
+            name: process.name,
+            command: process.command,
+            repo_id: process.repo_id,
+            port,
+            execution_process_id: latest.map(|run| run.id),
+            running,
+            ready,
+        });
+    }
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_named_processes_and_readiness() {
+        let processes = parse_procfile(
+            "# our stack\n\
+             api: cargo run -p api\n\
+             \n\
+             web: npm run dev -- --port $PORT # ready: Local:\\s+http\n\
+             worker: ./worker.sh # ready: started\n",
+        )
+        .unwrap();
+
+        let summary: Vec<_> = processes
+            .iter()
+            .map(|p| (p.name.as_str(), p.command.as_str(), &p.readiness))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("api", "cargo run -p api", &DevReadiness::Port),
+                (
+                    "web",
+                    "npm run dev -- --port $PORT",
+                    &DevReadiness::LogPattern("Local:\\s+http".to_string())
+                ),
+                ("worker", "./worker.sh", &DevReadiness::Started),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_lines_and_clashing_names() {
+        assert!(matches!(
+            parse_procfile("api cargo run"),
+            Err(DevProcessError::InvalidLine { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse_procfile("api: run\nweb app: npm start"),
+            Err(DevProcessError::InvalidLine { line: 2, .. })
+        ));
+        assert!(matches!(
+            parse_procfile("api: run # ready: (unclosed"),
+            Err(DevProcessError::InvalidLine { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse_procfile("story-book: a\nstory_book: b"),
+            Err(DevProcessError::Duplicate(name)) if name == "story_book"
+        ));
+    }
+
+    #[test]
+    fn names_port_variables() {
+        assert_eq!(port_env_var("api"), "VK_PORT_API");
+        assert_eq!(port_env_var("story-book"), "VK_PORT_STORY_BOOK");
+    }
+
+    #[tokio::test]
+    async fn becomes_ready_on_matching_log_line() {
+        let process = parse_procfile("web: npm run dev # ready: ready in \\d+ms")
+            .unwrap()
+            .remove(0);
+        let store = MsgStore::new();
+        store.push_stdout("compiling...\n");
+        assert!(!process.is_ready(None, Some(&store)).await);
+        store.push_stdout("VITE ready in 312ms\n");
+        assert!(process.is_ready(None, Some(&store)).await);
+    }
+
+    #[tokio::test]
+    async fn becomes_ready_once_port_accepts_connections() {
+        let process = parse_procfile("api: run").unwrap().remove(0);
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(process.is_ready(Some(port), None).await);
+        drop(listener);
+        assert!(!process.is_ready(Some(port), None).await);
+        assert!(!process.is_ready(None, None).await);
+    }
+}
@@ -252,3 +252,36 @@ pub mod scratch_patch {
         })])
     }
 }
+
+/// Helper functions for creating resource usage patches, keyed by execution process id
+pub mod resource_usage_patch {
+    use super::*;
+    use crate::services::resource_usage::ResourceUsage;
+
+    fn resource_usage_path(process_id: Uuid) -> String {
+        format!(
+            "/resource_usage/{}",
+            escape_pointer_segment(&process_id.to_string())
+        )
+    }
+
+    /// Create patch for adding or updating the latest sample of a process
+    pub fn add(usage: &ResourceUsage) -> Patch {
+        Patch(vec![PatchOperation::Add(AddOperation {
+            path: resource_usage_path(usage.execution_process_id)
+                .try_into()
+                .expect("Resource usage path should be valid"),
+            value: serde_json::to_value(usage)
+                .expect("Resource usage serialization should not fail"),
+        })])
+    }
+
+    /// Create patch for removing the sample of a process that exited
+    pub fn remove(process_id: Uuid) -> Patch {
+        Patch(vec![PatchOperation::Remove(RemoveOperation {
+            path: resource_usage_path(process_id)
+                .try_into()
+                .expect("Resource usage path should be valid"),
+        })])
+    }
+}
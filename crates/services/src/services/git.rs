@@ -65,6 +65,13 @@ pub struct GitBranch {
     pub last_commit_date: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DiffStat {
+    pub files_changed: usize,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct HeadInfo {
     pub branch: String,
@@ -1160,6 +1167,75 @@ impl GitService {
         Ok(())
     }
 
+    /// Snapshot the worktree, uncommitted and untracked changes included, into a commit kept
+    /// alive by `refname` so it can be restored later with [`Self::restore_worktree_snapshot`].
+    pub fn snapshot_worktree(
+        &self,
+        worktree_path: &Path,
+        refname: &str,
+        message: &str,
+    ) -> Result<String, GitServiceError> {
+        self.ensure_cli_commit_identity(worktree_path)?;
+        let cli = GitCli::new();
+        let sha = cli.snapshot_worktree(worktree_path, message)?;
+        cli.update_ref(worktree_path, refname, &sha)?;
+        Ok(sha)
+    }
+
+    /// Delete a ref created by [`Self::snapshot_worktree`], letting its commit be collected.
+    pub fn delete_ref(&self, repo_path: &Path, refname: &str) -> Result<(), GitServiceError> {
+        GitCli::new().delete_ref(repo_path, refname)?;
+        Ok(())
+    }
+
+    /// Discard all uncommitted changes, untracked files included, and reset the worktree to
+    /// `commit_sha`.
+    pub fn discard_and_reset_worktree(
+        &self,
+        worktree_path: &Path,
+        commit_sha: &str,
+    ) -> Result<(), GitServiceError> {
+        GitCli::new().clean_untracked(worktree_path)?;
+        self.reset_worktree_to_commit(worktree_path, commit_sha, true)
+    }
+
+    /// Restore a worktree captured by [`Self::snapshot_worktree`]: the snapshot contents
+    /// become the working tree again, left uncommitted on top of `head_sha`.
+    pub fn restore_worktree_snapshot(
+        &self,
+        worktree_path: &Path,
+        snapshot_sha: &str,
+        head_sha: &str,
+    ) -> Result<(), GitServiceError> {
+        self.discard_and_reset_worktree(worktree_path, snapshot_sha)?;
+        GitCli::new()
+            .git(worktree_path, ["reset", "--mixed", head_sha])
+            .map_err(|e| {
+                GitServiceError::InvalidRepository(format!("git reset --mixed failed: {e}"))
+            })?;
+        Ok(())
+    }
+
+    /// Files changed, lines added and lines deleted between two commits
+    pub fn diff_stat(
+        &self,
+        repo_path: &Path,
+        from_sha: &str,
+        to_sha: &str,
+    ) -> Result<DiffStat, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let from = repo.revparse_single(from_sha)?.peel_to_tree()?;
+        let to = repo.revparse_single(to_sha)?.peel_to_tree()?;
+        let stats = repo
+            .diff_tree_to_tree(Some(&from), Some(&to), None)?
+            .stats()?;
+        Ok(DiffStat {
+            files_changed: stats.files_changed(),
+            additions: stats.insertions(),
+            deletions: stats.deletions(),
+        })
+    }
+
     /// Add a worktree for a branch, optionally creating the branch
     pub fn add_worktree(
         &self,
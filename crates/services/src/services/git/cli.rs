@@ -342,6 +342,41 @@ impl GitCli {
         self.git(worktree_path, ["commit", "-m", message])?;
         Ok(())
     }
+
+    /// Record the full worktree contents, untracked files included, as a commit on top of HEAD
+    /// without touching the index, HEAD or any branch. Returns the commit sha.
+    pub fn snapshot_worktree(
+        &self,
+        worktree_path: &Path,
+        message: &str,
+    ) -> Result<String, GitCliError> {
+        let tmp_dir = tempfile::TempDir::new()
+            .map_err(|e| GitCliError::CommandFailed(format!("temp dir create failed: {e}")))?;
+        let tmp_index = tmp_dir.path().join("index");
+        let envs = vec![(
+            OsString::from("GIT_INDEX_FILE"),
+            tmp_index.as_os_str().to_os_string(),
+        )];
+
+        self.git_with_env(worktree_path, ["read-tree", "HEAD"], &envs)?;
+        self.git_with_env(
+            worktree_path,
+            Self::apply_default_excludes(vec!["add", "-A"]),
+            &envs,
+        )?;
+        let tree = self.git_with_env(worktree_path, ["write-tree"], &envs)?;
+        let sha = self.git(
+            worktree_path,
+            ["commit-tree", tree.trim(), "-p", "HEAD", "-m", message],
+        )?;
+        Ok(sha.trim().to_string())
+    }
+
+    /// Remove untracked files and directories, keeping ignored ones.
+    pub fn clean_untracked(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["clean", "-fd"])?;
+        Ok(())
+    }
     /// Fetch a branch to the given remote using native git authentication.
     pub fn fetch_with_refspec(
         &self,
@@ -620,6 +655,12 @@ impl GitCli {
             .map(|_| ())
     }
 
+    /// Delete a ref from the repo.
+    pub fn delete_ref(&self, repo_path: &Path, refname: &str) -> Result<(), GitCliError> {
+        self.git(repo_path, ["update-ref", "-d", refname])
+            .map(|_| ())
+    }
+
     pub fn abort_merge(&self, worktree_path: &Path) -> Result<(), GitCliError> {
         if !self.is_merge_in_progress(worktree_path)? {
             return Ok(());
@@ -10,12 +10,15 @@ use std::{
 };
 
 use chrono::{DateTime, Utc};
-use db::models::merge::{CiStatus, MergeStatus, PullRequestInfo};
+use db::models::{
+    merge::{CiStatus, MergeStatus, PullRequestInfo},
+    project::PrMergeMethod,
+};
 use serde::Deserialize;
 use thiserror::Error;
 use utils::shell::resolve_executable_path_blocking;
 
-use crate::services::git_host::types::{CreatePrRequest, UnifiedPrComment};
+use crate::services::git_host::types::{CreatePrRequest, ReviewDecision, UnifiedPrComment};
 
 #[derive(Debug, Clone)]
 pub struct AzureRepoInfo {
@@ -34,6 +37,19 @@ struct AzPrResponse {
     closed_date: Option<String>,
     repository: Option<AzRepository>,
     last_merge_commit: Option<AzCommit>,
+    #[serde(default)]
+    reviewers: Vec<AzReviewer>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzReviewer {
+    /// 10 = approved, 5 = approved with suggestions, 0 = no vote,
+    /// -5 = waiting for author, -10 = rejected
+    #[serde(default)]
+    vote: i64,
+    #[serde(default)]
+    is_required: bool,
 }
 
 #[derive(Deserialize)]
@@ -115,6 +131,8 @@ pub enum AzCliError {
     AuthFailed(String),
     #[error("Azure CLI returned unexpected output: {0}")]
     UnexpectedOutput(String),
+    #[error("Azure DevOps cannot complete a PR with the {0:?} merge method")]
+    UnsupportedMergeMethod(PrMergeMethod),
 }
 
 #[derive(Debug, Clone, Default)]
@@ -317,13 +335,19 @@ impl AzCli {
     }
 
     pub fn view_pr(&self, pr_url: &str) -> Result<PullRequestInfo, AzCliError> {
+        let raw = self.show_pr(pr_url)?;
+        Self::parse_pr_response(&raw)
+    }
+
+    /// Run `az repos pr show` for a PR URL and return the raw JSON output.
+    fn show_pr(&self, pr_url: &str) -> Result<String, AzCliError> {
         let (organization, pr_id) = Self::parse_pr_url(pr_url).ok_or_else(|| {
             AzCliError::UnexpectedOutput(format!("Could not parse Azure DevOps PR URL: {pr_url}"))
         })?;
 
         let org_url = format!("https://dev.azure.com/{}", organization);
 
-        let raw = self.run(
+        self.run(
             [
                 "repos",
                 "pr",
@@ -336,9 +360,53 @@ impl AzCli {
                 "json",
             ],
             None,
-        )?;
+        )
+    }
 
-        Self::parse_pr_response(&raw)
+    /// Derive the review decision from the PR's reviewer votes.
+    pub fn get_pr_review_decision(&self, pr_url: &str) -> Result<ReviewDecision, AzCliError> {
+        let raw = self.show_pr(pr_url)?;
+        let pr: AzPrResponse = serde_json::from_str(raw.trim()).map_err(|e| {
+            AzCliError::UnexpectedOutput(format!("Failed to parse PR response: {e}; raw: {raw}"))
+        })?;
+        Ok(Self::review_decision_from_reviewers(&pr.reviewers))
+    }
+
+    /// Complete a PR. The Azure CLI only distinguishes squash from a regular merge, so rebase
+    /// is rejected rather than completed some other way.
+    pub fn merge_pr(&self, pr_url: &str, method: PrMergeMethod) -> Result<(), AzCliError> {
+        let (organization, pr_id) = Self::parse_pr_url(pr_url).ok_or_else(|| {
+            AzCliError::UnexpectedOutput(format!("Could not parse Azure DevOps PR URL: {pr_url}"))
+        })?;
+
+        let org_url = format!("https://dev.azure.com/{}", organization);
+        let squash = match method {
+            PrMergeMethod::Squash => true,
+            PrMergeMethod::Merge => false,
+            PrMergeMethod::Rebase => {
+                return Err(AzCliError::UnsupportedMergeMethod(method));
+            }
+        };
+
+        self.run(
+            [
+                "repos",
+                "pr",
+                "update",
+                "--id",
+                &pr_id.to_string(),
+                "--organization",
+                &org_url,
+                "--status",
+                "completed",
+                "--squash",
+                if squash { "true" } else { "false" },
+                "--output",
+                "json",
+            ],
+            None,
+        )?;
+        Ok(())
     }
 
     pub fn list_prs_for_branch(
@@ -552,6 +620,19 @@ impl AzCli {
         Ok(comments)
     }
 
+    /// Aggregate reviewer votes into a single review decision
+    fn review_decision_from_reviewers(reviewers: &[AzReviewer]) -> ReviewDecision {
+        if reviewers.iter().any(|r| r.vote < 0) {
+            ReviewDecision::ChangesRequested
+        } else if reviewers.iter().any(|r| r.is_required && r.vote < 5) {
+            ReviewDecision::ReviewRequired
+        } else if reviewers.iter().any(|r| r.vote >= 5) {
+            ReviewDecision::Approved
+        } else {
+            ReviewDecision::NotRequired
+        }
+    }
+
     /// Map Azure DevOps PR status to MergeStatus
     fn map_azure_status(status: &str) -> MergeStatus {
         match status.to_lowercase().as_str() {
@@ -617,6 +698,40 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_merge_pr_rejects_rebase() {
+        let result = AzCli::new().merge_pr(
+            "https://dev.azure.com/myorg/myproject/_git/myrepo/pullrequest/123",
+            PrMergeMethod::Rebase,
+        );
+        assert!(matches!(
+            result,
+            Err(AzCliError::UnsupportedMergeMethod(PrMergeMethod::Rebase))
+        ));
+    }
+
+    #[test]
+    fn test_review_decision_from_reviewers() {
+        let reviewer = |vote, is_required| AzReviewer { vote, is_required };
+
+        assert_eq!(
+            AzCli::review_decision_from_reviewers(&[]),
+            ReviewDecision::NotRequired
+        );
+        assert_eq!(
+            AzCli::review_decision_from_reviewers(&[reviewer(10, true), reviewer(0, false)]),
+            ReviewDecision::Approved
+        );
+        assert_eq!(
+            AzCli::review_decision_from_reviewers(&[reviewer(10, false), reviewer(0, true)]),
+            ReviewDecision::ReviewRequired
+        );
+        assert_eq!(
+            AzCli::review_decision_from_reviewers(&[reviewer(10, true), reviewer(-10, false)]),
+            ReviewDecision::ChangesRequested
+        );
+    }
+
     #[test]
     fn test_urls_match() {
         // Exact match
@@ -8,13 +8,19 @@ use async_trait::async_trait;
 use backon::{ExponentialBuilder, Retryable};
 pub use cli::AzCli;
 use cli::{AzCliError, AzureRepoInfo};
-use db::models::merge::{CiStatus, PullRequestInfo};
+use db::models::{
+    merge::{CiStatus, PullRequestInfo},
+    project::PrMergeMethod,
+};
 use tokio::task;
 use tracing::info;
 
 use super::{
     GitHostProvider,
-    types::{CiFailureInfo, CreatePrRequest, GitHostError, ProviderKind, UnifiedPrComment},
+    types::{
+        CiFailureInfo, CreatePrRequest, GitHostError, ProviderKind, ReviewDecision,
+        UnifiedPrComment,
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -63,6 +69,7 @@ impl AzureDevOpsProvider {
                 AzCliError::UnexpectedOutput(msg) => GitHostError::Repository(format!(
                     "Unexpected output from Azure CLI auth check: {msg}"
                 )),
+                err @ AzCliError::UnsupportedMergeMethod(_) => err.into(),
             })
     }
 }
@@ -85,6 +92,10 @@ impl From<AzCliError> for GitHostError {
                 }
             }
             AzCliError::UnexpectedOutput(msg) => GitHostError::UnexpectedOutput(msg.clone()),
+            AzCliError::UnsupportedMergeMethod(method) => GitHostError::UnsupportedMergeMethod {
+                provider: ProviderKind::AzureDevOps,
+                method: format!("{method:?}").to_lowercase(),
+            },
         }
     }
 }
@@ -195,6 +206,58 @@ impl GitHostProvider for AzureDevOpsProvider {
         Ok(Vec::new())
     }
 
+    async fn get_review_decision(&self, pr_url: &str) -> Result<ReviewDecision, GitHostError> {
+        let cli = self.az_cli.clone();
+        let url = pr_url.to_string();
+
+        (|| async {
+            let cli = cli.clone();
+            let url = url.clone();
+
+            let decision = task::spawn_blocking(move || cli.get_pr_review_decision(&url))
+                .await
+                .map_err(|err| {
+                    GitHostError::PullRequest(format!(
+                        "Failed to execute Azure CLI for getting review decision: {err}"
+                    ))
+                })?;
+            decision.map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|err: &GitHostError| err.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "Azure DevOps API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn merge_pr(&self, pr_url: &str, method: PrMergeMethod) -> Result<(), GitHostError> {
+        // Merging is not idempotent, so it is not retried
+        let cli = self.az_cli.clone();
+        let url = pr_url.to_string();
+        task::spawn_blocking(move || cli.merge_pr(&url, method))
+            .await
+            .map_err(|err| {
+                GitHostError::PullRequest(format!(
+                    "Failed to execute Azure CLI for completing PR: {err}"
+                ))
+            })?
+            .map_err(GitHostError::from)?;
+
+        info!("Completed Azure DevOps PR {} ({:?})", pr_url, method);
+        Ok(())
+    }
+
     async fn list_prs_for_branch(
         &self,
         repo_path: &Path,
@@ -11,14 +11,17 @@ use std::{
 };
 
 use chrono::{DateTime, Utc};
-use db::models::merge::{CiStatus, MergeStatus, PullRequestInfo};
+use db::models::{
+    merge::{CiStatus, MergeStatus, PullRequestInfo},
+    project::PrMergeMethod,
+};
 use serde::{Deserialize, Serialize};
 use tempfile::NamedTempFile;
 use thiserror::Error;
 use utils::shell::resolve_executable_path_blocking;
 
 use crate::services::git_host::types::{
-    CreatePrRequest, PrComment, PrCommentAuthor, PrReviewComment, ReviewCommentUser,
+    CreatePrRequest, PrComment, PrCommentAuthor, PrReviewComment, ReviewCommentUser, ReviewDecision,
 };
 
 #[derive(Debug, Clone)]
@@ -396,6 +399,23 @@ impl GhCli {
         Self::parse_pr_check_failures(&raw)
     }
 
+    /// Get the review decision for a PR (`APPROVED`, `CHANGES_REQUESTED`, `REVIEW_REQUIRED`).
+    pub fn get_pr_review_decision(&self, pr_url: &str) -> Result<ReviewDecision, GhCliError> {
+        let raw = self.run(["pr", "view", pr_url, "--json", "reviewDecision"], None)?;
+        Self::parse_review_decision(&raw)
+    }
+
+    /// Merge a PR with `gh pr merge` using the given merge method.
+    pub fn merge_pr(&self, pr_url: &str, method: PrMergeMethod) -> Result<(), GhCliError> {
+        let method_flag = match method {
+            PrMergeMethod::Squash => "--squash",
+            PrMergeMethod::Merge => "--merge",
+            PrMergeMethod::Rebase => "--rebase",
+        };
+        self.run(["pr", "merge", pr_url, method_flag], None)?;
+        Ok(())
+    }
+
     /// Get workflow run logs for failed runs.
     /// This attempts to fetch logs from the GitHub API for a specific run.
     pub fn get_run_logs(&self, owner: &str, repo: &str, run_id: i64) -> Result<String, GhCliError> {
@@ -695,6 +715,34 @@ impl GhCli {
         }
     }
 
+    /// Parse the output of `gh pr view --json reviewDecision`.
+    /// An empty decision means the repository has no review requirements.
+    fn parse_review_decision(raw: &str) -> Result<ReviewDecision, GhCliError> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct GhReviewDecisionResponse {
+            #[serde(default)]
+            review_decision: Option<String>,
+        }
+
+        let resp: GhReviewDecisionResponse = serde_json::from_str(raw.trim()).map_err(|err| {
+            GhCliError::UnexpectedOutput(format!(
+                "Failed to parse gh pr view reviewDecision response: {err}; raw: {raw}"
+            ))
+        })?;
+
+        let decision = resp
+            .review_decision
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        Ok(match decision.as_str() {
+            "APPROVED" => ReviewDecision::Approved,
+            "CHANGES_REQUESTED" => ReviewDecision::ChangesRequested,
+            "REVIEW_REQUIRED" => ReviewDecision::ReviewRequired,
+            _ => ReviewDecision::NotRequired,
+        })
+    }
+
     /// Parse PR checks response to extract failed check details
     fn parse_pr_check_failures(raw: &str) -> Result<Vec<CiFailureDetails>, GhCliError> {
         #[derive(Deserialize)]
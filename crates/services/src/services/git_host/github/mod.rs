@@ -8,13 +8,19 @@ use async_trait::async_trait;
 use backon::{ExponentialBuilder, Retryable};
 use cli::GitHubRepoInfo;
 pub use cli::{GhCli, GhCliError, GitHubIssue};
-use db::models::merge::{CiStatus, PullRequestInfo};
+use db::models::{
+    merge::{CiStatus, PullRequestInfo},
+    project::PrMergeMethod,
+};
 use tokio::task;
 use tracing::info;
 
 use super::{
     GitHostProvider,
-    types::{CiFailureInfo, CreatePrRequest, GitHostError, ProviderKind, UnifiedPrComment},
+    types::{
+        CiFailureInfo, CreatePrRequest, GitHostError, ProviderKind, ReviewDecision,
+        UnifiedPrComment,
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -341,6 +347,57 @@ impl GitHostProvider for GitHubProvider {
         .await
     }
 
+    async fn get_review_decision(&self, pr_url: &str) -> Result<ReviewDecision, GitHostError> {
+        let cli = self.gh_cli.clone();
+        let url = pr_url.to_string();
+
+        (|| async {
+            let cli = cli.clone();
+            let url = url.clone();
+            let decision = task::spawn_blocking(move || cli.get_pr_review_decision(&url))
+                .await
+                .map_err(|err| {
+                    GitHostError::PullRequest(format!(
+                        "Failed to execute GitHub CLI for getting review decision: {err}"
+                    ))
+                })?;
+            decision.map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|err: &GitHostError| err.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn merge_pr(&self, pr_url: &str, method: PrMergeMethod) -> Result<(), GitHostError> {
+        // Merging is not idempotent, so it is not retried
+        let cli = self.gh_cli.clone();
+        let url = pr_url.to_string();
+        task::spawn_blocking(move || cli.merge_pr(&url, method))
+            .await
+            .map_err(|err| {
+                GitHostError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for merging PR: {err}"
+                ))
+            })?
+            .map_err(GitHostError::from)?;
+
+        info!("Merged GitHub PR {} ({:?})", pr_url, method);
+        Ok(())
+    }
+
     async fn list_prs_for_branch(
         &self,
         repo_path: &Path,
@@ -7,12 +7,15 @@ pub mod github;
 use std::path::Path;
 
 use async_trait::async_trait;
-use db::models::merge::{CiStatus, PullRequestInfo};
+use db::models::{
+    merge::{CiStatus, PullRequestInfo},
+    project::PrMergeMethod,
+};
 use detection::detect_provider_from_url;
 use enum_dispatch::enum_dispatch;
 pub use types::{
     CiFailureInfo, CreatePrRequest, GitHostError, PrComment, PrCommentAuthor, PrReviewComment,
-    ProviderKind, ReviewCommentUser, UnifiedPrComment,
+    ProviderKind, ReviewCommentUser, ReviewDecision, UnifiedPrComment,
 };
 
 use self::{azure::AzureDevOpsProvider, github::GitHubProvider};
@@ -35,6 +38,12 @@ pub trait GitHostProvider: Send + Sync {
     /// Get detailed information about CI failures for a PR
     async fn get_ci_failures(&self, pr_url: &str) -> Result<Vec<CiFailureInfo>, GitHostError>;
 
+    /// Get the aggregated review decision for a PR
+    async fn get_review_decision(&self, pr_url: &str) -> Result<ReviewDecision, GitHostError>;
+
+    /// Merge a PR using the given merge method
+    async fn merge_pr(&self, pr_url: &str, method: PrMergeMethod) -> Result<(), GitHostError>;
+
     async fn list_prs_for_branch(
         &self,
         repo_path: &Path,
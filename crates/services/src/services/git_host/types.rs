@@ -46,6 +46,11 @@ pub enum GitHostError {
     CliNotInstalled { provider: ProviderKind },
     #[error("Unsupported git hosting provider")]
     UnsupportedProvider,
+    #[error("{provider} does not support the {method} merge method")]
+    UnsupportedMergeMethod {
+        provider: ProviderKind,
+        method: String,
+    },
     #[error("CLI returned unexpected output: {0}")]
     UnexpectedOutput(String),
 }
@@ -59,6 +64,7 @@ impl GitHostError {
                 | GitHostError::RepoNotFoundOrNoAccess(_)
                 | GitHostError::CliNotInstalled { .. }
                 | GitHostError::UnsupportedProvider
+                | GitHostError::UnsupportedMergeMethod { .. }
         )
     }
 }
@@ -142,3 +148,23 @@ pub struct CiFailureInfo {
     /// URL to view more details about the failure
     pub details_url: Option<String>,
 }
+
+/// Aggregated review state for a PR, used to decide whether it may be merged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewDecision {
+    /// All required reviews have approved the PR
+    Approved,
+    /// At least one reviewer requested changes or rejected the PR
+    ChangesRequested,
+    /// Reviews are required but not yet satisfied
+    ReviewRequired,
+    /// The repository does not require reviews for this PR
+    NotRequired,
+}
+
+impl ReviewDecision {
+    /// Whether the PR satisfies its review requirements
+    pub fn is_satisfied(&self) -> bool {
+        matches!(self, ReviewDecision::Approved | ReviewDecision::NotRequired)
+    }
+}
@@ -0,0 +1,308 @@
+//! Handoff prompts for continuing a session with a different executor.
+//!
+//! Executors cannot resume each other's sessions, so the next executor starts fresh with a
+//! summary of the work done so far, built from the session [`Transcript`].
+
+use std::fmt::Write as _;
+
+use executors::logs::{ActionType, TodoItem};
+use utils::text::truncate_to_char_boundary;
+
+use crate::services::transcript::{
+    Transcript, TranscriptEntry, TranscriptEntryKind, TranscriptToolStatus,
+};
+
+/// Longest assistant message quoted per turn
+const MAX_MESSAGE_BYTES: usize = 1500;
+/// Files and commands listed per turn
+const MAX_ITEMS: usize = 20;
+
+/// Prompt for an executor taking over `transcript`'s session. `reason` is a sentence saying
+/// why the previous agent stopped; `prompt` is the request the new executor should work on.
+pub fn handoff_prompt(transcript: &Transcript, reason: &str, prompt: &str) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "You are taking over this task from another coding agent. {reason}"
+    );
+    let _ = writeln!(
+        out,
+        "Its changes are already in the working tree. Review them (for example with `git status` and `git diff`) and continue from there instead of starting over.\n"
+    );
+    out.push_str(&context_document(transcript));
+    let _ = writeln!(out, "## Request\n\n{}", prompt.trim());
+    out
+}
+
+/// Compact summary of a session: per turn the request, files edited, commands run and the
+/// agent's last message, followed by the todo list as the agents last left it
+pub fn context_document(transcript: &Transcript) -> String {
+    let mut out = String::new();
+    if let Some(title) = &transcript.task_title {
+        let _ = writeln!(out, "Task: {title}\n");
+    }
+
+    if !transcript.turns.is_empty() {
+        let _ = writeln!(out, "## Progress so far\n");
+    }
+    for (i, turn) in transcript.turns.iter().enumerate() {
+        let executor = turn.executor.as_deref().unwrap_or("unknown executor");
+        let _ = writeln!(out, "### Turn {} ({executor})\n", i + 1);
+        write_turn_summary(&mut out, &turn.entries);
+    }
+
+    let open_todos: Vec<&TodoItem> = latest_todos(transcript)
+        .iter()
+        .filter(|todo| !is_done(todo))
+        .collect();
+    if !open_todos.is_empty() {
+        let _ = writeln!(out, "## Open todos\n");
+        for todo in open_todos {
+            let _ = writeln!(out, "- [{}] {}", todo.status, todo.content.trim());
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn latest_todos(transcript: &Transcript) -> &[TodoItem] {
+    transcript
+        .turns
+        .iter()
+        .flat_map(|turn| &turn.entries)
+        .rev()
+        .find_map(|entry| match &entry.kind {
+            TranscriptEntryKind::ToolUse {
+                action: ActionType::TodoManagement { todos, .. },
+                ..
+            } => Some(todos.as_slice()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn is_done(todo: &TodoItem) -> bool {
+    matches!(
+        todo.status.to_ascii_lowercase().as_str(),
+        "completed" | "done" | "cancelled"
+    )
+}
+
+fn write_turn_summary(out: &mut String, entries: &[TranscriptEntry]) {
+    let mut files: Vec<&str> = Vec::new();
+    let mut commands: Vec<String> = Vec::new();
+    let mut last_message: Option<&str> = None;
+    let mut errors: Vec<&str> = Vec::new();
+
+    for entry in entries {
+        match &entry.kind {
+            TranscriptEntryKind::UserMessage => {
+                if let Some(request) = entry.content.lines().find(|l| !l.trim().is_empty()) {
+                    let _ = writeln!(out, "Request: {}", request.trim());
+                }
+            }
+            TranscriptEntryKind::AssistantMessage => last_message = Some(entry.content.trim()),
+            TranscriptEntryKind::Error => errors.push(entry.content.trim()),
+            TranscriptEntryKind::ToolUse { action, status, .. } => {
+                if matches!(status, TranscriptToolStatus::Denied { .. }) {
+                    continue;
+                }
+                match action {
+                    ActionType::FileEdit { path, .. } if !files.contains(&path.as_str()) => {
+                        files.push(path)
+                    }
+                    ActionType::CommandRun { command, .. } => {
+                        let failed = matches!(status, TranscriptToolStatus::Failed);
+                        commands.push(format!(
+                            "`{}`{}",
+                            command.trim(),
+                            if failed { " (failed)" } else { "" }
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !files.is_empty() {
+        let _ = writeln!(out, "Files edited: {}", capped(&files));
+    }
+    if !commands.is_empty() {
+        let _ = writeln!(out, "Commands run: {}", capped(&commands));
+    }
+    if let Some(message) = last_message {
+        let _ = writeln!(
+            out,
+            "Last message from the agent:\n\n{}",
+            quoted(truncate_to_char_boundary(message, MAX_MESSAGE_BYTES))
+        );
+    }
+    if let Some(error) = errors.last() {
+        let _ = writeln!(
+            out,
+            "Ended with error: {}",
+            truncate_to_char_boundary(error, MAX_MESSAGE_BYTES)
+        );
+    }
+    out.push('\n');
+}
+
+fn capped<S: AsRef<str>>(items: &[S]) -> String {
+    let mut listed: Vec<&str> = items.iter().take(MAX_ITEMS).map(AsRef::as_ref).collect();
+    let more = items.len().saturating_sub(MAX_ITEMS);
+    let more_label = format!("and {more} more");
+    if more > 0 {
+        listed.push(&more_label);
+    }
+    listed.join(", ")
+}
+
+fn quoted(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| format!("> {line}").trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::execution_process::ExecutionProcessStatus;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::services::transcript::{TRANSCRIPT_SCHEMA_VERSION, TranscriptTurn};
+
+    fn entry(kind: TranscriptEntryKind, content: &str) -> TranscriptEntry {
+        TranscriptEntry {
+            timestamp: None,
+            kind,
+            content: content.to_string(),
+        }
+    }
+
+    fn tool(action: ActionType, status: TranscriptToolStatus) -> TranscriptEntry {
+        entry(
+            TranscriptEntryKind::ToolUse {
+                tool_name: "tool".to_string(),
+                status,
+                action,
+            },
+            "",
+        )
+    }
+
+    #[test]
+    fn summarizes_turns_for_next_executor() {
+        let transcript = Transcript {
+            schema_version: TRANSCRIPT_SCHEMA_VERSION,
+            session_id: Uuid::nil(),
+            workspace_id: Uuid::nil(),
+            task_title: Some("Add login rate limiting".to_string()),
+            exported_at: Utc::now(),
+            turns: vec![TranscriptTurn {
+                execution_process_id: Uuid::nil(),
+                executor: Some("CLAUDE_CODE".to_string()),
+                status: ExecutionProcessStatus::Failed,
+                started_at: Utc::now(),
+                completed_at: None,
+                entries: vec![
+                    entry(
+                        TranscriptEntryKind::UserMessage,
+                        "Add rate limiting to /login",
+                    ),
+                    tool(
+                        ActionType::FileEdit {
+                            path: "src/auth.rs".to_string(),
+                            changes: Vec::new(),
+                        },
+                        TranscriptToolStatus::Success,
+                    ),
+                    tool(
+                        ActionType::CommandRun {
+                            command: "cargo test".to_string(),
+                            result: None,
+                        },
+                        TranscriptToolStatus::Failed,
+                    ),
+                    entry(
+                        TranscriptEntryKind::AssistantMessage,
+                        "Added a limiter; tests still fail.",
+                    ),
+                    entry(TranscriptEntryKind::Error, "Claude AI usage limit reached"),
+                ],
+            }],
+        };
+
+        let prompt = handoff_prompt(
+            &transcript,
+            "The previous agent (CLAUDE_CODE) hit a usage or rate limit.",
+            "Add rate limiting to /login",
+        );
+
+        assert!(prompt.starts_with(
+            "You are taking over this task from another coding agent. The previous agent (CLAUDE_CODE) hit a usage or rate limit.\n"
+        ));
+        assert!(prompt.contains("### Turn 1 (CLAUDE_CODE)"));
+        assert!(prompt.contains("Files edited: src/auth.rs"));
+        assert!(prompt.contains("Commands run: `cargo test` (failed)"));
+        assert!(prompt.contains("> Added a limiter; tests still fail."));
+        assert!(prompt.contains("Ended with error: Claude AI usage limit reached"));
+        assert!(prompt.ends_with("## Request\n\nAdd rate limiting to /login\n"));
+    }
+
+    #[test]
+    fn lists_open_todos_from_latest_update() {
+        let todos = |statuses: &[(&str, &str)]| {
+            tool(
+                ActionType::TodoManagement {
+                    todos: statuses
+                        .iter()
+                        .map(|(content, status)| TodoItem {
+                            content: content.to_string(),
+                            status: status.to_string(),
+                            priority: None,
+                        })
+                        .collect(),
+                    operation: "write".to_string(),
+                },
+                TranscriptToolStatus::Success,
+            )
+        };
+        let turn = |executor: &str, entries| TranscriptTurn {
+            execution_process_id: Uuid::nil(),
+            executor: Some(executor.to_string()),
+            status: ExecutionProcessStatus::Completed,
+            started_at: Utc::now(),
+            completed_at: None,
+            entries,
+        };
+        let transcript = Transcript {
+            schema_version: TRANSCRIPT_SCHEMA_VERSION,
+            session_id: Uuid::nil(),
+            workspace_id: Uuid::nil(),
+            task_title: None,
+            exported_at: Utc::now(),
+            turns: vec![
+                turn(
+                    "GEMINI",
+                    vec![todos(&[("Explore auth module", "in_progress")])],
+                ),
+                turn(
+                    "GEMINI",
+                    vec![todos(&[
+                        ("Explore auth module", "completed"),
+                        ("Write limiter", "pending"),
+                    ])],
+                ),
+            ],
+        };
+
+        let document = context_document(&transcript);
+        assert!(document.contains("### Turn 2 (GEMINI)"));
+        assert!(document.ends_with("## Open todos\n\n- [pending] Write limiter\n\n"));
+    }
+}
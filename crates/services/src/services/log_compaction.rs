@@ -0,0 +1,572 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use db::{
+    DBService,
+    models::{
+        execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+        execution_process_log_archive::{
+            ExecutionProcessLogArchive, LogArchiveContents, LogCompactionCandidate,
+        },
+        execution_process_logs::ExecutionProcessLogs,
+    },
+};
+use sqlx::error::Error as SqlxError;
+use thiserror::Error;
+use tokio::{
+    fs,
+    sync::RwLock,
+    time::{MissedTickBehavior, interval},
+};
+use tracing::{debug, error, info, warn};
+use utils::{assets::asset_dir, log_msg::LogMsg};
+use uuid::Uuid;
+
+use crate::services::{
+    config::{Config, LogRetentionConfig},
+    container::{ContainerError, ContainerService},
+};
+
+/// Processes handled per query, so a large backlog is worked off over several passes
+const BATCH_SIZE: i64 = 100;
+
+const ARCHIVE_FILE_SUFFIX: &str = ".jsonl.zst";
+
+#[derive(Debug, Error)]
+enum LogCompactionError {
+    #[error(transparent)]
+    Sqlx(#[from] SqlxError),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Execution process {0} not found")]
+    ProcessNotFound(Uuid),
+}
+
+/// Outcome of one compaction pass
+#[derive(Debug, Default, Clone)]
+pub struct LogCompactionReport {
+    /// Processes whose log rows were compressed into an archive
+    pub compressed: usize,
+    /// Coding agent processes whose raw output was replaced by normalized entries
+    pub normalized: usize,
+    /// Archives moved out of the database into files
+    pub moved_to_files: usize,
+    /// Orphaned archive files removed
+    pub orphans_removed: usize,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+impl LogCompactionReport {
+    /// How much smaller the stored logs got. SQLite reuses the freed pages for new rows, but
+    /// the database file itself only shrinks on `VACUUM`, so this is not disk space returned.
+    pub fn bytes_saved(&self) -> u64 {
+        self.bytes_before.saturating_sub(self.bytes_after)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.compressed == 0
+            && self.normalized == 0
+            && self.moved_to_files == 0
+            && self.orphans_removed == 0
+    }
+}
+
+/// Service applying the log retention policy to `execution_process_logs`.
+///
+/// Recent processes are never touched, so their logs still replay through a `MsgStore`
+/// exactly as recorded. Older logs are compressed, and past `drop_raw_after_days` the raw
+/// agent output is replaced by the normalized conversation it produced.
+pub struct LogCompactionService<C: ContainerService + Send + Sync + 'static> {
+    db: DBService,
+    container: C,
+    config: Arc<RwLock<Config>>,
+    poll_interval: Duration,
+}
+
+impl<C: ContainerService + Send + Sync + 'static> LogCompactionService<C> {
+    pub async fn spawn(
+        db: DBService,
+        container: C,
+        config: Arc<RwLock<Config>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            container,
+            config,
+            poll_interval: Duration::from_secs(60 * 60), // Every hour
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting log compaction service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        // Leave startup alone; the first pass runs one interval in
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+            let retention = self.config.read().await.log_retention.clone();
+            if !retention.enabled {
+                continue;
+            }
+            match self.compact(&retention).await {
+                Ok(report) if report.is_empty() => debug!("Log compaction: nothing to do"),
+                Ok(report) => info!(
+                    "Log compaction shrank stored logs by {} bytes ({} compressed, {} normalized, {} moved to files, {} orphaned files removed)",
+                    report.bytes_saved(),
+                    report.compressed,
+                    report.normalized,
+                    report.moved_to_files,
+                    report.orphans_removed
+                ),
+                Err(e) => error!("Log compaction failed: {}", e),
+            }
+        }
+    }
+
+    /// Run one compaction pass with the given policy
+    pub async fn compact(
+        &self,
+        retention: &LogRetentionConfig,
+    ) -> Result<LogCompactionReport, SqlxError> {
+        let pool = &self.db.pool;
+        let mut report = LogCompactionReport::default();
+        let drop_raw_after = retention.drop_raw_after_days;
+
+        for candidate in ExecutionProcessLogArchive::find_uncompacted(
+            pool,
+            &days_ago(retention.compress_after_days),
+            BATCH_SIZE,
+        )
+        .await?
+        {
+            if let Err(e) = self
+                .compact_process(&candidate, drop_raw_after, true, &mut report)
+                .await
+            {
+                warn!(
+                    "Failed to compact logs of execution {}: {}",
+                    candidate.execution_id, e
+                );
+            }
+        }
+
+        if let Some(days) = drop_raw_after {
+            for candidate in ExecutionProcessLogArchive::find_raw_coding_agent_archives(
+                pool,
+                &days_ago(days),
+                BATCH_SIZE,
+            )
+            .await?
+            {
+                if let Err(e) = self
+                    .compact_process(&candidate, drop_raw_after, false, &mut report)
+                    .await
+                {
+                    warn!(
+                        "Failed to normalize archived logs of execution {}: {}",
+                        candidate.execution_id, e
+                    );
+                }
+            }
+        }
+
+        if retention.archive_archived_workspaces {
+            for archive in
+                ExecutionProcessLogArchive::find_in_archived_workspaces(pool, BATCH_SIZE).await?
+            {
+                let path = archive_dir().join(archive_file_name(archive.execution_id));
+                match archive.move_to_file(pool, &path).await {
+                    Ok(()) => {
+                        report.moved_to_files += 1;
+                        report.bytes_before += archive.stored_bytes as u64;
+                    }
+                    Err(e) => warn!(
+                        "Failed to move logs of execution {} to {}: {}",
+                        archive.execution_id,
+                        path.display(),
+                        e
+                    ),
+                }
+            }
+        }
+
+        report.orphans_removed = remove_orphaned_files(pool).await?;
+        Ok(report)
+    }
+
+    /// Fold the logs of one process into its archive, keeping only the normalized entries
+    /// once the process finished more than `drop_raw_after` days ago. Without
+    /// `uncompressed_rows`, the archive is only rewritten if it can be normalized.
+    async fn compact_process(
+        &self,
+        candidate: &LogCompactionCandidate,
+        drop_raw_after: Option<u32>,
+        uncompressed_rows: bool,
+        report: &mut LogCompactionReport,
+    ) -> Result<(), LogCompactionError> {
+        let pool = &self.db.pool;
+        let records =
+            ExecutionProcessLogs::find_by_execution_id(pool, candidate.execution_id).await?;
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let mut contents = LogArchiveContents::Raw;
+        let mut jsonl: String = records.iter().map(|r| r.logs.as_str()).collect();
+
+        if let Some(days) = drop_raw_after
+            && let Some(normalized) = self.normalized_jsonl(candidate, days, &records).await?
+        {
+            contents = LogArchiveContents::Normalized;
+            jsonl = normalized;
+        }
+
+        if !uncompressed_rows && contents == LogArchiveContents::Raw {
+            return Ok(());
+        }
+
+        let stored_bytes =
+            ExecutionProcessLogArchive::store(pool, candidate.execution_id, contents, &jsonl)
+                .await?;
+
+        report.bytes_before += candidate.stored_bytes as u64;
+        report.bytes_after += stored_bytes as u64;
+        match contents {
+            LogArchiveContents::Normalized => report.normalized += 1,
+            LogArchiveContents::Raw => report.compressed += 1,
+        }
+        Ok(())
+    }
+
+    /// Replay the logs through the executor's normalizer and serialize the resulting patches.
+    /// Returns `None` when the raw output has to be kept: the process is too recent, is not a
+    /// coding agent, or its replay was partial or produced no normalized entries.
+    async fn normalized_jsonl(
+        &self,
+        candidate: &LogCompactionCandidate,
+        drop_raw_after: u32,
+        records: &[ExecutionProcessLogs],
+    ) -> Result<Option<String>, LogCompactionError> {
+        let pool = &self.db.pool;
+        let process = ExecutionProcess::find_by_id(pool, candidate.execution_id)
+            .await?
+            .ok_or(LogCompactionError::ProcessNotFound(candidate.execution_id))?;
+        let expired = raw_output_expired(process.completed_at, drop_raw_after, Utc::now());
+        if !expired || process.run_reason != ExecutionProcessRunReason::CodingAgent {
+            return Ok(None);
+        }
+
+        let raw_logs = ExecutionProcessLogs::parse_logs(records)?;
+        if !raw_logs
+            .iter()
+            .any(|msg| matches!(msg, LogMsg::Stdout(_) | LogMsg::Stderr(_)))
+        {
+            return Ok(None);
+        }
+
+        let Some((workspace, _session)) = process.parent_workspace_and_session(pool).await? else {
+            return Ok(None);
+        };
+        let output = self
+            .container
+            .replay_execution_logs(&process, &workspace, raw_logs)
+            .await?;
+        if !output.complete {
+            warn!(
+                "Replay of execution {} was incomplete; keeping its raw logs",
+                process.id
+            );
+            return Ok(None);
+        }
+        if output.patches.is_empty() {
+            return Ok(None);
+        }
+
+        let mut jsonl = String::new();
+        for patch in output.patches {
+            jsonl.push_str(&serde_json::to_string(&LogMsg::JsonPatch(patch))?);
+            jsonl.push('\n');
+        }
+        Ok(Some(jsonl))
+    }
+}
+
+/// SQLite datetime modifier for `days` days before now
+fn days_ago(days: u32) -> String {
+    format!("-{days} days")
+}
+
+/// Whether a process that completed at `completed_at` is past the `drop_raw_after` days
+/// cut-off. Processes that never completed are kept.
+fn raw_output_expired(
+    completed_at: Option<DateTime<Utc>>,
+    drop_raw_after: u32,
+    now: DateTime<Utc>,
+) -> bool {
+    let cutoff = now - chrono::Duration::days(i64::from(drop_raw_after));
+    completed_at.is_some_and(|at| at < cutoff)
+}
+
+fn archive_dir() -> PathBuf {
+    asset_dir().join("log_archives")
+}
+
+fn archive_file_name(execution_id: Uuid) -> String {
+    format!("{execution_id}{ARCHIVE_FILE_SUFFIX}")
+}
+
+/// Whether `name` is an archive file written by this service, `<execution id>.jsonl.zst`
+fn is_archive_file_name(name: &str) -> bool {
+    name.strip_suffix(ARCHIVE_FILE_SUFFIX)
+        .is_some_and(|id| Uuid::parse_str(id).is_ok())
+}
+
+/// Delete archive files whose execution process no longer exists
+async fn remove_orphaned_files(pool: &sqlx::SqlitePool) -> Result<usize, SqlxError> {
+    let known: HashSet<PathBuf> = ExecutionProcessLogArchive::find_file_paths(pool)
+        .await?
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+    Ok(remove_orphaned_files_in(&archive_dir(), &known).await)
+}
+
+/// Delete archive files in `dir` that are not in `known`. Files that do not follow the
+/// archive naming scheme are left alone.
+async fn remove_orphaned_files_in(dir: &Path, known: &HashSet<PathBuf>) -> usize {
+    let Ok(mut entries) = fs::read_dir(dir).await else {
+        return 0;
+    };
+
+    let mut removed = 0;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let is_archive = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(is_archive_file_name);
+        let is_file = entry.file_type().await.is_ok_and(|t| t.is_file());
+        if !is_archive || !is_file || known.contains(&path) {
+            continue;
+        }
+        match fs::remove_file(&path).await {
+            Ok(()) => removed += 1,
+            Err(e) => warn!(
+                "Failed to remove orphaned log archive {}: {}",
+                path.display(),
+                e
+            ),
+        }
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use db::{
+        models::{
+            execution_process::{CreateExecutionProcess, ExecutionProcessStatus},
+            session::{CreateSession, Session},
+        },
+        test_utils::test_pool,
+    };
+    use executors::actions::{
+        ExecutorAction, ExecutorActionType,
+        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+    };
+    use sqlx::SqlitePool;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// A process with one line of output, completed `completed_days_ago` or still running
+    async fn insert_process(pool: &SqlitePool, completed_days_ago: Option<i64>) -> Uuid {
+        let session = Session::create(
+            pool,
+            &CreateSession { executor: None },
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let action = ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: "make".to_string(),
+                language: ScriptRequestLanguage::Bash,
+                context: ScriptContext::SetupScript,
+                working_dir: None,
+                dev_process: None,
+            }),
+            None,
+        );
+        let process = ExecutionProcess::create(
+            pool,
+            &CreateExecutionProcess {
+                session_id: session.id,
+                executor_action: action,
+                run_reason: ExecutionProcessRunReason::SetupScript,
+            },
+            Uuid::new_v4(),
+            &[],
+        )
+        .await
+        .unwrap();
+        ExecutionProcessLogs::append_log_line(pool, process.id, "{\"Stdout\":\"hello\"}\n")
+            .await
+            .unwrap();
+
+        if let Some(days) = completed_days_ago {
+            ExecutionProcess::update_completion(
+                pool,
+                process.id,
+                ExecutionProcessStatus::Completed,
+                Some(0),
+            )
+            .await
+            .unwrap();
+            // Completion is stamped with the current time; move it back to age the process
+            sqlx::query(
+                "UPDATE execution_processes SET completed_at = datetime(completed_at, ?) WHERE id = ?",
+            )
+            .bind(format!("-{days} days"))
+            .bind(process.id)
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+        process.id
+    }
+
+    fn sample_jsonl() -> String {
+        (0..200)
+            .map(|i| format!("{{\"Stdout\":\"line {i} of a fairly repetitive build log\"}}\n"))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn compressed_archive_round_trips_and_replaces_log_rows() {
+        let pool = test_pool().await;
+        let id = insert_process(&pool, Some(5)).await;
+        let jsonl = sample_jsonl();
+
+        let stored_bytes =
+            ExecutionProcessLogArchive::store(&pool, id, LogArchiveContents::Raw, &jsonl)
+                .await
+                .unwrap();
+        assert!((stored_bytes as usize) < jsonl.len());
+
+        let records = ExecutionProcessLogs::find_by_execution_id(&pool, id)
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1, "uncompressed rows are removed");
+        assert_eq!(records[0].logs, jsonl);
+        assert_eq!(records[0].byte_size, jsonl.len() as i64);
+    }
+
+    #[tokio::test]
+    async fn archive_moved_to_a_file_round_trips() {
+        let dir = TempDir::new().unwrap();
+        let pool = test_pool().await;
+        let id = insert_process(&pool, Some(5)).await;
+        let jsonl = sample_jsonl();
+        ExecutionProcessLogArchive::store(&pool, id, LogArchiveContents::Raw, &jsonl)
+            .await
+            .unwrap();
+
+        let archive = ExecutionProcessLogArchive::find_by_execution_id(&pool, id)
+            .await
+            .unwrap()
+            .unwrap();
+        let path = dir.path().join("log_archives").join(archive_file_name(id));
+        archive.move_to_file(&pool, &path).await.unwrap();
+
+        let moved = ExecutionProcessLogArchive::find_by_execution_id(&pool, id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(moved.data.is_none());
+        assert_eq!(moved.file_path.as_deref(), Some(&*path.to_string_lossy()));
+        assert_eq!(moved.read_jsonl().unwrap(), jsonl);
+    }
+
+    #[tokio::test]
+    async fn only_processes_finished_before_the_cutoff_are_compacted() {
+        let pool = test_pool().await;
+        let old = insert_process(&pool, Some(5)).await;
+        let recent = insert_process(&pool, Some(1)).await;
+        let running = insert_process(&pool, None).await;
+
+        let candidates = ExecutionProcessLogArchive::find_uncompacted(&pool, &days_ago(2), 100)
+            .await
+            .unwrap();
+        let ids: Vec<Uuid> = candidates.iter().map(|c| c.execution_id).collect();
+        assert_eq!(ids, vec![old]);
+        assert!(!ids.contains(&recent) && !ids.contains(&running));
+    }
+
+    #[test]
+    fn raw_output_expires_only_past_the_cutoff() {
+        let now = Utc::now();
+        let days = |n: i64| Some(now - chrono::Duration::days(n));
+
+        assert!(raw_output_expired(days(31), 30, now));
+        assert!(!raw_output_expired(days(29), 30, now));
+        assert!(!raw_output_expired(None, 30, now));
+        assert!(raw_output_expired(days(1), 0, now));
+    }
+
+    #[test]
+    fn default_policy_keeps_logs_untouched() {
+        let policy = LogRetentionConfig::default();
+        assert!(!policy.enabled);
+        assert_eq!(policy.drop_raw_after_days, None);
+    }
+
+    #[tokio::test]
+    async fn removes_only_orphaned_archive_files() {
+        let dir = TempDir::new().unwrap();
+        let known = dir.path().join(archive_file_name(Uuid::new_v4()));
+        let orphan = dir.path().join(archive_file_name(Uuid::new_v4()));
+        let unrelated = [
+            dir.path().join("notes.txt"),
+            dir.path().join("backup.jsonl.zst"),
+            dir.path().join(format!("{}.jsonl", Uuid::new_v4())),
+        ];
+        for path in [&known, &orphan].into_iter().chain(unrelated.iter()) {
+            std::fs::write(path, b"data").unwrap();
+        }
+
+        let removed = remove_orphaned_files_in(dir.path(), &HashSet::from([known.clone()])).await;
+
+        assert_eq!(removed, 1);
+        assert!(known.exists());
+        assert!(!orphan.exists());
+        assert!(unrelated.iter().all(|path| path.exists()));
+    }
+
+    #[tokio::test]
+    async fn missing_archive_dir_removes_nothing() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(
+            remove_orphaned_files_in(&dir.path().join("missing"), &HashSet::new()).await,
+            0
+        );
+    }
+}
@@ -4,6 +4,8 @@ pub mod auth;
 pub mod categorization;
 pub mod config;
 pub mod container;
+pub mod db_backup;
+pub mod dev_processes;
 pub mod diff_stream;
 pub mod events;
 pub mod file_ranker;
@@ -12,9 +14,12 @@ pub mod filesystem;
 pub mod filesystem_watcher;
 pub mod git;
 pub mod git_host;
+pub mod handoff;
 pub mod image;
+pub mod log_compaction;
 pub mod notification;
 pub mod oauth_credentials;
+pub mod plan;
 pub mod pr_monitor;
 pub mod project;
 #[cfg(feature = "qa-mode")]
@@ -22,7 +27,12 @@ pub mod qa_repos;
 pub mod queued_message;
 pub mod remote_client;
 pub mod repo;
+pub mod resource_usage;
+pub mod secrets;
 pub mod share;
 pub mod task_deduplication;
+pub mod terminal;
+pub mod transcript;
 pub mod workspace_manager;
+pub mod worktree_gc;
 pub mod worktree_manager;
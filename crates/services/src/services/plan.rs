@@ -0,0 +1,99 @@
+//! Prompts and plan extraction for plan-then-execute workspaces.
+//!
+//! A planning run drafts a plan for the task. The plan is stored on the task as a
+//! [`db::models::task_plan::TaskPlan`] and the execution step of the action chain only starts
+//! once the user has approved it, with the (possibly edited) plan injected into its prompt.
+
+use executors::logs::{ActionType, NormalizedEntry, NormalizedEntryType};
+
+const PLANNING_INSTRUCTIONS: &str = "Do not modify any files yet. Investigate the codebase and reply with a step-by-step implementation plan for the task. The plan will be reviewed, possibly edited, and then handed to an agent to implement.";
+
+pub fn planning_prompt(prompt: &str) -> String {
+    format!("{}\n\n{PLANNING_INSTRUCTIONS}", prompt.trim_end())
+}
+
+pub fn execution_prompt(prompt: &str, plan: &str) -> String {
+    format!(
+        "{}\n\nImplement the task following this approved plan:\n\n{}",
+        prompt.trim_end(),
+        plan.trim()
+    )
+}
+
+/// The plan presented by a planning run: its last plan presentation, or failing that the
+/// agent's last message
+pub fn plan_from_entries(entries: &[NormalizedEntry]) -> Option<String> {
+    let presented = entries
+        .iter()
+        .rev()
+        .find_map(|entry| match &entry.entry_type {
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::PlanPresentation { plan },
+                ..
+            } => Some(plan),
+            _ => None,
+        });
+    presented
+        .or_else(|| {
+            entries.iter().rev().find_map(|entry| {
+                matches!(entry.entry_type, NormalizedEntryType::AssistantMessage)
+                    .then_some(&entry.content)
+            })
+        })
+        .map(|plan| plan.trim().to_string())
+        .filter(|plan| !plan.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::ToolStatus;
+
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn prefers_presented_plan_over_messages() {
+        let entries = vec![
+            entry(
+                NormalizedEntryType::ToolUse {
+                    tool_name: "ExitPlanMode".to_string(),
+                    action_type: ActionType::PlanPresentation {
+                        plan: "1. Add limiter\n2. Test it\n".to_string(),
+                    },
+                    status: ToolStatus::Success,
+                },
+                "",
+            ),
+            entry(
+                NormalizedEntryType::AssistantMessage,
+                "Waiting for approval.",
+            ),
+        ];
+        assert_eq!(
+            plan_from_entries(&entries).as_deref(),
+            Some("1. Add limiter\n2. Test it")
+        );
+    }
+
+    #[test]
+    fn falls_back_to_last_assistant_message() {
+        let entries = vec![
+            entry(NormalizedEntryType::AssistantMessage, "Looking around."),
+            entry(NormalizedEntryType::AssistantMessage, "Plan: edit auth.rs"),
+            entry(NormalizedEntryType::SystemMessage, "done"),
+        ];
+        assert_eq!(
+            plan_from_entries(&entries).as_deref(),
+            Some("Plan: edit auth.rs")
+        );
+        assert_eq!(plan_from_entries(&[]), None);
+    }
+}
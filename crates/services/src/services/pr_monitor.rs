@@ -5,6 +5,7 @@ use db::{
     models::{
         execution_process::{ExecutionProcess, ExecutionProcessError, ExecutionProcessRunReason},
         merge::{CiStatus, Merge, MergeStatus, PrMerge},
+        project::Project,
         repo::Repo,
         session::{CreateSession, Session, SessionError},
         task::{Task, TaskStatus},
@@ -225,43 +226,23 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
                     "PR #{} was merged, updating task {} to done and archiving workspace",
                     pr_merge.pr_info.number, workspace.task_id
                 );
-                Task::update_status(&self.db.pool, workspace.task_id, TaskStatus::Done).await?;
-
-                // Archive workspace unless pinned
-                if !workspace.pinned {
-                    Workspace::set_archived(&self.db.pool, workspace.id, true).await?;
-                }
-
-                // Track analytics event
-                if let Some(analytics) = &self.analytics
-                    && let Ok(Some(task)) = Task::find_by_id(&self.db.pool, workspace.task_id).await
-                {
-                    analytics.analytics_service.track_event(
-                        &analytics.user_id,
-                        "pr_merged",
-                        Some(json!({
-                            "task_id": workspace.task_id.to_string(),
-                            "workspace_id": workspace.id.to_string(),
-                            "project_id": task.project_id.to_string(),
-                        })),
-                    );
-                }
-
-                if let Some(publisher) = &self.publisher
-                    && let Err(err) = publisher.update_shared_task_by_id(workspace.task_id).await
-                {
-                    tracing::warn!(
-                        ?err,
-                        "Failed to propagate shared task update for {}",
-                        workspace.task_id
-                    );
-                }
+                self.handle_pr_merged(&workspace, "pr_merged").await?;
             }
         } else if ci_status_changed {
             // Only CI status changed, update just that
             Merge::update_ci_status(&self.db.pool, pr_merge.id, ci_status).await?;
         }
 
+        // Merge automatically once CI is green and reviews are satisfied
+        if matches!(&pr_status.status, MergeStatus::Open) && matches!(ci_status, CiStatus::Passing)
+        {
+            match self.check_and_auto_merge(pr_merge).await {
+                Ok(true) => return Ok(()),
+                Ok(false) => {}
+                Err(e) => warn!("Error auto-merging PR #{}: {}", pr_merge.pr_info.number, e),
+            }
+        }
+
         // For open PRs, check for merge conflicts and attempt auto-resolution
         if matches!(&pr_status.status, MergeStatus::Open) {
             if let Err(e) = self.check_and_resolve_conflicts(pr_merge).await {
@@ -285,6 +266,162 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
         Ok(())
     }
 
+    /// Move the task to done and archive the workspace after one of its PRs was merged
+    async fn handle_pr_merged(
+        &self,
+        workspace: &Workspace,
+        analytics_event: &str,
+    ) -> Result<(), PrMonitorError> {
+        Task::update_status(&self.db.pool, workspace.task_id, TaskStatus::Done).await?;
+
+        // Archive workspace unless pinned
+        if !workspace.pinned {
+            Workspace::set_archived(&self.db.pool, workspace.id, true).await?;
+        }
+
+        // Track analytics event
+        if let Some(analytics) = &self.analytics
+            && let Ok(Some(task)) = Task::find_by_id(&self.db.pool, workspace.task_id).await
+        {
+            analytics.analytics_service.track_event(
+                &analytics.user_id,
+                analytics_event,
+                Some(json!({
+                    "task_id": workspace.task_id.to_string(),
+                    "workspace_id": workspace.id.to_string(),
+                    "project_id": task.project_id.to_string(),
+                })),
+            );
+        }
+
+        if let Some(publisher) = &self.publisher
+            && let Err(err) = publisher.update_shared_task_by_id(workspace.task_id).await
+        {
+            tracing::warn!(
+                ?err,
+                "Failed to propagate shared task update for {}",
+                workspace.task_id
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Merge a PR whose workspace opted into auto-merge once CI passes and the
+    /// required reviews are present. Returns true if the PR was merged.
+    async fn check_and_auto_merge(&self, pr_merge: &PrMerge) -> Result<bool, PrMonitorError> {
+        let Some(workspace) = Workspace::find_by_id(&self.db.pool, pr_merge.workspace_id).await?
+        else {
+            return Ok(false);
+        };
+
+        if !workspace.auto_merge || workspace.archived {
+            return Ok(false);
+        }
+
+        let Some(task) = Task::find_by_id(&self.db.pool, workspace.task_id).await? else {
+            return Ok(false);
+        };
+
+        // Only merge work that is waiting for review, never while an agent is still running
+        if task.status != TaskStatus::InReview {
+            debug!(
+                "Skipping auto-merge for task {} (status: {:?})",
+                task.id, task.status
+            );
+            return Ok(false);
+        }
+
+        if self
+            .container
+            .has_running_processes(task.id)
+            .await
+            .map_err(|e| PrMonitorError::Container(e.to_string()))?
+        {
+            debug!(
+                "Skipping auto-merge for workspace {} - execution in progress",
+                workspace.id
+            );
+            return Ok(false);
+        }
+
+        let git_host = git_host::GitHostService::from_url(&pr_merge.pr_info.url)?;
+        let review_decision = git_host.get_review_decision(&pr_merge.pr_info.url).await?;
+        if !review_decision.is_satisfied() {
+            debug!(
+                "Skipping auto-merge for PR #{} - reviews not satisfied ({:?})",
+                pr_merge.pr_info.number, review_decision
+            );
+            return Ok(false);
+        }
+
+        let merge_method = Project::find_by_id(&self.db.pool, task.project_id)
+            .await?
+            .map(|project| project.pr_merge_method)
+            .unwrap_or_default();
+
+        info!(
+            "Auto-merging PR #{} for workspace {} ({:?})",
+            pr_merge.pr_info.number, workspace.id, merge_method
+        );
+        git_host
+            .merge_pr(&pr_merge.pr_info.url, merge_method)
+            .await?;
+
+        // Record the merge commit if the host already reports it
+        let merge_commit_sha = match git_host.get_pr_status(&pr_merge.pr_info.url).await {
+            Ok(status) => status.merge_commit_sha,
+            Err(e) => {
+                debug!(
+                    "Failed to fetch merge commit for PR #{}: {}",
+                    pr_merge.pr_info.number, e
+                );
+                None
+            }
+        };
+        Merge::update_status(
+            &self.db.pool,
+            pr_merge.id,
+            MergeStatus::Merged,
+            merge_commit_sha,
+            CiStatus::Passing,
+        )
+        .await?;
+
+        self.handle_pr_merged(&workspace, "pr_auto_merged").await?;
+
+        // Clean up the worktree once no other PRs of this workspace remain open, unless it is
+        // pinned. The workspace can still be restored from its branch later.
+        let has_open_prs = Merge::find_by_workspace_id(&self.db.pool, workspace.id)
+            .await?
+            .iter()
+            .any(|merge| {
+                matches!(merge, Merge::Pr(pr) if matches!(pr.pr_info.status, MergeStatus::Open))
+            });
+        if !workspace.pinned
+            && !has_open_prs
+            && let Err(e) = self.container.delete(&workspace).await
+        {
+            warn!(
+                "Failed to clean up worktree for auto-merged workspace {}: {}",
+                workspace.id, e
+            );
+        }
+
+        self.container
+            .notification_service()
+            .notify(
+                &format!("PR Merged: {}", task.title),
+                &format!(
+                    "🚀 PR #{} for '{}' was merged automatically after CI passed",
+                    pr_merge.pr_info.number, task.title
+                ),
+            )
+            .await;
+
+        Ok(true)
+    }
+
     /// Check if a PR has merge conflicts and attempt to resolve them
     async fn check_and_resolve_conflicts(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
         // Get the workspace for this PR
@@ -1,24 +1,18 @@
 use std::sync::Arc;
 
-use chrono::{DateTime, Utc};
+use chrono::Utc;
 use dashmap::DashMap;
-use db::models::scratch::DraftFollowUpData;
+use db::models::{
+    queued_message::QueuedMessage,
+    scratch::{
+        DraftFollowUpData, Scratch, ScratchError, ScratchPayload, ScratchType, UpdateScratch,
+    },
+};
 use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, types::Json};
 use ts_rs::TS;
 use uuid::Uuid;
 
-/// Represents a queued follow-up message for a session
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
-#[ts(export)]
-pub struct QueuedMessage {
-    /// The session this message is queued for
-    pub session_id: Uuid,
-    /// The follow-up data (message + variant)
-    pub data: DraftFollowUpData,
-    /// Timestamp when the message was queued
-    pub queued_at: DateTime<Utc>,
-}
-
 /// Status of the queue for a session (for frontend display)
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "status", rename_all = "snake_case")]
@@ -30,34 +24,76 @@ pub enum QueueStatus {
     Queued { message: QueuedMessage },
 }
 
-/// In-memory service for managing queued follow-up messages.
-/// One queued message per session.
+/// Service for managing queued follow-up messages.
+/// One queued message per session. Messages are kept in memory and written through to the
+/// database so they survive a restart, see [`Self::load`].
 #[derive(Clone)]
 pub struct QueuedMessageService {
+    pool: SqlitePool,
     queue: Arc<DashMap<Uuid, QueuedMessage>>,
 }
 
 impl QueuedMessageService {
-    pub fn new() -> Self {
+    pub fn new(pool: SqlitePool) -> Self {
         Self {
+            pool,
             queue: Arc::new(DashMap::new()),
         }
     }
 
+    /// Restore the messages queued before the last shutdown
+    pub async fn load(&self) -> Result<(), sqlx::Error> {
+        for message in QueuedMessage::find_all(&self.pool).await? {
+            self.queue.insert(message.session_id, message);
+        }
+        Ok(())
+    }
+
     /// Queue a message for a session. Replaces any existing queued message.
-    pub fn queue_message(&self, session_id: Uuid, data: DraftFollowUpData) -> QueuedMessage {
+    pub async fn queue_message(
+        &self,
+        session_id: Uuid,
+        data: DraftFollowUpData,
+    ) -> Result<QueuedMessage, sqlx::Error> {
         let queued = QueuedMessage {
             session_id,
-            data,
+            data: Json(data),
             queued_at: Utc::now(),
         };
+        QueuedMessage::upsert(&self.pool, &queued).await?;
         self.queue.insert(session_id, queued.clone());
-        queued
+        Ok(queued)
     }
 
     /// Cancel/remove a queued message for a session
-    pub fn cancel_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    pub async fn cancel_queued(
+        &self,
+        session_id: Uuid,
+    ) -> Result<Option<QueuedMessage>, sqlx::Error> {
+        QueuedMessage::delete(&self.pool, session_id).await?;
+        Ok(self.queue.remove(&session_id).map(|(_, v)| v))
+    }
+
+    /// Move the queued message of a session back into its follow-up draft, so it is not lost
+    /// when the turn it waited for will never finish. The draft is written before the message
+    /// leaves the queue.
+    pub async fn return_to_draft(
+        &self,
+        session_id: Uuid,
+    ) -> Result<Option<QueuedMessage>, ScratchError> {
+        let Some(queued) = self.get_queued(session_id) else {
+            return Ok(None);
+        };
+        Scratch::update(
+            &self.pool,
+            session_id,
+            &ScratchType::DraftFollowUp,
+            &UpdateScratch {
+                payload: ScratchPayload::DraftFollowUp(queued.data.0.clone()),
+            },
+        )
+        .await?;
+        Ok(self.cancel_queued(session_id).await?)
     }
 
     /// Get the queued message for a session (if any)
@@ -67,8 +103,11 @@ impl QueuedMessageService {
 
     /// Take (remove and return) the queued message for a session.
     /// Used by finalization flow to consume the queued message.
-    pub fn take_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    pub async fn take_queued(
+        &self,
+        session_id: Uuid,
+    ) -> Result<Option<QueuedMessage>, sqlx::Error> {
+        self.cancel_queued(session_id).await
     }
 
     /// Check if a session has a queued message
@@ -85,8 +124,56 @@ impl QueuedMessageService {
     }
 }
 
-impl Default for QueuedMessageService {
-    fn default() -> Self {
-        Self::new()
+#[cfg(test)]
+mod tests {
+    use db::test_utils::test_pool;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn return_to_draft_keeps_the_message() {
+        let pool = test_pool().await;
+        let service = QueuedMessageService::new(pool.clone());
+        let session_id = Uuid::new_v4();
+        service
+            .queue_message(
+                session_id,
+                DraftFollowUpData {
+                    message: "run the tests".to_string(),
+                    variant: Some("PLAN".to_string()),
+                },
+            )
+            .await
+            .unwrap();
+
+        let returned = service.return_to_draft(session_id).await.unwrap();
+        assert!(returned.is_some());
+        assert!(!service.has_queued(session_id));
+        assert!(QueuedMessage::find_all(&pool).await.unwrap().is_empty());
+
+        let draft = Scratch::find_by_id(&pool, session_id, &ScratchType::DraftFollowUp)
+            .await
+            .unwrap()
+            .unwrap();
+        let ScratchPayload::DraftFollowUp(data) = draft.payload else {
+            panic!("expected a follow-up draft");
+        };
+        assert_eq!(data.message, "run the tests");
+        assert_eq!(data.variant.as_deref(), Some("PLAN"));
+    }
+
+    #[tokio::test]
+    async fn return_to_draft_without_a_queued_message_leaves_the_draft() {
+        let pool = test_pool().await;
+        let service = QueuedMessageService::new(pool.clone());
+        let session_id = Uuid::new_v4();
+
+        assert!(service.return_to_draft(session_id).await.unwrap().is_none());
+        assert!(
+            Scratch::find_by_id(&pool, session_id, &ScratchType::DraftFollowUp)
+                .await
+                .unwrap()
+                .is_none()
+        );
     }
 }
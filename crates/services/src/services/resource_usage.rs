@@ -0,0 +1,137 @@
+//! Live CPU and memory usage of running execution processes.
+//!
+//! The container samples its process groups and records the results here; the latest sample
+//! of each process is served by the execution process API and streamed to the clients
+//! watching its session.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use futures::{StreamExt, stream::BoxStream};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use ts_rs::TS;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+use crate::services::events::patches::resource_usage_patch;
+
+/// Latest resource sample of a running execution process
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+pub struct ResourceUsage {
+    pub execution_process_id: Uuid,
+    pub session_id: Uuid,
+    /// Resident memory of the process and everything it spawned, in bytes
+    #[ts(type = "number")]
+    pub rss_bytes: u64,
+    /// CPU use since the previous sample; 100 is one full core
+    pub cpu_percent: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum UsageUpdate {
+    Sampled(ResourceUsage),
+    Exited {
+        execution_process_id: Uuid,
+        session_id: Uuid,
+    },
+}
+
+#[derive(Clone)]
+pub struct ResourceMonitor {
+    samples: Arc<RwLock<HashMap<Uuid, ResourceUsage>>>,
+    updates: broadcast::Sender<UsageUpdate>,
+}
+
+impl Default for ResourceMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResourceMonitor {
+    pub fn new() -> Self {
+        let (updates, _) = broadcast::channel(1024);
+        Self {
+            samples: Arc::new(RwLock::new(HashMap::new())),
+            updates,
+        }
+    }
+
+    pub fn record(&self, usage: ResourceUsage) {
+        self.samples
+            .write()
+            .unwrap()
+            .insert(usage.execution_process_id, usage);
+        let _ = self.updates.send(UsageUpdate::Sampled(usage));
+    }
+
+    /// Forget a process that is no longer running
+    pub fn remove(&self, execution_process_id: &Uuid) {
+        let removed = self.samples.write().unwrap().remove(execution_process_id);
+        if let Some(usage) = removed {
+            let _ = self.updates.send(UsageUpdate::Exited {
+                execution_process_id: usage.execution_process_id,
+                session_id: usage.session_id,
+            });
+        }
+    }
+
+    pub fn get(&self, execution_process_id: &Uuid) -> Option<ResourceUsage> {
+        self.samples
+            .read()
+            .unwrap()
+            .get(execution_process_id)
+            .copied()
+    }
+
+    /// Samples of the session's running processes as a `/resource_usage` snapshot, then a
+    /// patch for every new sample and exit
+    pub fn stream_for_session(
+        &self,
+        session_id: Uuid,
+    ) -> BoxStream<'static, Result<LogMsg, std::io::Error>> {
+        // Subscribe before taking the snapshot so that no sample falls in between
+        let updates = BroadcastStream::new(self.updates.subscribe());
+        let snapshot: serde_json::Map<String, serde_json::Value> = self
+            .samples
+            .read()
+            .unwrap()
+            .values()
+            .filter(|usage| usage.session_id == session_id)
+            .map(|usage| {
+                (
+                    usage.execution_process_id.to_string(),
+                    serde_json::to_value(usage).unwrap(),
+                )
+            })
+            .collect();
+        let initial_patch = serde_json::json!([{
+            "op": "add",
+            "path": "/resource_usage",
+            "value": snapshot
+        }]);
+        let initial_msg = LogMsg::JsonPatch(serde_json::from_value(initial_patch).unwrap());
+
+        let live = updates.filter_map(move |update| async move {
+            let patch = match update.ok()? {
+                UsageUpdate::Sampled(usage) if usage.session_id == session_id => {
+                    resource_usage_patch::add(&usage)
+                }
+                UsageUpdate::Exited {
+                    execution_process_id,
+                    session_id: exited_session_id,
+                } if exited_session_id == session_id => {
+                    resource_usage_patch::remove(execution_process_id)
+                }
+                _ => return None,
+            };
+            Some(Ok(LogMsg::JsonPatch(patch)))
+        });
+
+        futures::stream::iter([Ok(initial_msg)]).chain(live).boxed()
+    }
+}
@@ -0,0 +1,613 @@
+use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::Arc};
+
+use age::x25519::Identity;
+use chrono::{DateTime, Utc};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::RwLock;
+use ts_rs::TS;
+use utils::redact::SecretRedactor;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum SecretsError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Failed to encrypt secrets: {0}")]
+    Encrypt(String),
+    #[error("Failed to decrypt secrets: {0}")]
+    Decrypt(String),
+    #[error("Invalid secrets key: {0}")]
+    InvalidKey(String),
+    #[error("The secrets vault could not be unlocked; see the server log")]
+    Locked,
+    #[error("Secret not found")]
+    NotFound,
+    #[error("A secret named {0} already exists in this scope")]
+    DuplicateName(String),
+    #[error(
+        "Invalid secret name '{0}': use letters, digits and underscores, not starting with a digit"
+    )]
+    InvalidName(String),
+}
+
+/// A stored secret without its value. Values are write-only over the API.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct Secret {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// Only injected into workspaces containing this repository; `None` for the whole project
+    pub repo_id: Option<Uuid>,
+    /// Environment variable the value is exposed as
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateSecret {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub repo_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateSecret {
+    pub value: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct StoredSecret {
+    #[serde(flatten)]
+    secret: Secret,
+    value: String,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Vault {
+    secrets: Vec<StoredSecret>,
+}
+
+/// Per-project secrets, kept in an age-encrypted file. The age identity lives in the OS
+/// keyring (macOS keychain, Secret Service on Linux, Windows Credential Manager) and only falls
+/// back to a private key file next to the vault when no keyring is available.
+///
+/// Secrets are injected as environment variables into every process started for a
+/// workspace, and [`SecretsService::redactor_for`] masks their values in its logs.
+#[derive(Clone)]
+pub struct SecretsService {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    path: PathBuf,
+    key: KeyBackend,
+    /// `None` when an existing vault could not be decrypted; writes are refused so the
+    /// vault is never overwritten with a fresh key
+    vault: RwLock<Option<Vault>>,
+}
+
+impl SecretsService {
+    pub fn new(path: PathBuf, key_path: PathBuf) -> Self {
+        Self::with_key_backend(path, KeyBackend::detect(key_path))
+    }
+
+    fn with_key_backend(path: PathBuf, key: KeyBackend) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                path,
+                key,
+                vault: RwLock::new(Some(Vault::default())),
+            }),
+        }
+    }
+
+    pub async fn load(&self) -> Result<(), SecretsError> {
+        let inner = self.inner.clone();
+        let loaded = blocking(move || inner.read_vault()).await;
+        let mut vault = self.inner.vault.write().await;
+        match loaded {
+            Ok(loaded) => {
+                *vault = Some(loaded);
+                Ok(())
+            }
+            Err(e) => {
+                *vault = None;
+                Err(e)
+            }
+        }
+    }
+
+    pub async fn list(&self, project_id: Uuid) -> Result<Vec<Secret>, SecretsError> {
+        let vault = self.inner.vault.read().await;
+        let vault = vault.as_ref().ok_or(SecretsError::Locked)?;
+        let mut secrets: Vec<Secret> = vault
+            .secrets
+            .iter()
+            .filter(|s| s.secret.project_id == project_id)
+            .map(|s| s.secret.clone())
+            .collect();
+        secrets.sort_by(|a, b| (a.repo_id, &a.name).cmp(&(b.repo_id, &b.name)));
+        Ok(secrets)
+    }
+
+    pub async fn create(
+        &self,
+        project_id: Uuid,
+        data: &CreateSecret,
+    ) -> Result<Secret, SecretsError> {
+        let name = data.name.trim();
+        if !is_valid_name(name) {
+            return Err(SecretsError::InvalidName(name.to_string()));
+        }
+
+        let mut guard = self.inner.vault.write().await;
+        let vault = guard.as_mut().ok_or(SecretsError::Locked)?;
+        if vault.secrets.iter().any(|s| {
+            s.secret.project_id == project_id
+                && s.secret.repo_id == data.repo_id
+                && s.secret.name == name
+        }) {
+            return Err(SecretsError::DuplicateName(name.to_string()));
+        }
+
+        let now = Utc::now();
+        let secret = Secret {
+            id: Uuid::new_v4(),
+            project_id,
+            repo_id: data.repo_id,
+            name: name.to_string(),
+            created_at: now,
+            updated_at: now,
+        };
+        vault.secrets.push(StoredSecret {
+            secret: secret.clone(),
+            value: data.value.clone(),
+        });
+        if let Err(e) = self.write_vault(vault).await {
+            vault.secrets.pop();
+            return Err(e);
+        }
+        Ok(secret)
+    }
+
+    pub async fn update(
+        &self,
+        project_id: Uuid,
+        id: Uuid,
+        data: &UpdateSecret,
+    ) -> Result<Secret, SecretsError> {
+        let mut guard = self.inner.vault.write().await;
+        let vault = guard.as_mut().ok_or(SecretsError::Locked)?;
+        let index = vault.position(project_id, id)?;
+
+        let previous = vault.secrets[index].clone();
+        let stored = &mut vault.secrets[index];
+        stored.value = data.value.clone();
+        stored.secret.updated_at = Utc::now();
+        let secret = stored.secret.clone();
+        if let Err(e) = self.write_vault(vault).await {
+            vault.secrets[index] = previous;
+            return Err(e);
+        }
+        Ok(secret)
+    }
+
+    pub async fn delete(&self, project_id: Uuid, id: Uuid) -> Result<(), SecretsError> {
+        let mut guard = self.inner.vault.write().await;
+        let vault = guard.as_mut().ok_or(SecretsError::Locked)?;
+        let index = vault.position(project_id, id)?;
+        let removed = vault.secrets.remove(index);
+        if let Err(e) = self.write_vault(vault).await {
+            vault.secrets.insert(index, removed);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Drop every secret of a deleted project
+    pub async fn delete_for_project(&self, project_id: Uuid) -> Result<usize, SecretsError> {
+        let mut guard = self.inner.vault.write().await;
+        let vault = guard.as_mut().ok_or(SecretsError::Locked)?;
+        let before = vault.secrets.len();
+        vault.secrets.retain(|s| s.secret.project_id != project_id);
+        let removed = before - vault.secrets.len();
+        if removed > 0 {
+            self.write_vault(vault).await?;
+        }
+        Ok(removed)
+    }
+
+    /// Environment for a workspace of `project_id` containing `repo_ids`. Repository secrets
+    /// override project-wide ones of the same name.
+    pub async fn env_for(
+        &self,
+        project_id: Uuid,
+        repo_ids: &[Uuid],
+    ) -> Result<HashMap<String, String>, SecretsError> {
+        let vault = self.inner.vault.read().await;
+        let vault = vault.as_ref().ok_or(SecretsError::Locked)?;
+        let scoped = vault
+            .secrets
+            .iter()
+            .filter(|s| s.secret.project_id == project_id);
+
+        let mut env = HashMap::new();
+        for stored in scoped.clone().filter(|s| s.secret.repo_id.is_none()) {
+            env.insert(stored.secret.name.clone(), stored.value.clone());
+        }
+        for stored in scoped.filter(|s| s.secret.repo_id.is_some_and(|id| repo_ids.contains(&id))) {
+            env.insert(stored.secret.name.clone(), stored.value.clone());
+        }
+        Ok(env)
+    }
+
+    /// Redactor masking every secret value of `project_id`, whichever repository it is
+    /// scoped to
+    pub async fn redactor_for(&self, project_id: Uuid) -> SecretRedactor {
+        let vault = self.inner.vault.read().await;
+        let values = vault.iter().flat_map(|vault| {
+            vault
+                .secrets
+                .iter()
+                .filter(|s| s.secret.project_id == project_id)
+                .map(|s| s.value.clone())
+        });
+        SecretRedactor::new(values)
+    }
+
+    /// Encrypt and persist `vault`. Callers hold the write lock across the await, so writes
+    /// land in order.
+    async fn write_vault(&self, vault: &Vault) -> Result<(), SecretsError> {
+        let plaintext = serde_json::to_vec(vault)?;
+        let inner = self.inner.clone();
+        blocking(move || inner.write_vault(&plaintext)).await
+    }
+}
+
+impl Vault {
+    /// Index of secret `id`, which must belong to `project_id`
+    fn position(&self, project_id: Uuid, id: Uuid) -> Result<usize, SecretsError> {
+        self.secrets
+            .iter()
+            .position(|s| s.secret.id == id && s.secret.project_id == project_id)
+            .ok_or(SecretsError::NotFound)
+    }
+}
+
+/// File and keyring access. These block, so they only run through [`blocking`].
+impl Inner {
+    fn read_vault(&self) -> Result<Vault, SecretsError> {
+        if !self.path.exists() {
+            return Ok(Vault::default());
+        }
+        let identity = self.identity(false)?.ok_or_else(|| {
+            SecretsError::InvalidKey("the vault exists but its key is missing".into())
+        })?;
+        let ciphertext = std::fs::read(&self.path)?;
+        let plaintext = age::decrypt(&identity, &ciphertext)
+            .map_err(|e| SecretsError::Decrypt(e.to_string()))?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    fn write_vault(&self, plaintext: &[u8]) -> Result<(), SecretsError> {
+        let identity = self
+            .identity(true)?
+            .ok_or_else(|| SecretsError::InvalidKey("no key available".into()))?;
+        let ciphertext = age::encrypt(&identity.to_public(), plaintext)
+            .map_err(|e| SecretsError::Encrypt(e.to_string()))?;
+        write_private_file(&self.path, &ciphertext)?;
+        Ok(())
+    }
+
+    /// The vault's age identity, generated and stored on first write
+    fn identity(&self, create: bool) -> Result<Option<Identity>, SecretsError> {
+        if let Some(key) = self.key.load()? {
+            return Identity::from_str(key.trim())
+                .map(Some)
+                .map_err(|e| SecretsError::InvalidKey(e.to_string()));
+        }
+        if !create {
+            return Ok(None);
+        }
+        let identity = Identity::generate();
+        self.key.save(identity.to_string().expose_secret())?;
+        Ok(Some(identity))
+    }
+}
+
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, SecretsError> + Send + 'static,
+) -> Result<T, SecretsError> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| SecretsError::Io(std::io::Error::other(e)))?
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn write_private_file(path: &std::path::Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let tmp = path.with_extension("tmp");
+    let mut file = {
+        let mut opts = std::fs::OpenOptions::new();
+        opts.create(true).truncate(true).write(true);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            opts.mode(0o600);
+        }
+
+        opts.open(&tmp)?
+    };
+    file.write_all(contents)?;
+    file.sync_all()?;
+    drop(file);
+
+    std::fs::rename(&tmp, path)
+}
+
+enum KeyBackend {
+    File(PathBuf),
+    #[cfg(target_os = "macos")]
+    Keychain,
+    /// Secret Service on Linux, Credential Manager on Windows
+    #[cfg(any(target_os = "linux", windows))]
+    Keyring(keyring::Entry),
+}
+
+impl KeyBackend {
+    /// The OS keyring, unless `SECRETS_KEY_BACKEND=file` is set or this is a debug build, so
+    /// development never touches the real keyring. Falls back to the key file with a warning
+    /// when no keyring is available.
+    fn detect(path: PathBuf) -> Self {
+        let use_file = match std::env::var("SECRETS_KEY_BACKEND") {
+            Ok(v) if v.eq_ignore_ascii_case("file") => true,
+            Ok(v) if v.eq_ignore_ascii_case("keychain") || v.eq_ignore_ascii_case("keyring") => {
+                false
+            }
+            _ => cfg!(debug_assertions),
+        };
+        if use_file {
+            return KeyBackend::File(path);
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            KeyBackend::Keychain
+        }
+        #[cfg(any(target_os = "linux", windows))]
+        {
+            match open_keyring(&path) {
+                Ok(entry) => KeyBackend::Keyring(entry),
+                Err(e) => {
+                    tracing::warn!(
+                        "No OS keyring available ({}); keeping the secrets key in {}",
+                        e,
+                        path.display()
+                    );
+                    KeyBackend::File(path)
+                }
+            }
+        }
+        #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+        {
+            tracing::warn!(
+                "No OS keyring on this platform; keeping the secrets key in {}",
+                path.display()
+            );
+            KeyBackend::File(path)
+        }
+    }
+
+    fn load(&self) -> std::io::Result<Option<String>> {
+        match self {
+            KeyBackend::File(path) => match std::fs::read_to_string(path) {
+                Ok(key) => Ok(Some(key)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e),
+            },
+            #[cfg(target_os = "macos")]
+            KeyBackend::Keychain => {
+                use security_framework::passwords::get_generic_password;
+
+                match get_generic_password(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT) {
+                    Ok(bytes) => Ok(Some(String::from_utf8_lossy(&bytes).into_owned())),
+                    Err(e) if e.code() == ERR_SEC_ITEM_NOT_FOUND => Ok(None),
+                    Err(e) => Err(std::io::Error::other(e)),
+                }
+            }
+            #[cfg(any(target_os = "linux", windows))]
+            KeyBackend::Keyring(entry) => match entry.get_password() {
+                Ok(key) => Ok(Some(key)),
+                Err(keyring::Error::NoEntry) => Ok(None),
+                Err(e) => Err(std::io::Error::other(e)),
+            },
+        }
+    }
+
+    fn save(&self, key: &str) -> std::io::Result<()> {
+        match self {
+            KeyBackend::File(path) => write_private_file(path, key.as_bytes()),
+            #[cfg(target_os = "macos")]
+            KeyBackend::Keychain => {
+                use security_framework::passwords::set_generic_password;
+
+                set_generic_password(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT, key.as_bytes())
+                    .map_err(std::io::Error::other)
+            }
+            #[cfg(any(target_os = "linux", windows))]
+            KeyBackend::Keyring(entry) => entry.set_password(key).map_err(std::io::Error::other),
+        }
+    }
+}
+
+/// Open the keyring entry holding the vault key. A key file written while no keyring was
+/// used is moved into the keyring, so an existing vault stays readable.
+#[cfg(any(target_os = "linux", windows))]
+fn open_keyring(key_path: &std::path::Path) -> keyring::Result<keyring::Entry> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)?;
+    match entry.get_password() {
+        Ok(_) => return Ok(entry),
+        Err(keyring::Error::NoEntry) => {}
+        Err(e) => return Err(e),
+    }
+
+    if let Ok(key) = std::fs::read_to_string(key_path) {
+        entry.set_password(key.trim())?;
+        match std::fs::remove_file(key_path) {
+            Ok(()) => tracing::info!(
+                "Moved the secrets key from {} into the OS keyring",
+                key_path.display()
+            ),
+            Err(e) => tracing::warn!(
+                "Copied the secrets key into the OS keyring but could not remove {}: {}",
+                key_path.display(),
+                e
+            ),
+        }
+    }
+    Ok(entry)
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux", windows))]
+const KEYCHAIN_SERVICE: &str = concat!(env!("CARGO_PKG_NAME"), ":secrets");
+#[cfg(any(target_os = "macos", target_os = "linux", windows))]
+const KEYCHAIN_ACCOUNT: &str = "vault-key";
+#[cfg(target_os = "macos")]
+const ERR_SEC_ITEM_NOT_FOUND: i32 = -25300;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(dir: &tempfile::TempDir) -> SecretsService {
+        SecretsService::with_key_backend(
+            dir.path().join("secrets.age"),
+            KeyBackend::File(dir.path().join("secrets.key")),
+        )
+    }
+
+    fn create(name: &str, value: &str, repo_id: Option<Uuid>) -> CreateSecret {
+        CreateSecret {
+            name: name.to_string(),
+            value: value.to_string(),
+            repo_id,
+        }
+    }
+
+    #[tokio::test]
+    async fn vault_is_encrypted_and_reloads() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_id = Uuid::new_v4();
+        service(&dir)
+            .create(project_id, &create("API_KEY", "sk-live-123456", None))
+            .await
+            .unwrap();
+
+        let on_disk = std::fs::read(dir.path().join("secrets.age")).unwrap();
+        assert!(!String::from_utf8_lossy(&on_disk).contains("sk-live-123456"));
+
+        let reloaded = service(&dir);
+        reloaded.load().await.unwrap();
+        let env = reloaded.env_for(project_id, &[]).await.unwrap();
+        assert_eq!(
+            env.get("API_KEY").map(String::as_str),
+            Some("sk-live-123456")
+        );
+    }
+
+    #[tokio::test]
+    async fn repo_secrets_override_project_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        let secrets = service(&dir);
+        let project_id = Uuid::new_v4();
+        let (repo_a, repo_b) = (Uuid::new_v4(), Uuid::new_v4());
+        for data in [
+            create("TOKEN", "project-token", None),
+            create("TOKEN", "repo-a-token", Some(repo_a)),
+            create("ONLY_B", "repo-b-value", Some(repo_b)),
+        ] {
+            secrets.create(project_id, &data).await.unwrap();
+        }
+
+        let env = secrets.env_for(project_id, &[repo_a]).await.unwrap();
+        assert_eq!(env.get("TOKEN").map(String::as_str), Some("repo-a-token"));
+        assert!(!env.contains_key("ONLY_B"));
+
+        let env = secrets.env_for(project_id, &[repo_b]).await.unwrap();
+        assert_eq!(env.get("TOKEN").map(String::as_str), Some("project-token"));
+        assert_eq!(env.get("ONLY_B").map(String::as_str), Some("repo-b-value"));
+
+        assert!(matches!(
+            secrets
+                .create(project_id, &create("TOKEN", "again", None))
+                .await,
+            Err(SecretsError::DuplicateName(_))
+        ));
+        assert!(matches!(
+            secrets.create(project_id, &create("1BAD", "x", None)).await,
+            Err(SecretsError::InvalidName(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn secrets_of_other_projects_are_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let secrets = service(&dir);
+        let (project_a, project_b) = (Uuid::new_v4(), Uuid::new_v4());
+        let secret = secrets
+            .create(project_a, &create("API_KEY", "value-1234", None))
+            .await
+            .unwrap();
+
+        let update = UpdateSecret {
+            value: "value-5678".to_string(),
+        };
+        assert!(matches!(
+            secrets.update(project_b, secret.id, &update).await,
+            Err(SecretsError::NotFound)
+        ));
+        assert!(matches!(
+            secrets.delete(project_b, secret.id).await,
+            Err(SecretsError::NotFound)
+        ));
+
+        let env = secrets.env_for(project_a, &[]).await.unwrap();
+        assert_eq!(env.get("API_KEY").map(String::as_str), Some("value-1234"));
+        secrets.delete(project_a, secret.id).await.unwrap();
+        assert!(secrets.list(project_a).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn missing_key_locks_vault() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_id = Uuid::new_v4();
+        service(&dir)
+            .create(project_id, &create("API_KEY", "value-1234", None))
+            .await
+            .unwrap();
+        std::fs::remove_file(dir.path().join("secrets.key")).unwrap();
+
+        let reloaded = service(&dir);
+        assert!(reloaded.load().await.is_err());
+        assert!(matches!(
+            reloaded
+                .create(project_id, &create("OTHER", "value-5678", None))
+                .await,
+            Err(SecretsError::Locked)
+        ));
+    }
+}
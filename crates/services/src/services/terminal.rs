@@ -0,0 +1,73 @@
+//! Interactive terminals attached to a workspace.
+//!
+//! A terminal is a shell running on a PTY in the workspace directory. It is recorded as an
+//! [`ExecutionProcess`](db::models::execution_process::ExecutionProcess) with the `terminal`
+//! run reason, and everything it prints (typed commands are echoed back by the PTY) becomes the
+//! process's raw logs, so what humans ran in a workspace can be audited later.
+
+use serde::Deserialize;
+use ts_rs::TS;
+use utils::shell::UnixShell;
+
+/// Messages a terminal client sends over the WebSocket
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TerminalInput {
+    /// Keystrokes or pasted text
+    Input {
+        data: String,
+    },
+    Resize {
+        cols: u16,
+        rows: u16,
+    },
+}
+
+impl TerminalInput {
+    /// Parse a client message, ignoring resizes to an empty window
+    pub fn parse(message: &str) -> Option<Self> {
+        match serde_json::from_str(message).ok()? {
+            TerminalInput::Resize { cols, rows } if cols == 0 || rows == 0 => None,
+            input => Some(input),
+        }
+    }
+}
+
+/// The user's shell, used as the terminal program
+pub fn shell_program() -> String {
+    if cfg!(windows) {
+        "cmd".to_string()
+    } else {
+        UnixShell::current_shell()
+            .path()
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_client_messages() {
+        assert_eq!(
+            TerminalInput::parse(r#"{"type":"input","data":"ls\r"}"#),
+            Some(TerminalInput::Input {
+                data: "ls\r".to_string()
+            })
+        );
+        assert_eq!(
+            TerminalInput::parse(r#"{"type":"resize","cols":120,"rows":40}"#),
+            Some(TerminalInput::Resize {
+                cols: 120,
+                rows: 40
+            })
+        );
+        assert_eq!(
+            TerminalInput::parse(r#"{"type":"resize","cols":0,"rows":40}"#),
+            None
+        );
+        assert_eq!(TerminalInput::parse("not json"), None);
+    }
+}
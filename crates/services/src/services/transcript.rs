@@ -0,0 +1,855 @@
+//! Agent-agnostic session transcripts.
+//!
+//! Every executor normalizes its output into [`NormalizedEntry`] values. This module
+//! replays those entries for a session and renders them as Markdown, a stable JSON
+//! schema, or a self-contained HTML page.
+
+use std::fmt::Write as _;
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    session::Session,
+    task::Task,
+    workspace::Workspace,
+};
+use executors::{
+    actions::ExecutorActionType,
+    logs::{
+        ActionType, CommandExitStatus, FileChange, NormalizedEntry, NormalizedEntryType, TodoItem,
+        ToolStatus,
+        utils::patch::{apply_conversation_patches, normalized_entries_from_conversation},
+    },
+};
+use futures::StreamExt;
+use json_patch::Patch;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+use thiserror::Error;
+use ts_rs::TS;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+use crate::services::container::ContainerService;
+
+/// Bumped whenever the JSON transcript layout changes in a breaking way
+pub const TRANSCRIPT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum TranscriptError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    #[error("Workspace {0} not found")]
+    WorkspaceNotFound(Uuid),
+    #[error("Failed to build response: {0}")]
+    ResponseBuildError(String),
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS, Display, EnumString,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum TranscriptFormat {
+    #[default]
+    #[serde(alias = "md")]
+    #[strum(serialize = "markdown", serialize = "md")]
+    Markdown,
+    Json,
+    Html,
+}
+
+impl TranscriptFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            TranscriptFormat::Markdown => "text/markdown; charset=utf-8",
+            TranscriptFormat::Json => "application/json",
+            TranscriptFormat::Html => "text/html; charset=utf-8",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TranscriptFormat::Markdown => "md",
+            TranscriptFormat::Json => "json",
+            TranscriptFormat::Html => "html",
+        }
+    }
+}
+
+/// Full history of a session, one turn per coding agent execution
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct Transcript {
+    pub schema_version: u32,
+    pub session_id: Uuid,
+    pub workspace_id: Uuid,
+    pub task_title: Option<String>,
+    pub exported_at: DateTime<Utc>,
+    pub turns: Vec<TranscriptTurn>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TranscriptTurn {
+    pub execution_process_id: Uuid,
+    /// Executor profile that ran this turn, e.g. `CLAUDE_CODE:PLAN`
+    pub executor: Option<String>,
+    pub status: ExecutionProcessStatus,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TranscriptEntry {
+    pub timestamp: Option<String>,
+    #[serde(flatten)]
+    pub kind: TranscriptEntryKind,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TranscriptEntryKind {
+    UserMessage,
+    /// Feedback given when the user denied a tool call
+    UserFeedback {
+        denied_tool: String,
+    },
+    AssistantMessage,
+    Thinking,
+    SystemMessage,
+    Error,
+    ToolUse {
+        tool_name: String,
+        status: TranscriptToolStatus,
+        action: ActionType,
+    },
+}
+
+/// Outcome of a tool call, including approval decisions
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TranscriptToolStatus {
+    Pending,
+    Success,
+    Failed,
+    AwaitingApproval,
+    Denied { reason: Option<String> },
+    TimedOut,
+}
+
+impl From<&ToolStatus> for TranscriptToolStatus {
+    fn from(status: &ToolStatus) -> Self {
+        match status {
+            ToolStatus::Created => TranscriptToolStatus::Pending,
+            ToolStatus::Success => TranscriptToolStatus::Success,
+            ToolStatus::Failed => TranscriptToolStatus::Failed,
+            ToolStatus::Denied { reason } => TranscriptToolStatus::Denied {
+                reason: reason.clone(),
+            },
+            ToolStatus::PendingApproval { .. } => TranscriptToolStatus::AwaitingApproval,
+            ToolStatus::TimedOut => TranscriptToolStatus::TimedOut,
+        }
+    }
+}
+
+impl TranscriptToolStatus {
+    fn label(&self) -> String {
+        match self {
+            TranscriptToolStatus::Pending => "pending".to_string(),
+            TranscriptToolStatus::Success => "success".to_string(),
+            TranscriptToolStatus::Failed => "failed".to_string(),
+            TranscriptToolStatus::AwaitingApproval => "awaiting approval".to_string(),
+            TranscriptToolStatus::Denied {
+                reason: Some(reason),
+            } => {
+                format!("denied: {reason}")
+            }
+            TranscriptToolStatus::Denied { reason: None } => "denied".to_string(),
+            TranscriptToolStatus::TimedOut => "approval timed out".to_string(),
+        }
+    }
+}
+
+impl TranscriptEntry {
+    /// Convert a normalized entry, skipping UI-only entries such as loading indicators
+    pub fn from_normalized(entry: NormalizedEntry) -> Option<Self> {
+        let kind = match entry.entry_type {
+            NormalizedEntryType::UserMessage => TranscriptEntryKind::UserMessage,
+            NormalizedEntryType::UserFeedback { denied_tool } => {
+                TranscriptEntryKind::UserFeedback { denied_tool }
+            }
+            NormalizedEntryType::AssistantMessage => TranscriptEntryKind::AssistantMessage,
+            NormalizedEntryType::Thinking => TranscriptEntryKind::Thinking,
+            NormalizedEntryType::SystemMessage => TranscriptEntryKind::SystemMessage,
+            NormalizedEntryType::ErrorMessage { .. } => TranscriptEntryKind::Error,
+            NormalizedEntryType::ToolUse {
+                tool_name,
+                action_type,
+                status,
+            } => TranscriptEntryKind::ToolUse {
+                tool_name,
+                status: TranscriptToolStatus::from(&status),
+                action: action_type,
+            },
+            NormalizedEntryType::Loading | NormalizedEntryType::NextAction { .. } => return None,
+        };
+        Some(Self {
+            timestamp: entry.timestamp,
+            kind,
+            content: entry.content,
+        })
+    }
+}
+
+impl Transcript {
+    /// Build the transcript for a session from its coding agent executions
+    pub async fn for_session<C>(container: &C, session: &Session) -> Result<Self, TranscriptError>
+    where
+        C: ContainerService + Sync + ?Sized,
+    {
+        let pool = &container.db().pool;
+        let workspace = Workspace::find_by_id(pool, session.workspace_id)
+            .await?
+            .ok_or(TranscriptError::WorkspaceNotFound(session.workspace_id))?;
+        let task_title = Task::find_by_id(pool, workspace.task_id)
+            .await?
+            .map(|task| task.title);
+
+        let processes = ExecutionProcess::find_by_session_id(pool, session.id, false).await?;
+        let mut turns = Vec::new();
+        for process in processes
+            .into_iter()
+            .filter(|p| p.run_reason == ExecutionProcessRunReason::CodingAgent)
+        {
+            let (prompt, executor) = match process.executor_action().map(|a| a.typ()) {
+                Ok(ExecutorActionType::CodingAgentInitialRequest(request)) => (
+                    Some(request.prompt.clone()),
+                    Some(request.executor_profile_id.to_string()),
+                ),
+                Ok(ExecutorActionType::CodingAgentFollowUpRequest(request)) => (
+                    Some(request.prompt.clone()),
+                    Some(request.executor_profile_id.to_string()),
+                ),
+                _ => (None, None),
+            };
+
+            let normalized = collect_normalized_entries(container, &process.id).await;
+            let mut entries: Vec<TranscriptEntry> = prompt
+                .iter()
+                .map(|prompt| TranscriptEntry {
+                    timestamp: Some(process.started_at.to_rfc3339()),
+                    kind: TranscriptEntryKind::UserMessage,
+                    content: prompt.clone(),
+                })
+                .collect();
+            for entry in normalized {
+                // Some executors echo the prompt back as their first user message
+                if entries.len() == 1
+                    && matches!(entry.entry_type, NormalizedEntryType::UserMessage)
+                    && prompt.as_deref() == Some(entry.content.as_str())
+                {
+                    continue;
+                }
+                entries.extend(TranscriptEntry::from_normalized(entry));
+            }
+
+            turns.push(TranscriptTurn {
+                execution_process_id: process.id,
+                executor,
+                status: process.status,
+                started_at: process.started_at,
+                completed_at: process.completed_at,
+                entries,
+            });
+        }
+
+        Ok(Self {
+            schema_version: TRANSCRIPT_SCHEMA_VERSION,
+            session_id: session.id,
+            workspace_id: workspace.id,
+            task_title,
+            exported_at: Utc::now(),
+            turns,
+        })
+    }
+
+    pub fn render(&self, format: TranscriptFormat) -> Result<String, TranscriptError> {
+        Ok(match format {
+            TranscriptFormat::Markdown => self.to_markdown(),
+            TranscriptFormat::Json => serde_json::to_string_pretty(self)?,
+            TranscriptFormat::Html => self.to_html(),
+        })
+    }
+
+    /// Suggested file name for a download of this transcript
+    pub fn file_name(&self, format: TranscriptFormat) -> String {
+        let short_id: String = self.session_id.to_string().chars().take(8).collect();
+        format!("transcript-{short_id}.{}", format.extension())
+    }
+
+    fn title(&self) -> String {
+        self.task_title
+            .clone()
+            .unwrap_or_else(|| format!("Session {}", self.session_id))
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# {}\n", self.title());
+        let _ = writeln!(out, "- Session: `{}`", self.session_id);
+        let _ = writeln!(
+            out,
+            "- Exported: {}\n",
+            self.exported_at.format("%Y-%m-%d %H:%M UTC")
+        );
+
+        for (i, turn) in self.turns.iter().enumerate() {
+            let _ = writeln!(out, "## Turn {}{}\n", i + 1, turn_suffix(turn));
+            for entry in &turn.entries {
+                write_markdown_entry(&mut out, entry);
+            }
+        }
+        out
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut body = String::new();
+        let _ = writeln!(body, "<h1>{}</h1>", escape_html(&self.title()));
+        let _ = writeln!(
+            body,
+            "<p class=\"meta\">Session <code>{}</code> &middot; exported {}</p>",
+            self.session_id,
+            self.exported_at.format("%Y-%m-%d %H:%M UTC")
+        );
+
+        for (i, turn) in self.turns.iter().enumerate() {
+            let _ = writeln!(
+                body,
+                "<section class=\"turn\"><h2>Turn {}{}</h2>",
+                i + 1,
+                escape_html(&turn_suffix(turn))
+            );
+            for entry in &turn.entries {
+                write_html_entry(&mut body, entry);
+            }
+            body.push_str("</section>\n");
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            escape_html(&self.title()),
+            HTML_STYLE,
+            body
+        )
+    }
+}
+
+/// Replay the normalized log patches of an execution into its final list of entries
+pub(crate) async fn collect_normalized_entries<C>(
+    container: &C,
+    execution_id: &Uuid,
+) -> Vec<NormalizedEntry>
+where
+    C: ContainerService + Sync + ?Sized,
+{
+    // Running processes keep their store in memory; take a snapshot instead of waiting
+    // for the live stream to finish.
+    let messages: Vec<LogMsg> =
+        if let Some(store) = container.get_msg_store_by_id(execution_id).await {
+            store.get_history()
+        } else if let Some(stream) = container.stream_normalized_logs(execution_id).await {
+            stream
+                .filter_map(|msg| async move { msg.ok() })
+                .collect()
+                .await
+        } else {
+            return Vec::new();
+        };
+
+    let patches: Vec<Patch> = messages
+        .into_iter()
+        .filter_map(|msg| match msg {
+            LogMsg::JsonPatch(patch) => Some(patch),
+            _ => None,
+        })
+        .collect();
+    normalized_entries_from_conversation(apply_conversation_patches(&patches))
+}
+
+fn turn_suffix(turn: &TranscriptTurn) -> String {
+    let mut suffix = String::new();
+    if let Some(executor) = &turn.executor {
+        let _ = write!(suffix, " · {executor}");
+    }
+    if turn.status != ExecutionProcessStatus::Completed {
+        let status = format!("{:?}", turn.status).to_lowercase();
+        let _ = write!(suffix, " · {status}");
+    }
+    suffix
+}
+
+/// Wrap `content` in a code fence longer than any backtick run it contains
+fn fenced(content: &str, lang: &str) -> String {
+    let mut longest = 0;
+    let mut current = 0;
+    for c in content.chars() {
+        if c == '`' {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!(
+        "{fence}{lang}\n{}\n{fence}\n",
+        content.trim_end_matches('\n')
+    )
+}
+
+fn quoted(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| format!("> {line}").trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn todo_marker(todo: &TodoItem) -> &'static str {
+    match todo.status.to_ascii_lowercase().as_str() {
+        "completed" | "done" => "[x]",
+        "in_progress" | "in-progress" => "[~]",
+        _ => "[ ]",
+    }
+}
+
+fn exit_status_label(status: &CommandExitStatus) -> String {
+    match status {
+        CommandExitStatus::ExitCode { code } => format!("exit code {code}"),
+        CommandExitStatus::Success { success: true } => "succeeded".to_string(),
+        CommandExitStatus::Success { success: false } => "failed".to_string(),
+    }
+}
+
+fn write_markdown_entry(out: &mut String, entry: &TranscriptEntry) {
+    match &entry.kind {
+        TranscriptEntryKind::UserMessage => {
+            let _ = writeln!(out, "**User**\n\n{}\n", entry.content.trim());
+        }
+        TranscriptEntryKind::UserFeedback { denied_tool } => {
+            let _ = writeln!(
+                out,
+                "**User denied `{}`**\n\n{}\n",
+                denied_tool,
+                entry.content.trim()
+            );
+        }
+        TranscriptEntryKind::AssistantMessage => {
+            let _ = writeln!(out, "**Assistant**\n\n{}\n", entry.content.trim());
+        }
+        TranscriptEntryKind::Thinking => {
+            let _ = writeln!(
+                out,
+                "{}\n",
+                quoted(&format!("_Thinking:_ {}", entry.content))
+            );
+        }
+        TranscriptEntryKind::SystemMessage => {
+            let _ = writeln!(out, "{}\n", quoted(&format!("_System:_ {}", entry.content)));
+        }
+        TranscriptEntryKind::Error => {
+            let _ = writeln!(out, "**Error**\n\n{}", fenced(&entry.content, ""));
+        }
+        TranscriptEntryKind::ToolUse {
+            tool_name,
+            status,
+            action,
+        } => write_markdown_tool(out, tool_name, status, action, &entry.content),
+    }
+}
+
+fn write_markdown_tool(
+    out: &mut String,
+    tool_name: &str,
+    status: &TranscriptToolStatus,
+    action: &ActionType,
+    content: &str,
+) {
+    let status = status.label();
+    match action {
+        ActionType::FileRead { path } => {
+            let _ = writeln!(out, "- 📄 Read `{path}` ({status})\n");
+        }
+        ActionType::FileEdit { path, changes } => {
+            let _ = writeln!(out, "**✏️ {tool_name}** `{path}` ({status})\n");
+            for change in changes {
+                match change {
+                    FileChange::Write { content } => {
+                        let _ = writeln!(out, "Wrote file:\n\n{}", fenced(content, ""));
+                    }
+                    FileChange::Delete => {
+                        let _ = writeln!(out, "Deleted file.\n");
+                    }
+                    FileChange::Rename { new_path } => {
+                        let _ = writeln!(out, "Renamed to `{new_path}`.\n");
+                    }
+                    FileChange::Edit { unified_diff, .. } => {
+                        let _ = writeln!(out, "{}", fenced(unified_diff, "diff"));
+                    }
+                }
+            }
+        }
+        ActionType::CommandRun { command, result } => {
+            let _ = writeln!(out, "**$ {tool_name}** ({status})\n");
+            let mut shell = format!("$ {command}");
+            if let Some(output) = result.as_ref().and_then(|r| r.output.as_deref()) {
+                shell.push('\n');
+                shell.push_str(output);
+            }
+            let _ = writeln!(out, "{}", fenced(&shell, "sh"));
+            if let Some(exit_status) = result.as_ref().and_then(|r| r.exit_status.as_ref()) {
+                let _ = writeln!(out, "_{}_\n", exit_status_label(exit_status));
+            }
+        }
+        ActionType::Search { query } => {
+            let _ = writeln!(out, "- 🔍 Search `{query}` ({status})\n");
+        }
+        ActionType::WebFetch { url } => {
+            let _ = writeln!(out, "- 🌐 Fetch <{url}> ({status})\n");
+        }
+        ActionType::Tool {
+            tool_name,
+            arguments,
+            result,
+        } => {
+            let _ = writeln!(out, "**🔧 {tool_name}** ({status})\n");
+            if let Some(arguments) = arguments {
+                let pretty = serde_json::to_string_pretty(arguments).unwrap_or_default();
+                let _ = writeln!(out, "{}", fenced(&pretty, "json"));
+            }
+            if let Some(result) = result {
+                let rendered = match &result.value {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => serde_json::to_string_pretty(other).unwrap_or_default(),
+                };
+                let _ = writeln!(out, "Result:\n\n{}", fenced(&rendered, ""));
+            }
+        }
+        ActionType::TaskCreate { description } => {
+            let _ = writeln!(out, "- 🧩 Subtask: {description} ({status})\n");
+        }
+        ActionType::PlanPresentation { plan } => {
+            let _ = writeln!(out, "**📋 Plan** ({status})\n\n{}\n", plan.trim());
+        }
+        ActionType::TodoManagement { todos, .. } => {
+            let _ = writeln!(out, "**Todos**\n");
+            for todo in todos {
+                let _ = writeln!(out, "- {} {}", todo_marker(todo), todo.content);
+            }
+            out.push('\n');
+        }
+        ActionType::Other { .. } => {
+            let _ = writeln!(out, "- **{tool_name}**: {} ({status})\n", content.trim());
+        }
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn html_pre(content: &str) -> String {
+    format!("<pre>{}</pre>", escape_html(content.trim_end_matches('\n')))
+}
+
+fn html_diff(diff: &str) -> String {
+    let lines: Vec<String> = diff
+        .lines()
+        .map(|line| {
+            let class = if line.starts_with("+++") || line.starts_with("---") {
+                "meta"
+            } else if line.starts_with('+') {
+                "add"
+            } else if line.starts_with('-') {
+                "del"
+            } else if line.starts_with("@@") {
+                "hunk"
+            } else {
+                "ctx"
+            };
+            format!("<span class=\"{class}\">{}</span>", escape_html(line))
+        })
+        .collect();
+    format!("<pre class=\"diff\">{}</pre>", lines.join("\n"))
+}
+
+fn write_html_entry(out: &mut String, entry: &TranscriptEntry) {
+    let (class, label, body) = match &entry.kind {
+        TranscriptEntryKind::UserMessage => ("user", "User".to_string(), html_pre(&entry.content)),
+        TranscriptEntryKind::UserFeedback { denied_tool } => (
+            "user",
+            format!("User denied {denied_tool}"),
+            html_pre(&entry.content),
+        ),
+        TranscriptEntryKind::AssistantMessage => (
+            "assistant",
+            "Assistant".to_string(),
+            html_pre(&entry.content),
+        ),
+        TranscriptEntryKind::Thinking => {
+            ("thinking", "Thinking".to_string(), html_pre(&entry.content))
+        }
+        TranscriptEntryKind::SystemMessage => {
+            ("system", "System".to_string(), html_pre(&entry.content))
+        }
+        TranscriptEntryKind::Error => ("error", "Error".to_string(), html_pre(&entry.content)),
+        TranscriptEntryKind::ToolUse {
+            tool_name,
+            status,
+            action,
+        } => (
+            "tool",
+            format!("{tool_name} · {}", status.label()),
+            html_tool_body(action, &entry.content),
+        ),
+    };
+
+    if class == "tool" || class == "thinking" {
+        let _ = writeln!(
+            out,
+            "<details class=\"entry {class}\"><summary>{}</summary>{body}</details>",
+            escape_html(&label)
+        );
+    } else {
+        let _ = writeln!(
+            out,
+            "<div class=\"entry {class}\"><div class=\"label\">{}</div>{body}</div>",
+            escape_html(&label)
+        );
+    }
+}
+
+fn html_tool_body(action: &ActionType, content: &str) -> String {
+    match action {
+        ActionType::FileRead { path } => format!("<p>Read <code>{}</code></p>", escape_html(path)),
+        ActionType::FileEdit { path, changes } => {
+            let mut body = format!("<p><code>{}</code></p>", escape_html(path));
+            for change in changes {
+                match change {
+                    FileChange::Write { content } => body.push_str(&html_pre(content)),
+                    FileChange::Delete => body.push_str("<p>Deleted file.</p>"),
+                    FileChange::Rename { new_path } => {
+                        let _ = write!(
+                            body,
+                            "<p>Renamed to <code>{}</code></p>",
+                            escape_html(new_path)
+                        );
+                    }
+                    FileChange::Edit { unified_diff, .. } => {
+                        body.push_str(&html_diff(unified_diff))
+                    }
+                }
+            }
+            body
+        }
+        ActionType::CommandRun { command, result } => {
+            let mut shell = format!("$ {command}");
+            if let Some(output) = result.as_ref().and_then(|r| r.output.as_deref()) {
+                shell.push('\n');
+                shell.push_str(output);
+            }
+            let mut body = html_pre(&shell);
+            if let Some(exit_status) = result.as_ref().and_then(|r| r.exit_status.as_ref()) {
+                let _ = write!(
+                    body,
+                    "<p class=\"meta\">{}</p>",
+                    escape_html(&exit_status_label(exit_status))
+                );
+            }
+            body
+        }
+        ActionType::Search { query } => {
+            format!("<p>Search <code>{}</code></p>", escape_html(query))
+        }
+        ActionType::WebFetch { url } => format!("<p>Fetch <code>{}</code></p>", escape_html(url)),
+        ActionType::Tool {
+            arguments, result, ..
+        } => {
+            let mut body = String::new();
+            if let Some(arguments) = arguments {
+                body.push_str(&html_pre(
+                    &serde_json::to_string_pretty(arguments).unwrap_or_default(),
+                ));
+            }
+            if let Some(result) = result {
+                let rendered = match &result.value {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => serde_json::to_string_pretty(other).unwrap_or_default(),
+                };
+                body.push_str(&html_pre(&rendered));
+            }
+            body
+        }
+        ActionType::TaskCreate { description } => html_pre(description),
+        ActionType::PlanPresentation { plan } => html_pre(plan),
+        ActionType::TodoManagement { todos, .. } => {
+            let items: String = todos
+                .iter()
+                .map(|todo| {
+                    format!(
+                        "<li><code>{}</code> {}</li>",
+                        todo_marker(todo),
+                        escape_html(&todo.content)
+                    )
+                })
+                .collect();
+            format!("<ul class=\"todos\">{items}</ul>")
+        }
+        ActionType::Other { .. } => html_pre(content),
+    }
+}
+
+const HTML_STYLE: &str = "\
+body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;max-width:960px;\
+margin:2rem auto;padding:0 1rem;color:#1f2328;background:#fff;line-height:1.5}\
+h1{font-size:1.6rem}h2{font-size:1.2rem;border-bottom:1px solid #d0d7de;padding-bottom:.3rem}\
+.meta{color:#59636e;font-size:.85rem}\
+.entry{margin:.75rem 0;padding:.5rem .75rem;border-radius:6px;border:1px solid #d0d7de}\
+.entry .label,.entry summary{font-weight:600;font-size:.85rem;cursor:pointer}\
+.user{background:#ddf4ff}.assistant{background:#f6f8fa}.thinking{color:#59636e}\
+.system{color:#59636e;font-size:.85rem}.error{background:#ffebe9}\
+pre{white-space:pre-wrap;word-break:break-word;font-size:.85rem;margin:.5rem 0}\
+.diff .add{background:#dafbe1}.diff .del{background:#ffebe9}.diff .hunk{color:#8250df}\
+.diff .meta{font-weight:600}.todos{list-style:none;padding-left:0}";
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::CommandRunResult;
+
+    use super::*;
+
+    fn transcript(entries: Vec<TranscriptEntry>) -> Transcript {
+        Transcript {
+            schema_version: TRANSCRIPT_SCHEMA_VERSION,
+            session_id: Uuid::nil(),
+            workspace_id: Uuid::nil(),
+            task_title: Some("Fix <login> bug".to_string()),
+            exported_at: Utc::now(),
+            turns: vec![TranscriptTurn {
+                execution_process_id: Uuid::nil(),
+                executor: Some("CLAUDE_CODE".to_string()),
+                status: ExecutionProcessStatus::Completed,
+                started_at: Utc::now(),
+                completed_at: None,
+                entries,
+            }],
+        }
+    }
+
+    fn tool(action: ActionType) -> TranscriptEntry {
+        TranscriptEntry {
+            timestamp: None,
+            kind: TranscriptEntryKind::ToolUse {
+                tool_name: "Edit".to_string(),
+                status: TranscriptToolStatus::Success,
+                action,
+            },
+            content: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_fence_is_longer_than_content_backticks() {
+        let fenced = fenced("```rust\nfn main() {}\n```", "");
+        assert!(fenced.starts_with("````\n"));
+        assert!(fenced.ends_with("\n````\n"));
+    }
+
+    #[test]
+    fn test_markdown_renders_diffs_and_commands() {
+        let markdown = transcript(vec![
+            tool(ActionType::FileEdit {
+                path: "src/lib.rs".to_string(),
+                changes: vec![FileChange::Edit {
+                    unified_diff: "@@ -1 +1 @@\n-old\n+new".to_string(),
+                    has_line_numbers: true,
+                }],
+            }),
+            tool(ActionType::CommandRun {
+                command: "cargo test".to_string(),
+                result: Some(CommandRunResult {
+                    exit_status: Some(CommandExitStatus::ExitCode { code: 0 }),
+                    output: Some("ok".to_string()),
+                }),
+            }),
+        ])
+        .to_markdown();
+
+        assert!(markdown.contains("## Turn 1 · CLAUDE_CODE"));
+        assert!(markdown.contains("```diff\n@@ -1 +1 @@\n-old\n+new\n```"));
+        assert!(markdown.contains("```sh\n$ cargo test\nok\n```"));
+        assert!(markdown.contains("_exit code 0_"));
+    }
+
+    #[test]
+    fn test_html_escapes_content() {
+        let html = transcript(vec![TranscriptEntry {
+            timestamp: None,
+            kind: TranscriptEntryKind::AssistantMessage,
+            content: "<script>alert(1)</script>".to_string(),
+        }])
+        .to_html();
+
+        assert!(html.contains("<title>Fix &lt;login&gt; bug</title>"));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn test_json_schema_is_tagged() {
+        let json = transcript(vec![tool(ActionType::FileRead {
+            path: "README.md".to_string(),
+        })])
+        .render(TranscriptFormat::Json)
+        .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["schema_version"], TRANSCRIPT_SCHEMA_VERSION);
+        let entry = &value["turns"][0]["entries"][0];
+        assert_eq!(entry["kind"], "tool_use");
+        assert_eq!(entry["status"]["type"], "success");
+        assert_eq!(entry["action"]["action"], "file_read");
+    }
+
+    #[test]
+    fn test_format_parsing() {
+        assert_eq!(
+            "md".parse::<TranscriptFormat>().unwrap(),
+            TranscriptFormat::Markdown
+        );
+        assert_eq!(
+            "HTML".parse::<TranscriptFormat>().unwrap(),
+            TranscriptFormat::Html
+        );
+        // Query strings are deserialized by serde rather than strum
+        assert_eq!(
+            serde_json::from_str::<TranscriptFormat>("\"md\"").unwrap(),
+            TranscriptFormat::Markdown
+        );
+    }
+}
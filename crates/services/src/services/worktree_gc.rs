@@ -0,0 +1,721 @@
+//! Disk usage of workspace worktrees and their garbage collection.
+//!
+//! Removing a worktree keeps the workspace and its branch; the worktree is recreated by
+//! `ensure_container_exists` when the workspace is opened again. Only what was not committed
+//! is lost, so worktrees with uncommitted changes or untracked files are never removed, and
+//! neither are ones of pinned workspaces or with processes, dev servers or terminals running.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use db::{
+    DBService,
+    models::{
+        execution_process::ExecutionProcess,
+        merge::{Merge, MergeStatus},
+        repo::Repo,
+        workspace::Workspace,
+        workspace_repo::WorkspaceRepo,
+    },
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tokio::{
+    sync::RwLock,
+    time::{MissedTickBehavior, interval},
+};
+use tracing::{debug, error, info};
+use ts_rs::TS;
+use utils::path::dir_size;
+use uuid::Uuid;
+
+use crate::services::{
+    config::{Config, WorktreeGcConfig},
+    container::{ContainerError, ContainerService},
+    git::GitCli,
+};
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct WorktreeDiskUsage {
+    #[ts(type = "number")]
+    pub total_bytes: u64,
+    /// Largest first
+    pub workspaces: Vec<WorkspaceDiskUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct WorkspaceDiskUsage {
+    pub workspace_id: Uuid,
+    pub task_id: Uuid,
+    pub branch: String,
+    pub path: String,
+    pub archived: bool,
+    pub pinned: bool,
+    pub last_used_at: DateTime<Utc>,
+    #[ts(type = "number")]
+    pub total_bytes: u64,
+    pub repos: Vec<RepoDiskUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RepoDiskUsage {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    #[ts(type = "number")]
+    pub total_bytes: u64,
+    /// Gitignored files, such as build artifacts and installed dependencies
+    #[ts(type = "number")]
+    pub ignored_bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum WorktreeGcReason {
+    Archived,
+    Merged,
+    OverQuota,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum WorktreeGcSkipReason {
+    Running,
+    UncommittedChanges,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct WorktreeGcRemoval {
+    pub workspace_id: Uuid,
+    pub path: String,
+    pub reason: WorktreeGcReason,
+    #[ts(type = "number")]
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct WorktreeGcSkip {
+    pub workspace_id: Uuid,
+    pub path: String,
+    pub reason: WorktreeGcSkipReason,
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct WorktreeGcReport {
+    /// Nothing was removed; `removed` lists what would have been
+    pub dry_run: bool,
+    pub removed: Vec<WorktreeGcRemoval>,
+    /// Worktrees a policy selected but that were kept
+    pub skipped: Vec<WorktreeGcSkip>,
+    #[ts(type = "number")]
+    pub freed_bytes: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct RunWorktreeGcRequest {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Removes a workspace's worktree for garbage collection
+#[async_trait]
+pub trait WorktreeRemover {
+    async fn remove_worktree(&self, workspace: &Workspace) -> Result<(), ContainerError>;
+}
+
+#[async_trait]
+impl<C: ContainerService + Sync> WorktreeRemover for C {
+    async fn remove_worktree(&self, workspace: &Workspace) -> Result<(), ContainerError> {
+        self.delete(workspace).await
+    }
+}
+
+/// Disk usage of every workspace worktree
+pub async fn disk_usage(pool: &SqlitePool) -> Result<WorktreeDiskUsage, ContainerError> {
+    let mut workspaces = Vec::new();
+    for workspace in workspaces_with_worktrees(pool).await? {
+        workspaces.push(workspace_disk_usage(pool, &workspace).await?);
+    }
+    workspaces.sort_by(|a, b| b.total_bytes.cmp(&a.total_bytes));
+    Ok(WorktreeDiskUsage {
+        total_bytes: workspaces.iter().map(|w| w.total_bytes).sum(),
+        workspaces,
+    })
+}
+
+/// Apply `policy` to the worktrees, only reporting what would be removed when `dry_run` is set
+pub async fn collect<R: WorktreeRemover + Sync>(
+    pool: &SqlitePool,
+    container: &R,
+    policy: &WorktreeGcConfig,
+    dry_run: bool,
+) -> Result<WorktreeGcReport, ContainerError> {
+    let mut report = WorktreeGcReport {
+        dry_run,
+        ..Default::default()
+    };
+    let mut remaining = Vec::new();
+    // Worktrees a policy selected but that have to stay
+    let mut kept_bytes = 0;
+
+    for workspace in workspaces_with_worktrees(pool).await? {
+        if workspace.pinned {
+            continue;
+        }
+        match policy_reason(pool, policy, &workspace).await? {
+            Some(reason) => {
+                let bytes = worktree_size(&workspace).await?;
+                if !collect_one(pool, container, &workspace, reason, bytes, &mut report).await? {
+                    kept_bytes += bytes;
+                }
+            }
+            None => remaining.push(workspace),
+        }
+    }
+
+    if let Some(max_total_gb) = policy.max_total_gb {
+        let quota = u64::from(max_total_gb) * GIB;
+        let mut sized = Vec::with_capacity(remaining.len());
+        for workspace in remaining {
+            let bytes = worktree_size(&workspace).await?;
+            sized.push((workspace, bytes));
+        }
+        let mut total: u64 = kept_bytes + sized.iter().map(|(_, bytes)| bytes).sum::<u64>();
+        // Least recently used first
+        sized.sort_by_key(|(workspace, _)| workspace.updated_at);
+        for (workspace, bytes) in sized {
+            if total <= quota {
+                break;
+            }
+            if collect_one(
+                pool,
+                container,
+                &workspace,
+                WorktreeGcReason::OverQuota,
+                bytes,
+                &mut report,
+            )
+            .await?
+            {
+                total = total.saturating_sub(bytes);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Remove the worktree unless it is in use, returning whether it was (or would be) removed
+async fn collect_one<R: WorktreeRemover + Sync>(
+    pool: &SqlitePool,
+    container: &R,
+    workspace: &Workspace,
+    reason: WorktreeGcReason,
+    bytes: u64,
+    report: &mut WorktreeGcReport,
+) -> Result<bool, ContainerError> {
+    let path = workspace.container_ref.clone().unwrap_or_default();
+    let skip_reason =
+        if ExecutionProcess::has_running_processes_for_workspace(pool, workspace.id).await? {
+            Some(WorktreeGcSkipReason::Running)
+        } else if has_uncommitted_changes(pool, workspace).await? {
+            Some(WorktreeGcSkipReason::UncommittedChanges)
+        } else {
+            None
+        };
+    if let Some(reason) = skip_reason {
+        report.skipped.push(WorktreeGcSkip {
+            workspace_id: workspace.id,
+            path,
+            reason,
+        });
+        return Ok(false);
+    }
+
+    if !report.dry_run {
+        container.remove_worktree(workspace).await?;
+    }
+    report.removed.push(WorktreeGcRemoval {
+        workspace_id: workspace.id,
+        path,
+        reason,
+        bytes,
+    });
+    report.freed_bytes += bytes;
+    Ok(true)
+}
+
+async fn policy_reason(
+    pool: &SqlitePool,
+    policy: &WorktreeGcConfig,
+    workspace: &Workspace,
+) -> Result<Option<WorktreeGcReason>, ContainerError> {
+    // Archiving and opening a workspace both bump `updated_at`
+    if workspace.archived
+        && let Some(days) = policy.archived_after_days
+        && workspace.updated_at < Utc::now() - chrono::Duration::days(i64::from(days))
+    {
+        return Ok(Some(WorktreeGcReason::Archived));
+    }
+
+    if policy.merged_prs {
+        let statuses: Vec<MergeStatus> = Merge::find_by_workspace_id(pool, workspace.id)
+            .await?
+            .into_iter()
+            .filter_map(|merge| match merge {
+                Merge::Pr(pr) => Some(pr.pr_info.status),
+                Merge::Direct(_) => None,
+            })
+            .collect();
+        if statuses.iter().any(|s| matches!(s, MergeStatus::Merged))
+            && !statuses.iter().any(|s| matches!(s, MergeStatus::Open))
+        {
+            return Ok(Some(WorktreeGcReason::Merged));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Whether a worktree of the workspace has uncommitted changes or untracked files. Ignored
+/// files are not counted: they are what garbage collection reclaims.
+async fn has_uncommitted_changes(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+) -> Result<bool, ContainerError> {
+    let dir = PathBuf::from(workspace.container_ref.clone().unwrap_or_default());
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    tokio::task::spawn_blocking(move || {
+        repos
+            .iter()
+            .any(|repo| worktree_has_changes(&dir.join(&repo.name)))
+    })
+    .await
+    .map_err(|e| ContainerError::Other(e.into()))
+}
+
+fn worktree_has_changes(worktree: &Path) -> bool {
+    if !worktree.exists() {
+        return false;
+    }
+    match GitCli::new().git(worktree, ["status", "--porcelain"]) {
+        Ok(status) => !status.trim().is_empty(),
+        Err(e) => {
+            // Keep worktrees whose state is unknown
+            debug!("Cannot get the status of {}: {}", worktree.display(), e);
+            true
+        }
+    }
+}
+
+/// Workspaces whose worktree directory exists
+async fn workspaces_with_worktrees(pool: &SqlitePool) -> Result<Vec<Workspace>, ContainerError> {
+    Ok(Workspace::fetch_all(pool, None)
+        .await?
+        .into_iter()
+        .filter(|workspace| {
+            workspace
+                .container_ref
+                .as_deref()
+                .is_some_and(|dir| Path::new(dir).is_dir())
+        })
+        .collect())
+}
+
+async fn worktree_size(workspace: &Workspace) -> Result<u64, ContainerError> {
+    let dir = PathBuf::from(workspace.container_ref.clone().unwrap_or_default());
+    tokio::task::spawn_blocking(move || dir_size(&dir))
+        .await
+        .map_err(|e| ContainerError::Other(e.into()))
+}
+
+async fn workspace_disk_usage(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+) -> Result<WorkspaceDiskUsage, ContainerError> {
+    let dir = PathBuf::from(workspace.container_ref.clone().unwrap_or_default());
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let (total_bytes, repos) = {
+        let dir = dir.clone();
+        tokio::task::spawn_blocking(move || {
+            let repos: Vec<RepoDiskUsage> = repos
+                .iter()
+                .map(|repo| repo_disk_usage(&dir, repo))
+                .collect();
+            (dir_size(&dir), repos)
+        })
+        .await
+        .map_err(|e| ContainerError::Other(e.into()))?
+    };
+
+    Ok(WorkspaceDiskUsage {
+        workspace_id: workspace.id,
+        task_id: workspace.task_id,
+        branch: workspace.branch.clone(),
+        path: dir.to_string_lossy().into_owned(),
+        archived: workspace.archived,
+        pinned: workspace.pinned,
+        last_used_at: workspace.updated_at,
+        total_bytes,
+        repos,
+    })
+}
+
+fn repo_disk_usage(workspace_dir: &Path, repo: &Repo) -> RepoDiskUsage {
+    let worktree = workspace_dir.join(&repo.name);
+    // Ignored directories are listed once rather than file by file
+    let ignored_bytes = GitCli::new()
+        .git(
+            &worktree,
+            [
+                "ls-files",
+                "--others",
+                "--ignored",
+                "--exclude-standard",
+                "--directory",
+                "-z",
+            ],
+        )
+        .map(|listing| {
+            listing
+                .split('\0')
+                .filter(|path| !path.is_empty())
+                .map(|path| {
+                    let path = worktree.join(path);
+                    match path.symlink_metadata() {
+                        Ok(meta) if meta.is_dir() => dir_size(&path),
+                        Ok(meta) if meta.is_file() => meta.len(),
+                        _ => 0,
+                    }
+                })
+                .sum()
+        })
+        .unwrap_or_else(|e| {
+            debug!("Cannot list ignored files of {}: {}", worktree.display(), e);
+            0
+        });
+
+    RepoDiskUsage {
+        repo_id: repo.id,
+        repo_name: repo.name.clone(),
+        total_bytes: dir_size(&worktree),
+        ignored_bytes,
+    }
+}
+
+/// Service applying the worktree garbage collection policy of the config
+pub struct WorktreeGcService<C: ContainerService + Send + Sync + 'static> {
+    db: DBService,
+    container: C,
+    config: Arc<RwLock<Config>>,
+    poll_interval: Duration,
+}
+
+impl<C: ContainerService + Send + Sync + 'static> WorktreeGcService<C> {
+    pub async fn spawn(
+        db: DBService,
+        container: C,
+        config: Arc<RwLock<Config>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            container,
+            config,
+            poll_interval: Duration::from_secs(60 * 60), // Every hour
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting worktree garbage collection service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        // Leave startup alone; the first pass runs one interval in
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+            let policy = self.config.read().await.worktree_gc.clone();
+            if !policy.enabled {
+                continue;
+            }
+            match collect(&self.db.pool, &self.container, &policy, false).await {
+                Ok(report) if report.removed.is_empty() => {
+                    debug!("Worktree garbage collection: nothing to do")
+                }
+                Ok(report) => info!(
+                    "Worktree garbage collection removed {} worktrees, freeing {} bytes ({} kept)",
+                    report.removed.len(),
+                    report.freed_bytes,
+                    report.skipped.len()
+                ),
+                Err(e) => error!("Worktree garbage collection failed: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs, process::Command, sync::Mutex};
+
+    use db::{
+        models::{
+            execution_process::{CreateExecutionProcess, ExecutionProcessRunReason},
+            session::{CreateSession, Session},
+            workspace::CreateWorkspace,
+            workspace_repo::CreateWorkspaceRepo,
+        },
+        test_utils::test_pool,
+    };
+    use executors::actions::{
+        ExecutorAction, ExecutorActionType,
+        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+    };
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// Records the worktrees it is asked to remove
+    #[derive(Default)]
+    struct RecordingRemover {
+        removed: Mutex<Vec<Uuid>>,
+    }
+
+    #[async_trait]
+    impl WorktreeRemover for RecordingRemover {
+        async fn remove_worktree(&self, workspace: &Workspace) -> Result<(), ContainerError> {
+            self.removed.lock().unwrap().push(workspace.id);
+            Ok(())
+        }
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    /// An archived workspace last used `days_ago` with a committed worktree of repo `app`
+    async fn archived_workspace(pool: &SqlitePool, root: &Path, days_ago: i64) -> Uuid {
+        let id = Uuid::new_v4();
+        let dir = root.join(id.to_string());
+        let worktree = dir.join("app");
+        fs::create_dir_all(&worktree).unwrap();
+        git(&worktree, &["init", "-q"]);
+        fs::write(worktree.join(".gitignore"), "target\n").unwrap();
+        fs::write(worktree.join("main.rs"), "fn main() {}").unwrap();
+        git(&worktree, &["add", "."]);
+        git(&worktree, &["commit", "-q", "-m", "init"]);
+
+        let repo = Repo::find_or_create(pool, &worktree, "app").await.unwrap();
+        let workspace = Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch: "main".to_string(),
+                agent_working_dir: None,
+            },
+            id,
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        WorkspaceRepo::create_many(
+            pool,
+            workspace.id,
+            &[CreateWorkspaceRepo {
+                repo_id: repo.id,
+                target_branch: "main".to_string(),
+            }],
+        )
+        .await
+        .unwrap();
+        Workspace::update_container_ref(pool, workspace.id, &dir.to_string_lossy())
+            .await
+            .unwrap();
+        Workspace::set_archived(pool, workspace.id, true)
+            .await
+            .unwrap();
+        // Archiving stamps the current time; move it back to age the workspace
+        sqlx::query("UPDATE workspaces SET updated_at = datetime('now', ?) WHERE id = ?")
+            .bind(format!("-{days_ago} days"))
+            .bind(workspace.id)
+            .execute(pool)
+            .await
+            .unwrap();
+        workspace.id
+    }
+
+    async fn start_process(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        run_reason: ExecutionProcessRunReason,
+    ) {
+        let session = Session::create(
+            pool,
+            &CreateSession { executor: None },
+            Uuid::new_v4(),
+            workspace_id,
+        )
+        .await
+        .unwrap();
+        let (script, context) = match run_reason {
+            ExecutionProcessRunReason::Terminal => ("bash", ScriptContext::Terminal),
+            _ => ("npm run dev", ScriptContext::DevServer),
+        };
+        let action = ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: script.to_string(),
+                language: ScriptRequestLanguage::Bash,
+                context,
+                working_dir: None,
+                dev_process: None,
+            }),
+            None,
+        );
+        ExecutionProcess::create(
+            pool,
+            &CreateExecutionProcess {
+                session_id: session.id,
+                executor_action: action,
+                run_reason,
+            },
+            Uuid::new_v4(),
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    fn archived_policy() -> WorktreeGcConfig {
+        WorktreeGcConfig {
+            enabled: true,
+            archived_after_days: Some(7),
+            merged_prs: false,
+            max_total_gb: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn collects_archived_worktrees_unless_dry_run() {
+        let pool = test_pool().await;
+        let root = TempDir::new().unwrap();
+        let old = archived_workspace(&pool, root.path(), 30).await;
+        archived_workspace(&pool, root.path(), 1).await;
+        let remover = RecordingRemover::default();
+
+        let report = collect(&pool, &remover, &archived_policy(), true)
+            .await
+            .unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.removed.len(), 1);
+        assert_eq!(report.removed[0].workspace_id, old);
+        assert_eq!(report.removed[0].reason, WorktreeGcReason::Archived);
+        assert!(report.freed_bytes > 0);
+        assert!(remover.removed.lock().unwrap().is_empty());
+
+        let report = collect(&pool, &remover, &archived_policy(), false)
+            .await
+            .unwrap();
+        assert_eq!(report.removed.len(), 1);
+        assert_eq!(*remover.removed.lock().unwrap(), vec![old]);
+    }
+
+    #[tokio::test]
+    async fn keeps_worktrees_in_use_or_with_changes() {
+        let pool = test_pool().await;
+        let root = TempDir::new().unwrap();
+        let terminal = archived_workspace(&pool, root.path(), 30).await;
+        start_process(&pool, terminal, ExecutionProcessRunReason::Terminal).await;
+        let dev_server = archived_workspace(&pool, root.path(), 30).await;
+        start_process(&pool, dev_server, ExecutionProcessRunReason::DevServer).await;
+        let untracked = archived_workspace(&pool, root.path(), 30).await;
+        fs::write(
+            root.path().join(untracked.to_string()).join("app/notes.md"),
+            "wip",
+        )
+        .unwrap();
+        let ignored = archived_workspace(&pool, root.path(), 30).await;
+        let target = root.path().join(ignored.to_string()).join("app/target");
+        fs::create_dir(&target).unwrap();
+        fs::write(target.join("app"), vec![0u8; 64]).unwrap();
+        let pinned = archived_workspace(&pool, root.path(), 30).await;
+        Workspace::update(&pool, pinned, None, Some(true), None, None)
+            .await
+            .unwrap();
+        let remover = RecordingRemover::default();
+
+        let report = collect(&pool, &remover, &archived_policy(), false)
+            .await
+            .unwrap();
+        let skipped: HashMap<Uuid, WorktreeGcSkipReason> = report
+            .skipped
+            .iter()
+            .map(|skip| (skip.workspace_id, skip.reason))
+            .collect();
+        assert_eq!(skipped.len(), 3);
+        assert_eq!(skipped[&terminal], WorktreeGcSkipReason::Running);
+        assert_eq!(skipped[&dev_server], WorktreeGcSkipReason::Running);
+        assert_eq!(
+            skipped[&untracked],
+            WorktreeGcSkipReason::UncommittedChanges
+        );
+        assert_eq!(*remover.removed.lock().unwrap(), vec![ignored]);
+    }
+
+    #[test]
+    fn counts_gitignored_files_as_ignored_bytes() {
+        let workspace_dir = TempDir::new().unwrap();
+        let worktree = workspace_dir.path().join("app");
+        fs::create_dir(&worktree).unwrap();
+        let status = Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(&worktree)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        fs::write(worktree.join(".gitignore"), "target\n*.log\n").unwrap();
+        fs::write(worktree.join("main.rs"), "fn main() {}").unwrap();
+        fs::create_dir_all(worktree.join("target/debug")).unwrap();
+        fs::write(worktree.join("target/debug/app"), vec![0u8; 1000]).unwrap();
+        fs::write(worktree.join("build.log"), vec![0u8; 24]).unwrap();
+
+        let now = Utc::now();
+        let repo = Repo {
+            id: Uuid::new_v4(),
+            path: PathBuf::from("/unused"),
+            name: "app".to_string(),
+            display_name: "app".to_string(),
+            setup_script: None,
+            cleanup_script: None,
+            copy_files: None,
+            parallel_setup_script: false,
+            dev_server_script: None,
+            warm_pool_size: 0,
+            dev_processes: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let usage = repo_disk_usage(workspace_dir.path(), &repo);
+        assert_eq!(usage.ignored_bytes, 1024);
+        assert!(usage.total_bytes > usage.ignored_bytes);
+    }
+}
@@ -158,6 +158,11 @@ fn staged_but_uncommitted_changes_is_dirty() {
     write_file(&repo_path, "t1.txt", "b\n");
     add_path(&repo_path, "t1.txt");
     assert!(!s.is_worktree_clean(&repo_path).unwrap());
+
+    let repo = Repository::open(&repo_path).unwrap();
+    assert!(repo.find_reference("refs/test/snapshot").is_ok());
+    s.delete_ref(&repo_path, "refs/test/snapshot").unwrap();
+    assert!(repo.find_reference("refs/test/snapshot").is_err());
 }
 
 #[test]
@@ -520,3 +525,47 @@ fn squash_merge_libgit2_sets_author_without_user() {
         assert_eq!(email.as_deref(), Some("noreply@vibekanban.com"));
     }
 }
+
+#[test]
+fn revert_to_checkpoint_and_restore_snapshot() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let checkpoint = s.get_head_info(&repo_path).unwrap().oid;
+
+    write_file(&repo_path, "a.txt", "turn one\n");
+    s.commit(&repo_path, "turn one").unwrap();
+    let head = s.get_head_info(&repo_path).unwrap().oid;
+    let stat = s.diff_stat(&repo_path, &checkpoint, &head).unwrap();
+    assert_eq!(
+        (stat.files_changed, stat.additions, stat.deletions),
+        (1, 1, 0)
+    );
+
+    // Uncommitted and untracked changes must survive a revert/undo round trip
+    write_file(&repo_path, "a.txt", "turn one\nedited\n");
+    write_file(&repo_path, "new.txt", "untracked\n");
+    let snapshot = s
+        .snapshot_worktree(&repo_path, "refs/test/snapshot", "snapshot")
+        .unwrap();
+    assert_eq!(s.get_head_info(&repo_path).unwrap().oid, head);
+
+    s.discard_and_reset_worktree(&repo_path, &checkpoint)
+        .unwrap();
+    assert_eq!(s.get_head_info(&repo_path).unwrap().oid, checkpoint);
+    assert!(!repo_path.join("a.txt").exists());
+    assert!(!repo_path.join("new.txt").exists());
+
+    s.restore_worktree_snapshot(&repo_path, &snapshot, &head)
+        .unwrap();
+    assert_eq!(s.get_head_info(&repo_path).unwrap().oid, head);
+    assert_eq!(
+        fs::read_to_string(repo_path.join("a.txt")).unwrap(),
+        "turn one\nedited\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo_path.join("new.txt")).unwrap(),
+        "untracked\n"
+    );
+    assert!(!s.is_worktree_clean(&repo_path).unwrap());
+}
@@ -14,6 +14,7 @@ pub mod msg_store;
 pub mod path;
 pub mod port_allocator;
 pub mod port_file;
+pub mod redact;
 pub mod response;
 pub mod sentry;
 pub mod shell;
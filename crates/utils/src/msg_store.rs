@@ -8,7 +8,7 @@ use futures::{StreamExt, TryStreamExt, future};
 use tokio::{sync::broadcast, task::JoinHandle};
 use tokio_stream::wrappers::BroadcastStream;
 
-use crate::{log_msg::LogMsg, stream_lines::LinesStreamExt};
+use crate::{log_msg::LogMsg, redact::SecretRedactor, stream_lines::LinesStreamExt};
 
 // 100 MB Limit
 const HISTORY_BYTES: usize = 100000 * 1024;
@@ -22,11 +22,15 @@ struct StoredMsg {
 struct Inner {
     history: VecDeque<StoredMsg>,
     total_bytes: usize,
+    /// Output held back by the redactor because it may be the start of a secret
+    pending_stdout: String,
+    pending_stderr: String,
 }
 
 pub struct MsgStore {
     inner: RwLock<Inner>,
     sender: broadcast::Sender<LogMsg>,
+    redactor: SecretRedactor,
 }
 
 impl Default for MsgStore {
@@ -37,21 +41,67 @@ impl Default for MsgStore {
 
 impl MsgStore {
     pub fn new() -> Self {
+        Self::with_redactor(SecretRedactor::default())
+    }
+
+    /// A store that masks the redactor's secrets in everything pushed to it, before any
+    /// listener (including the database writer) sees the message
+    pub fn with_redactor(redactor: SecretRedactor) -> Self {
         let (sender, _) = broadcast::channel(10000);
         Self {
             inner: RwLock::new(Inner {
                 history: VecDeque::with_capacity(32),
                 total_bytes: 0,
+                pending_stdout: String::new(),
+                pending_stderr: String::new(),
             }),
             sender,
+            redactor,
         }
     }
 
     pub fn push(&self, msg: LogMsg) {
-        let _ = self.sender.send(msg.clone()); // live listeners
+        let mut inner = self.inner.write().unwrap();
+        if self.redactor.is_empty() {
+            Self::record(&mut inner, &self.sender, msg);
+            return;
+        }
+
+        match msg {
+            LogMsg::Stdout(s) => {
+                let out = self.redactor.redact_chunk(&mut inner.pending_stdout, &s);
+                if !out.is_empty() {
+                    Self::record(&mut inner, &self.sender, LogMsg::Stdout(out));
+                }
+            }
+            LogMsg::Stderr(s) => {
+                let out = self.redactor.redact_chunk(&mut inner.pending_stderr, &s);
+                if !out.is_empty() {
+                    Self::record(&mut inner, &self.sender, LogMsg::Stderr(out));
+                }
+            }
+            LogMsg::Finished => {
+                let stdout = self.redactor.flush(&mut inner.pending_stdout);
+                let stderr = self.redactor.flush(&mut inner.pending_stderr);
+                if !stdout.is_empty() {
+                    Self::record(&mut inner, &self.sender, LogMsg::Stdout(stdout));
+                }
+                if !stderr.is_empty() {
+                    Self::record(&mut inner, &self.sender, LogMsg::Stderr(stderr));
+                }
+                Self::record(&mut inner, &self.sender, LogMsg::Finished);
+            }
+            msg => {
+                let msg = self.redactor.redact_msg(msg);
+                Self::record(&mut inner, &self.sender, msg);
+            }
+        }
+    }
+
+    fn record(inner: &mut Inner, sender: &broadcast::Sender<LogMsg>, msg: LogMsg) {
+        let _ = sender.send(msg.clone()); // live listeners
         let bytes = msg.approx_bytes();
 
-        let mut inner = self.inner.write().unwrap();
         while inner.total_bytes.saturating_add(bytes) > HISTORY_BYTES {
             if let Some(front) = inner.history.pop_front() {
                 inner.total_bytes = inner.total_bytes.saturating_sub(front.bytes);
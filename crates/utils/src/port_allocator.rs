@@ -13,20 +13,20 @@ pub struct AllocatedPorts {
 /// before the dev server binds to them. This is acceptable for dev servers which typically
 /// handle port conflicts gracefully.
 pub fn allocate_dev_server_ports() -> std::io::Result<AllocatedPorts> {
-    let frontend_listener = TcpListener::bind("127.0.0.1:0")?;
-    let frontend_port = frontend_listener.local_addr()?.port();
-    drop(frontend_listener);
-
-    let backend_listener = TcpListener::bind("127.0.0.1:0")?;
-    let backend_port = backend_listener.local_addr()?.port();
-    drop(backend_listener);
-
     Ok(AllocatedPorts {
-        frontend: frontend_port,
-        backend: backend_port,
+        frontend: allocate_port()?,
+        backend: allocate_port()?,
     })
 }
 
+/// Allocate one free port, with the same race window as [`allocate_dev_server_ports`]
+pub fn allocate_port() -> std::io::Result<u16> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let port = listener.local_addr()?.port();
+    drop(listener);
+    Ok(port)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
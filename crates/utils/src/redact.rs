@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::log_msg::LogMsg;
+
+/// Replacement for a redacted secret value
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Values shorter than this are not redacted; masking every occurrence of a two-letter
+/// string would make logs unreadable while protecting nothing
+const MIN_SECRET_LEN: usize = 4;
+
+/// Masks known secret values in process output and normalized log entries
+#[derive(Debug, Clone, Default)]
+pub struct SecretRedactor {
+    /// Longest first, so a secret containing another one is masked as a whole
+    secrets: Arc<Vec<String>>,
+}
+
+impl SecretRedactor {
+    pub fn new<I, S>(values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut secrets: Vec<String> = values
+            .into_iter()
+            .map(Into::into)
+            .filter(|v| v.len() >= MIN_SECRET_LEN)
+            .collect();
+        secrets.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        secrets.dedup();
+        Self {
+            secrets: Arc::new(secrets),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.secrets.is_empty()
+    }
+
+    pub fn redact(&self, text: &str) -> String {
+        let mut redacted = text.to_string();
+        for secret in self.secrets.iter() {
+            if redacted.contains(secret.as_str()) {
+                redacted = redacted.replace(secret.as_str(), REDACTED);
+            }
+        }
+        redacted
+    }
+
+    /// Redact every string in a JSON value, returning whether anything changed
+    pub fn redact_json(&self, value: &mut Value) -> bool {
+        match value {
+            Value::String(s) => {
+                let redacted = self.redact(s);
+                let changed = redacted != *s;
+                *s = redacted;
+                changed
+            }
+            Value::Array(items) => items
+                .iter_mut()
+                .fold(false, |changed, item| self.redact_json(item) | changed),
+            Value::Object(map) => map
+                .values_mut()
+                .fold(false, |changed, item| self.redact_json(item) | changed),
+            _ => false,
+        }
+    }
+
+    /// Redact a log message that is complete on its own (not part of a chunked stream)
+    pub fn redact_msg(&self, msg: LogMsg) -> LogMsg {
+        if self.is_empty() {
+            return msg;
+        }
+        match msg {
+            LogMsg::Stdout(s) => LogMsg::Stdout(self.redact(&s)),
+            LogMsg::Stderr(s) => LogMsg::Stderr(self.redact(&s)),
+            LogMsg::JsonPatch(patch) => {
+                let Ok(mut value) = serde_json::to_value(&patch) else {
+                    return LogMsg::JsonPatch(patch);
+                };
+                if !self.redact_json(&mut value) {
+                    return LogMsg::JsonPatch(patch);
+                }
+                match serde_json::from_value(value) {
+                    Ok(redacted) => LogMsg::JsonPatch(redacted),
+                    Err(_) => LogMsg::JsonPatch(patch),
+                }
+            }
+            other => other,
+        }
+    }
+
+    /// Redact the next chunk of a stream. A secret may be split across chunks, so any tail
+    /// that could be the start of a secret is held back in `pending` until the next chunk
+    /// (or [`Self::flush`]) decides it.
+    pub fn redact_chunk(&self, pending: &mut String, chunk: &str) -> String {
+        pending.push_str(chunk);
+        let redacted = self.redact(pending);
+        let hold = self.partial_secret_suffix(&redacted);
+        let split = redacted.len() - hold;
+        *pending = redacted[split..].to_string();
+        redacted[..split].to_string()
+    }
+
+    /// Release whatever [`Self::redact_chunk`] held back once the stream has ended
+    pub fn flush(&self, pending: &mut String) -> String {
+        std::mem::take(pending)
+    }
+
+    /// Length of the longest suffix of `text` that is a proper prefix of a secret
+    fn partial_secret_suffix(&self, text: &str) -> usize {
+        self.secrets
+            .iter()
+            .filter_map(|secret| {
+                (1..secret.len().min(text.len() + 1))
+                    .rev()
+                    .filter(|&len| secret.is_char_boundary(len))
+                    .find(|&len| text.ends_with(&secret[..len]))
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_longest_secret_first() {
+        let redactor = SecretRedactor::new(["token", "token-with-suffix", "ab"]);
+        assert_eq!(
+            redactor.redact("a token-with-suffix and a token, ab"),
+            format!("a {REDACTED} and a {REDACTED}, ab")
+        );
+    }
+
+    #[test]
+    fn holds_back_secret_split_across_chunks() {
+        let redactor = SecretRedactor::new(["s3cr3t-value"]);
+        let mut pending = String::new();
+
+        assert_eq!(redactor.redact_chunk(&mut pending, "key=s3c"), "key=");
+        assert_eq!(pending, "s3c");
+        assert_eq!(
+            redactor.redact_chunk(&mut pending, "r3t-value\nnext s"),
+            format!("{REDACTED}\nnext ")
+        );
+        assert_eq!(redactor.redact_chunk(&mut pending, "ome"), "some");
+        assert_eq!(redactor.flush(&mut pending), "");
+    }
+
+    #[test]
+    fn flush_releases_unfinished_prefix() {
+        let redactor = SecretRedactor::new(["🔑secret"]);
+        let mut pending = String::new();
+
+        assert_eq!(redactor.redact_chunk(&mut pending, "end 🔑se"), "end ");
+        assert_eq!(redactor.flush(&mut pending), "🔑se");
+    }
+
+    #[test]
+    fn redacts_json_patch_values() {
+        let redactor = SecretRedactor::new(["hunter22"]);
+        let patch: json_patch::Patch = serde_json::from_value(serde_json::json!([
+            { "op": "add", "path": "/entries/0", "value": { "content": "password is hunter22" } }
+        ]))
+        .unwrap();
+
+        let LogMsg::JsonPatch(redacted) = redactor.redact_msg(LogMsg::JsonPatch(patch)) else {
+            panic!("expected a patch");
+        };
+        let value = serde_json::to_value(redacted).unwrap();
+        assert_eq!(
+            value[0]["value"]["content"],
+            format!("password is {REDACTED}")
+        );
+    }
+}
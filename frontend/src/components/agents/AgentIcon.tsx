@@ -38,6 +38,10 @@ export function getAgentName(
       return 'Copilot';
     case BaseCodingAgent.DROID:
       return 'Droid';
+    case BaseCodingAgent.AIDER:
+      return 'Aider';
+    case BaseCodingAgent.CUSTOM:
+      return 'Custom';
   }
 }
 
@@ -8,11 +8,14 @@ import {
   Clock,
   Cog,
   ArrowLeft,
+  TimerOff,
 } from 'lucide-react';
 import { executionProcessesApi } from '@/lib/api.ts';
+import { formatFileSize } from '@/lib/utils';
 import { ProfileVariantBadge } from '@/components/common/ProfileVariantBadge.tsx';
 import { useExecutionProcesses } from '@/hooks/useExecutionProcesses';
 import { useLogStream } from '@/hooks/useLogStream';
+import { isCodingAgent } from '@/constants/processes';
 import { ProcessLogsViewerContent } from './ProcessLogsViewer';
 import type { ExecutionProcessStatus, ExecutionProcess } from 'shared/types';
 
@@ -28,6 +31,7 @@ function ProcessesTab({ sessionId }: ProcessesTabProps) {
   const {
     executionProcesses,
     executionProcessesById,
+    resourceUsageById,
     isLoading: processesLoading,
     isConnected,
     error: processesError,
@@ -38,6 +42,10 @@ function ProcessesTab({ sessionId }: ProcessesTabProps) {
     Record<string, ExecutionProcess>
   >({});
   const [copied, setCopied] = useState(false);
+  const [renormalizing, setRenormalizing] = useState(false);
+  const [renormalizeStatus, setRenormalizeStatus] = useState<string | null>(
+    null
+  );
 
   const selectedProcess = selectedProcessId
     ? localProcessDetails[selectedProcessId] ||
@@ -51,6 +59,35 @@ function ProcessesTab({ sessionId }: ProcessesTabProps) {
     setLoadingProcessId(null);
   }, [sessionId]);
 
+  useEffect(() => {
+    setRenormalizeStatus(null);
+  }, [selectedProcessId]);
+
+  const canRenormalize =
+    !!selectedProcess &&
+    isCodingAgent(selectedProcess.run_reason) &&
+    selectedProcess.status !== 'running';
+
+  const handleRenormalize = useCallback(async () => {
+    if (!selectedProcess) return;
+
+    setRenormalizing(true);
+    setRenormalizeStatus(null);
+    try {
+      const result = await executionProcessesApi.renormalize(
+        selectedProcess.id
+      );
+      setRenormalizeStatus(
+        t('processes.renormalized', { count: result.entries.length })
+      );
+    } catch (err) {
+      console.error('Failed to re-normalize process logs:', err);
+      setRenormalizeStatus(t('processes.renormalizeFailed'));
+    } finally {
+      setRenormalizing(false);
+    }
+  }, [selectedProcess, t]);
+
   const handleCopyLogs = useCallback(async () => {
     if (logs.length === 0) return;
 
@@ -74,6 +111,8 @@ function ProcessesTab({ sessionId }: ProcessesTabProps) {
         return <AlertCircle className="h-4 w-4 text-destructive" />;
       case 'killed':
         return <Square className="h-4 w-4 text-gray-500" />;
+      case 'timedout':
+        return <TimerOff className="h-4 w-4 text-orange-500" />;
       default:
         return <Clock className="h-4 w-4 text-gray-400" />;
     }
@@ -89,6 +128,8 @@ function ProcessesTab({ sessionId }: ProcessesTabProps) {
         return 'bg-red-50 border-red-200 text-red-800';
       case 'killed':
         return 'bg-gray-50 border-gray-200 text-gray-800';
+      case 'timedout':
+        return 'bg-orange-50 border-orange-200 text-orange-800';
       default:
         return 'bg-gray-50 border-gray-200 text-gray-800';
     }
@@ -250,6 +291,19 @@ function ProcessesTab({ sessionId }: ProcessesTabProps) {
                           })}
                         </p>
                       )}
+                      {process.status === 'running' &&
+                        resourceUsageById[process.id] && (
+                          <p className="text-xs text-muted-foreground mt-1">
+                            {t('processes.resources', {
+                              memory: formatFileSize(
+                                BigInt(resourceUsageById[process.id].rss_bytes)
+                              ),
+                              cpu: Math.round(
+                                resourceUsageById[process.id].cpu_percent
+                              ),
+                            })}
+                          </p>
+                        )}
                     </div>
                   </div>
                   <div className="mt-3 text-xs text-muted-foreground">
@@ -280,6 +334,22 @@ function ProcessesTab({ sessionId }: ProcessesTabProps) {
               {t('processes.detailsTitle')}
             </h2>
             <div className="flex items-center gap-2">
+              {renormalizeStatus && (
+                <span className="text-sm text-muted-foreground">
+                  {renormalizeStatus}
+                </span>
+              )}
+              {canRenormalize && (
+                <button
+                  onClick={handleRenormalize}
+                  disabled={renormalizing}
+                  className="flex items-center gap-2 px-3 py-2 text-sm font-medium text-muted-foreground hover:text-foreground hover:bg-muted/50 rounded-md border border-border transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+                >
+                  {renormalizing
+                    ? t('processes.renormalizing')
+                    : t('processes.renormalize')}
+                </button>
+              )}
               <button
                 onClick={handleCopyLogs}
                 disabled={logs.length === 0}
@@ -228,6 +228,22 @@ export const Actions = {
     },
   },
 
+  AutoMergeWorkspace: {
+    id: 'auto-merge-workspace',
+    label: (workspace?: Workspace) =>
+      workspace?.auto_merge ? 'Disable Auto-Merge' : 'Enable Auto-Merge',
+    icon: GitMergeIcon,
+    requiresTarget: true,
+    isVisible: (ctx) => ctx.hasWorkspace,
+    execute: async (ctx, workspaceId) => {
+      const workspace = getWorkspaceFromCache(ctx.queryClient, workspaceId);
+      await attemptsApi.update(workspaceId, {
+        auto_merge: !workspace.auto_merge,
+      });
+      invalidateWorkspaceQueries(ctx.queryClient, workspaceId);
+    },
+  },
+
   ArchiveWorkspace: {
     id: 'archive-workspace',
     label: (workspace?: Workspace) =>
@@ -1,10 +1,12 @@
 import { useCallback, useState, useEffect } from 'react';
+import { useQuery } from '@tanstack/react-query';
 import { PreviewControls } from '../views/PreviewControls';
 import { usePreviewDevServer } from '../hooks/usePreviewDevServer';
 import { usePreviewUrl } from '../hooks/usePreviewUrl';
 import { useLogStream } from '@/hooks/useLogStream';
 import { useLayoutStore } from '@/stores/useLayoutStore';
 import { useWorkspaceContext } from '@/contexts/WorkspaceContext';
+import { attemptsApi } from '@/lib/api';
 
 interface PreviewControlsContainerProps {
   attemptId?: string;
@@ -50,6 +52,14 @@ export function PreviewControlsContainer({
   const { logs: primaryLogs } = useLogStream(primaryDevServer?.id ?? '');
   const urlInfo = usePreviewUrl(primaryLogs);
 
+  // Stable URL through the preview proxy, which survives dev server restarts
+  const { data: previewUrls } = useQuery({
+    queryKey: ['previewUrls', attemptId],
+    queryFn: () => attemptsApi.getPreviewUrls(attemptId!),
+    enabled: !!attemptId,
+    retry: false,
+  });
+
   const handleViewFullLogs = useCallback(
     (processId?: string) => {
       const targetId = processId ?? activeProcess?.id;
@@ -79,10 +89,11 @@ export function PreviewControlsContainer({
   }, [triggerPreviewRefresh]);
 
   const handleCopyUrl = useCallback(async () => {
-    if (urlInfo?.url) {
-      await navigator.clipboard.writeText(urlInfo.url);
+    const url = previewUrls?.host_url ?? urlInfo?.url;
+    if (url) {
+      await navigator.clipboard.writeText(url);
     }
-  }, [urlInfo?.url]);
+  }, [previewUrls?.host_url, urlInfo?.url]);
 
   const handleOpenInNewTab = useCallback(() => {
     if (urlInfo?.url) {
@@ -91,7 +102,9 @@ export function PreviewControlsContainer({
   }, [urlInfo?.url]);
 
   const hasDevScript = repos.some(
-    (repo) => repo.dev_server_script && repo.dev_server_script.trim() !== ''
+    (repo) =>
+      (repo.dev_server_script && repo.dev_server_script.trim() !== '') ||
+      (repo.dev_processes && repo.dev_processes.trim() !== '')
   );
 
   // Don't render if no repos have dev server scripts configured
@@ -24,7 +24,12 @@ export interface SidebarWorkspace {
   hasRunningDevServer?: boolean;
   hasUnseenActivity?: boolean;
   latestProcessCompletedAt?: string;
-  latestProcessStatus?: 'running' | 'completed' | 'failed' | 'killed';
+  latestProcessStatus?:
+    | 'running'
+    | 'completed'
+    | 'failed'
+    | 'killed'
+    | 'timedout';
   prStatus?: 'open' | 'merged' | 'closed' | 'unknown';
 }
 
@@ -28,7 +28,9 @@ export function PreviewBrowser({
   const showIframe = url && !isLoading && isServerRunning;
 
   const hasDevScript = repos.some(
-    (repo) => repo.dev_server_script && repo.dev_server_script.trim() !== ''
+    (repo) =>
+      (repo.dev_server_script && repo.dev_server_script.trim() !== '') ||
+      (repo.dev_processes && repo.dev_processes.trim() !== '')
   );
 
   return (
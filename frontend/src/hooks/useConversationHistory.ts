@@ -220,7 +220,8 @@ export const useConversationHistory = ({
       executionProcesses?.current.filter(
         (p) =>
           p.status === ExecutionProcessStatus.running &&
-          p.run_reason !== 'devserver'
+          p.run_reason !== 'devserver' &&
+          p.run_reason !== 'terminal'
       ) ?? []
     );
   };
@@ -303,6 +304,7 @@ export const useConversationHistory = ({
               liveProcessStatus === ExecutionProcessStatus.running;
             const processFailedOrKilled =
               liveProcessStatus === ExecutionProcessStatus.failed ||
+              liveProcessStatus === ExecutionProcessStatus.timedout ||
               liveProcessStatus === ExecutionProcessStatus.killed;
 
             if (isProcessRunning) {
@@ -365,6 +367,7 @@ export const useConversationHistory = ({
 
             if (
               (executionProcess?.status === ExecutionProcessStatus.failed ||
+                executionProcess?.status === ExecutionProcessStatus.timedout ||
                 executionProcess?.status === ExecutionProcessStatus.killed) &&
               index === Object.keys(executionProcessState).length - 1
             ) {
@@ -383,7 +386,8 @@ export const useConversationHistory = ({
             const toolStatus: ToolStatus =
               executionProcess?.status === ExecutionProcessStatus.running
                 ? { status: 'created' }
-                : exitCode === 0
+                : exitCode === 0 &&
+                    executionProcess?.status !== ExecutionProcessStatus.timedout
                   ? { status: 'success' }
                   : { status: 'failed' };
 
@@ -1,14 +1,16 @@
 import { useCallback, useEffect } from 'react';
 import { useJsonPatchWsStream } from './useJsonPatchWsStream';
-import type { ExecutionProcess } from 'shared/types';
+import type { ExecutionProcess, ResourceUsage } from 'shared/types';
 
 type ExecutionProcessState = {
   execution_processes: Record<string, ExecutionProcess>;
+  resource_usage: Record<string, ResourceUsage>;
 };
 
 interface UseExecutionProcessesResult {
   executionProcesses: ExecutionProcess[];
   executionProcessesById: Record<string, ExecutionProcess>;
+  resourceUsageById: Record<string, ResourceUsage>;
   isAttemptRunning: boolean;
   isLoading: boolean;
   isConnected: boolean;
@@ -19,6 +21,7 @@ interface UseExecutionProcessesResult {
  * Stream execution processes for a session via WebSocket (JSON Patch) and expose as array + map.
  * Server sends initial snapshot: replace /execution_processes with an object keyed by id.
  * Live updates arrive at /execution_processes/<id> via add/replace/remove operations.
+ * The latest CPU/memory sample of each running process arrives at /resource_usage/<id>.
  */
 export const useExecutionProcesses = (
   sessionId: string | undefined,
@@ -36,7 +39,10 @@ export const useExecutionProcesses = (
   }
 
   const initialData = useCallback(
-    (): ExecutionProcessState => ({ execution_processes: {} }),
+    (): ExecutionProcessState => ({
+      execution_processes: {},
+      resource_usage: {},
+    }),
     []
   );
 
@@ -48,6 +54,7 @@ export const useExecutionProcesses = (
     );
 
   const executionProcessesById = data?.execution_processes ?? {};
+  const resourceUsageById = data?.resource_usage ?? {};
   const executionProcesses = Object.values(executionProcessesById).sort(
     (a, b) =>
       new Date(a.created_at as unknown as string).getTime() -
@@ -92,6 +99,7 @@ export const useExecutionProcesses = (
   return {
     executionProcesses,
     executionProcessesById,
+    resourceUsageById,
     isAttemptRunning,
     isLoading,
     isConnected,
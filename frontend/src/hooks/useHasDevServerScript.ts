@@ -9,7 +9,9 @@ export function useHasDevServerScript(projectId?: string) {
 
       const repos = await projectsApi.getRepositories(projectId);
       return repos.some(
-        (repo) => repo.dev_server_script && repo.dev_server_script.trim() !== ''
+        (repo) =>
+          (repo.dev_server_script && repo.dev_server_script.trim() !== '') ||
+          (repo.dev_processes && repo.dev_processes.trim() !== '')
       );
     },
     enabled: !!projectId,
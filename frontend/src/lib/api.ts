@@ -5,6 +5,8 @@ import {
   ApiResponse,
   CategoriesResponse,
   CategorizeTaskResponse,
+  CheckpointRevert,
+  CheckpointTimeline,
   Config,
   CreateFollowUpAttempt,
   EditorType,
@@ -49,6 +51,7 @@ import {
   ChangeTargetBranchResponse,
   RenameBranchRequest,
   RenameBranchResponse,
+  RenormalizeResponse,
   CheckEditorAvailabilityResponse,
   AvailabilityInfo,
   BaseCodingAgent,
@@ -93,6 +96,11 @@ import {
   RepoBranchStatus,
   AbortConflictsRequest,
   Session,
+  SessionHandoffRequest,
+  SessionHandoffResponse,
+  TaskPlan,
+  UpdateTaskPlan,
+  ApproveTaskPlan,
   Workspace,
   GitHubIssueResponse,
   GitHubRepoInfoResponse,
@@ -101,6 +109,13 @@ import {
   MergeTasksResponse,
   BulkMergeRequest,
   BulkMergeResponse,
+  WorkspaceTemplate,
+  SaveWorkspaceTemplate,
+  Secret,
+  CreateSecret,
+  UpdateSecret,
+  DevProcessStatus,
+  PreviewUrls,
 } from 'shared/types';
 import type { WorkspaceWithSession } from '@/types/attempt';
 import { createWorkspaceWithSession } from '@/types/attempt';
@@ -381,6 +396,98 @@ export const projectsApi = {
     });
     return handleApiResponse<Project>(response);
   },
+
+  getWorkspaceTemplates: async (
+    projectId: string
+  ): Promise<WorkspaceTemplate[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/workspace-templates`
+    );
+    return handleApiResponse<WorkspaceTemplate[]>(response);
+  },
+
+  createWorkspaceTemplate: async (
+    projectId: string,
+    data: SaveWorkspaceTemplate
+  ): Promise<WorkspaceTemplate> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/workspace-templates`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<WorkspaceTemplate>(response);
+  },
+
+  updateWorkspaceTemplate: async (
+    projectId: string,
+    templateId: string,
+    data: SaveWorkspaceTemplate
+  ): Promise<WorkspaceTemplate> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/workspace-templates/${templateId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<WorkspaceTemplate>(response);
+  },
+
+  deleteWorkspaceTemplate: async (
+    projectId: string,
+    templateId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/workspace-templates/${templateId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getSecrets: async (projectId: string): Promise<Secret[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/secrets`);
+    return handleApiResponse<Secret[]>(response);
+  },
+
+  createSecret: async (
+    projectId: string,
+    data: CreateSecret
+  ): Promise<Secret> => {
+    const response = await makeRequest(`/api/projects/${projectId}/secrets`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Secret>(response);
+  },
+
+  updateSecret: async (
+    projectId: string,
+    secretId: string,
+    data: UpdateSecret
+  ): Promise<Secret> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/secrets/${secretId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<Secret>(response);
+  },
+
+  deleteSecret: async (projectId: string, secretId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/secrets/${secretId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
 };
 
 // Project Groups APIs
@@ -442,6 +549,40 @@ export const tasksApi = {
     return handleApiResponse<TaskWithAttemptStatus>(response);
   },
 
+  getPlans: async (taskId: string): Promise<TaskPlan[]> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/plans`);
+    return handleApiResponse<TaskPlan[]>(response);
+  },
+
+  updatePlan: async (
+    planId: string,
+    data: UpdateTaskPlan
+  ): Promise<TaskPlan> => {
+    const response = await makeRequest(`/api/task-plans/${planId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskPlan>(response);
+  },
+
+  approvePlan: async (
+    planId: string,
+    data: ApproveTaskPlan
+  ): Promise<ExecutionProcess> => {
+    const response = await makeRequest(`/api/task-plans/${planId}/approve`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ExecutionProcess>(response);
+  },
+
+  rejectPlan: async (planId: string): Promise<void> => {
+    const response = await makeRequest(`/api/task-plans/${planId}/reject`, {
+      method: 'POST',
+    });
+    return handleApiResponse<void>(response);
+  },
+
   update: async (taskId: string, data: UpdateTask): Promise<Task> => {
     const response = await makeRequest(`/api/tasks/${taskId}`, {
       method: 'PUT',
@@ -573,6 +714,17 @@ export const sessionsApi = {
     });
     return handleApiResponse<ExecutionProcess>(response);
   },
+
+  handoff: async (
+    sessionId: string,
+    data: SessionHandoffRequest
+  ): Promise<SessionHandoffResponse> => {
+    const response = await makeRequest(`/api/sessions/${sessionId}/handoff`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<SessionHandoffResponse>(response);
+  },
 };
 
 // Task Attempts APIs
@@ -602,7 +754,12 @@ export const attemptsApi = {
 
   update: async (
     attemptId: string,
-    data: { archived?: boolean; pinned?: boolean; name?: string }
+    data: {
+      archived?: boolean;
+      pinned?: boolean;
+      auto_merge?: boolean;
+      name?: string;
+    }
   ): Promise<Workspace> => {
     const response = await makeRequest(`/api/task-attempts/${attemptId}`, {
       method: 'PUT',
@@ -808,6 +965,34 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  getPreviewUrls: async (attemptId: string): Promise<PreviewUrls> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/preview-urls`
+    );
+    return handleApiResponse<PreviewUrls>(response);
+  },
+
+  getDevProcesses: async (attemptId: string): Promise<DevProcessStatus[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/dev-processes`
+    );
+    return handleApiResponse<DevProcessStatus[]>(response);
+  },
+
+  controlDevProcess: async (
+    attemptId: string,
+    name: string,
+    action: 'start' | 'stop' | 'restart'
+  ): Promise<ExecutionProcess | null> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/dev-processes/${encodeURIComponent(name)}/${action}`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<ExecutionProcess | null>(response);
+  },
+
   setupGhCli: async (attemptId: string): Promise<ExecutionProcess> => {
     const response = await makeRequest(
       `/api/task-attempts/${attemptId}/gh-cli-setup`,
@@ -866,6 +1051,42 @@ export const attemptsApi = {
     );
     return handleApiResponse<void>(response);
   },
+
+  getCheckpoints: async (attemptId: string): Promise<CheckpointTimeline> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checkpoints`
+    );
+    return handleApiResponse<CheckpointTimeline>(response);
+  },
+
+  revertToCheckpoint: async (
+    attemptId: string,
+    processId: string
+  ): Promise<CheckpointRevert> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checkpoints/${processId}/revert`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<CheckpointRevert>(response);
+  },
+
+  undoCheckpointRevert: async (
+    attemptId: string,
+    revertId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checkpoint-reverts/${revertId}/undo`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getTerminalUrl: (attemptId: string, cols: number, rows: number): string =>
+    `/api/task-attempts/${attemptId}/terminal/ws?cols=${cols}&rows=${rows}`,
 };
 
 // Execution Process APIs
@@ -893,6 +1114,16 @@ export const executionProcessesApi = {
     );
     return handleApiResponse<void>(response);
   },
+
+  renormalize: async (processId: string): Promise<RenormalizeResponse> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/renormalize`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<RenormalizeResponse>(response);
+  },
 };
 
 // File System APIs
@@ -20,6 +20,7 @@ import {
 } from '@/components/ui/select';
 import { Label } from '@/components/ui/label';
 import { Input } from '@/components/ui/input';
+import { AutoExpandingTextarea } from '@/components/ui/auto-expanding-textarea';
 import { Alert, AlertDescription } from '@/components/ui/alert';
 import { Loader2, Plus, Trash2 } from 'lucide-react';
 import { useProjects } from '@/hooks/useProjects';
@@ -27,15 +28,44 @@ import { useProjectMutations } from '@/hooks/useProjectMutations';
 import { RepoPickerDialog } from '@/components/dialogs/shared/RepoPickerDialog';
 import { projectsApi } from '@/lib/api';
 import { repoBranchKeys } from '@/hooks/useRepoBranches';
-import type { Project, Repo, UpdateProject } from 'shared/types';
+import type {
+  LoopPolicy,
+  PrMergeMethod,
+  Project,
+  Repo,
+  UpdateProject,
+} from 'shared/types';
 
 interface ProjectFormState {
   name: string;
+  pr_merge_method: PrMergeMethod;
+  loop_policy: LoopPolicy;
+  max_memory_mb: number | null;
+  max_cpu_percent: number | null;
+  dev_processes: string;
+}
+
+const PR_MERGE_METHODS: PrMergeMethod[] = ['squash', 'merge', 'rebase'];
+const LOOP_POLICIES: LoopPolicy[] = ['off', 'follow_up', 'pause', 'stop'];
+
+const DEV_PROCESSES_PLACEHOLDER = `api: cd backend && cargo run
+web: cd frontend && npm run dev -- --port $PORT
+storybook: cd frontend && npm run storybook -- -p $PORT`;
+
+// Empty input means no limit
+function parseLimit(value: string): number | null {
+  const parsed = Number.parseInt(value, 10);
+  return Number.isFinite(parsed) && parsed > 0 ? parsed : null;
 }
 
 function projectToFormState(project: Project): ProjectFormState {
   return {
     name: project.name,
+    pr_merge_method: project.pr_merge_method,
+    loop_policy: project.loop_policy,
+    max_memory_mb: project.max_memory_mb,
+    max_cpu_percent: project.max_cpu_percent,
+    dev_processes: project.dev_processes ?? '',
   };
 }
 
@@ -293,6 +323,11 @@ export function ProjectSettings() {
       const updateData: UpdateProject = {
         name: draft.name.trim(),
         group_id: selectedProject.group_id ?? null,
+        pr_merge_method: draft.pr_merge_method,
+        loop_policy: draft.loop_policy,
+        max_memory_mb: draft.max_memory_mb,
+        max_cpu_percent: draft.max_cpu_percent,
+        dev_processes: draft.dev_processes.trim() || null,
       };
 
       updateProject.mutate({
@@ -426,6 +461,136 @@ export function ProjectSettings() {
                 </p>
               </div>
 
+              <div className="space-y-2">
+                <Label htmlFor="project-pr-merge-method">
+                  {t('settings.projects.general.prMergeMethod.label')}
+                </Label>
+                <Select
+                  value={draft.pr_merge_method}
+                  onValueChange={(value) =>
+                    updateDraft({ pr_merge_method: value as PrMergeMethod })
+                  }
+                >
+                  <SelectTrigger id="project-pr-merge-method">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    {PR_MERGE_METHODS.map((method) => (
+                      <SelectItem key={method} value={method}>
+                        {t(
+                          `settings.projects.general.prMergeMethod.options.${method}`
+                        )}
+                      </SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.projects.general.prMergeMethod.helper')}
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <Label htmlFor="project-loop-policy">
+                  {t('settings.projects.general.loopPolicy.label')}
+                </Label>
+                <Select
+                  value={draft.loop_policy}
+                  onValueChange={(value) =>
+                    updateDraft({ loop_policy: value as LoopPolicy })
+                  }
+                >
+                  <SelectTrigger id="project-loop-policy">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    {LOOP_POLICIES.map((policy) => (
+                      <SelectItem key={policy} value={policy}>
+                        {t(
+                          `settings.projects.general.loopPolicy.options.${policy}`
+                        )}
+                      </SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.projects.general.loopPolicy.helper')}
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <Label>
+                  {t('settings.projects.general.resourceLimits.label')}
+                </Label>
+                <div className="grid grid-cols-2 gap-4">
+                  <div className="space-y-1">
+                    <Label
+                      htmlFor="project-max-memory"
+                      className="text-xs text-muted-foreground"
+                    >
+                      {t('settings.projects.general.resourceLimits.memory')}
+                    </Label>
+                    <Input
+                      id="project-max-memory"
+                      type="number"
+                      min={1}
+                      value={draft.max_memory_mb ?? ''}
+                      onChange={(e) =>
+                        updateDraft({
+                          max_memory_mb: parseLimit(e.target.value),
+                        })
+                      }
+                      placeholder={t(
+                        'settings.projects.general.resourceLimits.unlimited'
+                      )}
+                    />
+                  </div>
+                  <div className="space-y-1">
+                    <Label
+                      htmlFor="project-max-cpu"
+                      className="text-xs text-muted-foreground"
+                    >
+                      {t('settings.projects.general.resourceLimits.cpu')}
+                    </Label>
+                    <Input
+                      id="project-max-cpu"
+                      type="number"
+                      min={1}
+                      value={draft.max_cpu_percent ?? ''}
+                      onChange={(e) =>
+                        updateDraft({
+                          max_cpu_percent: parseLimit(e.target.value),
+                        })
+                      }
+                      placeholder={t(
+                        'settings.projects.general.resourceLimits.unlimited'
+                      )}
+                    />
+                  </div>
+                </div>
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.projects.general.resourceLimits.helper')}
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <Label htmlFor="project-dev-processes">
+                  {t('settings.projects.general.devProcesses.label')}
+                </Label>
+                <AutoExpandingTextarea
+                  id="project-dev-processes"
+                  value={draft.dev_processes}
+                  onChange={(e) =>
+                    updateDraft({ dev_processes: e.target.value })
+                  }
+                  placeholder={DEV_PROCESSES_PLACEHOLDER}
+                  maxRows={12}
+                  className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md focus:outline-none focus:ring-2 focus:ring-ring font-mono"
+                />
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.projects.general.devProcesses.helper')}
+                </p>
+              </div>
+
               {/* Save Button */}
               <div className="flex items-center justify-between pt-4 border-t">
                 {hasUnsavedChanges ? (
@@ -35,8 +35,14 @@ interface RepoScriptsFormState {
   cleanup_script: string;
   copy_files: string;
   dev_server_script: string;
+  dev_processes: string;
+  warm_pool_size: number;
 }
 
+const DEV_PROCESSES_PLACEHOLDER = `api: cargo run --bin api
+web: npm run dev -- --port $PORT # ready: Local:\\s+http
+worker: npm run worker # ready: started`;
+
 function repoToFormState(repo: Repo): RepoScriptsFormState {
   return {
     display_name: repo.display_name,
@@ -45,6 +51,8 @@ function repoToFormState(repo: Repo): RepoScriptsFormState {
     cleanup_script: repo.cleanup_script ?? '',
     copy_files: repo.copy_files ?? '',
     dev_server_script: repo.dev_server_script ?? '',
+    dev_processes: repo.dev_processes ?? '',
+    warm_pool_size: repo.warm_pool_size,
   };
 }
 
@@ -181,6 +189,8 @@ export function ReposSettings() {
         copy_files: draft.copy_files.trim() || null,
         parallel_setup_script: draft.parallel_setup_script,
         dev_server_script: draft.dev_server_script.trim() || null,
+        dev_processes: draft.dev_processes.trim() || null,
+        warm_pool_size: draft.warm_pool_size,
       };
 
       const updatedRepo = await repoApi.update(selectedRepo.id, updateData);
@@ -356,6 +366,27 @@ export function ReposSettings() {
                 </p>
               </div>
 
+              <div className="space-y-2">
+                <Label htmlFor="dev-processes">
+                  {t('settings.repos.scripts.devProcesses.label')}
+                </Label>
+                <AutoExpandingTextarea
+                  id="dev-processes"
+                  value={draft.dev_processes}
+                  onChange={(e) =>
+                    updateDraft({
+                      dev_processes: e.target.value,
+                    })
+                  }
+                  placeholder={DEV_PROCESSES_PLACEHOLDER}
+                  maxRows={12}
+                  className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md focus:outline-none focus:ring-2 focus:ring-ring font-mono"
+                />
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.repos.scripts.devProcesses.helper')}
+                </p>
+              </div>
+
               <div className="space-y-2">
                 <Label htmlFor="setup-script">
                   {t('settings.repos.scripts.setup.label')}
@@ -397,6 +428,30 @@ export function ReposSettings() {
                 </p>
               </div>
 
+              <div className="space-y-2">
+                <Label htmlFor="warm-pool-size">
+                  {t('settings.repos.scripts.warmPool.label')}
+                </Label>
+                <Input
+                  id="warm-pool-size"
+                  type="number"
+                  min={0}
+                  value={draft.warm_pool_size}
+                  onChange={(e) =>
+                    updateDraft({
+                      warm_pool_size: Math.max(
+                        0,
+                        Number.parseInt(e.target.value, 10) || 0
+                      ),
+                    })
+                  }
+                  className="w-32"
+                />
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.repos.scripts.warmPool.helper')}
+                </p>
+              </div>
+
               <div className="space-y-2">
                 <Label htmlFor="cleanup-script">
                   {t('settings.repos.scripts.cleanup.label')}
@@ -33,6 +33,7 @@ export function createMockWorkspace(
     updated_at: new Date().toISOString(),
     archived: false,
     pinned: false,
+    auto_merge: false,
     name: null,
     ...overrides,
   };
@@ -0,0 +1,80 @@
+const http = require("http");
+const https = require("https");
+
+const { resolveBackendUrl, get } = require("./export");
+
+function usage() {
+  console.error("Usage: vibe-kanban backup <list|create|restore <name>>");
+  console.error("");
+  console.error("Manages database backups of a running vibe-kanban instance.");
+  console.error("A restore is staged and applied the next time vibe-kanban starts.");
+  console.error("To restore without a running instance, start it with VK_RESTORE_BACKUP=<name>.");
+}
+
+function post(url) {
+  const client = url.startsWith("https:") ? https : http;
+  return new Promise((resolve, reject) => {
+    const req = client.request(url, { method: "POST" }, (res) => {
+      const chunks = [];
+      res.on("data", (c) => chunks.push(c));
+      res.on("end", () =>
+        resolve({ status: res.statusCode, body: Buffer.concat(chunks) })
+      );
+    });
+    req.on("error", reject);
+    req.end();
+  });
+}
+
+function unwrap(res, action) {
+  let body = null;
+  try {
+    body = JSON.parse(res.body.toString("utf8"));
+  } catch {}
+  if (res.status !== 200 || !body || !body.success) {
+    const message = (body && body.message) || res.body.toString("utf8");
+    throw new Error(`${action} failed (HTTP ${res.status}): ${message}`);
+  }
+  return body.data;
+}
+
+function formatSize(bytes) {
+  if (bytes < 1024 * 1024) {
+    return `${(bytes / 1024).toFixed(1)} KB`;
+  }
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+async function backup(args) {
+  const [command, name] = args;
+  const base = `${resolveBackendUrl()}/api/backups`;
+
+  if (command === "list") {
+    const backups = unwrap(await get(base), "Listing backups");
+    if (backups.length === 0) {
+      console.log("No backups found.");
+      return;
+    }
+    for (const b of backups) {
+      console.log(
+        `${b.name}\t${b.kind}\t${formatSize(b.size_bytes)}\tschema ${b.schema_version ?? "?"}`
+      );
+    }
+  } else if (command === "create") {
+    const info = unwrap(await post(base), "Creating backup");
+    console.log(`Created backup ${info.name} (${formatSize(info.size_bytes)})`);
+  } else if (command === "restore" && name) {
+    const result = unwrap(
+      await post(`${base}/${encodeURIComponent(name)}/restore`),
+      "Restoring backup"
+    );
+    console.log(
+      `Restore of ${result.backup.name} staged. Restart vibe-kanban to apply it.`
+    );
+  } else {
+    usage();
+    process.exit(command === "--help" || command === "-h" ? 0 : 1);
+  }
+}
+
+module.exports = { backup };
@@ -4,6 +4,8 @@ const { execSync, spawn } = require("child_process");
 const AdmZip = require("adm-zip");
 const path = require("path");
 const fs = require("fs");
+const { exportTranscript } = require("./export");
+const { backup } = require("./backup");
 const { ensureBinary, BINARY_TAG, CACHE_DIR, LOCAL_DEV_MODE, LOCAL_DIST_DIR, R2_BASE_URL, getLatestVersion } = require("./download");
 
 const CLI_VERSION = require("../package.json").version;
@@ -147,6 +149,18 @@ async function main() {
   const args = process.argv.slice(2);
   const isMcpMode = args.includes("--mcp");
   const isReviewMode = args[0] === "review";
+  const isExportMode = args[0] === "export";
+  const isBackupMode = args[0] === "backup";
+
+  // Export talks to an already running instance, so no binary is needed
+  if (isExportMode) {
+    await exportTranscript(args.slice(1));
+    return;
+  }
+  if (isBackupMode) {
+    await backup(args.slice(1));
+    return;
+  }
 
   // Non-blocking update check (skip in MCP mode, local dev mode, and when R2 URL not configured)
   const hasValidR2Url = !R2_BASE_URL.startsWith("__");
@@ -0,0 +1,100 @@
+const http = require("http");
+const https = require("https");
+const os = require("os");
+const path = require("path");
+const fs = require("fs");
+
+const FORMATS = ["markdown", "md", "json", "html"];
+
+function usage() {
+  console.error("Usage: vibe-kanban export <session-id> [--format markdown|json|html] [--output <file>]");
+  console.error("");
+  console.error("Exports a session transcript from a running vibe-kanban instance.");
+  console.error("The backend is located via VIBE_BACKEND_URL, BACKEND_PORT/PORT, or the port file.");
+}
+
+function parseArgs(args) {
+  const opts = { sessionId: null, format: "markdown", output: null };
+  for (let i = 0; i < args.length; i++) {
+    const arg = args[i];
+    if (arg === "--format" || arg === "-f") {
+      opts.format = args[++i];
+    } else if (arg === "--output" || arg === "-o") {
+      opts.output = args[++i];
+    } else if (arg === "--help" || arg === "-h") {
+      opts.help = true;
+    } else if (!opts.sessionId) {
+      opts.sessionId = arg;
+    } else {
+      throw new Error(`Unexpected argument: ${arg}`);
+    }
+  }
+  return opts;
+}
+
+// Mirrors the lookup order used by the MCP task server
+function resolveBackendUrl() {
+  if (process.env.VIBE_BACKEND_URL) {
+    return process.env.VIBE_BACKEND_URL.replace(/\/$/, "");
+  }
+  const host = process.env.HOST || "127.0.0.1";
+  let port = process.env.BACKEND_PORT || process.env.PORT;
+  if (!port) {
+    const portFile = path.join(os.tmpdir(), "vibe-kanban", "vibe-kanban.port");
+    try {
+      port = fs.readFileSync(portFile, "utf8").trim();
+    } catch {
+      throw new Error(
+        `Could not find a running vibe-kanban instance (no port file at ${portFile})`
+      );
+    }
+  }
+  return `http://${host}:${port}`;
+}
+
+function get(url) {
+  const client = url.startsWith("https:") ? https : http;
+  return new Promise((resolve, reject) => {
+    client
+      .get(url, (res) => {
+        const chunks = [];
+        res.on("data", (c) => chunks.push(c));
+        res.on("end", () =>
+          resolve({ status: res.statusCode, body: Buffer.concat(chunks) })
+        );
+      })
+      .on("error", reject);
+  });
+}
+
+async function exportTranscript(args) {
+  const opts = parseArgs(args);
+  if (opts.help || !opts.sessionId) {
+    usage();
+    process.exit(opts.help ? 0 : 1);
+  }
+  if (!FORMATS.includes(opts.format)) {
+    throw new Error(`Unknown format '${opts.format}'. Use markdown, json or html.`);
+  }
+
+  const url = `${resolveBackendUrl()}/api/sessions/${encodeURIComponent(
+    opts.sessionId
+  )}/transcript?format=${opts.format}`;
+  const res = await get(url);
+  if (res.status !== 200) {
+    let message = res.body.toString("utf8");
+    try {
+      message = JSON.parse(message).message || message;
+    } catch {}
+    throw new Error(`Export failed (HTTP ${res.status}): ${message}`);
+  }
+
+  if (opts.output) {
+    fs.writeFileSync(opts.output, res.body);
+    console.error(`Transcript written to ${opts.output}`);
+  } else {
+    process.stdout.write(res.body);
+  }
+}
+
+module.exports = { exportTranscript, resolveBackendUrl, get };
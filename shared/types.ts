@@ -12,11 +12,28 @@ export type SharedTask = { id: string, organization_id: string, project_id: stri
 
 export type UserData = { user_id: string, first_name: string | null, last_name: string | null, username: string | null, };
 
-export type Project = { id: string, name: string, default_agent_working_dir: string | null, remote_project_id: string | null, group_id: string | null, task_prefix: string | null, created_at: Date, updated_at: Date, };
+export type Project = { id: string, name: string, default_agent_working_dir: string | null, remote_project_id: string | null, group_id: string | null, task_prefix: string | null, pr_merge_method: PrMergeMethod, loop_policy: LoopPolicy, 
+/**
+ * Memory cap for each process the project's workspaces run, in MiB
+ */
+max_memory_mb: number | null, 
+/**
+ * CPU cap for each process the project's workspaces run; 100 is one full core
+ */
+max_cpu_percent: number | null, 
+/**
+ * Named dev processes run in the workspace directory, one `<name>: <command>` per line
+ * like a Procfile
+ */
+dev_processes: string | null, created_at: Date, updated_at: Date, };
 
 export type CreateProject = { name: string, repositories: Array<CreateProjectRepo>, };
 
-export type UpdateProject = { name: string | null, group_id: string | null, };
+export type UpdateProject = { name: string | null, group_id: string | null, pr_merge_method: PrMergeMethod | null, loop_policy: LoopPolicy | null, max_memory_mb: number | null, max_cpu_percent: number | null, dev_processes: string | null, };
+
+export type PrMergeMethod = "squash" | "merge" | "rebase";
+
+export type LoopPolicy = "off" | "follow_up" | "pause" | "stop";
 
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, 
 /**
@@ -32,19 +49,43 @@ export type CreateProjectGroup = { name: string, position: number | null, };
 
 export type UpdateProjectGroup = { name: string | null, position: number | null, };
 
-export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, created_at: Date, updated_at: Date, };
+export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, 
+/**
+ * Worktrees kept ready with the setup script already run, for new workspaces to claim
+ */
+warm_pool_size: number, 
+/**
+ * Named dev processes, one `<name>: <command>` per line like a Procfile
+ */
+dev_processes: string | null, created_at: Date, updated_at: Date, };
 
-export type UpdateRepo = { display_name: string | null, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean | null, dev_server_script: string | null, };
+export type UpdateRepo = { display_name: string | null, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean | null, dev_server_script: string | null, warm_pool_size: number | null, dev_processes: string | null, };
 
 export type ProjectRepo = { id: string, project_id: string, repo_id: string, };
 
 export type CreateProjectRepo = { display_name: string, git_repo_path: string, };
 
+export type BackupKind = "pre_migration" | "scheduled" | "manual" | "pre_restore";
+
+export type BackupInfo = { name: string, kind: BackupKind, created_at: string, size_bytes: bigint, 
+/**
+ * Latest migration applied in the backup
+ */
+schema_version: bigint | null, };
+
 export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string, target_branch: string, created_at: Date, updated_at: Date, };
 
 export type CreateWorkspaceRepo = { repo_id: string, target_branch: string, };
 
-export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, created_at: Date, updated_at: Date, };
+export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, 
+/**
+ * Worktrees kept ready with the setup script already run, for new workspaces to claim
+ */
+warm_pool_size: number, 
+/**
+ * Named dev processes, one `<name>: <command>` per line like a Procfile
+ */
+dev_processes: string | null, created_at: Date, updated_at: Date, };
 
 export type Tag = { id: string, tag_name: string, content: string, created_at: string, updated_at: string, };
 
@@ -174,13 +215,58 @@ export type CreateScratch = { payload: ScratchPayload, };
 
 export type UpdateScratch = { payload: ScratchPayload, };
 
+export type WorkspaceTemplateRepo = { repo_id: string, target_branch: string, 
+/**
+ * Replaces the repository's `copy_files` for workspaces created from the template
+ */
+copy_files: string | null, };
+
+export type WorkspaceTemplate = { id: string, project_id: string, name: string, repos: Array<WorkspaceTemplateRepo>, executor_profile_id: ExecutorProfileId | null, agent_working_dir: string | null, 
+/**
+ * Extra environment variables for every process run in the workspace
+ */
+env: { [key in string]?: string }, created_at: string, updated_at: string, };
+
+export type SaveWorkspaceTemplate = { name: string, repos: Array<WorkspaceTemplateRepo>, executor_profile_id: ExecutorProfileId | null, agent_working_dir: string | null, env: { [key in string]?: string }, };
+
+export type TaskPlanStatus = "drafting" | "awaiting_approval" | "approved" | "rejected" | "failed";
+
+export type TaskPlan = { id: string, task_id: string, workspace_id: string, 
+/**
+ * Session of the planning run
+ */
+session_id: string, content: string | null, status: TaskPlanStatus, 
+/**
+ * Session started when the plan was approved
+ */
+execution_session_id: string | null, created_at: string, updated_at: string, };
+
+/**
+ * Worktree state of one repository just before a revert
+ */
+export type CheckpointRevertRepo = { repo_id: string, 
+/**
+ * HEAD before the revert
+ */
+head_commit: string, 
+/**
+ * Commit holding the worktree contents before the revert, uncommitted changes included
+ */
+snapshot_commit: string, };
+
+export type CheckpointRevert = { id: string, workspace_id: string, 
+/**
+ * The worktrees were reset to the state before this turn ran
+ */
+execution_process_id: string, repos: Array<CheckpointRevertRepo>, dropped_process_ids: Array<string>, undone_at: string | null, created_at: string, };
+
 export type Image = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
 
 export type CreateImage = { file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, };
 
-export type Workspace = { id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, };
+export type Workspace = { id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, auto_merge: boolean, name: string | null, };
 
-export type WorkspaceWithStatus = { is_running: boolean, is_errored: boolean, id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, };
+export type WorkspaceWithStatus = { is_running: boolean, is_errored: boolean, id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, auto_merge: boolean, name: string | null, };
 
 export type Session = { id: string, workspace_id: string, executor: string | null, created_at: string, updated_at: string, };
 
@@ -192,9 +278,9 @@ export type ExecutionProcess = { id: string, session_id: string, run_reason: Exe
  */
 dropped: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
 
-export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
+export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed", timedout = "timedout" }
 
-export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
+export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver" | "terminal";
 
 export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
 
@@ -300,6 +386,12 @@ export type InitRepoRequest = { parent_path: string, folder_name: string, };
 
 export type TagSearchParams = { search: string | null, };
 
+export type RestoreBackupResponse = { backup: BackupInfo, 
+/**
+ * The restore is applied when Vibe Kanban next starts
+ */
+restart_required: boolean, };
+
 export type TokenResponse = { access_token: string, expires_at: string | null, };
 
 export type UserSystemInfo = { config: Config, analytics_user_id: string, login_status: LoginStatus, environment: Environment, 
@@ -326,6 +418,16 @@ export type CurrentUserResponse = { user_id: string, };
 
 export type CreateFollowUpAttempt = { prompt: string, variant: string | null, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
 
+export type SessionHandoffRequest = { executor_profile_id: ExecutorProfileId, 
+/**
+ * What the new executor should do next; defaults to continuing the task
+ */
+prompt: string, };
+
+export type SessionHandoffResponse = { session: Session, execution_process: ExecutionProcess, };
+
+export type RenormalizeResponse = { entries: Array<NormalizedEntry>, };
+
 export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: string, };
 
 export type ChangeTargetBranchResponse = { repo_id: string, new_target_branch: string, status: [number, number], };
@@ -342,11 +444,45 @@ export type OpenEditorRequest = { editor_type: string | null, file_path: string
 
 export type OpenEditorResponse = { url: string | null, };
 
+/**
+ * Stable URLs of the workspace's dev server through the preview proxy
+ */
+export type PreviewUrls = { 
+/**
+ * `http://<short id>.localhost:<port>/`; `<process>.` in front of the host picks a named
+ * dev process
+ */
+host_url: string, 
+/**
+ * `http://localhost:<port>/<short id>/`, for clients that do not resolve `*.localhost`
+ */
+path_url: string, };
+
+export type UpdateTaskPlan = { content: string, };
+
+export type ApproveTaskPlan = { 
+/**
+ * Edited plan; the stored plan is used when omitted
+ */
+content: string | null, 
+/**
+ * Execute with a different profile than the one chosen when the workspace was started
+ */
+executor_profile_id: ExecutorProfileId | null, };
+
 export type AssignSharedTaskRequest = { new_assignee_user_id: string | null, };
 
 export type ShareTaskResponse = { shared_task_id: string, };
 
-export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+export type CreateAndStartTaskRequest = { task: CreateTask, 
+/**
+ * Falls back to the template's executor profile when omitted
+ */
+executor_profile_id: ExecutorProfileId | null, 
+/**
+ * Falls back to the template's repositories when empty
+ */
+repos: Array<WorkspaceRepoInput>, template_id?: string, };
 
 export type CategorizeTaskResponse = { labels: Array<TaskLabel>, applied: boolean, };
 
@@ -360,7 +496,19 @@ export type ImageResponse = { id: string, file_path: string, original_name: stri
 
 export type ImageMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: bigint | null, format: string | null, proxy_url: string | null, };
 
-export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+export type CreateTaskAttemptBody = { task_id: string, 
+/**
+ * Falls back to the template's executor profile when omitted
+ */
+executor_profile_id: ExecutorProfileId | null, 
+/**
+ * Falls back to the template's repositories when empty
+ */
+repos: Array<WorkspaceRepoInput>, template_id?: string, 
+/**
+ * Draft a plan with this profile first; `executor_profile_id` starts once it is approved
+ */
+plan_executor_profile_id?: ExecutorProfileId, };
 
 export type WorkspaceRepoInput = { repo_id: string, target_branch: string, };
 
@@ -454,7 +602,7 @@ conflict_op: ConflictOp | null,
  */
 conflicted_files: Array<string>, };
 
-export type UpdateWorkspace = { archived: boolean | null, pinned: boolean | null, name: string | null, };
+export type UpdateWorkspace = { archived: boolean | null, pinned: boolean | null, auto_merge: boolean | null, name: string | null, };
 
 export type WorkspaceSummaryRequest = { archived: boolean, };
 
@@ -502,11 +650,38 @@ pr_status: MergeStatus | null, };
 
 export type WorkspaceSummaryResponse = { summaries: Array<WorkspaceSummary>, };
 
+export type CheckpointRepo = { repo_id: string, repo_name: string, before_head_commit: string | null, after_head_commit: string | null, 
+/**
+ * Changes committed by the turn; missing while it runs or when a commit is gone
+ */
+diff_stat: DiffStat | null, };
+
+/**
+ * One coding agent turn of a workspace; reverting to it restores the worktrees to the state
+ * before the turn ran
+ */
+export type Checkpoint = { execution_process_id: string, session_id: string, prompt: string | null, executor: string | null, status: ExecutionProcessStatus, dropped: boolean, started_at: string, completed_at: string | null, repos: Array<CheckpointRepo>, };
+
+export type CheckpointTimeline = { 
+/**
+ * Oldest first
+ */
+checkpoints: Array<Checkpoint>, 
+/**
+ * Newest first
+ */
+reverts: Array<CheckpointRevert>, };
+
 export type DirectoryEntry = { name: string, path: string, is_directory: boolean, is_git_repo: boolean, last_modified: bigint | null, };
 
 export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, pr_conflict_resolution_prompt: string | null, ci_failure_auto_fix_enabled: boolean, ci_failure_resolution_prompt: string | null, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, pr_conflict_resolution_prompt: string | null, ci_failure_auto_fix_enabled: boolean, ci_failure_resolution_prompt: string | null, log_retention: LogRetentionConfig, 
+/**
+ * Continue coding agent turns interrupted by a server restart, for executors that can
+ * resume their sessions
+ */
+resume_interrupted_sessions: boolean, execution_limits: ExecutionLimitsConfig, sandbox: SandboxConfig, worktree_gc: WorktreeGcConfig, preview_proxy: PreviewProxyConfig, db_backup: DbBackupConfig, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
@@ -526,6 +701,86 @@ export type UiLanguage = "BROWSER" | "EN" | "JA" | "ES" | "KO" | "ZH_HANS" | "ZH
 
 export type ShowcaseState = { seen_features: Array<string>, };
 
+/**
+ * How long execution logs are kept in full before being compacted
+ */
+export type LogRetentionConfig = { enabled: boolean, 
+/**
+ * Compress the logs of processes that finished more than this many days ago
+ */
+compress_after_days: number, 
+/**
+ * Replace raw agent output with its normalized entries after this many days.
+ * `None` keeps raw output forever.
+ */
+drop_raw_after_days: number | null, 
+/**
+ * Move the logs of archived workspaces out of the database into files
+ */
+archive_archived_workspaces: boolean, };
+
+/**
+ * Default timeouts per run reason. Dev servers and terminals are never stopped.
+ */
+export type ExecutionLimitsConfig = { setup_script: ExecutionLimits, cleanup_script: ExecutionLimits, 
+/**
+ * Profiles in `profiles.json` can override these for their own turns
+ */
+coding_agent: ExecutionLimits, };
+
+/**
+ * Run coding agents in a bubblewrap sandbox (Linux only)
+ */
+export type SandboxConfig = { enabled: boolean, 
+/**
+ * Hosts agents may reach, subdomains included. The agent gets no network of its own and
+ * connects through a proxy enforcing the list, which needs `socat`. `None` leaves the
+ * network alone.
+ */
+allowed_hosts: Array<string> | null, 
+/**
+ * Writable paths on top of the workspace, the temp dir and agent state directories
+ */
+extra_writable_paths: Array<string>, };
+
+/**
+ * When workspace worktrees are removed to free disk. A removed worktree is recreated from its
+ * branch when the workspace is next opened, so worktrees with uncommitted changes are kept.
+ */
+export type WorktreeGcConfig = { enabled: boolean, 
+/**
+ * Remove worktrees of archived workspaces that were not used for this many days
+ */
+archived_after_days: number | null, 
+/**
+ * Remove worktrees of workspaces whose pull requests are merged
+ */
+merged_prs: boolean, 
+/**
+ * Remove the least recently used worktrees while all of them take more than this many GiB
+ */
+max_total_gb: number | null, };
+
+/**
+ * Reverse proxy giving each workspace's dev server a stable preview URL, at
+ * `http://<short id>.localhost:<port>` or `http://localhost:<port>/<short id>/`
+ */
+export type PreviewProxyConfig = { enabled: boolean, 
+/**
+ * Takes effect when Vibe Kanban restarts
+ */
+port: number, };
+
+/**
+ * Scheduled snapshots of the database. Backups before migrations, before restores and
+ * manual ones are taken regardless.
+ */
+export type DbBackupConfig = { enabled: boolean, 
+/**
+ * Hours between scheduled backups
+ */
+interval_hours: number, };
+
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
 
 export type SharedTaskDetails = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, };
@@ -546,31 +801,140 @@ queued_at: string, };
 
 export type QueueStatus = { "status": "empty" } | { "status": "queued", message: QueuedMessage, };
 
+export type ResourceUsage = { execution_process_id: string, session_id: string, 
+/**
+ * Resident memory of the process and everything it spawned, in bytes
+ */
+rss_bytes: number, 
+/**
+ * CPU use since the previous sample; 100 is one full core
+ */
+cpu_percent: number, };
+
+export type WorktreeDiskUsage = { total_bytes: number, 
+/**
+ * Largest first
+ */
+workspaces: Array<WorkspaceDiskUsage>, };
+
+export type WorkspaceDiskUsage = { workspace_id: string, task_id: string, branch: string, path: string, archived: boolean, pinned: boolean, last_used_at: string, total_bytes: number, repos: Array<RepoDiskUsage>, };
+
+export type RepoDiskUsage = { repo_id: string, repo_name: string, total_bytes: number, 
+/**
+ * Gitignored files, such as build artifacts and installed dependencies
+ */
+ignored_bytes: number, };
+
+export type WorktreeGcReason = "archived" | "merged" | "over_quota";
+
+export type WorktreeGcSkipReason = "running" | "uncommitted_changes";
+
+export type WorktreeGcRemoval = { workspace_id: string, path: string, reason: WorktreeGcReason, bytes: number, };
+
+export type WorktreeGcSkip = { workspace_id: string, path: string, reason: WorktreeGcSkipReason, };
+
+export type WorktreeGcReport = { 
+/**
+ * Nothing was removed; `removed` lists what would have been
+ */
+dry_run: boolean, removed: Array<WorktreeGcRemoval>, 
+/**
+ * Worktrees a policy selected but that were kept
+ */
+skipped: Array<WorktreeGcSkip>, freed_bytes: number, };
+
+export type RunWorktreeGcRequest = { dry_run: boolean, };
+
+export type DevProcessStatus = { name: string, command: string, repo_id: string | null, 
+/**
+ * Variable that holds the process's port, e.g. `VK_PORT_API`
+ */
+env_var: string, port: number | null, 
+/**
+ * Latest run, whose logs are streamed by the execution process endpoints
+ */
+execution_process_id: string | null, running: boolean, ready: boolean, };
+
 export type ConflictOp = "rebase" | "merge" | "cherry_pick" | "revert";
 
+export type DiffStat = { files_changed: number, additions: number, deletions: number, };
+
+/**
+ * Messages a terminal client sends over the WebSocket
+ */
+export type TerminalInput = { "type": "input", data: string, } | { "type": "resize", cols: number, rows: number, };
+
+export type TranscriptFormat = "markdown" | "json" | "html";
+
+export type Transcript = { schema_version: number, session_id: string, workspace_id: string, task_title: string | null, exported_at: string, turns: Array<TranscriptTurn>, };
+
+export type TranscriptTurn = { execution_process_id: string, 
+/**
+ * Executor profile that ran this turn, e.g. `CLAUDE_CODE:PLAN`
+ */
+executor: string | null, status: ExecutionProcessStatus, started_at: string, completed_at: string | null, entries: Array<TranscriptEntry>, };
+
+export type TranscriptEntry = { timestamp: string | null, content: string, } & TranscriptEntryKind;
+
+export type TranscriptEntryKind = { "kind": "user_message" } | { "kind": "user_feedback", denied_tool: string, } | { "kind": "assistant_message" } | { "kind": "thinking" } | { "kind": "system_message" } | { "kind": "error" } | { "kind": "tool_use", tool_name: string, status: TranscriptToolStatus, action: ActionType, };
+
+export type TranscriptToolStatus = { "type": "pending" } | { "type": "success" } | { "type": "failed" } | { "type": "awaiting_approval" } | { "type": "denied", reason: string | null, } | { "type": "timed_out" };
+
+/**
+ * A stored secret without its value. Values are write-only over the API.
+ */
+export type Secret = { id: string, project_id: string, 
+/**
+ * Only injected into workspaces containing this repository; `None` for the whole project
+ */
+repo_id: string | null, 
+/**
+ * Environment variable the value is exposed as
+ */
+name: string, created_at: string, updated_at: string, };
+
+export type CreateSecret = { name: string, value: string, repo_id: string | null, };
+
+export type UpdateSecret = { value: string, };
+
 export type ExecutorAction = { typ: ExecutorActionType, next_action: ExecutorAction | null, };
 
 export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path: Array<string>, template: JsonValue, preconfigured: JsonValue, is_toml_config: boolean, };
 
 export type ExecutorActionType = { "type": "CodingAgentInitialRequest" } & CodingAgentInitialRequest | { "type": "CodingAgentFollowUpRequest" } & CodingAgentFollowUpRequest | { "type": "ScriptRequest" } & ScriptRequest;
 
-export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript";
+export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript" | "Terminal";
 
 export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, 
 /**
  * Optional relative path to execute the script in (relative to container_ref).
  * If None, uses the container_ref directory directly.
  */
-working_dir: string | null, };
+working_dir: string | null, 
+/**
+ * Name of the dev process this dev server runs, for projects and repositories that
+ * define several of them
+ */
+dev_process: string | null, };
 
 export type ScriptRequestLanguage = "Bash";
 
-export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID" }
+export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", AIDER = "AIDER", CUSTOM = "CUSTOM" }
 
-export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid };
+export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "AIDER": Aider } | { "CUSTOM": Custom };
 
 export type AvailabilityInfo = { "type": "LOGIN_DETECTED", last_auth_timestamp: bigint, } | { "type": "INSTALLATION_FOUND" } | { "type": "NOT_FOUND" };
 
+export type ExecutionLimits = { 
+/**
+ * Stop the process once it has run this many seconds
+ */
+max_runtime_secs: number | null, 
+/**
+ * Stop the process after this many seconds without output
+ */
+max_idle_secs: number | null, };
+
 export type CommandBuilder = { 
 /**
  * Base executable command (e.g., "npx -y @anthropic-ai/claude-code@latest")
@@ -591,11 +955,21 @@ executor: BaseCodingAgent,
  */
 variant: string | null, };
 
-export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } };
+export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "AIDER": Aider } | { "CUSTOM": Custom } };
 
-export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
+export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorConfig }, 
+/**
+ * Profiles to retry a coding agent turn with, in order, when it fails with a usage
+ * limit, an auth error or a missing CLI. Keyed by `EXECUTOR:VARIANT`.
+ */
+fallbacks: { [key in string]?: Array<ExecutorProfileId> }, 
+/**
+ * Timeouts for a profile's coding agent turns, overriding the app's defaults. Keyed by
+ * `EXECUTOR:VARIANT`.
+ */
+limits: { [key in string]?: ExecutionLimits }, };
 
-export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER = "SETUP_HELPER" }
+export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SESSION_RESUME = "SESSION_RESUME", SETUP_HELPER = "SETUP_HELPER" }
 
 export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
@@ -633,6 +1007,18 @@ export type Autonomy = "normal" | "low" | "medium" | "high" | "skip-permissions-
 
 export type DroidReasoningEffort = "none" | "dynamic" | "off" | "low" | "medium" | "high";
 
+export type Aider = { append_prompt: AppendPrompt, model?: string | null, edit_format?: string | null, auto_commits?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+
+export type Custom = { append_prompt: AppendPrompt, command: string, follow_up_args?: Array<string> | null, session_id_regex?: string | null, session_id_json_path?: string | null, output_format: CustomOutputFormat, output_rules: Array<CustomOutputRule>, mcp_config_path?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+
+export type CustomOutputFormat = "plain_text" | "json_lines";
+
+export type CustomEntryType = "assistant_message" | "user_message" | "system_message" | "thinking" | "error_message" | "tool_use" | "ignore";
+
+export type CustomToolAction = "command_run" | "file_read" | "file_edit" | "search" | "web_fetch" | "tool";
+
+export type CustomOutputRule = { field: string, equals?: string | null, entry_type: CustomEntryType, content_path?: string | null, tool_name_path?: string | null, tool_action?: CustomToolAction | null, tool_argument_path?: string | null, };
+
 export type AppendPrompt = string | null;
 
 export type CodingAgentInitialRequest = { prompt: string, 